@@ -0,0 +1,434 @@
+//! The hash algorithms of the command line tool.
+use std::hash::Hasher;
+use std::io::{self, Read, Write};
+
+use fasthash::{
+    city, farm, highway, lookup3, metro, mum, murmur, murmur2, murmur3, sea, spooky, t1ha, xx,
+    xxh3, FastHash, FastHasher, HasherExt,
+};
+
+use crate::Error;
+
+/// A hash algorithm and its supported widths.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Algorithm {
+    /// The name of the algorithm.
+    pub name: &'static str,
+    /// The supported widths in bits.
+    pub widths: &'static [u32],
+    /// The width used when none is given.
+    pub default_width: u32,
+}
+
+/// All the supported algorithms.
+///
+/// `t1ha0` is left out, it picks its implementation from the CPU features,
+/// so its checksums would differ between machines.
+pub const ALGORITHMS: &[Algorithm] = &[
+    algorithm("city", &[32, 64, 128], 64),
+    algorithm("farm", &[32, 64, 128], 64),
+    algorithm("highway", &[64, 128, 256], 64),
+    algorithm("lookup3", &[32], 32),
+    algorithm("metro", &[64, 128], 64),
+    algorithm("mum", &[64], 64),
+    algorithm("murmur1", &[32], 32),
+    algorithm("murmur2", &[32, 64], 64),
+    algorithm("murmur3", &[32, 128], 128),
+    algorithm("sea", &[64], 64),
+    algorithm("spooky", &[32, 64, 128], 128),
+    algorithm("t1ha1", &[64], 64),
+    algorithm("t1ha2", &[64, 128], 64),
+    algorithm("xx", &[32, 64], 64),
+    algorithm("xxh3", &[64, 128], 64),
+];
+
+/// The algorithm used when none is given.
+pub const DEFAULT_ALGORITHM: &str = "xxh3";
+
+const fn algorithm(name: &'static str, widths: &'static [u32], default_width: u32) -> Algorithm {
+    Algorithm {
+        name,
+        widths,
+        default_width,
+    }
+}
+
+/// The BSD-style tags of `xxhsum`, with their algorithm and width.
+const XXHSUM_TAGS: &[(&str, &str, u32)] = &[
+    ("XXH32", "xx", 32),
+    ("XXH64", "xx", 64),
+    ("XXH3", "xxh3", 64),
+    ("XXH128", "xxh3", 128),
+];
+
+/// Returns the algorithm with the name.
+pub fn find(name: &str) -> Option<&'static Algorithm> {
+    ALGORITHMS.iter().find(|a| a.name == name)
+}
+
+/// Returns the algorithm and width of a BSD-style tag, e.g. `CITY-128` or `xxhsum`'s `XXH64`.
+pub fn from_tag(tag: &str) -> Option<(&'static Algorithm, u32)> {
+    if let Some(&(_, name, width)) = XXHSUM_TAGS.iter().find(|&&(t, _, _)| t == tag) {
+        return find(name).map(|algorithm| (algorithm, width));
+    }
+
+    let pos = tag.rfind('-')?;
+    let algorithm = find(&tag[..pos].to_lowercase())?;
+    let width = tag[pos + 1..].parse().ok()?;
+
+    if algorithm.widths.contains(&width) {
+        Some((algorithm, width))
+    } else {
+        None
+    }
+}
+
+impl Algorithm {
+    /// Returns the BSD-style tag of the algorithm with a width,
+    /// the xx and xxh3 algorithms use the tags of `xxhsum`.
+    pub fn tag(&self, width: u32) -> String {
+        match XXHSUM_TAGS
+            .iter()
+            .find(|&&(_, name, w)| name == self.name && w == width)
+        {
+            Some(&(tag, _, _)) => tag.to_owned(),
+            None => format!("{}-{}", self.name.to_uppercase(), width),
+        }
+    }
+
+    /// Hashes a stream with the seed, given as 64-bit words, returns the digest in hexadecimal.
+    ///
+    /// The digest is the value of the algorithm's `FastHasher` after writing the whole stream.
+    /// The streaming `sea` and `t1ha2` hashers don't match their one-shot hashes,
+    /// these algorithms read the whole stream and return the one-shot hash.
+    pub fn digest<R: Read>(
+        &self,
+        width: u32,
+        seed: Option<&[u64]>,
+        r: &mut R,
+    ) -> Result<String, Error> {
+        let digest = match (self.name, width) {
+            ("city", 32) => stream::<city::Hash32, _>(r, seed),
+            ("city", 64) => stream::<city::Hash64, _>(r, seed),
+            ("city", 128) => stream_ext::<city::Hash128, _>(r, seed),
+            ("farm", 32) => stream::<farm::Hash32, _>(r, seed),
+            ("farm", 64) => stream::<farm::Hash64, _>(r, seed),
+            ("farm", 128) => stream_ext::<farm::Hash128, _>(r, seed),
+            ("highway", 64) => stream::<highway::Hash64, _>(r, seed),
+            ("highway", 128) => stream_ext::<highway::Hash128, _>(r, seed),
+            // the 256-bit digest doesn't fit in a `u128`
+            ("highway", 256) => return highway256(r, seed),
+            ("lookup3", 32) => stream::<lookup3::Hash32, _>(r, seed),
+            ("metro", 64) => stream::<metro::Hash64_1, _>(r, seed),
+            ("metro", 128) => stream_ext::<metro::Hash128_1, _>(r, seed),
+            ("mum", 64) => stream::<mum::Hash64, _>(r, seed),
+            ("murmur1", 32) => stream::<murmur::Hash32, _>(r, seed),
+            ("murmur2", 32) => stream::<murmur2::Hash32, _>(r, seed),
+            ("murmur2", 64) => stream::<murmur2::Hash64_x64, _>(r, seed),
+            ("murmur3", 32) => stream::<murmur3::Hash32, _>(r, seed),
+            ("murmur3", 128) => stream_ext::<murmur3::Hash128_x64, _>(r, seed),
+            ("sea", 64) => at_once::<sea::Hash64, _>(r, seed),
+            ("spooky", 32) => stream::<spooky::Hash32, _>(r, seed),
+            ("spooky", 64) => stream::<spooky::Hash64, _>(r, seed),
+            ("spooky", 128) => stream_ext::<spooky::Hash128, _>(r, seed),
+            ("t1ha1", 64) => stream::<t1ha::t1ha1::Hash64Le, _>(r, seed),
+            ("t1ha2", 64) => at_once::<t1ha::t1ha2::Hash64AtOnce, _>(r, seed),
+            ("t1ha2", 128) => at_once::<t1ha::t1ha2::Hash128AtOnce, _>(r, seed),
+            ("xx", 32) => stream::<xx::Hash32, _>(r, seed),
+            ("xx", 64) => stream::<xx::Hash64, _>(r, seed),
+            ("xxh3", 64) => stream::<xxh3::Hash64, _>(r, seed),
+            ("xxh3", 128) => stream_ext::<xxh3::Hash128, _>(r, seed),
+            _ => Err(Error::Usage(format!(
+                "{} doesn't support a width of {} bits",
+                self.name, width
+            ))),
+        }?;
+
+        Ok(to_hex(digest, width))
+    }
+}
+
+/// The seeds that can be built from 64-bit words.
+trait FromWords: Sized {
+    /// Describes the expected words.
+    const EXPECTED: &'static str;
+
+    fn from_words(words: &[u64]) -> Option<Self>;
+}
+
+impl FromWords for u32 {
+    const EXPECTED: &'static str = "one 32-bit word";
+
+    fn from_words(words: &[u64]) -> Option<Self> {
+        match *words {
+            [w] if w <= 0xffff_ffff => Some(w as u32),
+            _ => None,
+        }
+    }
+}
+
+impl FromWords for u64 {
+    const EXPECTED: &'static str = "one 64-bit word";
+
+    fn from_words(words: &[u64]) -> Option<Self> {
+        match *words {
+            [w] => Some(w),
+            _ => None,
+        }
+    }
+}
+
+impl FromWords for u128 {
+    const EXPECTED: &'static str = "one or two 64-bit words, low word first";
+
+    fn from_words(words: &[u64]) -> Option<Self> {
+        match *words {
+            [lo] => Some(u128::from(lo)),
+            [lo, hi] => Some(u128::from(hi) << 64 | u128::from(lo)),
+            _ => None,
+        }
+    }
+}
+
+impl FromWords for (u64, u64) {
+    const EXPECTED: &'static str = "two 64-bit words";
+
+    fn from_words(words: &[u64]) -> Option<Self> {
+        match *words {
+            [a, b] => Some((a, b)),
+            _ => None,
+        }
+    }
+}
+
+impl FromWords for (u64, u64, u64, u64) {
+    const EXPECTED: &'static str = "four 64-bit words";
+
+    fn from_words(words: &[u64]) -> Option<Self> {
+        match *words {
+            [a, b, c, d] => Some((a, b, c, d)),
+            _ => None,
+        }
+    }
+}
+
+impl FromWords for [u64; 4] {
+    const EXPECTED: &'static str = "four 64-bit words";
+
+    fn from_words(words: &[u64]) -> Option<Self> {
+        match *words {
+            [a, b, c, d] => Some([a, b, c, d]),
+            _ => None,
+        }
+    }
+}
+
+/// Creates the hasher `H` with the seed.
+fn hasher<H>(seed: Option<&[u64]>) -> Result<H, Error>
+where
+    H: FastHasher,
+    H::Seed: FromWords,
+{
+    match seed {
+        Some(words) => FromWords::from_words(words)
+            .map(H::with_seed)
+            .ok_or(Error::Seed(H::Seed::EXPECTED)),
+        None => Ok(H::new()),
+    }
+}
+
+/// Writes the bytes into a hasher.
+struct HashWriter<'a, H>(&'a mut H);
+
+impl<'a, H: Hasher> Write for HashWriter<'a, H> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf);
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Hashes a stream with the 32-bit or 64-bit hasher of `H`.
+fn stream<H, R>(r: &mut R, seed: Option<&[u64]>) -> Result<u128, Error>
+where
+    H: FastHash,
+    <H::FastHasher as FastHasher>::Seed: FromWords,
+    R: Read,
+{
+    let mut h = hasher::<H::FastHasher>(seed)?;
+
+    io::copy(r, &mut HashWriter(&mut h))?;
+
+    Ok(u128::from(h.finish()))
+}
+
+/// Hashes a stream with the 128-bit hasher of `H`.
+fn stream_ext<H, R>(r: &mut R, seed: Option<&[u64]>) -> Result<u128, Error>
+where
+    H: FastHash,
+    H::FastHasher: HasherExt,
+    <H::FastHasher as FastHasher>::Seed: FromWords,
+    R: Read,
+{
+    let mut h = hasher::<H::FastHasher>(seed)?;
+
+    io::copy(r, &mut HashWriter(&mut h))?;
+
+    Ok(h.finish_ext())
+}
+
+/// Hashes a stream with the 256-bit `HighwayHash` hasher, the high word first like `to_hex`.
+fn highway256<R: Read>(r: &mut R, seed: Option<&[u64]>) -> Result<String, Error> {
+    let mut h = hasher::<highway::Hasher256>(seed)?;
+
+    io::copy(r, &mut HashWriter(&mut h))?;
+
+    Ok(h.finish256()
+        .iter()
+        .rev()
+        .map(|w| format!("{:016x}", w))
+        .collect())
+}
+
+/// Hashes a whole stream at once with `H`.
+fn at_once<H, R>(r: &mut R, seed: Option<&[u64]>) -> Result<u128, Error>
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+    H::Seed: FromWords,
+    R: Read,
+{
+    let seed = seed
+        .map(|words| H::Seed::from_words(words).ok_or(Error::Seed(H::Seed::EXPECTED)))
+        .transpose()?;
+    let mut bytes = Vec::new();
+
+    r.read_to_end(&mut bytes)?;
+
+    Ok(match seed {
+        Some(seed) => H::hash_with_seed(&bytes, seed),
+        None => H::hash(&bytes),
+    }
+    .into())
+}
+
+/// Formats a digest as `width / 4` hexadecimal digits.
+pub fn to_hex(digest: u128, width: u32) -> String {
+    let digest = if width < 128 {
+        digest & ((1 << width) - 1)
+    } else {
+        digest
+    };
+
+    format!("{:01$x}", digest, width as usize / 4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digest() {
+        let data = b"hello world";
+
+        for algorithm in ALGORITHMS {
+            assert!(algorithm.widths.contains(&algorithm.default_width));
+
+            for &width in algorithm.widths {
+                let digest = algorithm.digest(width, None, &mut &data[..]).unwrap();
+
+                assert_eq!(digest.len(), width as usize / 4);
+                assert_eq!(from_tag(&algorithm.tag(width)), Some((algorithm, width)));
+            }
+        }
+
+        let xx = find("xx").unwrap();
+
+        assert_eq!(
+            xx.digest(32, Some(&[123]), &mut &data[..]).unwrap(),
+            to_hex(u128::from(xx::hash32_with_seed(data, 123)), 32)
+        );
+        assert_eq!(
+            xx.digest(64, None, &mut &data[..]).unwrap(),
+            to_hex(u128::from(xx::hash64(data)), 64)
+        );
+        assert_eq!(
+            find("city")
+                .unwrap()
+                .digest(128, None, &mut &data[..])
+                .unwrap(),
+            to_hex(city::Hash128::hash(data), 128)
+        );
+        assert_eq!(
+            find("xxh3")
+                .unwrap()
+                .digest(128, Some(&[7]), &mut &data[..])
+                .unwrap(),
+            to_hex(xxh3::Hash128::hash_with_seed(data, 7), 128)
+        );
+        assert_eq!(
+            find("sea")
+                .unwrap()
+                .digest(64, None, &mut &data[..])
+                .unwrap(),
+            to_hex(u128::from(sea::hash64(data)), 64)
+        );
+        assert_eq!(
+            find("t1ha2")
+                .unwrap()
+                .digest(64, Some(&[5]), &mut &data[..])
+                .unwrap(),
+            to_hex(
+                u128::from(t1ha::t1ha2::Hash64AtOnce::hash_with_seed(data, 5)),
+                64
+            )
+        );
+        assert_eq!(
+            find("t1ha2")
+                .unwrap()
+                .digest(128, None, &mut &data[..])
+                .unwrap(),
+            to_hex(t1ha::t1ha2::Hash128AtOnce::hash(data), 128)
+        );
+
+        let words = highway::hash256_with_seed(data, [1, 2, 3, 4]);
+
+        assert_eq!(
+            find("highway")
+                .unwrap()
+                .digest(256, Some(&[1, 2, 3, 4]), &mut &data[..])
+                .unwrap(),
+            format!(
+                "{:016x}{:016x}{:016x}{:016x}",
+                words[3], words[2], words[1], words[0]
+            )
+        );
+        assert_eq!(find("t1ha0"), None);
+        assert!(xx.digest(128, None, &mut &data[..]).is_err());
+        assert!(xx.digest(32, Some(&[1 << 32]), &mut &data[..]).is_err());
+        assert!(find("sea")
+            .unwrap()
+            .digest(64, Some(&[1, 2]), &mut &data[..])
+            .is_err());
+    }
+
+    #[test]
+    fn test_to_hex() {
+        assert_eq!(to_hex(0x1234, 32), "00001234");
+        assert_eq!(to_hex(0x1_0000_0001, 32), "00000001");
+        assert_eq!(to_hex(1, 128), format!("{:032x}", 1));
+        assert_eq!(from_tag("XX-64"), Some((find("xx").unwrap(), 64)));
+        assert_eq!(from_tag("XXH64"), Some((find("xx").unwrap(), 64)));
+        assert_eq!(from_tag("XXH3"), Some((find("xxh3").unwrap(), 64)));
+        assert_eq!(from_tag("XXH128"), Some((find("xxh3").unwrap(), 128)));
+        assert_eq!(find("xx").unwrap().tag(32), "XXH32");
+        assert_eq!(find("xxh3").unwrap().tag(128), "XXH128");
+        assert_eq!(find("city").unwrap().tag(128), "CITY-128");
+        assert_eq!(from_tag("XX-128"), None);
+        assert_eq!(from_tag("MD5"), None);
+    }
+}
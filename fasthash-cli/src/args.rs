@@ -0,0 +1,255 @@
+//! The command line arguments.
+use crate::algorithm::{self, Algorithm};
+use crate::Error;
+
+/// The usage of the command line tool.
+pub const USAGE: &str = "\
+Usage: fasthash [OPTIONS] [FILE]...
+       fasthash --check [OPTIONS] [FILE]...
+
+Print or check the non-cryptographic hashes of files, or the standard input when FILE is - or missing.
+The sea and t1ha2 hashes are computed at once, they read each file in memory.
+
+Options:
+  -a, --algorithm NAME   hash algorithm, see --list (default: xxh3)
+  -w, --width BITS       hash width in bits (default: depends on the algorithm,
+                         or the length of the checksums when checking)
+  -s, --seed WORDS       seed as comma separated 64-bit words, decimal or 0x hexadecimal
+      --tag              print BSD-style checksums
+  -c, --check            read checksums from the FILEs and check them
+  -q, --quiet            don't print OK for each successfully verified file
+      --status           don't print anything, the exit code shows success
+      --strict           fail on improperly formatted checksum lines
+  -l, --list             list the algorithms and their widths
+  -h, --help             print this help
+  -V, --version          print the version
+";
+
+/// The output format of the checksums.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// `<hex>  <file>`, like `sha256sum`.
+    Gnu,
+    /// `<ALGORITHM-WIDTH> (<file>) = <hex>`, like `sha256sum --tag`,
+    /// or `xxhsum`'s `XXH32`, `XXH64`, `XXH3` and `XXH128` tags.
+    Bsd,
+}
+
+/// The options to hash or check the files.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Options {
+    pub algorithm: &'static Algorithm,
+    /// The width given with `-w`.
+    pub width: Option<u32>,
+    pub seed: Option<Vec<u64>>,
+    pub format: Format,
+    pub check: bool,
+    pub quiet: bool,
+    pub status: bool,
+    pub strict: bool,
+    pub files: Vec<String>,
+}
+
+impl Options {
+    /// Returns the width given with `-w`, or the default width of the algorithm.
+    pub fn width(&self) -> u32 {
+        self.width.unwrap_or(self.algorithm.default_width)
+    }
+}
+
+/// The command to run.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Run(Options),
+    List,
+    Help,
+    Version,
+}
+
+/// Parses a seed as comma separated 64-bit words.
+fn parse_seed(s: &str) -> Result<Vec<u64>, Error> {
+    s.split(',')
+        .map(|word| {
+            let word = word.trim();
+            let parsed = if word.starts_with("0x") || word.starts_with("0X") {
+                u64::from_str_radix(&word[2..], 16)
+            } else {
+                word.parse()
+            };
+
+            parsed.map_err(|_| Error::Usage(format!("invalid seed word `{}`", word)))
+        })
+        .collect()
+}
+
+/// Parses the arguments, without the program name.
+pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Command, Error> {
+    let mut args = args.into_iter();
+    let mut algorithm = None;
+    let mut width = None;
+    let mut seed = None;
+    let mut format = Format::Gnu;
+    let mut check = false;
+    let mut quiet = false;
+    let mut status = false;
+    let mut strict = false;
+    let mut files = Vec::new();
+
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            files.extend(args.by_ref());
+            break;
+        }
+        if arg == "-" || !arg.starts_with('-') {
+            files.push(arg);
+            continue;
+        }
+
+        let (name, inline) = match arg.find('=') {
+            Some(pos) if arg.starts_with("--") => (&arg[..pos], Some(arg[pos + 1..].to_owned())),
+            _ => (&arg[..], None),
+        };
+        let mut value = || {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| Error::Usage(format!("missing value for `{}`", name)))
+        };
+
+        match name {
+            "-a" | "--algorithm" => {
+                let name = value()?;
+
+                algorithm = Some(
+                    algorithm::find(&name)
+                        .ok_or_else(|| Error::Usage(format!("unknown algorithm `{}`", name)))?,
+                );
+            }
+            "-w" | "--width" => {
+                let bits = value()?;
+
+                width = Some(
+                    bits.parse()
+                        .map_err(|_| Error::Usage(format!("invalid width `{}`", bits)))?,
+                );
+            }
+            "-s" | "--seed" => seed = Some(parse_seed(&value()?)?),
+            "--tag" => format = Format::Bsd,
+            "-c" | "--check" => check = true,
+            "-q" | "--quiet" => quiet = true,
+            "--status" => status = true,
+            "--strict" => strict = true,
+            "-l" | "--list" => return Ok(Command::List),
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            _ => return Err(Error::Usage(format!("unknown option `{}`", arg))),
+        }
+
+        if inline.is_some() && !matches!(name, "--algorithm" | "--width" | "--seed") {
+            return Err(Error::Usage(format!("`{}` doesn't take a value", name)));
+        }
+    }
+
+    let algorithm =
+        algorithm.unwrap_or_else(|| algorithm::find(algorithm::DEFAULT_ALGORITHM).unwrap());
+    if let Some(width) = width.filter(|w| !algorithm.widths.contains(w)) {
+        return Err(Error::Usage(format!(
+            "{} doesn't support a width of {} bits",
+            algorithm.name, width
+        )));
+    }
+    if files.is_empty() {
+        files.push("-".to_owned());
+    }
+
+    Ok(Command::Run(Options {
+        algorithm,
+        width,
+        seed,
+        format,
+        check,
+        quiet,
+        status,
+        strict,
+        files,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Result<Command, Error> {
+        parse(args.iter().map(|&s| s.to_owned()))
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse_args(&[]).unwrap(),
+            Command::Run(Options {
+                algorithm: algorithm::find("xxh3").unwrap(),
+                width: None,
+                seed: None,
+                format: Format::Gnu,
+                check: false,
+                quiet: false,
+                status: false,
+                strict: false,
+                files: vec!["-".to_owned()],
+            })
+        );
+        assert_eq!(
+            parse_args(&[
+                "-a",
+                "city",
+                "--width=128",
+                "--seed",
+                "1,0x2",
+                "--tag",
+                "a",
+                "--",
+                "-b"
+            ])
+            .unwrap(),
+            Command::Run(Options {
+                algorithm: algorithm::find("city").unwrap(),
+                width: Some(128),
+                seed: Some(vec![1, 2]),
+                format: Format::Bsd,
+                check: false,
+                quiet: false,
+                status: false,
+                strict: false,
+                files: vec!["a".to_owned(), "-b".to_owned()],
+            })
+        );
+
+        match parse_args(&["-c", "-q", "--status", "--strict", "sums.txt"]).unwrap() {
+            Command::Run(opts) => {
+                assert!(opts.check && opts.quiet && opts.status && opts.strict);
+                assert_eq!(opts.files, vec!["sums.txt"]);
+            }
+            cmd => panic!("unexpected command {:?}", cmd),
+        }
+
+        assert_eq!(parse_args(&["--list"]).unwrap(), Command::List);
+        assert_eq!(parse_args(&["-h"]).unwrap(), Command::Help);
+        assert_eq!(parse_args(&["-V"]).unwrap(), Command::Version);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for args in &[
+            &["-a", "md5"][..],
+            &["-a", "xx", "-w", "128"],
+            &["-w", "wide"],
+            &["-s", "0xgg"],
+            &["--seed"],
+            &["--tag=1"],
+            &["-x"],
+        ] {
+            assert!(parse_args(args).is_err(), "{:?}", args);
+        }
+    }
+}
@@ -0,0 +1,314 @@
+//! The check mode, verifies the checksums of a list like `xxhsum -c`.
+use std::fs::File;
+use std::io::{self, BufRead, Write};
+
+use crate::algorithm::{self, Algorithm};
+use crate::args::Options;
+use crate::Error;
+
+/// A line of a checksum list.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Entry<'a> {
+    /// The algorithm and width of a BSD-style line.
+    pub tag: Option<(&'static Algorithm, u32)>,
+    pub digest: &'a str,
+    pub path: &'a str,
+}
+
+/// Parses a line in the GNU or BSD-style format, returns `None` if it is improperly formatted.
+pub fn parse_line(line: &str) -> Option<Entry<'_>> {
+    let is_hex = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_hexdigit());
+
+    if let (Some(open), Some(close)) = (line.find(" ("), line.rfind(") = ")) {
+        let digest = &line[close + 4..];
+
+        if open < close && is_hex(digest) {
+            return Some(Entry {
+                tag: Some(algorithm::from_tag(&line[..open])?),
+                digest,
+                path: &line[open + 2..close],
+            });
+        }
+    }
+
+    let pos = line.find(' ')?;
+    let (digest, rest) = (&line[..pos], &line[pos + 1..]);
+
+    if is_hex(digest) && (rest.starts_with(' ') || rest.starts_with('*')) && rest.len() > 1 {
+        Some(Entry {
+            tag: None,
+            digest,
+            path: &rest[1..],
+        })
+    } else {
+        None
+    }
+}
+
+/// The counters of a check.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub matched: usize,
+    pub mismatched: usize,
+    pub unreadable: usize,
+    pub improper: usize,
+}
+
+impl Summary {
+    /// Returns `true` if every checksum was verified.
+    ///
+    /// The improperly formatted lines are only warned about, like `xxhsum -c`,
+    /// unless `strict` is set.
+    pub fn is_ok(&self, strict: bool) -> bool {
+        self.matched > 0
+            && self.mismatched == 0
+            && self.unreadable == 0
+            && (!strict || self.improper == 0)
+    }
+}
+
+/// Verifies the checksums listed in `list`, named `name` in the messages.
+pub fn check<R: BufRead, W: Write>(
+    opts: &Options,
+    name: &str,
+    list: R,
+    out: &mut W,
+) -> Result<Summary, Error> {
+    let mut summary = Summary::default();
+
+    for line in list.lines() {
+        let line = line?;
+        let line = line.trim_end_matches('\r');
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let entry = parse_line(line).and_then(|entry| {
+            let (algorithm, width) = match entry.tag {
+                Some(tag) => tag,
+                // the GNU lines have no tag, the width is given with `-w`
+                // or the length of the digest
+                None => (
+                    opts.algorithm,
+                    match opts.width {
+                        Some(width) => width,
+                        None => opts
+                            .algorithm
+                            .widths
+                            .iter()
+                            .cloned()
+                            .find(|&w| w as usize / 4 == entry.digest.len())?,
+                    },
+                ),
+            };
+
+            if entry.digest.len() == width as usize / 4 {
+                Some((entry, algorithm, width))
+            } else {
+                None
+            }
+        });
+
+        let (entry, algorithm, width) = match entry {
+            Some(entry) => entry,
+            None => {
+                summary.improper += 1;
+                continue;
+            }
+        };
+
+        let digest = match File::open(entry.path)
+            .map_err(Error::from)
+            .and_then(|mut f| algorithm.digest(width, opts.seed.as_deref(), &mut f))
+        {
+            Ok(digest) => digest,
+            Err(Error::Io(_)) => {
+                summary.unreadable += 1;
+
+                if !opts.status {
+                    writeln!(out, "{}: FAILED open or read", entry.path)?;
+                }
+                continue;
+            }
+            Err(err) => return Err(err),
+        };
+
+        if digest.eq_ignore_ascii_case(entry.digest) {
+            summary.matched += 1;
+
+            if !opts.quiet && !opts.status {
+                writeln!(out, "{}: OK", entry.path)?;
+            }
+        } else {
+            summary.mismatched += 1;
+
+            if !opts.status {
+                writeln!(out, "{}: FAILED", entry.path)?;
+            }
+        }
+    }
+
+    if !opts.status {
+        let stderr = io::stderr();
+        let mut err = stderr.lock();
+
+        if summary.matched + summary.mismatched + summary.unreadable == 0 {
+            writeln!(
+                err,
+                "fasthash: {}: no properly formatted checksum lines found",
+                name
+            )?;
+        } else {
+            if summary.improper > 0 {
+                writeln!(
+                    err,
+                    "fasthash: WARNING: {} line(s) improperly formatted",
+                    summary.improper
+                )?;
+            }
+            if summary.unreadable > 0 {
+                writeln!(
+                    err,
+                    "fasthash: WARNING: {} listed file(s) could not be read",
+                    summary.unreadable
+                )?;
+            }
+            if summary.mismatched > 0 {
+                writeln!(
+                    err,
+                    "fasthash: WARNING: {} computed checksum(s) did NOT match",
+                    summary.mismatched
+                )?;
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+
+    use super::*;
+    use crate::args::{self, Command};
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(
+            parse_line("0123abcd  file name.txt"),
+            Some(Entry {
+                tag: None,
+                digest: "0123abcd",
+                path: "file name.txt"
+            })
+        );
+        assert_eq!(parse_line("0123abcd *bin").unwrap().path, "bin");
+        assert_eq!(
+            parse_line("XX-32 (a (b) = c) = 0123abcd"),
+            Some(Entry {
+                tag: Some((algorithm::find("xx").unwrap(), 32)),
+                digest: "0123abcd",
+                path: "a (b) = c"
+            })
+        );
+        assert_eq!(
+            parse_line("XXH3 (a) = 0123456789abcdef").unwrap().tag,
+            Some((algorithm::find("xxh3").unwrap(), 64))
+        );
+        assert_eq!(parse_line("MD5 (a) = 0123abcd"), None);
+        assert_eq!(parse_line("xyz  file"), None);
+        assert_eq!(parse_line("0123abcd file"), None);
+        assert_eq!(parse_line("0123abcd"), None);
+    }
+
+    #[test]
+    fn test_check() {
+        let dir = env::temp_dir().join(format!("fasthash-cli-{}", std::process::id()));
+
+        fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join("hello.txt");
+
+        fs::write(&path, "hello world").unwrap();
+
+        let path = path.to_str().unwrap();
+        let xx = algorithm::find("xx").unwrap();
+        let xx32 = xx.digest(32, None, &mut &b"hello world"[..]).unwrap();
+        let xx64 = xx.digest(64, None, &mut &b"hello world"[..]).unwrap();
+        let wrong = algorithm::to_hex(u128::from(fasthash::xx::hash32(b"hello world")) + 1, 32);
+        let list = format!(
+            "{}  {}\n{} ({}) = {}\n{}  {}\n{}  missing.txt\nnot a checksum line\n",
+            xx64,
+            path,
+            xx.tag(32),
+            path,
+            xx32,
+            wrong,
+            path,
+            xx64,
+        );
+        let options = |args: &[&str]| match args::parse(args.iter().map(|&s| s.to_owned())) {
+            Ok(Command::Run(opts)) => opts,
+            cmd => panic!("unexpected command {:?}", cmd),
+        };
+        let mut out = Vec::new();
+        let summary = check(
+            &options(&["-c", "-a", "xx"]),
+            "list",
+            list.as_bytes(),
+            &mut out,
+        );
+        // the 32-bit GNU line doesn't match the width given with `-w`
+        let wide = check(
+            &options(&["-c", "-a", "xx", "-w", "64"]),
+            "list",
+            list.as_bytes(),
+            &mut Vec::new(),
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+
+        let summary = summary.unwrap();
+
+        assert_eq!(
+            wide.unwrap(),
+            Summary {
+                matched: 2,
+                mismatched: 0,
+                unreadable: 1,
+                improper: 2,
+            }
+        );
+
+        assert_eq!(
+            summary,
+            Summary {
+                matched: 2,
+                mismatched: 1,
+                unreadable: 1,
+                improper: 1,
+            }
+        );
+        assert!(!summary.is_ok(false));
+
+        let ok = Summary {
+            matched: 1,
+            improper: 1,
+            ..Summary::default()
+        };
+
+        assert!(ok.is_ok(false));
+        assert!(!ok.is_ok(true));
+        assert!(!Summary::default().is_ok(false));
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!(
+                "{0}: OK\n{0}: OK\n{0}: FAILED\nmissing.txt: FAILED open or read\n",
+                path
+            )
+        );
+    }
+}
@@ -0,0 +1,173 @@
+//! `fasthash`, prints or checks the non-cryptographic hashes of files, like `xxhsum`.
+//!
+//! # Example
+//!
+//! ```sh
+//! $ fasthash -a city -w 128 Cargo.toml > sums.txt
+//! $ fasthash --tag -a xx -w 32 src/main.rs >> sums.txt
+//! $ fasthash -c -a city -w 128 sums.txt
+//! ```
+use std::env;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Write};
+use std::process;
+
+mod algorithm;
+mod args;
+mod check;
+
+use crate::args::{Command, Format, Options};
+
+/// The errors of the command line tool.
+#[derive(Debug)]
+pub enum Error {
+    /// The arguments are invalid.
+    Usage(String),
+    /// The seed doesn't fit the algorithm, with the expected words.
+    Seed(&'static str),
+    /// Reading or writing failed.
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Usage(msg) => f.write_str(msg),
+            Error::Seed(expected) => write!(f, "invalid seed, expected {}", expected),
+            Error::Io(err) => err.fmt(f),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+/// Opens a file, or the standard input for `-`.
+fn open(path: &str) -> io::Result<Box<dyn Read>> {
+    if path == "-" {
+        Ok(Box::new(io::stdin()))
+    } else {
+        Ok(Box::new(File::open(path)?))
+    }
+}
+
+/// Prints the hashes of the files, returns `false` if a file could not be read.
+fn hash(opts: &Options) -> Result<bool, Error> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut ok = true;
+
+    for path in &opts.files {
+        let digest = open(path).map_err(Error::from).and_then(|mut r| {
+            opts.algorithm
+                .digest(opts.width(), opts.seed.as_deref(), &mut r)
+        });
+
+        let digest = match digest {
+            Ok(digest) => digest,
+            Err(Error::Io(err)) => {
+                eprintln!("fasthash: {}: {}", path, err);
+                ok = false;
+                continue;
+            }
+            Err(err) => return Err(err),
+        };
+
+        match opts.format {
+            Format::Gnu => writeln!(out, "{}  {}", digest, path)?,
+            Format::Bsd => writeln!(
+                out,
+                "{} ({}) = {}",
+                opts.algorithm.tag(opts.width()),
+                path,
+                digest
+            )?,
+        }
+    }
+
+    Ok(ok)
+}
+
+/// Checks the checksum lists, returns `false` if a checksum could not be verified.
+fn check(opts: &Options) -> Result<bool, Error> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut ok = true;
+
+    for path in &opts.files {
+        match open(path) {
+            Ok(list) => {
+                ok &= check::check(opts, path, BufReader::new(list), &mut out)?.is_ok(opts.strict);
+            }
+            Err(err) => {
+                eprintln!("fasthash: {}: {}", path, err);
+                ok = false;
+            }
+        }
+    }
+
+    Ok(ok)
+}
+
+fn run(cmd: Command) -> Result<bool, Error> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+
+    match cmd {
+        Command::Run(ref opts) if opts.check => check(opts),
+        Command::Run(ref opts) => hash(opts),
+        Command::List => {
+            for algorithm in algorithm::ALGORITHMS {
+                let widths = algorithm
+                    .widths
+                    .iter()
+                    .map(|w| w.to_string())
+                    .collect::<Vec<_>>();
+
+                writeln!(
+                    out,
+                    "{:<8} {} (default {})",
+                    algorithm.name,
+                    widths.join(", "),
+                    algorithm.default_width
+                )?;
+            }
+
+            Ok(true)
+        }
+        Command::Help => {
+            write!(out, "{}", args::USAGE)?;
+
+            Ok(true)
+        }
+        Command::Version => {
+            writeln!(out, "fasthash {}", env!("CARGO_PKG_VERSION"))?;
+
+            Ok(true)
+        }
+    }
+}
+
+fn main() {
+    let code = match args::parse(env::args().skip(1)).and_then(run) {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(Error::Usage(msg)) => {
+            eprintln!(
+                "fasthash: {}\nTry `fasthash --help` for more information.",
+                msg
+            );
+            2
+        }
+        Err(err) => {
+            eprintln!("fasthash: {}", err);
+            2
+        }
+    };
+
+    process::exit(code)
+}
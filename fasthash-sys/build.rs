@@ -8,59 +8,72 @@ lazy_static! {
     static ref CPUID: CpuId = CpuId::new();
 }
 
+/// Probe the build machine only when the binary is meant to run on it.
+fn detect_host() -> bool {
+    cfg!(feature = "native") && !cfg!(feature = "runtime")
+}
+
+/// All SIMD variants are compiled in and selected at runtime.
+///
+/// Only x86_64 dispatches at runtime, the other targets build the portable code
+/// and the SIMD variants enabled by the features or target features.
+fn dispatch_at_runtime() -> bool {
+    cfg!(feature = "runtime") && env::var("CARGO_CFG_TARGET_ARCH").as_deref() == Ok("x86_64")
+}
+
 fn has_aesni() -> bool {
-    cfg!(feature = "native")
+    detect_host()
         && CPUID
             .get_feature_info()
             .map_or(false, |features| features.has_aesni())
 }
 
 fn has_sse41() -> bool {
-    cfg!(feature = "native")
+    detect_host()
         && CPUID
             .get_feature_info()
             .map_or(false, |features| features.has_sse41())
 }
 
 fn has_sse42() -> bool {
-    cfg!(feature = "native")
+    detect_host()
         && CPUID
             .get_feature_info()
             .map_or(false, |features| features.has_sse42())
 }
 
 fn has_avx() -> bool {
-    cfg!(feature = "native")
+    detect_host()
         && CPUID
             .get_feature_info()
             .map_or(false, |features| features.has_avx())
 }
 
 fn has_avx2() -> bool {
-    cfg!(feature = "native")
+    detect_host()
         && CPUID
             .get_extended_feature_info()
             .map_or(false, |features| features.has_avx2())
 }
 
 fn support_aesni() -> bool {
-    cfg!(any(feature = "aes", target_feature = "aes")) || has_aesni()
+    cfg!(any(feature = "aes", target_feature = "aes")) || has_aesni() || dispatch_at_runtime()
 }
 
 fn support_sse41() -> bool {
-    cfg!(any(feature = "sse41", target_feature = "sse41")) || has_sse41()
+    cfg!(any(feature = "sse41", target_feature = "sse41")) || has_sse41() || dispatch_at_runtime()
 }
 
 fn support_sse42() -> bool {
-    cfg!(any(feature = "sse42", target_feature = "sse42")) || has_sse42()
+    cfg!(any(feature = "sse42", target_feature = "sse42")) || has_sse42() || dispatch_at_runtime()
 }
 
 fn support_avx() -> bool {
-    cfg!(any(feature = "avx", target_feature = "avx")) || has_avx()
+    cfg!(any(feature = "avx", target_feature = "avx")) || has_avx() || dispatch_at_runtime()
 }
 
 fn support_avx2() -> bool {
-    cfg!(any(feature = "avx2", target_feature = "avx2")) || has_avx2()
+    cfg!(any(feature = "avx2", target_feature = "avx2")) || has_avx2() || dispatch_at_runtime()
 }
 
 #[cfg(feature = "gen")]
@@ -83,11 +96,7 @@ fn generate_binding(out_file: &Path) {
         } else {
             &[][..]
         })
-        .clang_arg(if cfg!(feature = "native") {
-            "-march=native"
-        } else {
-            ""
-        })
+        .clang_arg(if detect_host() { "-march=native" } else { "" })
         .clang_arg(if support_sse42() { "-msse4.2" } else { "" })
         .clang_arg(if support_avx() { "-mavx" } else { "" })
         .clang_arg(if support_avx2() { "-mavx2" } else { "" })
@@ -138,14 +147,33 @@ fn build_fasthash() {
         .file("src/smhasher/Spooky.cpp")
         .file("src/xxHash/xxhash.c");
 
+    // `fasthash.cpp` forces the HighwayHash targets which `build_highway` compiles in.
+    if cfg!(any(target_arch = "x86", target_arch = "x86_64")) {
+        if support_sse41() {
+            build.define("FASTHASH_HIGHWAY_SSE41", None);
+        }
+
+        if support_avx2() {
+            build.define("FASTHASH_HIGHWAY_AVX2", None);
+        }
+    }
+
+    build.compile("fasthash");
+
+    // Only the CRC variants are built with SSE 4.2 enabled, so the rest of the
+    // library stays safe to run on CPUs without it.
     if support_sse42() {
-        build
+        cc::Build::new()
+            .cpp(true)
+            .flag("-std=c++11")
+            .flag("-Wno-implicit-fallthrough")
+            .flag("-Wno-unknown-attributes")
             .flag("-msse4.2")
+            .file("src/city_crc.cpp")
             .file("src/smhasher/metrohash64crc.cpp")
-            .file("src/smhasher/metrohash128crc.cpp");
+            .file("src/smhasher/metrohash128crc.cpp")
+            .compile("fasthash_sse42");
     }
-
-    build.static_flag(true).compile("fasthash");
 }
 
 fn build_t1() {
@@ -159,22 +187,35 @@ fn build_t1() {
     if support_aesni() {
         build
             .define("T1HA0_RUNTIME_SELECT", Some("1"))
-            .define("T1HA0_AESNI_AVAILABLE", Some("1"))
-            .flag("-maes")
-            .file("src/t1ha/src/t1ha0_ia32aes_noavx.c")
-            .file("src/t1ha/src/t1ha0_ia32aes_avx.c")
-            .file("src/t1ha/src/t1ha0_ia32aes_avx2.c");
-
-        if support_avx() {
-            build.flag("-mavx");
-        }
+            .define("T1HA0_AESNI_AVAILABLE", Some("1"));
+    }
 
-        if support_avx2() {
-            build.flag("-mavx2");
+    build.compile("t1ha");
+
+    // `t1ha0_resolve` picks one of the AES variants at runtime,
+    // each of them is built with its own instruction set.
+    if support_aesni() {
+        for &name in &["noavx", "avx", "avx2"] {
+            let mut build = cc::Build::new();
+
+            build
+                .define("T1HA0_RUNTIME_SELECT", Some("1"))
+                .define("T1HA0_AESNI_AVAILABLE", Some("1"))
+                .flag("-maes");
+
+            if name != "noavx" && support_avx() {
+                build.flag("-mavx");
+            }
+
+            if name == "avx2" && support_avx2() {
+                build.flag("-mavx2");
+            }
+
+            build
+                .file(format!("src/t1ha/src/t1ha0_ia32aes_{}.c", name))
+                .compile(&format!("t1ha_{}", name));
         }
     }
-
-    build.static_flag(true).compile("t1ha");
 }
 
 fn build_highway() {
@@ -190,21 +231,40 @@ fn build_highway() {
         .file("src/highwayhash/highwayhash/hh_portable.cc")
         .file("src/highwayhash/highwayhash/c_bindings.cc");
 
+    build.compile("highwayhash");
+
+    // The SIMD targets are dispatched by `instruction_sets.cc` at runtime,
+    // only their own translation units are built with the wider instruction set.
     if cfg!(any(target_arch = "x86", target_arch = "x86_64")) {
         if support_sse41() {
-            build
+            cc::Build::new()
+                .cpp(true)
+                .flag("-std=c++11")
                 .flag("-msse4.1")
-                .file("src/highwayhash/highwayhash/hh_sse41.cc");
+                .include("src/highwayhash")
+                .file("src/highwayhash/highwayhash/hh_sse41.cc")
+                .compile("highwayhash_sse41");
         }
 
         if support_avx2() {
-            build
+            cc::Build::new()
+                .cpp(true)
+                .flag("-std=c++11")
                 .flag("-mavx2")
-                .file("src/highwayhash/highwayhash/hh_avx2.cc");
+                .include("src/highwayhash")
+                .file("src/highwayhash/highwayhash/hh_avx2.cc")
+                .compile("highwayhash_avx2");
         }
     }
 
-    build.static_flag(true).compile("highwayhash");
+    // The portable C implementation provides the streaming (cat) API,
+    // rename its one-shot functions which clash with `c_bindings.cc`.
+    cc::Build::new()
+        .define("HighwayHash64", Some("HighwayHash64_C"))
+        .define("HighwayHash128", Some("HighwayHash128_C"))
+        .define("HighwayHash256", Some("HighwayHash256_C"))
+        .file("src/highwayhash/c/highwayhash.c")
+        .compile("highwayhash_c");
 }
 
 fn main() {
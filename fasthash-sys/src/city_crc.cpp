@@ -0,0 +1,12 @@
+// Build the CityHashCrc functions in their own translation unit with SSE 4.2 enabled.
+//
+// `City.cpp` also defines the portable CityHash functions, rename the copies
+// compiled here so the ones from the regular build are used everywhere else.
+#define CityHash32WithSeed CityHash32WithSeed_sse42
+#define CityHash64 CityHash64_sse42
+#define CityHash64WithSeed CityHash64WithSeed_sse42
+#define CityHash64WithSeeds CityHash64WithSeeds_sse42
+#define CityHash128 CityHash128_sse42
+#define CityHash128WithSeed CityHash128WithSeed_sse42
+
+#include "smhasher/City.cpp"
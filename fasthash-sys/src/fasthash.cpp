@@ -1,5 +1,7 @@
 #include "fasthash.hpp"
 
+#include <string.h>
+
 #include "highwayhash/highwayhash_target.h"
 #include "highwayhash/instruction_sets.h"
 
@@ -31,6 +33,14 @@ void *SpookyHasherNew() { return new SpookyHash(); }
 
 void SpookyHasherFree(void *h) { delete ((SpookyHash *)h); }
 
+void SpookyHasherCopy(void *dst, const void *src) { *((SpookyHash *)dst) = *((const SpookyHash *)src); }
+
+static_assert(sizeof(SpookyHash) == sizeof(SpookyHasherState), "SpookyHash layout mismatch");
+
+void SpookyHasherGetState(const void *h, SpookyHasherState *state) { memcpy(state, h, sizeof(SpookyHasherState)); }
+
+void SpookyHasherSetState(void *h, const SpookyHasherState *state) { memcpy(h, state, sizeof(SpookyHasherState)); }
+
 void SpookyHasherInit(
     void *h,
     uint64 seed1, // any 64-bit value will do, including 0
@@ -69,3 +79,34 @@ void HighwayHash256(const HHKey key, const char* bytes, const uint64_t size, HHR
     highwayhash::InstructionSets::Run<highwayhash::HighwayHash>(
         *reinterpret_cast<const HHKey*>(key), bytes, size, reinterpret_cast<HHResult256*>(hash));
 }
+
+template <typename Result>
+static bool HighwayHashTarget(uint32_t target, const uint64_t key[4], const char* bytes, uint64_t size, Result* hash) {
+    const highwayhash::HHKey& k = *reinterpret_cast<const highwayhash::HHKey*>(key);
+
+    switch (target) {
+    case HH_TARGET_Portable:
+        highwayhash::HighwayHash<HH_TARGET_Portable>()(k, bytes, size, hash);
+        return true;
+#ifdef FASTHASH_HIGHWAY_SSE41
+    case HH_TARGET_SSE41:
+        highwayhash::HighwayHash<HH_TARGET_SSE41>()(k, bytes, size, hash);
+        return true;
+#endif
+#ifdef FASTHASH_HIGHWAY_AVX2
+    case HH_TARGET_AVX2:
+        highwayhash::HighwayHash<HH_TARGET_AVX2>()(k, bytes, size, hash);
+        return true;
+#endif
+    default:
+        return false;
+    }
+}
+
+bool HighwayHash128_Target(uint32_t target, const uint64_t key[4], const char* bytes, uint64_t size, uint64_t hash[2]) {
+    return HighwayHashTarget(target, key, bytes, size, reinterpret_cast<highwayhash::HHResult128*>(hash));
+}
+
+bool HighwayHash256_Target(uint32_t target, const uint64_t key[4], const char* bytes, uint64_t size, uint64_t hash[4]) {
+    return HighwayHashTarget(target, key, bytes, size, reinterpret_cast<highwayhash::HHResult256*>(hash));
+}
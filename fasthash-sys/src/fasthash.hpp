@@ -29,6 +29,20 @@ void *SpookyHasherNew();
 
 void SpookyHasherFree(void *h);
 
+void SpookyHasherCopy(void *dst, const void *src);
+
+// The state of a `SpookyHash`, laid out like its private members.
+typedef struct {
+    uint64 data[24];  // unhashed data, for partial messages
+    uint64 state[12]; // internal state of the hash
+    size_t length;    // total length of the input so far
+    uint8 remainder;  // length of unhashed data stashed in data
+} SpookyHasherState;
+
+void SpookyHasherGetState(const void *h, SpookyHasherState *state);
+
+void SpookyHasherSetState(void *h, const SpookyHasherState *state);
+
 void SpookyHasherInit(
     void *h,
     uint64 seed1,  // any 64-bit value will do, including 0
@@ -49,3 +63,38 @@ uint64_t t1ha0_64(const void *data, size_t length, uint64_t seed);
 void HighwayHash128(const HHKey key, const char* bytes, const uint64_t size, HHResult128& hash);
 
 void HighwayHash256(const HHKey key, const char* bytes, const uint64_t size, HHResult256& hash);
+
+// Streaming (cat) API of the portable C implementation in `highwayhash/c/highwayhash.h`,
+// declared here since its one-shot functions clash with `c_bindings.h`.
+extern "C" {
+
+typedef struct {
+    uint64_t v0[4];
+    uint64_t v1[4];
+    uint64_t mul0[4];
+    uint64_t mul1[4];
+} HighwayHashState;
+
+typedef struct {
+    HighwayHashState state;
+    uint8_t packet[32];
+    int num;
+} HighwayHashCat;
+
+void HighwayHashCatStart(const uint64_t key[4], HighwayHashCat* state);
+
+void HighwayHashCatAppend(const uint8_t* bytes, size_t num, HighwayHashCat* state);
+
+uint64_t HighwayHashCatFinish64(const HighwayHashCat* state);
+
+void HighwayHashCatFinish128(const HighwayHashCat* state, uint64_t hash[2]);
+
+void HighwayHashCatFinish256(const HighwayHashCat* state, uint64_t hash[4]);
+
+// One-shot hashing with the implementation of a particular target (`HH_TARGET_*`),
+// returns false if the target wasn't built in.
+bool HighwayHash128_Target(uint32_t target, const uint64_t key[4], const char* bytes, uint64_t size, uint64_t hash[2]);
+
+bool HighwayHash256_Target(uint32_t target, const uint64_t key[4], const char* bytes, uint64_t size, uint64_t hash[4]);
+
+}
@@ -20,6 +20,7 @@ pub type pair__PCCFP = u8;
 pub type __uint8_t = ::std::os::raw::c_uchar;
 pub type __uint32_t = ::std::os::raw::c_uint;
 pub type __uint64_t = ::std::os::raw::c_ulong;
+pub type uint8 = u8;
 pub type uint32 = u32;
 pub type uint64 = u64;
 pub type uint128 = pair<uint64, uint64>;
@@ -502,6 +503,20 @@ extern "C" {
 extern "C" {
     pub fn t1ha1_be(data: *const ::std::os::raw::c_void, length: usize, seed: u64) -> u64;
 }
+extern "C" {
+    pub fn t1ha0_ia32aes_noavx(
+        data: *const ::std::os::raw::c_void,
+        length: usize,
+        seed: u64,
+    ) -> u64;
+}
+extern "C" {
+    pub fn t1ha0_ia32aes_avx(data: *const ::std::os::raw::c_void, length: usize, seed: u64) -> u64;
+}
+extern "C" {
+    pub fn t1ha0_ia32aes_avx2(data: *const ::std::os::raw::c_void, length: usize, seed: u64)
+        -> u64;
+}
 pub type t1ha0_function_t = ::std::option::Option<
     unsafe extern "C" fn(arg1: *const ::std::os::raw::c_void, arg2: usize, arg3: u64) -> u64,
 >;
@@ -1312,6 +1327,79 @@ extern "C" {
     #[link_name = "\u{1}_Z16SpookyHasherFreePv"]
     pub fn SpookyHasherFree(h: *mut ::std::os::raw::c_void);
 }
+extern "C" {
+    #[link_name = "\u{1}_Z16SpookyHasherCopyPvPKv"]
+    pub fn SpookyHasherCopy(dst: *mut ::std::os::raw::c_void, src: *const ::std::os::raw::c_void);
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct SpookyHasherState {
+    pub data: [uint64; 24usize],
+    pub state: [uint64; 12usize],
+    pub length: usize,
+    pub remainder: uint8,
+}
+#[test]
+fn bindgen_test_layout_SpookyHasherState() {
+    assert_eq!(
+        ::std::mem::size_of::<SpookyHasherState>(),
+        304usize,
+        concat!("Size of: ", stringify!(SpookyHasherState))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<SpookyHasherState>(),
+        8usize,
+        concat!("Alignment of ", stringify!(SpookyHasherState))
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<SpookyHasherState>())).data as *const _ as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(SpookyHasherState),
+            "::",
+            stringify!(data)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<SpookyHasherState>())).state as *const _ as usize },
+        192usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(SpookyHasherState),
+            "::",
+            stringify!(state)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<SpookyHasherState>())).length as *const _ as usize },
+        288usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(SpookyHasherState),
+            "::",
+            stringify!(length)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<SpookyHasherState>())).remainder as *const _ as usize },
+        296usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(SpookyHasherState),
+            "::",
+            stringify!(remainder)
+        )
+    );
+}
+extern "C" {
+    #[link_name = "\u{1}_Z20SpookyHasherGetStatePKvP17SpookyHasherState"]
+    pub fn SpookyHasherGetState(h: *const ::std::os::raw::c_void, state: *mut SpookyHasherState);
+}
+extern "C" {
+    #[link_name = "\u{1}_Z20SpookyHasherSetStatePvPK17SpookyHasherState"]
+    pub fn SpookyHasherSetState(h: *mut ::std::os::raw::c_void, state: *const SpookyHasherState);
+}
 extern "C" {
     #[link_name = "\u{1}_Z16SpookyHasherInitPvmm"]
     pub fn SpookyHasherInit(h: *mut ::std::os::raw::c_void, seed1: uint64, seed2: uint64);
@@ -1354,6 +1442,150 @@ extern "C" {
         hash: *mut HHResult256,
     );
 }
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct HighwayHashState {
+    pub v0: [u64; 4usize],
+    pub v1: [u64; 4usize],
+    pub mul0: [u64; 4usize],
+    pub mul1: [u64; 4usize],
+}
+#[test]
+fn bindgen_test_layout_HighwayHashState() {
+    assert_eq!(
+        ::std::mem::size_of::<HighwayHashState>(),
+        128usize,
+        concat!("Size of: ", stringify!(HighwayHashState))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<HighwayHashState>(),
+        8usize,
+        concat!("Alignment of ", stringify!(HighwayHashState))
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<HighwayHashState>())).v0 as *const _ as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(HighwayHashState),
+            "::",
+            stringify!(v0)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<HighwayHashState>())).v1 as *const _ as usize },
+        32usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(HighwayHashState),
+            "::",
+            stringify!(v1)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<HighwayHashState>())).mul0 as *const _ as usize },
+        64usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(HighwayHashState),
+            "::",
+            stringify!(mul0)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<HighwayHashState>())).mul1 as *const _ as usize },
+        96usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(HighwayHashState),
+            "::",
+            stringify!(mul1)
+        )
+    );
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct HighwayHashCat {
+    pub state: HighwayHashState,
+    pub packet: [u8; 32usize],
+    pub num: ::std::os::raw::c_int,
+}
+#[test]
+fn bindgen_test_layout_HighwayHashCat() {
+    assert_eq!(
+        ::std::mem::size_of::<HighwayHashCat>(),
+        168usize,
+        concat!("Size of: ", stringify!(HighwayHashCat))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<HighwayHashCat>(),
+        8usize,
+        concat!("Alignment of ", stringify!(HighwayHashCat))
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<HighwayHashCat>())).state as *const _ as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(HighwayHashCat),
+            "::",
+            stringify!(state)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<HighwayHashCat>())).packet as *const _ as usize },
+        128usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(HighwayHashCat),
+            "::",
+            stringify!(packet)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<HighwayHashCat>())).num as *const _ as usize },
+        160usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(HighwayHashCat),
+            "::",
+            stringify!(num)
+        )
+    );
+}
+extern "C" {
+    pub fn HighwayHashCatStart(key: *const u64, state: *mut HighwayHashCat);
+}
+extern "C" {
+    pub fn HighwayHashCatAppend(bytes: *const u8, num: usize, state: *mut HighwayHashCat);
+}
+extern "C" {
+    pub fn HighwayHashCatFinish64(state: *const HighwayHashCat) -> u64;
+}
+extern "C" {
+    pub fn HighwayHashCatFinish128(state: *const HighwayHashCat, hash: *mut u64);
+}
+extern "C" {
+    pub fn HighwayHashCatFinish256(state: *const HighwayHashCat, hash: *mut u64);
+}
+extern "C" {
+    pub fn HighwayHash128_Target(
+        target: u32,
+        key: *const u64,
+        bytes: *const ::std::os::raw::c_char,
+        size: u64,
+        hash: *mut u64,
+    ) -> bool;
+}
+extern "C" {
+    pub fn HighwayHash256_Target(
+        target: u32,
+        key: *const u64,
+        bytes: *const ::std::os::raw::c_char,
+        size: u64,
+        hash: *mut u64,
+    ) -> bool;
+}
 #[test]
 fn __bindgen_test_layout_pair_open0_uint64_uint64_close0_instantiation() {
     assert_eq!(
@@ -1,4 +1,17 @@
+use std::env;
+
 fn main() {
+    if cfg!(feature = "runtime") {
+        // every SIMD variant is compiled in and selected at runtime
+        if env::var("CARGO_CFG_TARGET_ARCH").as_deref() == Ok("x86_64") {
+            for feature in &["aes", "sse41", "sse42", "avx", "avx2"] {
+                println!(r#"cargo:rustc-cfg=feature="{}""#, feature);
+            }
+        }
+
+        return;
+    }
+
     let cpuid = raw_cpuid::CpuId::new();
 
     if cfg!(feature = "native") {
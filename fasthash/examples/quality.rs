@@ -0,0 +1,79 @@
+//! Runs the quality tests against the hash functions and prints the reports.
+//!
+//! ```sh
+//! $ cargo run --release --features quality --example quality -- [--quick] [NAME]...
+//! ```
+//!
+//! The names filter the hash functions, e.g. `city` or `xxh3::Hash128`.
+use std::env;
+use std::process;
+
+use fasthash::quality::{self, Config, Report};
+use fasthash::*;
+
+fn main() {
+    let mut config = Config::default();
+    let mut names = Vec::new();
+
+    for arg in env::args().skip(1) {
+        if arg == "--quick" {
+            config = Config::quick();
+        } else {
+            names.push(arg);
+        }
+    }
+
+    let mut passed = true;
+
+    macro_rules! run {
+        ($($hash:ty),*) => {
+            $(
+                let name = stringify!($hash);
+
+                if names.is_empty() || names.iter().any(|filter| name.contains(filter.as_str())) {
+                    let report: Report = quality::run::<$hash>(&config);
+
+                    println!("{}\n", report);
+
+                    passed &= report.passed();
+                }
+            )*
+        };
+    }
+
+    run!(
+        city::Hash32,
+        city::Hash64,
+        city::Hash128,
+        farm::Hash32,
+        farm::Hash64,
+        farm::Hash128,
+        highway::Hash64,
+        highway::Hash128,
+        lookup3::Hash32,
+        metro::Hash64_1,
+        metro::Hash128_1,
+        mum::Hash64,
+        murmur::Hash32,
+        murmur2::Hash32,
+        murmur2::Hash64_x64,
+        murmur3::Hash32,
+        murmur3::Hash128_x64,
+        sea::Hash64,
+        spooky::Hash32,
+        spooky::Hash64,
+        spooky::Hash128,
+        t1ha0::Hash64,
+        t1ha1::Hash64Le,
+        t1ha2::Hash64AtOnce,
+        t1ha2::Hash128AtOnce,
+        xx::Hash32,
+        xx::Hash64,
+        xxh3::Hash64,
+        xxh3::Hash128
+    );
+
+    if !passed {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,584 @@
+//! Bloom filters, space-efficient probabilistic sets.
+//!
+//! A Bloom filter answers whether a key may be in the set,
+//! it never gives a false negative but gives false positives at a tunable rate.
+//!
+//! - `BloomFilter`, the standard Bloom filter, each key sets `k` bits in the whole bit array.
+//! - `BlockedBloomFilter`, each key sets `k` bits in a single 512-bit cache line.
+//! - `RegisterBloomFilter`, each key sets `k` bits in a single 64-bit word.
+//!
+//! The blocked variants need only one cache miss or one memory access per key,
+//! at the cost of a slightly higher false positive rate for the same size.
+//!
+//! The `k` bit positions are derived with the Kirsch–Mitzenmacher double hashing
+//! `g(i) = h1 + i * h2` from one hash value of a `FastHash` function:
+//! a 128-bit hash is split into two 64-bit halves, a 64-bit hash is used as `h1`
+//! and mixed with the `SplitMix64` finalizer into `h2`.
+//! The blocked variants pick the block with `h1` and the bits in it with `g(i) = h2 + i * (h1 | 1)`,
+//! the odd step keeps the `k` bits of a key distinct in a power of two sized block.
+//!
+//! The filters with the same size and number of hashes can be merged,
+//! and serialized with a versioned binary format.
+//!
+//! # Example
+//!
+//! ```
+//! use fasthash::{bloom::BloomFilter, sea};
+//!
+//! let mut filter = BloomFilter::<sea::Hash64>::with_rate(1000, 0.01);
+//!
+//! filter.insert("hello");
+//!
+//! assert!(filter.contains("hello"));
+//! assert!(!filter.contains("world"));
+//!
+//! let bytes = filter.to_bytes();
+//! let filter = BloomFilter::<sea::Hash64>::from_bytes(&bytes).unwrap();
+//!
+//! assert!(filter.contains("hello"));
+//! ```
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::hasher::{mix64, FastHash};
+use crate::state::{self, Reader, Writer};
+
+/// The hash values that can be split into the two hashes of double hashing.
+pub trait DoubleHash: Copy {
+    /// Returns the two independent hashes `(h1, h2)`.
+    fn double_hash(self) -> (u64, u64);
+}
+
+impl DoubleHash for u64 {
+    #[inline(always)]
+    fn double_hash(self) -> (u64, u64) {
+        (self, mix64(self))
+    }
+}
+
+impl DoubleHash for u128 {
+    #[inline(always)]
+    fn double_hash(self) -> (u64, u64) {
+        (self as u64, (self >> 64) as u64)
+    }
+}
+
+/// The errors of merging or deserializing the filters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The filters have a different kind, size or number of hashes.
+    Incompatible,
+    /// The bytes are not a serialized filter of this kind.
+    InvalidFormat,
+    /// The serialized filter has an unsupported format version.
+    UnsupportedVersion(u8),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Incompatible => f.write_str("incompatible filters"),
+            Error::InvalidFormat => f.write_str("invalid filter format"),
+            Error::UnsupportedVersion(v) => write!(f, "unsupported filter format version {}", v),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+impl From<state::Error> for Error {
+    fn from(err: state::Error) -> Self {
+        match err {
+            state::Error::InvalidFormat => Error::InvalidFormat,
+            state::Error::UnsupportedVersion(v) => Error::UnsupportedVersion(v),
+        }
+    }
+}
+
+/// The magic number of the serialized filters.
+const MAGIC: &[u8; 4] = b"FHBF";
+/// The current version of the serialization format.
+pub const FORMAT_VERSION: u8 = 1;
+
+/// Maps `h` to `0..n` without a division.
+#[inline(always)]
+fn fastrange(h: u64, n: usize) -> usize {
+    ((u128::from(h) * n as u128) >> 64) as usize
+}
+
+/// Returns the optimal number of bits for `items` keys with false positive rate `fp_rate`.
+#[cfg(feature = "std")]
+fn optimal_num_bits(items: usize, fp_rate: f64) -> usize {
+    assert!(
+        fp_rate > 0.0 && fp_rate < 1.0,
+        "false positive rate must be in (0, 1)"
+    );
+
+    let ln2 = core::f64::consts::LN_2;
+
+    (-(items.max(1) as f64) * fp_rate.ln() / (ln2 * ln2)).ceil() as usize
+}
+
+/// Returns the optimal number of hashes for `num_bits` bits and `items` keys.
+#[cfg(feature = "std")]
+fn optimal_num_hashes(num_bits: usize, items: usize) -> u32 {
+    ((num_bits as f64 / items.max(1) as f64) * core::f64::consts::LN_2)
+        .round()
+        .max(1.0) as u32
+}
+
+macro_rules! impl_filter {
+    ($filter:ident, $kind:expr, $unit:expr) => {
+        impl<H> $filter<H>
+        where
+            H: FastHash,
+            H::Hash: DoubleHash,
+        {
+            /// Constructs a new filter with at least `num_bits` bits and `num_hashes` hashes per key.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `num_bits` or `num_hashes` is zero.
+            pub fn new(num_bits: usize, num_hashes: u32) -> Self {
+                assert!(num_bits > 0, "number of bits must be positive");
+                assert!(num_hashes > 0, "number of hashes must be positive");
+
+                let num_bits = ((num_bits - 1) / $unit + 1) * $unit;
+
+                $filter {
+                    bits: alloc::vec![0; num_bits / 64],
+                    num_hashes,
+                    phantom: PhantomData,
+                }
+            }
+
+            /// Constructs a new filter sized for `items` keys with the false positive rate `fp_rate`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `fp_rate` is not in `(0, 1)`.
+            #[cfg(feature = "std")]
+            pub fn with_rate(items: usize, fp_rate: f64) -> Self {
+                let num_bits = optimal_num_bits(items, fp_rate);
+
+                Self::new(num_bits, optimal_num_hashes(num_bits, items))
+            }
+
+            /// Returns the number of bits.
+            pub fn num_bits(&self) -> usize {
+                self.bits.len() * 64
+            }
+
+            /// Returns the number of hashes per key.
+            pub fn num_hashes(&self) -> u32 {
+                self.num_hashes
+            }
+
+            /// Returns `true` if no key was inserted.
+            pub fn is_empty(&self) -> bool {
+                self.bits.iter().all(|&w| w == 0)
+            }
+
+            /// Removes all the keys.
+            pub fn clear(&mut self) {
+                for w in self.bits.iter_mut() {
+                    *w = 0;
+                }
+            }
+
+            /// Inserts a key.
+            #[inline(always)]
+            pub fn insert<T: AsRef<[u8]>>(&mut self, key: T) {
+                let (h1, h2) = H::hash(key).double_hash();
+
+                self.insert_hash(h1, h2)
+            }
+
+            /// Returns `true` if the key may be in the set, `false` if it is definitely not.
+            #[inline(always)]
+            pub fn contains<T: AsRef<[u8]>>(&self, key: T) -> bool {
+                let (h1, h2) = H::hash(key).double_hash();
+
+                self.contains_hash(h1, h2)
+            }
+
+            /// Merges the keys of another filter, the result contains the keys of both.
+            pub fn union(&mut self, other: &Self) -> Result<(), Error> {
+                self.check_compatible(other)?;
+
+                for (w, o) in self.bits.iter_mut().zip(other.bits.iter()) {
+                    *w |= *o;
+                }
+
+                Ok(())
+            }
+
+            /// Intersects with another filter, the result contains the keys of both,
+            /// with a higher false positive rate than a filter built from the intersection.
+            pub fn intersect(&mut self, other: &Self) -> Result<(), Error> {
+                self.check_compatible(other)?;
+
+                for (w, o) in self.bits.iter_mut().zip(other.bits.iter()) {
+                    *w &= *o;
+                }
+
+                Ok(())
+            }
+
+            fn check_compatible(&self, other: &Self) -> Result<(), Error> {
+                if self.bits.len() == other.bits.len() && self.num_hashes == other.num_hashes {
+                    Ok(())
+                } else {
+                    Err(Error::Incompatible)
+                }
+            }
+
+            /// Serializes the filter.
+            ///
+            /// The format is the magic `FHBF`, the format version, the filter kind,
+            /// the number of hashes as `u32`, the number of bits as `u64`,
+            /// followed by the bits as `u64` words, all in little endian.
+            /// The hash function is not recorded, it must be the same to deserialize.
+            pub fn to_bytes(&self) -> Vec<u8> {
+                let mut w = Writer::header(MAGIC, FORMAT_VERSION, 1 + 4 + 8 + self.bits.len() * 8);
+
+                w.u8($kind)
+                    .u32(self.num_hashes)
+                    .u64(self.num_bits() as u64)
+                    .words(&self.bits);
+                w.into_bytes()
+            }
+
+            /// Deserializes a filter serialized by `to_bytes`.
+            pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+                let mut r = Reader::header(bytes, MAGIC, FORMAT_VERSION)?;
+                let kind = r.u8()?;
+                let num_hashes = r.u32()?;
+                let num_bits = r.u64()? as usize;
+
+                if kind != $kind
+                    || num_hashes == 0
+                    || num_bits == 0
+                    || num_bits % $unit != 0
+                    || r.rest().len() * 8 != num_bits
+                {
+                    return Err(Error::InvalidFormat);
+                }
+
+                let mut bits = alloc::vec![0; num_bits / 64];
+
+                r.words(&mut bits)?;
+                r.finish()?;
+
+                Ok($filter {
+                    bits,
+                    num_hashes,
+                    phantom: PhantomData,
+                })
+            }
+        }
+
+        impl<H> Clone for $filter<H> {
+            fn clone(&self) -> Self {
+                $filter {
+                    bits: self.bits.clone(),
+                    num_hashes: self.num_hashes,
+                    phantom: PhantomData,
+                }
+            }
+        }
+
+        impl<H> fmt::Debug for $filter<H> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.debug_struct(stringify!($filter))
+                    .field("num_bits", &(self.bits.len() * 64))
+                    .field("num_hashes", &self.num_hashes)
+                    .finish()
+            }
+        }
+    };
+}
+
+/// The standard Bloom filter.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(any(feature = "sys", feature = "pure-xxh3"))]
+/// # {
+/// use fasthash::{bloom::BloomFilter, xxh3};
+///
+/// let mut a = BloomFilter::<xxh3::Hash128>::new(1024, 7);
+/// let mut b = a.clone();
+///
+/// a.insert("hello");
+/// b.insert("world");
+/// a.union(&b).unwrap();
+///
+/// assert!(a.contains("hello"));
+/// assert!(a.contains("world"));
+/// # }
+/// ```
+pub struct BloomFilter<H> {
+    bits: Vec<u64>,
+    num_hashes: u32,
+    phantom: PhantomData<H>,
+}
+
+impl_filter!(BloomFilter, 0, 64);
+
+impl<H> BloomFilter<H>
+where
+    H: FastHash,
+    H::Hash: DoubleHash,
+{
+    /// Inserts a key by its double hashes.
+    #[inline(always)]
+    pub fn insert_hash(&mut self, h1: u64, h2: u64) {
+        let m = self.num_bits() as u64;
+
+        for i in 0..u64::from(self.num_hashes) {
+            let bit = h1.wrapping_add(i.wrapping_mul(h2)) % m;
+
+            self.bits[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+    }
+
+    /// Checks a key by its double hashes.
+    #[inline(always)]
+    pub fn contains_hash(&self, h1: u64, h2: u64) -> bool {
+        let m = self.num_bits() as u64;
+
+        (0..u64::from(self.num_hashes)).all(|i| {
+            let bit = h1.wrapping_add(i.wrapping_mul(h2)) % m;
+
+            self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0
+        })
+    }
+}
+
+/// The number of `u64` words in a cache line.
+const BLOCK_WORDS: usize = 8;
+
+/// The cache-line-blocked Bloom filter, all the bits of a key are in one 512-bit block.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(any(feature = "sys", feature = "pure-xxh3"))]
+/// # {
+/// use fasthash::{bloom::BlockedBloomFilter, xxh3};
+///
+/// let mut filter = BlockedBloomFilter::<xxh3::Hash64>::with_rate(1000, 0.01);
+///
+/// filter.insert("hello");
+///
+/// assert!(filter.contains("hello"));
+/// assert!(!filter.contains("world"));
+/// # }
+/// ```
+pub struct BlockedBloomFilter<H> {
+    bits: Vec<u64>,
+    num_hashes: u32,
+    phantom: PhantomData<H>,
+}
+
+impl_filter!(BlockedBloomFilter, 1, BLOCK_WORDS * 64);
+
+impl<H> BlockedBloomFilter<H>
+where
+    H: FastHash,
+    H::Hash: DoubleHash,
+{
+    /// Inserts a key by its double hashes.
+    #[inline(always)]
+    pub fn insert_hash(&mut self, h1: u64, h2: u64) {
+        let block = fastrange(h1, self.bits.len() / BLOCK_WORDS) * BLOCK_WORDS;
+        let block = &mut self.bits[block..block + BLOCK_WORDS];
+
+        let step = h1 | 1;
+
+        for i in 0..u64::from(self.num_hashes) {
+            let bit = h2.wrapping_add(i.wrapping_mul(step)) % (BLOCK_WORDS as u64 * 64);
+
+            block[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+    }
+
+    /// Checks a key by its double hashes.
+    #[inline(always)]
+    pub fn contains_hash(&self, h1: u64, h2: u64) -> bool {
+        let block = fastrange(h1, self.bits.len() / BLOCK_WORDS) * BLOCK_WORDS;
+        let block = &self.bits[block..block + BLOCK_WORDS];
+
+        let step = h1 | 1;
+
+        (0..u64::from(self.num_hashes)).all(|i| {
+            let bit = h2.wrapping_add(i.wrapping_mul(step)) % (BLOCK_WORDS as u64 * 64);
+
+            block[(bit / 64) as usize] & (1 << (bit % 64)) != 0
+        })
+    }
+}
+
+/// The register-blocked Bloom filter, all the bits of a key are in one 64-bit word.
+///
+/// It checks a key with a single memory access and a mask,
+/// a small number of hashes like 4 to 8 works best.
+///
+/// # Example
+///
+/// ```
+/// use fasthash::{bloom::RegisterBloomFilter, sea};
+///
+/// let mut filter = RegisterBloomFilter::<sea::Hash64>::new(16 * 1024, 6);
+///
+/// filter.insert("hello");
+///
+/// assert!(filter.contains("hello"));
+/// assert!(!filter.contains("world"));
+/// ```
+pub struct RegisterBloomFilter<H> {
+    bits: Vec<u64>,
+    num_hashes: u32,
+    phantom: PhantomData<H>,
+}
+
+impl_filter!(RegisterBloomFilter, 2, 64);
+
+impl<H> RegisterBloomFilter<H>
+where
+    H: FastHash,
+    H::Hash: DoubleHash,
+{
+    #[inline(always)]
+    fn mask(&self, h1: u64, h2: u64) -> u64 {
+        let step = h1 | 1;
+
+        (0..u64::from(self.num_hashes)).fold(0, |mask, i| {
+            mask | 1 << (h2.wrapping_add(i.wrapping_mul(step)) % 64)
+        })
+    }
+
+    /// Inserts a key by its double hashes.
+    #[inline(always)]
+    pub fn insert_hash(&mut self, h1: u64, h2: u64) {
+        let mask = self.mask(h1, h2);
+        let word = fastrange(h1, self.bits.len());
+
+        self.bits[word] |= mask;
+    }
+
+    /// Checks a key by its double hashes.
+    #[inline(always)]
+    pub fn contains_hash(&self, h1: u64, h2: u64) -> bool {
+        let mask = self.mask(h1, h2);
+
+        self.bits[fastrange(h1, self.bits.len())] & mask == mask
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::sea;
+    use crate::state::check_format;
+
+    fn key(i: u32) -> [u8; 4] {
+        i.to_le_bytes()
+    }
+
+    macro_rules! test_filter {
+        ($filter:ident, $max_fpr:expr) => {{
+            let mut filter = $filter::<sea::Hash64>::with_rate(10_000, 0.01);
+
+            assert!(filter.is_empty());
+
+            for i in 0..10_000 {
+                filter.insert(key(i));
+            }
+
+            assert!((0..10_000).all(|i| filter.contains(key(i))));
+
+            let fp = (10_000..110_000)
+                .filter(|&i| filter.contains(key(i)))
+                .count();
+
+            assert!(fp < 100_000 * $max_fpr / 1000, "fp = {}", fp);
+
+            let bytes = filter.to_bytes();
+            let decoded = $filter::<sea::Hash64>::from_bytes(&bytes).unwrap();
+
+            assert_eq!(decoded.to_bytes(), bytes);
+            assert!((0..10_000).all(|i| decoded.contains(key(i))));
+
+            check_format(&bytes, $filter::<sea::Hash64>::from_bytes);
+
+            let mut a = $filter::<sea::Hash64>::new(filter.num_bits(), filter.num_hashes());
+            let mut b = a.clone();
+
+            for i in 0..1000 {
+                a.insert(key(i));
+                b.insert(key(i + 500));
+            }
+
+            let mut union = a.clone();
+            union.union(&b).unwrap();
+            assert!((0..1500).all(|i| union.contains(key(i))));
+
+            let mut intersection = a.clone();
+            intersection.intersect(&b).unwrap();
+            assert!((500..1000).all(|i| intersection.contains(key(i))));
+
+            let other = $filter::<sea::Hash64>::new(filter.num_bits() * 2, filter.num_hashes());
+            assert_eq!(a.union(&other).unwrap_err(), Error::Incompatible);
+
+            a.clear();
+            assert!(a.is_empty());
+        }};
+    }
+
+    #[test]
+    fn test_bloom_filter() {
+        test_filter!(BloomFilter, 12);
+    }
+
+    #[test]
+    fn test_blocked_bloom_filter() {
+        test_filter!(BlockedBloomFilter, 15);
+    }
+
+    #[test]
+    fn test_register_bloom_filter() {
+        test_filter!(RegisterBloomFilter, 40);
+    }
+
+    #[test]
+    fn test_blocked_probes() {
+        // the probes of a key never collide, even if `h1` is even
+        for &(h1, h2) in &[(0, 0), (2, 5), (64, 1), (1 << 40, 7), (u64::MAX, 3)] {
+            let mut filter = BlockedBloomFilter::<sea::Hash64>::new(4096, 8);
+            filter.insert_hash(h1, h2);
+            assert_eq!(filter.bits.iter().map(|w| w.count_ones()).sum::<u32>(), 8);
+
+            let mut filter = RegisterBloomFilter::<sea::Hash64>::new(4096, 8);
+            filter.insert_hash(h1, h2);
+            assert_eq!(filter.bits.iter().map(|w| w.count_ones()).sum::<u32>(), 8);
+        }
+    }
+
+    #[cfg(any(feature = "sys", feature = "pure-xxh3"))]
+    #[test]
+    fn test_double_hash() {
+        let mut filter = BloomFilter::<crate::xxh3::Hash128>::new(4096, 5);
+
+        filter.insert("hello");
+
+        let (h1, h2) = crate::xxh3::Hash128::hash("hello").double_hash();
+
+        assert!(filter.contains_hash(h1, h2));
+        assert_eq!(filter.bits.iter().map(|w| w.count_ones()).sum::<u32>(), 5);
+    }
+}
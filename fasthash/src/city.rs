@@ -120,11 +120,28 @@
 //! assert_eq!(h, hash(&"hello world"));
 //! ```
 //!
-use std::mem;
+//! By default it is backed by the vendored C++ implementation,
+//! the `pure-city` feature switches to a pure Rust port which gives bit for bit the same results,
+//! for targets like WebAssembly or embedded devices without a C++ toolchain.
+use crate::hasher::FastHash;
 
-use crate::ffi;
+// `runtime` builds also use the port when the CPU lacks the CRC instruction
+#[cfg(any(
+    feature = "pure-city",
+    all(feature = "runtime", any(target_arch = "x86", target_arch = "x86_64"))
+))]
+#[cfg_attr(not(feature = "pure-city"), allow(dead_code))]
+mod pure;
+#[cfg(all(feature = "sys", any(test, not(feature = "pure-city"))))]
+mod sys;
 
-use crate::hasher::FastHash;
+cfg_if! {
+    if #[cfg(feature = "pure-city")] {
+        use self::pure as imp;
+    } else {
+        use self::sys as imp;
+    }
+}
 
 /// `CityHash` 32-bit hash functions
 ///
@@ -146,13 +163,7 @@ impl FastHash for Hash32 {
 
     #[inline(always)]
     fn hash_with_seed<T: AsRef<[u8]>>(bytes: T, seed: u32) -> u32 {
-        unsafe {
-            ffi::CityHash32WithSeed(
-                bytes.as_ref().as_ptr() as *const i8,
-                bytes.as_ref().len(),
-                seed,
-            )
-        }
+        imp::hash32_with_seed(bytes.as_ref(), seed)
     }
 }
 
@@ -172,7 +183,7 @@ trivial_hasher! {
     /// h.write(b"world");
     /// assert_eq!(h.finish(), 4037657980);
     /// ```
-    Hasher32(Hash32) -> u32
+    Hasher32(Hash32) -> u32, "fasthash::city::Hasher32"
 }
 
 /// `CityHash` 64-bit hash functions
@@ -193,22 +204,48 @@ trivial_hasher! {
 /// );
 /// assert_eq!(Hash64::hash(b"helloworld"), 16622738483577116029);
 /// ```
+///
+/// The integer keys are hashed in Rust without calling into C,
+/// with the same result as hashing their little endian bytes.
+///
+/// ```
+/// use fasthash::{city::Hash64, FastHash};
+///
+/// assert_eq!(Hash64::hash_u64(123), Hash64::hash(123_u64.to_le_bytes()));
+/// assert_eq!(
+///     Hash64::hash_u32_with_seed(123, 456),
+///     Hash64::hash_with_seed(123_u32.to_le_bytes(), 456)
+/// );
+/// ```
 #[derive(Clone)]
 pub struct Hash64;
 
+const K2: u64 = 0x9ae1_6a3b_2f90_404f;
+
+/// `HashLen16` from CityHash, the 128-bit to 64-bit mixer.
+#[inline(always)]
+fn hash_len16(u: u64, v: u64) -> u64 {
+    const MUL: u64 = 0x9ddf_ea08_eb38_2d69;
+
+    let mut a = (u ^ v).wrapping_mul(MUL);
+    a ^= a >> 47;
+    let mut b = (v ^ a).wrapping_mul(MUL);
+    b ^= b >> 47;
+    b.wrapping_mul(MUL)
+}
+
+/// `HashLen0to16` from CityHash for 4 to 8 bytes, `lo` and `hi` are the first and last 4 bytes.
+#[inline(always)]
+fn hash_len4to8(len: u64, lo: u32, hi: u32) -> u64 {
+    hash_len16(len + (u64::from(lo) << 3), u64::from(hi))
+}
+
 impl Hash64 {
     /// Hash functions for a byte array.
     /// For convenience, seeds are also hashed into the result.
     #[inline(always)]
     pub fn hash_with_seeds<T: AsRef<[u8]>>(bytes: T, seed0: u64, seed1: u64) -> u64 {
-        unsafe {
-            ffi::CityHash64WithSeeds(
-                bytes.as_ref().as_ptr() as *const i8,
-                bytes.as_ref().len(),
-                seed0,
-                seed1,
-            )
-        }
+        imp::hash64_with_seeds(bytes.as_ref(), seed0, seed1)
     }
 }
 
@@ -218,20 +255,34 @@ impl FastHash for Hash64 {
 
     #[inline(always)]
     fn hash<T: AsRef<[u8]>>(bytes: T) -> u64 {
-        unsafe { ffi::CityHash64(bytes.as_ref().as_ptr() as *const i8, bytes.as_ref().len()) }
+        imp::hash64(bytes.as_ref())
     }
 
     /// Hash functions for a byte array.
     /// For convenience, a seed is also hashed into the result.
     #[inline(always)]
     fn hash_with_seed<T: AsRef<[u8]>>(bytes: T, seed: u64) -> u64 {
-        unsafe {
-            ffi::CityHash64WithSeed(
-                bytes.as_ref().as_ptr() as *const i8,
-                bytes.as_ref().len(),
-                seed,
-            )
-        }
+        imp::hash64_with_seed(bytes.as_ref(), seed)
+    }
+
+    #[inline(always)]
+    fn hash_u32(n: u32) -> u64 {
+        hash_len4to8(4, n, n)
+    }
+
+    #[inline(always)]
+    fn hash_u32_with_seed(n: u32, seed: u64) -> u64 {
+        hash_len16(Self::hash_u32(n).wrapping_sub(K2), seed)
+    }
+
+    #[inline(always)]
+    fn hash_u64(n: u64) -> u64 {
+        hash_len4to8(8, n as u32, (n >> 32) as u32)
+    }
+
+    #[inline(always)]
+    fn hash_u64_with_seed(n: u64, seed: u64) -> u64 {
+        hash_len16(Self::hash_u64(n).wrapping_sub(K2), seed)
     }
 }
 
@@ -251,7 +302,7 @@ trivial_hasher! {
     /// h.write(b"world");
     /// assert_eq!(h.finish(), 16622738483577116029);
     /// ```
-    Hasher64(Hash64) -> u64
+    Hasher64(Hash64) -> u64, "fasthash::city::Hasher64"
 }
 
 /// `CityHash` 128-bit hash functions
@@ -283,23 +334,12 @@ impl FastHash for Hash128 {
 
     #[inline(always)]
     fn hash<T: AsRef<[u8]>>(bytes: T) -> u128 {
-        unsafe {
-            mem::transmute(ffi::CityHash128(
-                bytes.as_ref().as_ptr() as *const i8,
-                bytes.as_ref().len(),
-            ))
-        }
+        imp::hash128(bytes.as_ref())
     }
 
     #[inline(always)]
     fn hash_with_seed<T: AsRef<[u8]>>(bytes: T, seed: u128) -> u128 {
-        unsafe {
-            mem::transmute(ffi::CityHash128WithSeed(
-                bytes.as_ref().as_ptr() as *const i8,
-                bytes.as_ref().len(),
-                mem::transmute(seed),
-            ))
-        }
+        imp::hash128_with_seed(bytes.as_ref(), seed)
     }
 }
 
@@ -319,14 +359,16 @@ trivial_hasher! {
     /// h.write(b"world");
     /// assert_eq!(h.finish_ext(), 137438709495761624905137796394169174828);
     /// ```
-    Hasher128(Hash128) -> u128
+    Hasher128(Hash128) -> u128, "fasthash::city::Hasher128"
 }
 
 /// `CityHash` hash functions using HW CRC instruction.
-#[cfg(any(feature = "sse42", target_feature = "sse4.2"))]
+///
+/// If the CPU does not support SSE 4.2, the `runtime` feature
+/// and the `pure-city` port fall back to a software CRC.
+#[cfg(any(feature = "pure-city", feature = "sse42", target_feature = "sse4.2"))]
 pub mod crc {
-    use std::mem;
-
+    use super::imp;
     use crate::FastHash;
 
     /// `CityHash` 128-bit hash functions using HW CRC instruction.
@@ -359,23 +401,12 @@ pub mod crc {
 
         #[inline(always)]
         fn hash<T: AsRef<[u8]>>(bytes: T) -> u128 {
-            unsafe {
-                mem::transmute(ffi::CityHashCrc128(
-                    bytes.as_ref().as_ptr() as *const i8,
-                    bytes.as_ref().len(),
-                ))
-            }
+            imp::crc128(bytes.as_ref())
         }
 
         #[inline(always)]
         fn hash_with_seed<T: AsRef<[u8]>>(bytes: T, seed: u128) -> u128 {
-            unsafe {
-                mem::transmute(ffi::CityHashCrc128WithSeed(
-                    bytes.as_ref().as_ptr() as *const i8,
-                    bytes.as_ref().len(),
-                    mem::transmute(seed),
-                ))
-            }
+            imp::crc128_with_seed(bytes.as_ref(), seed)
         }
     }
 
@@ -395,7 +426,7 @@ pub mod crc {
         /// h.write(b"world");
         /// assert_eq!(h.finish_ext(), 137438709495761624905137796394169174828);
         /// ```
-        Hasher128(Hash128) -> u128
+        Hasher128(Hash128) -> u128, "fasthash::city::crc::Hasher128"
     }
 }
 
@@ -436,7 +467,7 @@ pub fn hash64_with_seeds<T: AsRef<[u8]>>(v: T, seed0: u64, seed1: u64) -> u64 {
 }
 
 cfg_if! {
-    if #[cfg(any(feature = "sse42", target_feature = "sse4.2"))] {
+    if #[cfg(all(not(feature = "runtime"), any(feature = "sse42", target_feature = "sse4.2")))] {
         /// `CityHash` 128-bit hash function for a byte array using HW CRC instruction.
         ///
         /// That require SSE4.2 instructions to be available.
@@ -469,3 +500,136 @@ cfg_if! {
         }
     }
 }
+
+/// The pure Rust port against the C++ implementation.
+#[cfg(all(test, feature = "sys", feature = "pure-city"))]
+mod tests {
+    use super::{pure, sys};
+
+    const SEEDS: [u64; 3] = [0, 123, 0x9E37_79B1_85EB_CA87];
+
+    fn sample(len: usize) -> Vec<u8> {
+        let mut x = 0x9E37_79B1_u64;
+
+        (0..len)
+            .map(|_| {
+                x = x.wrapping_mul(0x9E37_79B1_85EB_CA87);
+                (x >> 56) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_hash32() {
+        let data = sample(1024);
+
+        for len in 0..=data.len() {
+            let v = &data[..len];
+
+            for &seed in &SEEDS {
+                assert_eq!(
+                    pure::hash32_with_seed(v, seed as u32),
+                    sys::hash32_with_seed(v, seed as u32),
+                    "hash32, len={}",
+                    len
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_hash64() {
+        let data = sample(1024);
+
+        for len in 0..=data.len() {
+            let v = &data[..len];
+
+            assert_eq!(pure::hash64(v), sys::hash64(v), "hash64, len={}", len);
+
+            for &seed in &SEEDS {
+                assert_eq!(
+                    pure::hash64_with_seed(v, seed),
+                    sys::hash64_with_seed(v, seed),
+                    "hash64_with_seed, len={}",
+                    len
+                );
+                assert_eq!(
+                    pure::hash64_with_seeds(v, seed, !seed),
+                    sys::hash64_with_seeds(v, seed, !seed),
+                    "hash64_with_seeds, len={}",
+                    len
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_hash128() {
+        let data = sample(1024);
+
+        for len in 0..=data.len() {
+            let v = &data[..len];
+
+            assert_eq!(pure::hash128(v), sys::hash128(v), "hash128, len={}", len);
+
+            for &seed in &SEEDS {
+                let seed = u128::from(seed) << 64 | u128::from(!seed);
+
+                assert_eq!(
+                    pure::hash128_with_seed(v, seed),
+                    sys::hash128_with_seed(v, seed),
+                    "hash128_with_seed, len={}",
+                    len
+                );
+            }
+        }
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn test_crc32c() {
+        use core::arch::x86_64::_mm_crc32_u64;
+
+        use crate::hasher::crc32c_u64;
+
+        if is_x86_feature_detected!("sse4.2") {
+            let data = sample(4096);
+
+            for (i, chunk) in data.chunks_exact(16).enumerate() {
+                let crc = u64::from_le_bytes([
+                    chunk[0], chunk[1], chunk[2], chunk[3], chunk[4], chunk[5], chunk[6], chunk[7],
+                ]) >> (i % 64);
+                let v = u64::from_le_bytes([
+                    chunk[8], chunk[9], chunk[10], chunk[11], chunk[12], chunk[13], chunk[14],
+                    chunk[15],
+                ]);
+
+                assert_eq!(crc32c_u64(crc, v), unsafe { _mm_crc32_u64(crc, v) });
+            }
+        }
+    }
+
+    #[cfg(any(feature = "sse42", target_feature = "sse4.2"))]
+    #[test]
+    fn test_crc128() {
+        let data = sample(4096);
+
+        // the CRC variants fall back to `CityHash128` up to 900 bytes
+        for len in (0..=data.len()).filter(|len| *len <= 64 || *len >= 880) {
+            let v = &data[..len];
+
+            assert_eq!(pure::crc128(v), sys::crc128(v), "crc128, len={}", len);
+
+            for &seed in &SEEDS {
+                let seed = u128::from(seed) << 64 | u128::from(!seed);
+
+                assert_eq!(
+                    pure::crc128_with_seed(v, seed),
+                    sys::crc128_with_seed(v, seed),
+                    "crc128_with_seed, len={}",
+                    len
+                );
+            }
+        }
+    }
+}
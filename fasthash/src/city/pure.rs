@@ -0,0 +1,699 @@
+//! CityHash implemented in Rust, bit for bit compatible with the vendored C++ implementation.
+//!
+//! The C++ implementation adds the bytes of a key of up to 4 bytes to `CityHash32` as `char`,
+//! which is signed on x86 and unsigned on ARM, the port always follows x86.
+use core::convert::TryInto;
+use core::mem;
+
+use crate::hasher::crc32c_u64;
+
+use super::{hash_len16, hash_len4to8, K2};
+
+const K0: u64 = 0xc3a5_c85c_97cb_3127;
+const K1: u64 = 0xb492_b66f_be98_f273;
+const K3: u64 = 0xc949_d7c7_509e_6557;
+
+const C1: u32 = 0xcc9e_2d51;
+const C2: u32 = 0x1b87_3593;
+
+#[inline(always)]
+fn fetch32(s: &[u8], i: usize) -> u32 {
+    u32::from_le_bytes(s[i..i + 4].try_into().unwrap())
+}
+
+#[inline(always)]
+fn fetch64(s: &[u8], i: usize) -> u64 {
+    u64::from_le_bytes(s[i..i + 8].try_into().unwrap())
+}
+
+/// The 32-bit finalizer of Murmur3.
+#[inline(always)]
+fn fmix(mut h: u32) -> u32 {
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2_ae35);
+    h ^ (h >> 16)
+}
+
+#[inline(always)]
+fn premix(a: u32) -> u32 {
+    a.wrapping_mul(C1).rotate_right(17).wrapping_mul(C2)
+}
+
+#[inline(always)]
+fn mix(h: u32, a: u32) -> u32 {
+    (h ^ a)
+        .rotate_right(19)
+        .wrapping_mul(5)
+        .wrapping_add(0xe654_6b64)
+}
+
+/// Combines two 32-bit values, from Murmur3.
+#[inline(always)]
+fn mur(a: u32, h: u32) -> u32 {
+    mix(h, premix(a))
+}
+
+#[inline(always)]
+fn shift_mix(v: u64) -> u64 {
+    v ^ (v >> 47)
+}
+
+fn hash32_len0to4(s: &[u8], seed: u32) -> u32 {
+    let mut b = seed;
+    let mut c = 9_u32;
+
+    for &v in s {
+        b = b.wrapping_mul(C1).wrapping_add(v as i8 as u32);
+        c ^= b;
+    }
+
+    fmix(mur(b, mur(s.len() as u32, c)))
+}
+
+fn hash32_len5to12(s: &[u8], seed: u32) -> u32 {
+    let len = s.len();
+    let a = (len as u32).wrapping_add(seed).wrapping_add(fetch32(s, 0));
+    let b = (len as u32 * 5).wrapping_add(fetch32(s, len - 4));
+    let c = 9_u32.wrapping_add(fetch32(s, (len >> 1) & 4));
+    let d = len as u32 * 5;
+
+    fmix(mur(c, mur(b, mur(a, d))))
+}
+
+fn hash32_len13to24(s: &[u8], seed: u32) -> u32 {
+    let len = s.len();
+    let a = fetch32(s, (len >> 1) - 4);
+    let b = fetch32(s, 4);
+    let c = fetch32(s, len - 8);
+    let d = fetch32(s, len >> 1);
+    let e = fetch32(s, 0);
+    let f = fetch32(s, len - 4);
+    let h = seed.wrapping_add(len as u32);
+
+    fmix(mur(f, mur(e, mur(d, mur(c, mur(b, mur(a, h)))))))
+}
+
+pub fn hash32_with_seed(s: &[u8], seed: u32) -> u32 {
+    let len = s.len();
+
+    if len <= 4 {
+        return hash32_len0to4(s, seed);
+    } else if len <= 12 {
+        return hash32_len5to12(s, seed);
+    } else if len <= 24 {
+        return hash32_len13to24(s, seed);
+    }
+
+    let mut h = (len as u32).wrapping_add(seed);
+    let mut g = C1.wrapping_mul(len as u32);
+    let mut f = g;
+
+    h = mix(h, premix(fetch32(s, len - 4)));
+    h = mix(h, premix(fetch32(s, len - 16)));
+    g = mix(g, premix(fetch32(s, len - 8)));
+    g = mix(g, premix(fetch32(s, len - 12)));
+    f = f
+        .wrapping_add(premix(fetch32(s, len - 20)))
+        .rotate_right(19)
+        .wrapping_mul(5)
+        .wrapping_add(0xe654_6b64);
+
+    for chunk in s.chunks_exact(20).take((len - 1) / 20) {
+        let a0 = premix(fetch32(chunk, 0));
+        let a1 = fetch32(chunk, 4);
+        let a2 = premix(fetch32(chunk, 8));
+        let a3 = premix(fetch32(chunk, 12));
+        let a4 = fetch32(chunk, 16);
+
+        h = (h ^ a0)
+            .rotate_right(18)
+            .wrapping_mul(5)
+            .wrapping_add(0xe654_6b64);
+        f = f.wrapping_add(a1).rotate_right(19).wrapping_mul(C1);
+        g = g
+            .wrapping_add(a2)
+            .rotate_right(18)
+            .wrapping_mul(5)
+            .wrapping_add(0xe654_6b64);
+        h = mix(h, a3.wrapping_add(a1));
+        g = (g ^ a4).swap_bytes().wrapping_mul(5);
+        h = h.wrapping_add(a4.wrapping_mul(5)).swap_bytes();
+        f = f.wrapping_add(a0);
+
+        // PERMUTE3(f, h, g)
+        let (f0, h0, g0) = (f, h, g);
+
+        f = g0;
+        h = f0;
+        g = h0;
+    }
+
+    g = g.rotate_right(11).wrapping_mul(C1);
+    g = g.rotate_right(17).wrapping_mul(C1);
+    f = f.rotate_right(11).wrapping_mul(C1);
+    f = f.rotate_right(17).wrapping_mul(C1);
+    h = h
+        .wrapping_add(g)
+        .rotate_right(19)
+        .wrapping_mul(5)
+        .wrapping_add(0xe654_6b64);
+    h = h.rotate_right(17).wrapping_mul(C1);
+    h = h
+        .wrapping_add(f)
+        .rotate_right(19)
+        .wrapping_mul(5)
+        .wrapping_add(0xe654_6b64);
+    h.rotate_right(17).wrapping_mul(C1)
+}
+
+fn hash_len0to16(s: &[u8]) -> u64 {
+    let len = s.len();
+
+    if len > 8 {
+        let a = fetch64(s, 0);
+        let b = fetch64(s, len - 8);
+
+        hash_len16(a, b.wrapping_add(len as u64).rotate_right(len as u32)) ^ b
+    } else if len >= 4 {
+        hash_len4to8(len as u64, fetch32(s, 0), fetch32(s, len - 4))
+    } else if len > 0 {
+        let y = u32::from(s[0]) + (u32::from(s[len >> 1]) << 8);
+        let z = len as u32 + (u32::from(s[len - 1]) << 2);
+
+        shift_mix(u64::from(y).wrapping_mul(K2) ^ u64::from(z).wrapping_mul(K3)).wrapping_mul(K2)
+    } else {
+        K2
+    }
+}
+
+fn hash_len17to32(s: &[u8]) -> u64 {
+    let len = s.len();
+    let a = fetch64(s, 0).wrapping_mul(K1);
+    let b = fetch64(s, 8);
+    let c = fetch64(s, len - 8).wrapping_mul(K2);
+    let d = fetch64(s, len - 16).wrapping_mul(K0);
+
+    hash_len16(
+        a.wrapping_sub(b)
+            .rotate_right(43)
+            .wrapping_add(c.rotate_right(30))
+            .wrapping_add(d),
+        a.wrapping_add((b ^ K3).rotate_right(20))
+            .wrapping_sub(c)
+            .wrapping_add(len as u64),
+    )
+}
+
+fn hash_len33to64(s: &[u8]) -> u64 {
+    let len = s.len();
+    let mut z = fetch64(s, 24);
+    let mut a = fetch64(s, 0).wrapping_add(
+        (len as u64)
+            .wrapping_add(fetch64(s, len - 16))
+            .wrapping_mul(K0),
+    );
+    let mut b = a.wrapping_add(z).rotate_right(52);
+    let mut c = a.rotate_right(37);
+    a = a.wrapping_add(fetch64(s, 8));
+    c = c.wrapping_add(a.rotate_right(7));
+    a = a.wrapping_add(fetch64(s, 16));
+    let vf = a.wrapping_add(z);
+    let vs = b.wrapping_add(a.rotate_right(31)).wrapping_add(c);
+    a = fetch64(s, 16).wrapping_add(fetch64(s, len - 32));
+    z = fetch64(s, len - 8);
+    b = a.wrapping_add(z).rotate_right(52);
+    c = a.rotate_right(37);
+    a = a.wrapping_add(fetch64(s, len - 24));
+    c = c.wrapping_add(a.rotate_right(7));
+    a = a.wrapping_add(fetch64(s, len - 16));
+    let wf = a.wrapping_add(z);
+    let ws = b.wrapping_add(a.rotate_right(31)).wrapping_add(c);
+    let r = shift_mix(
+        vf.wrapping_add(ws)
+            .wrapping_mul(K2)
+            .wrapping_add(wf.wrapping_add(vs).wrapping_mul(K0)),
+    );
+
+    shift_mix(r.wrapping_mul(K0).wrapping_add(vs)).wrapping_mul(K2)
+}
+
+/// Returns a 16-byte hash of `w`, `x`, `y`, `z`, `a` and `b`, quick and dirty.
+#[inline(always)]
+fn weak_hash_len32_with_seeds(
+    w: u64,
+    x: u64,
+    y: u64,
+    z: u64,
+    mut a: u64,
+    mut b: u64,
+) -> (u64, u64) {
+    a = a.wrapping_add(w);
+    b = b.wrapping_add(a).wrapping_add(z).rotate_right(21);
+    let c = a;
+    a = a.wrapping_add(x).wrapping_add(y);
+    b = b.wrapping_add(a.rotate_right(44));
+
+    (a.wrapping_add(z), b.wrapping_add(c))
+}
+
+/// Returns a 16-byte hash of the first 32 bytes of `s`, `a` and `b`, quick and dirty.
+#[inline(always)]
+fn weak_hash_bytes32_with_seeds(s: &[u8], a: u64, b: u64) -> (u64, u64) {
+    weak_hash_len32_with_seeds(
+        fetch64(s, 0),
+        fetch64(s, 8),
+        fetch64(s, 16),
+        fetch64(s, 24),
+        a,
+        b,
+    )
+}
+
+/// The 56 bytes of state of the loops of `CityHash64` and `CityHash128`.
+struct State {
+    v: (u64, u64),
+    w: (u64, u64),
+    x: u64,
+    y: u64,
+    z: u64,
+}
+
+impl State {
+    /// Hashes a 64-byte chunk.
+    #[inline(always)]
+    fn update(&mut self, chunk: &[u8]) {
+        self.x = self
+            .x
+            .wrapping_add(self.y)
+            .wrapping_add(self.v.0)
+            .wrapping_add(fetch64(chunk, 8))
+            .rotate_right(37)
+            .wrapping_mul(K1);
+        self.y = self
+            .y
+            .wrapping_add(self.v.1)
+            .wrapping_add(fetch64(chunk, 48))
+            .rotate_right(42)
+            .wrapping_mul(K1);
+        self.x ^= self.w.1;
+        self.y = self
+            .y
+            .wrapping_add(self.v.0)
+            .wrapping_add(fetch64(chunk, 40));
+        self.z = self
+            .z
+            .wrapping_add(self.w.0)
+            .rotate_right(33)
+            .wrapping_mul(K1);
+        self.v = weak_hash_bytes32_with_seeds(
+            chunk,
+            self.v.1.wrapping_mul(K1),
+            self.x.wrapping_add(self.w.0),
+        );
+        self.w = weak_hash_bytes32_with_seeds(
+            &chunk[32..],
+            self.z.wrapping_add(self.w.1),
+            self.y.wrapping_add(fetch64(chunk, 16)),
+        );
+        mem::swap(&mut self.z, &mut self.x);
+    }
+}
+
+pub fn hash64(s: &[u8]) -> u64 {
+    let len = s.len();
+
+    if len <= 16 {
+        return hash_len0to16(s);
+    } else if len <= 32 {
+        return hash_len17to32(s);
+    } else if len <= 64 {
+        return hash_len33to64(s);
+    }
+
+    // for strings over 64 bytes we hash the end first, and then as we
+    // loop we keep 56 bytes of state: v, w, x, y, and z.
+    let x = fetch64(s, len - 40);
+    let y = fetch64(s, len - 16).wrapping_add(fetch64(s, len - 56));
+    let z = hash_len16(
+        fetch64(s, len - 48).wrapping_add(len as u64),
+        fetch64(s, len - 24),
+    );
+    let mut state = State {
+        v: weak_hash_bytes32_with_seeds(&s[len - 64..], len as u64, z),
+        w: weak_hash_bytes32_with_seeds(&s[len - 32..], y.wrapping_add(K1), x),
+        x: x.wrapping_mul(K1).wrapping_add(fetch64(s, 0)),
+        y,
+        z,
+    };
+
+    // operate on 64-byte chunks up to the nearest multiple of 64 below the length
+    for chunk in s[..(len - 1) & !63].chunks_exact(64) {
+        state.update(chunk);
+    }
+
+    let State { v, w, x, y, z } = state;
+
+    hash_len16(
+        hash_len16(v.0, w.0)
+            .wrapping_add(shift_mix(y).wrapping_mul(K1))
+            .wrapping_add(z),
+        hash_len16(v.1, w.1).wrapping_add(x),
+    )
+}
+
+#[inline(always)]
+pub fn hash64_with_seed(s: &[u8], seed: u64) -> u64 {
+    hash64_with_seeds(s, K2, seed)
+}
+
+#[inline(always)]
+pub fn hash64_with_seeds(s: &[u8], seed0: u64, seed1: u64) -> u64 {
+    hash_len16(hash64(s).wrapping_sub(seed0), seed1)
+}
+
+/// Returns a decent 128-bit hash for strings of any length, based on City and Murmur.
+fn city_murmur(s: &[u8], seed: (u64, u64)) -> (u64, u64) {
+    let len = s.len();
+    let (mut a, mut b) = seed;
+    let mut c;
+    let mut d;
+
+    if len <= 16 {
+        a = shift_mix(a.wrapping_mul(K1)).wrapping_mul(K1);
+        c = b.wrapping_mul(K1).wrapping_add(hash_len0to16(s));
+        d = shift_mix(a.wrapping_add(if len >= 8 { fetch64(s, 0) } else { c }));
+    } else {
+        c = hash_len16(fetch64(s, len - 8).wrapping_add(K1), a);
+        d = hash_len16(
+            b.wrapping_add(len as u64),
+            c.wrapping_add(fetch64(s, len - 16)),
+        );
+        a = a.wrapping_add(d);
+
+        for i in (0..len - 16).step_by(16) {
+            a ^= shift_mix(fetch64(s, i).wrapping_mul(K1)).wrapping_mul(K1);
+            a = a.wrapping_mul(K1);
+            b ^= a;
+            c ^= shift_mix(fetch64(s, i + 8).wrapping_mul(K1)).wrapping_mul(K1);
+            c = c.wrapping_mul(K1);
+            d ^= c;
+        }
+    }
+
+    a = hash_len16(a, c);
+    b = hash_len16(d, b);
+
+    (a ^ b, hash_len16(b, a))
+}
+
+fn city_hash128_with_seed(s: &[u8], seed: (u64, u64)) -> (u64, u64) {
+    let len = s.len();
+
+    if len < 128 {
+        return city_murmur(s, seed);
+    }
+
+    // we expect len >= 128 to be the common case.  Keep 56 bytes of state:
+    // v, w, x, y, and z.
+    let (x, y) = seed;
+    let z = (len as u64).wrapping_mul(K1);
+    let v0 = (y ^ K1)
+        .rotate_right(49)
+        .wrapping_mul(K1)
+        .wrapping_add(fetch64(s, 0));
+    let mut state = State {
+        v: (
+            v0,
+            v0.rotate_right(42)
+                .wrapping_mul(K1)
+                .wrapping_add(fetch64(s, 8)),
+        ),
+        w: (
+            y.wrapping_add(z)
+                .rotate_right(35)
+                .wrapping_mul(K1)
+                .wrapping_add(x),
+            x.wrapping_add(fetch64(s, 88))
+                .rotate_right(53)
+                .wrapping_mul(K1),
+        ),
+        x,
+        y,
+        z,
+    };
+
+    // this is the same inner loop as `CityHash64`, manually unrolled.
+    let mut chunks = s.chunks_exact(128);
+
+    for chunk in &mut chunks {
+        state.update(&chunk[..64]);
+        state.update(&chunk[64..]);
+    }
+
+    let State {
+        mut v,
+        mut w,
+        mut x,
+        mut y,
+        mut z,
+    } = state;
+    let tail = chunks.remainder().len();
+    let end = len;
+
+    x = x.wrapping_add(v.0.wrapping_add(z).rotate_right(49).wrapping_mul(K0));
+    z = z.wrapping_add(w.0.rotate_right(37).wrapping_mul(K0));
+
+    // if 0 < tail < 128, hash up to 4 chunks of 32 bytes each from the end of s.
+    let mut tail_done = 0;
+
+    while tail_done < tail {
+        tail_done += 32;
+
+        let chunk = &s[end - tail_done..];
+
+        y = x
+            .wrapping_add(y)
+            .rotate_right(42)
+            .wrapping_mul(K0)
+            .wrapping_add(v.1);
+        w.0 = w.0.wrapping_add(fetch64(chunk, 16));
+        x = x.wrapping_mul(K0).wrapping_add(w.0);
+        z = z.wrapping_add(w.1).wrapping_add(fetch64(chunk, 0));
+        w.1 = w.1.wrapping_add(v.0);
+        v = weak_hash_bytes32_with_seeds(chunk, v.0.wrapping_add(z), v.1);
+    }
+
+    // at this point our 56 bytes of state should contain more than
+    // enough information for a strong 128-bit hash.  We use two
+    // different 56-byte-to-8-byte hashes to get a 16-byte final result.
+    x = hash_len16(x, v.0);
+    y = hash_len16(y.wrapping_add(z), w.0);
+
+    (
+        hash_len16(x.wrapping_add(v.1), w.1).wrapping_add(y),
+        hash_len16(x.wrapping_add(w.1), y.wrapping_add(v.1)),
+    )
+}
+
+fn city_hash128(s: &[u8]) -> (u64, u64) {
+    let len = s.len();
+
+    if len >= 16 {
+        city_hash128_with_seed(&s[16..], (fetch64(s, 0) ^ K3, fetch64(s, 8)))
+    } else if len >= 8 {
+        city_hash128_with_seed(
+            &[],
+            (
+                fetch64(s, 0) ^ (len as u64).wrapping_mul(K0),
+                fetch64(s, len - 8) ^ K1,
+            ),
+        )
+    } else {
+        city_hash128_with_seed(s, (K0, K1))
+    }
+}
+
+#[inline(always)]
+fn to_u128((lo, hi): (u64, u64)) -> u128 {
+    u128::from(lo) | (u128::from(hi) << 64)
+}
+
+#[inline(always)]
+fn from_u128(n: u128) -> (u64, u64) {
+    (n as u64, (n >> 64) as u64)
+}
+
+#[inline(always)]
+pub fn hash128(s: &[u8]) -> u128 {
+    to_u128(city_hash128(s))
+}
+
+#[inline(always)]
+pub fn hash128_with_seed(s: &[u8], seed: u128) -> u128 {
+    to_u128(city_hash128_with_seed(s, from_u128(seed)))
+}
+
+cfg_if! {
+    if #[cfg(all(feature = "std", target_arch = "x86_64"))] {
+        #[inline(always)]
+        fn has_sse42() -> bool {
+            is_x86_feature_detected!("sse4.2")
+        }
+    } else if #[cfg(target_arch = "x86_64")] {
+        // the CPU can't be probed without `std`, trust the build configuration
+        #[inline(always)]
+        fn has_sse42() -> bool {
+            cfg!(target_feature = "sse4.2")
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.2")]
+unsafe fn crc256_long_sse42(s: &[u8], seed: u32) -> [u64; 4] {
+    use core::arch::x86_64::_mm_crc32_u64;
+
+    crc256_long_with(s, seed, |crc, v| _mm_crc32_u64(crc, v))
+}
+
+#[inline(always)]
+fn crc256_long(s: &[u8], seed: u32) -> [u64; 4] {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if has_sse42() {
+            return unsafe { crc256_long_sse42(s, seed) };
+        }
+    }
+
+    crc256_long_with(s, seed, crc32c_u64)
+}
+
+/// `CityHashCrc256Long`, `s` has at least 240 bytes.
+///
+/// `CityHashCrc256` pads shorter keys, but the 128-bit variants only use it for more than 900 bytes.
+#[inline(always)]
+fn crc256_long_with<F: Fn(u64, u64) -> u64>(s: &[u8], seed: u32, crc: F) -> [u64; 4] {
+    let len = s.len();
+    let mut result = [0; 4];
+    let mut a = fetch64(s, 56).wrapping_add(K0);
+    let mut b = fetch64(s, 96).wrapping_add(K0);
+    let mut c = hash_len16(b, len as u64);
+    let mut d = fetch64(s, 120).wrapping_mul(K0).wrapping_add(len as u64);
+    let mut e = fetch64(s, 184).wrapping_add(u64::from(seed));
+    let mut f = u64::from(seed);
+    let mut g = 0;
+    let mut h = 0;
+    let mut i = 0;
+    let mut j = 0;
+    let mut t = c.wrapping_add(d);
+
+    result[0] = c;
+    result[1] = d;
+
+    let mut chunk = |s: &[u8], mul: u64, z: u32| {
+        let old_a = a;
+
+        a = b
+            .rotate_right(41 ^ z)
+            .wrapping_mul(mul)
+            .wrapping_add(fetch64(s, 0));
+        b = c
+            .rotate_right(27 ^ z)
+            .wrapping_mul(mul)
+            .wrapping_add(fetch64(s, 8));
+        c = d
+            .rotate_right(41 ^ z)
+            .wrapping_mul(mul)
+            .wrapping_add(fetch64(s, 16));
+        d = e
+            .rotate_right(33 ^ z)
+            .wrapping_mul(mul)
+            .wrapping_add(fetch64(s, 24));
+        e = t
+            .rotate_right(25 ^ z)
+            .wrapping_mul(mul)
+            .wrapping_add(fetch64(s, 32));
+        t = old_a;
+        f = crc(f, a);
+        g = crc(g, b);
+        h = crc(h, c);
+        i = crc(i, d);
+        j = crc(j, e);
+    };
+
+    // 240 bytes of input per iteration
+    let mut blocks = s.chunks_exact(240);
+
+    for block in &mut blocks {
+        for pair in block.chunks_exact(80) {
+            chunk(&pair[..40], 1, 1);
+            chunk(&pair[40..], K0, 0);
+        }
+    }
+
+    let rest = blocks.remainder();
+    let mut rest_chunks = rest.chunks_exact(40);
+
+    for rest_chunk in &mut rest_chunks {
+        chunk(rest_chunk, K0, 0);
+    }
+    if !rest_chunks.remainder().is_empty() {
+        chunk(&s[len - 40..], K0, 0);
+    }
+
+    j = j.wrapping_add(i << 32);
+    a = hash_len16(a, j);
+    h = h.wrapping_add(g << 32);
+    b = b.wrapping_add(h);
+    c = hash_len16(c, f).wrapping_add(i);
+    d = hash_len16(d, e.wrapping_add(result[0]));
+    j = j.wrapping_add(e);
+    i = i.wrapping_add(hash_len16(h, t));
+    e = hash_len16(a, d).wrapping_add(j);
+    f = hash_len16(b, c).wrapping_add(a);
+    g = hash_len16(j, i).wrapping_add(c);
+    result[0] = e.wrapping_add(f).wrapping_add(g).wrapping_add(h);
+    a = shift_mix(a.wrapping_add(g).wrapping_mul(K0))
+        .wrapping_mul(K0)
+        .wrapping_add(b);
+    result[1] = result[1].wrapping_add(a).wrapping_add(result[0]);
+    a = shift_mix(a.wrapping_mul(K0))
+        .wrapping_mul(K0)
+        .wrapping_add(c);
+    result[2] = a.wrapping_add(result[1]);
+    a = shift_mix(a.wrapping_add(e).wrapping_mul(K0)).wrapping_mul(K0);
+    result[3] = a.wrapping_add(result[2]);
+
+    result
+}
+
+pub fn crc128(s: &[u8]) -> u128 {
+    if s.len() <= 900 {
+        hash128(s)
+    } else {
+        let result = crc256_long(s, 0);
+
+        to_u128((result[2], result[3]))
+    }
+}
+
+pub fn crc128_with_seed(s: &[u8], seed: u128) -> u128 {
+    if s.len() <= 900 {
+        hash128_with_seed(s, seed)
+    } else {
+        let result = crc256_long(s, 0);
+        let (lo, hi) = from_u128(seed);
+        let u = hi.wrapping_add(result[0]);
+        let v = lo.wrapping_add(result[1]);
+
+        to_u128((
+            hash_len16(u, v.wrapping_add(result[2])),
+            hash_len16(
+                v.rotate_right(32),
+                u.wrapping_mul(K0).wrapping_add(result[3]),
+            ),
+        ))
+    }
+}
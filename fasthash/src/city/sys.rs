@@ -0,0 +1,74 @@
+//! CityHash backed by the vendored C++ implementation.
+use core::mem;
+
+use crate::ffi;
+
+#[inline(always)]
+pub fn hash32_with_seed(bytes: &[u8], seed: u32) -> u32 {
+    unsafe { ffi::CityHash32WithSeed(bytes.as_ptr() as *const _, bytes.len(), seed) }
+}
+
+#[inline(always)]
+pub fn hash64(bytes: &[u8]) -> u64 {
+    unsafe { ffi::CityHash64(bytes.as_ptr() as *const _, bytes.len()) }
+}
+
+#[inline(always)]
+pub fn hash64_with_seed(bytes: &[u8], seed: u64) -> u64 {
+    unsafe { ffi::CityHash64WithSeed(bytes.as_ptr() as *const _, bytes.len(), seed) }
+}
+
+#[inline(always)]
+pub fn hash64_with_seeds(bytes: &[u8], seed0: u64, seed1: u64) -> u64 {
+    unsafe { ffi::CityHash64WithSeeds(bytes.as_ptr() as *const _, bytes.len(), seed0, seed1) }
+}
+
+#[inline(always)]
+pub fn hash128(bytes: &[u8]) -> u128 {
+    unsafe { mem::transmute(ffi::CityHash128(bytes.as_ptr() as *const _, bytes.len())) }
+}
+
+#[inline(always)]
+pub fn hash128_with_seed(bytes: &[u8], seed: u128) -> u128 {
+    unsafe {
+        mem::transmute(ffi::CityHash128WithSeed(
+            bytes.as_ptr() as *const _,
+            bytes.len(),
+            mem::transmute(seed),
+        ))
+    }
+}
+
+#[cfg(any(feature = "sse42", target_feature = "sse4.2"))]
+#[inline(always)]
+pub fn crc128(bytes: &[u8]) -> u128 {
+    #[cfg(all(feature = "runtime", any(target_arch = "x86", target_arch = "x86_64")))]
+    {
+        // the CRC variants are always built in `runtime` mode, fall back to the Rust port
+        if !is_x86_feature_detected!("sse4.2") {
+            return super::pure::crc128(bytes);
+        }
+    }
+
+    unsafe { mem::transmute(ffi::CityHashCrc128(bytes.as_ptr() as *const _, bytes.len())) }
+}
+
+#[cfg(any(feature = "sse42", target_feature = "sse4.2"))]
+#[inline(always)]
+pub fn crc128_with_seed(bytes: &[u8], seed: u128) -> u128 {
+    #[cfg(all(feature = "runtime", any(target_arch = "x86", target_arch = "x86_64")))]
+    {
+        // the CRC variants are always built in `runtime` mode, fall back to the Rust port
+        if !is_x86_feature_detected!("sse4.2") {
+            return super::pure::crc128_with_seed(bytes, seed);
+        }
+    }
+
+    unsafe {
+        mem::transmute(ffi::CityHashCrc128WithSeed(
+            bytes.as_ptr() as *const _,
+            bytes.len(),
+            mem::transmute(seed),
+        ))
+    }
+}
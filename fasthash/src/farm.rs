@@ -103,6 +103,8 @@
 //! # Example
 //!
 //! ```
+//! # #[cfg(feature = "sys")]
+//! # {
 //! use std::hash::{Hash, Hasher};
 //!
 //! use fasthash::{farm, FarmHasher};
@@ -116,13 +118,34 @@
 //! let h = farm::hash64(b"hello world\xff");
 //!
 //! assert_eq!(h, hash(&"hello world"));
+//! # }
 //! ```
 //!
-use std::mem;
+//! By default it is backed by the vendored C implementation,
+//! the `pure-farm` feature switches the fingerprints to a pure Rust port which gives
+//! bit for bit the same results, while the hash functions still need the `sys` feature.
+#[cfg(feature = "sys")]
+use core::mem;
 
+#[cfg(feature = "sys")]
 use crate::ffi;
 
-use crate::hasher::{FastHash, Fingerprint};
+#[cfg(feature = "sys")]
+use crate::hasher::FastHash;
+use crate::hasher::Fingerprint;
+
+#[cfg(feature = "pure-farm")]
+mod pure;
+#[cfg(all(feature = "sys", any(test, not(feature = "pure-farm"))))]
+mod sys;
+
+cfg_if! {
+    if #[cfg(feature = "pure-farm")] {
+        use self::pure as imp;
+    } else {
+        use self::sys as imp;
+    }
+}
 
 /// `FarmHash` 32-bit hash functions
 ///
@@ -135,23 +158,25 @@ use crate::hasher::{FastHash, Fingerprint};
 /// assert_eq!(Hash32::hash_with_seed(b"world", 123), 60914537);
 /// assert_eq!(Hash32::hash(b"helloworld"), 2214725017);
 /// ```
+#[cfg(feature = "sys")]
 #[derive(Clone)]
 pub struct Hash32;
 
+#[cfg(feature = "sys")]
 impl FastHash for Hash32 {
     type Hash = u32;
     type Seed = u32;
 
     #[inline(always)]
     fn hash<T: AsRef<[u8]>>(bytes: T) -> u32 {
-        unsafe { ffi::farmhash32(bytes.as_ref().as_ptr() as *const i8, bytes.as_ref().len()) }
+        unsafe { ffi::farmhash32(bytes.as_ref().as_ptr() as *const _, bytes.as_ref().len()) }
     }
 
     #[inline(always)]
     fn hash_with_seed<T: AsRef<[u8]>>(bytes: T, seed: u32) -> u32 {
         unsafe {
             ffi::farmhash32_with_seed(
-                bytes.as_ref().as_ptr() as *const i8,
+                bytes.as_ref().as_ptr() as *const _,
                 bytes.as_ref().len(),
                 seed,
             )
@@ -159,6 +184,7 @@ impl FastHash for Hash32 {
     }
 }
 
+#[cfg(feature = "sys")]
 trivial_hasher! {
     /// # Example
     ///
@@ -175,7 +201,7 @@ trivial_hasher! {
     /// h.write(b"world");
     /// assert_eq!(h.finish(), 2214725017);
     /// ```
-    Hasher32(Hash32) -> u32
+    Hasher32(Hash32) -> u32, "fasthash::farm::Hasher32"
 }
 
 /// `FarmHash` 64-bit hash functions
@@ -196,9 +222,11 @@ trivial_hasher! {
 /// );
 /// assert_eq!(Hash64::hash(b"helloworld"), 1077737941828767314);
 /// ```
+#[cfg(feature = "sys")]
 #[derive(Clone)]
 pub struct Hash64;
 
+#[cfg(feature = "sys")]
 impl Hash64 {
     /// Hash functions for a byte array.
     /// For convenience, seeds are also hashed into the result.
@@ -206,7 +234,7 @@ impl Hash64 {
     pub fn hash_with_seeds<T: AsRef<[u8]>>(bytes: T, seed0: u64, seed1: u64) -> u64 {
         unsafe {
             ffi::farmhash64_with_seeds(
-                bytes.as_ref().as_ptr() as *const i8,
+                bytes.as_ref().as_ptr() as *const _,
                 bytes.as_ref().len(),
                 seed0,
                 seed1,
@@ -215,20 +243,21 @@ impl Hash64 {
     }
 }
 
+#[cfg(feature = "sys")]
 impl FastHash for Hash64 {
     type Hash = u64;
     type Seed = u64;
 
     #[inline(always)]
     fn hash<T: AsRef<[u8]>>(bytes: T) -> u64 {
-        unsafe { ffi::farmhash64(bytes.as_ref().as_ptr() as *const i8, bytes.as_ref().len()) }
+        unsafe { ffi::farmhash64(bytes.as_ref().as_ptr() as *const _, bytes.as_ref().len()) }
     }
 
     #[inline(always)]
     fn hash_with_seed<T: AsRef<[u8]>>(bytes: T, seed: u64) -> u64 {
         unsafe {
             ffi::farmhash64_with_seed(
-                bytes.as_ref().as_ptr() as *const i8,
+                bytes.as_ref().as_ptr() as *const _,
                 bytes.as_ref().len(),
                 seed,
             )
@@ -236,6 +265,7 @@ impl FastHash for Hash64 {
     }
 }
 
+#[cfg(feature = "sys")]
 trivial_hasher! {
     /// # Example
     ///
@@ -252,7 +282,7 @@ trivial_hasher! {
     /// h.write(b"world");
     /// assert_eq!(h.finish(), 1077737941828767314);
     /// ```
-    Hasher64(Hash64) -> u64
+    Hasher64(Hash64) -> u64, "fasthash::farm::Hasher64"
 }
 
 /// `FarmHash` 128-bit hash functions
@@ -275,9 +305,11 @@ trivial_hasher! {
 ///     296377541162803340912737385112946231361
 /// );
 /// ```
+#[cfg(feature = "sys")]
 #[derive(Clone)]
 pub struct Hash128;
 
+#[cfg(feature = "sys")]
 impl FastHash for Hash128 {
     type Hash = u128;
     type Seed = u128;
@@ -286,7 +318,7 @@ impl FastHash for Hash128 {
     fn hash<T: AsRef<[u8]>>(bytes: T) -> u128 {
         unsafe {
             mem::transmute(ffi::farmhash128(
-                bytes.as_ref().as_ptr() as *const i8,
+                bytes.as_ref().as_ptr() as *const _,
                 bytes.as_ref().len(),
             ))
         }
@@ -296,7 +328,7 @@ impl FastHash for Hash128 {
     fn hash_with_seed<T: AsRef<[u8]>>(bytes: T, seed: u128) -> u128 {
         unsafe {
             mem::transmute(ffi::farmhash128_with_seed(
-                bytes.as_ref().as_ptr() as *const i8,
+                bytes.as_ref().as_ptr() as *const _,
                 bytes.as_ref().len(),
                 mem::transmute(seed),
             ))
@@ -304,6 +336,7 @@ impl FastHash for Hash128 {
     }
 }
 
+#[cfg(feature = "sys")]
 trivial_hasher! {
     /// # Example
     ///
@@ -320,13 +353,14 @@ trivial_hasher! {
     /// h.write(b"world");
     /// assert_eq!(h.finish_ext(), 296377541162803340912737385112946231361);
     /// ```
-    Hasher128(Hash128) -> u128
+    Hasher128(Hash128) -> u128, "fasthash::farm::Hasher128"
 }
 
 /// `FarmHash` 32-bit hash function for a byte array.
 ///
 /// May change from time to time, may differ on different platforms, may differ depending on NDEBUG.
 ///
+#[cfg(feature = "sys")]
 #[inline(always)]
 pub fn hash32<T: AsRef<[u8]>>(v: T) -> u32 {
     Hash32::hash(v)
@@ -337,6 +371,7 @@ pub fn hash32<T: AsRef<[u8]>>(v: T) -> u32 {
 ///
 /// May change from time to time, may differ on different platforms, may differ depending on NDEBUG.
 ///
+#[cfg(feature = "sys")]
 #[inline(always)]
 pub fn hash32_with_seed<T: AsRef<[u8]>>(v: T, seed: u32) -> u32 {
     Hash32::hash_with_seed(v, seed)
@@ -345,6 +380,7 @@ pub fn hash32_with_seed<T: AsRef<[u8]>>(v: T, seed: u32) -> u32 {
 /// `FarmHash` 64-bit hash function for a byte array.
 ///
 /// May change from time to time, may differ on different platforms, may differ depending on NDEBUG.
+#[cfg(feature = "sys")]
 #[inline(always)]
 pub fn hash64<T: AsRef<[u8]>>(v: T) -> u64 {
     Hash64::hash(v)
@@ -355,6 +391,7 @@ pub fn hash64<T: AsRef<[u8]>>(v: T) -> u64 {
 ///
 /// May change from time to time, may differ on different platforms, may differ depending on NDEBUG.
 ///
+#[cfg(feature = "sys")]
 #[inline(always)]
 pub fn hash64_with_seed<T: AsRef<[u8]>>(v: T, seed: u64) -> u64 {
     Hash64::hash_with_seed(v, seed)
@@ -365,6 +402,7 @@ pub fn hash64_with_seed<T: AsRef<[u8]>>(v: T, seed: u64) -> u64 {
 ///
 /// May change from time to time, may differ on different platforms, may differ depending on NDEBUG.
 ///
+#[cfg(feature = "sys")]
 pub fn hash64_with_seeds<T: AsRef<[u8]>>(v: T, seed0: u64, seed1: u64) -> u64 {
     Hash64::hash_with_seeds(v, seed0, seed1)
 }
@@ -373,6 +411,7 @@ pub fn hash64_with_seeds<T: AsRef<[u8]>>(v: T, seed0: u64, seed1: u64) -> u64 {
 ///
 /// May change from time to time, may differ on different platforms, may differ depending on NDEBUG.
 ///
+#[cfg(feature = "sys")]
 #[inline(always)]
 pub fn hash128<T: AsRef<[u8]>>(v: T) -> u128 {
     Hash128::hash(v)
@@ -383,6 +422,7 @@ pub fn hash128<T: AsRef<[u8]>>(v: T) -> u128 {
 ///
 /// May change from time to time, may differ on different platforms, may differ depending on NDEBUG.
 ///
+#[cfg(feature = "sys")]
 #[inline(always)]
 pub fn hash128_with_seed<T: AsRef<[u8]>>(v: T, seed: u128) -> u128 {
     Hash128::hash_with_seed(v, seed)
@@ -399,7 +439,7 @@ pub fn hash128_with_seed<T: AsRef<[u8]>>(v: T, seed: u128) -> u128 {
 /// ```
 #[inline(always)]
 pub fn fingerprint32<T: AsRef<[u8]>>(v: T) -> u32 {
-    unsafe { ffi::farmhash_fingerprint32(v.as_ref().as_ptr() as *const i8, v.as_ref().len()) }
+    imp::fingerprint32(v.as_ref())
 }
 
 /// `FarmHash` 64-bit fingerprint function for a byte array.
@@ -413,7 +453,7 @@ pub fn fingerprint32<T: AsRef<[u8]>>(v: T) -> u32 {
 /// ```
 #[inline(always)]
 pub fn fingerprint64<T: AsRef<[u8]>>(v: T) -> u64 {
-    unsafe { ffi::farmhash_fingerprint64(v.as_ref().as_ptr() as *const i8, v.as_ref().len()) }
+    imp::fingerprint64(v.as_ref())
 }
 
 /// `FarmHash` 128-bit fingerprint function for a byte array.
@@ -427,25 +467,20 @@ pub fn fingerprint64<T: AsRef<[u8]>>(v: T) -> u64 {
 /// ```
 #[inline(always)]
 pub fn fingerprint128<T: AsRef<[u8]>>(v: T) -> u128 {
-    unsafe {
-        mem::transmute(ffi::farmhash_fingerprint128(
-            v.as_ref().as_ptr() as *const i8,
-            v.as_ref().len(),
-        ))
-    }
+    imp::fingerprint128(v.as_ref())
 }
 
 impl Fingerprint<u64> for u64 {
     #[inline(always)]
     fn fingerprint(&self) -> u64 {
-        unsafe { ffi::farmhash_fingerprint_uint64(*self) }
+        imp::fingerprint_u64(*self)
     }
 }
 
 impl Fingerprint<u64> for u128 {
     #[inline(always)]
     fn fingerprint(&self) -> u64 {
-        unsafe { ffi::farmhash_fingerprint_uint128(mem::transmute(*self)) }
+        imp::fingerprint_u128(*self)
     }
 }
 
@@ -458,4 +493,66 @@ mod tests {
         assert_eq!(123u64.fingerprint(), 4781265650859502840);
         assert_eq!(123u128.fingerprint(), 4011577241381678309);
     }
+
+    /// The pure Rust port against the C implementation.
+    #[cfg(all(feature = "sys", feature = "pure-farm"))]
+    mod compat {
+        use super::super::{pure, sys};
+
+        fn sample(len: usize) -> Vec<u8> {
+            let mut x = 0x9E37_79B1_u64;
+
+            (0..len)
+                .map(|_| {
+                    x = x.wrapping_mul(0x9E37_79B1_85EB_CA87);
+                    (x >> 56) as u8
+                })
+                .collect()
+        }
+
+        #[test]
+        fn test_fingerprint_bytes() {
+            let data = sample(1024);
+
+            for len in 0..=data.len() {
+                let v = &data[..len];
+
+                assert_eq!(
+                    pure::fingerprint32(v),
+                    sys::fingerprint32(v),
+                    "fingerprint32, len={}",
+                    len
+                );
+                assert_eq!(
+                    pure::fingerprint64(v),
+                    sys::fingerprint64(v),
+                    "fingerprint64, len={}",
+                    len
+                );
+                assert_eq!(
+                    pure::fingerprint128(v),
+                    sys::fingerprint128(v),
+                    "fingerprint128, len={}",
+                    len
+                );
+            }
+        }
+
+        #[test]
+        fn test_fingerprint_integers() {
+            for chunk in sample(4096).chunks_exact(16) {
+                let n = u128::from_le_bytes([
+                    chunk[0], chunk[1], chunk[2], chunk[3], chunk[4], chunk[5], chunk[6], chunk[7],
+                    chunk[8], chunk[9], chunk[10], chunk[11], chunk[12], chunk[13], chunk[14],
+                    chunk[15],
+                ]);
+
+                assert_eq!(
+                    pure::fingerprint_u64(n as u64),
+                    sys::fingerprint_u64(n as u64)
+                );
+                assert_eq!(pure::fingerprint_u128(n), sys::fingerprint_u128(n));
+            }
+        }
+    }
 }
@@ -0,0 +1,567 @@
+//! `FarmHash` fingerprints implemented in Rust, bit for bit compatible with the vendored C implementation.
+//!
+//! The fingerprints are `farmhash32_mk`, `farmhash64_na` and `farmhash128_cc_city`,
+//! which only need plain integer arithmetic and are the same on every platform.
+use core::convert::TryInto;
+use core::mem;
+
+const K0: u64 = 0xc3a5_c85c_97cb_3127;
+const K1: u64 = 0xb492_b66f_be98_f273;
+const K2: u64 = 0x9ae1_6a3b_2f90_404f;
+const K_MUL: u64 = 0x9ddf_ea08_eb38_2d69;
+
+const C1: u32 = 0xcc9e_2d51;
+const C2: u32 = 0x1b87_3593;
+
+#[inline(always)]
+fn fetch32(s: &[u8], i: usize) -> u32 {
+    u32::from_le_bytes(s[i..i + 4].try_into().unwrap())
+}
+
+#[inline(always)]
+fn fetch64(s: &[u8], i: usize) -> u64 {
+    u64::from_le_bytes(s[i..i + 8].try_into().unwrap())
+}
+
+/// The 32-bit finalizer of Murmur3.
+#[inline(always)]
+fn fmix(mut h: u32) -> u32 {
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2_ae35);
+    h ^ (h >> 16)
+}
+
+#[inline(always)]
+fn premix(a: u32) -> u32 {
+    a.wrapping_mul(C1).rotate_right(17).wrapping_mul(C2)
+}
+
+#[inline(always)]
+fn mix(h: u32, a: u32) -> u32 {
+    (h ^ a)
+        .rotate_right(19)
+        .wrapping_mul(5)
+        .wrapping_add(0xe654_6b64)
+}
+
+/// Combines two 32-bit values, from Murmur3.
+#[inline(always)]
+fn mur(a: u32, h: u32) -> u32 {
+    mix(h, premix(a))
+}
+
+#[inline(always)]
+fn smix(v: u64) -> u64 {
+    v ^ (v >> 47)
+}
+
+#[inline(always)]
+fn len_16_mul(u: u64, v: u64, mul: u64) -> u64 {
+    let mut a = (u ^ v).wrapping_mul(mul);
+    a ^= a >> 47;
+    let mut b = (v ^ a).wrapping_mul(mul);
+    b ^= b >> 47;
+    b.wrapping_mul(mul)
+}
+
+#[inline(always)]
+fn len_16(u: u64, v: u64) -> u64 {
+    len_16_mul(u, v, K_MUL)
+}
+
+fn mk_len_0_to_4(s: &[u8]) -> u32 {
+    let mut b = 0_u32;
+    let mut c = 9_u32;
+
+    for &v in s {
+        b = b.wrapping_mul(C1).wrapping_add(v as i8 as u32);
+        c ^= b;
+    }
+
+    fmix(mur(b, mur(s.len() as u32, c)))
+}
+
+fn mk_len_5_to_12(s: &[u8]) -> u32 {
+    let len = s.len();
+    let a = (len as u32).wrapping_add(fetch32(s, 0));
+    let b = (len as u32 * 5).wrapping_add(fetch32(s, len - 4));
+    let c = 9_u32.wrapping_add(fetch32(s, (len >> 1) & 4));
+    let d = len as u32 * 5;
+
+    fmix(mur(c, mur(b, mur(a, d))))
+}
+
+fn mk_len_13_to_24(s: &[u8]) -> u32 {
+    let len = s.len();
+    let mut a = fetch32(s, (len >> 1) - 4);
+    let b = fetch32(s, 4);
+    let c = fetch32(s, len - 8);
+    let d = fetch32(s, len >> 1);
+    let e = fetch32(s, 0);
+    let f = fetch32(s, len - 4);
+    let mut h = d.wrapping_mul(C1).wrapping_add(len as u32);
+
+    a = a.rotate_right(12).wrapping_add(f);
+    h = mur(c, h).wrapping_add(a);
+    a = a.rotate_right(3).wrapping_add(c);
+    h = mur(e, h).wrapping_add(a);
+    a = a.wrapping_add(f).rotate_right(12).wrapping_add(d);
+    h = mur(b, h).wrapping_add(a);
+
+    fmix(h)
+}
+
+pub fn fingerprint32(s: &[u8]) -> u32 {
+    let len = s.len();
+
+    if len <= 4 {
+        return mk_len_0_to_4(s);
+    } else if len <= 12 {
+        return mk_len_5_to_12(s);
+    } else if len <= 24 {
+        return mk_len_13_to_24(s);
+    }
+
+    let mut h = len as u32;
+    let mut g = C1.wrapping_mul(len as u32);
+    let mut f = g;
+
+    h = mix(h, premix(fetch32(s, len - 4)));
+    h = mix(h, premix(fetch32(s, len - 16)));
+    g = mix(g, premix(fetch32(s, len - 8)));
+    g = mix(g, premix(fetch32(s, len - 12)));
+    f = f
+        .wrapping_add(premix(fetch32(s, len - 20)))
+        .rotate_right(19)
+        .wrapping_add(113);
+
+    for chunk in s.chunks_exact(20).take((len - 1) / 20) {
+        let a = fetch32(chunk, 0);
+        let b = fetch32(chunk, 4);
+        let c = fetch32(chunk, 8);
+        let d = fetch32(chunk, 12);
+        let e = fetch32(chunk, 16);
+
+        h = h.wrapping_add(a);
+        g = g.wrapping_add(b);
+        f = f.wrapping_add(c);
+        h = mur(d, h).wrapping_add(e);
+        g = mur(c, g).wrapping_add(a);
+        f = mur(b.wrapping_add(e.wrapping_mul(C1)), f).wrapping_add(d);
+        f = f.wrapping_add(g);
+        g = g.wrapping_add(f);
+    }
+
+    g = g.rotate_right(11).wrapping_mul(C1);
+    g = g.rotate_right(17).wrapping_mul(C1);
+    f = f.rotate_right(11).wrapping_mul(C1);
+    f = f.rotate_right(17).wrapping_mul(C1);
+    h = h
+        .wrapping_add(g)
+        .rotate_right(19)
+        .wrapping_mul(5)
+        .wrapping_add(0xe654_6b64);
+    h = h.rotate_right(17).wrapping_mul(C1);
+    h = h
+        .wrapping_add(f)
+        .rotate_right(19)
+        .wrapping_mul(5)
+        .wrapping_add(0xe654_6b64);
+    h.rotate_right(17).wrapping_mul(C1)
+}
+
+fn na_len_0_to_16(s: &[u8]) -> u64 {
+    let len = s.len();
+
+    if len >= 8 {
+        let mul = K2.wrapping_add(len as u64 * 2);
+        let a = fetch64(s, 0).wrapping_add(K2);
+        let b = fetch64(s, len - 8);
+        let c = b.rotate_right(37).wrapping_mul(mul).wrapping_add(a);
+        let d = a.rotate_right(25).wrapping_add(b).wrapping_mul(mul);
+
+        len_16_mul(c, d, mul)
+    } else if len >= 4 {
+        let mul = K2.wrapping_add(len as u64 * 2);
+        let a = u64::from(fetch32(s, 0));
+
+        len_16_mul(len as u64 + (a << 3), u64::from(fetch32(s, len - 4)), mul)
+    } else if len > 0 {
+        let y = u32::from(s[0]) + (u32::from(s[len >> 1]) << 8);
+        let z = len as u32 + (u32::from(s[len - 1]) << 2);
+
+        smix(u64::from(y).wrapping_mul(K2) ^ u64::from(z).wrapping_mul(K0)).wrapping_mul(K2)
+    } else {
+        K2
+    }
+}
+
+fn na_len_17_to_32(s: &[u8]) -> u64 {
+    let len = s.len();
+    let mul = K2.wrapping_add(len as u64 * 2);
+    let a = fetch64(s, 0).wrapping_mul(K1);
+    let b = fetch64(s, 8);
+    let c = fetch64(s, len - 8).wrapping_mul(mul);
+    let d = fetch64(s, len - 16).wrapping_mul(K2);
+
+    len_16_mul(
+        a.wrapping_add(b)
+            .rotate_right(43)
+            .wrapping_add(c.rotate_right(30))
+            .wrapping_add(d),
+        a.wrapping_add(b.wrapping_add(K2).rotate_right(18))
+            .wrapping_add(c),
+        mul,
+    )
+}
+
+fn na_len_33_to_64(s: &[u8]) -> u64 {
+    let len = s.len();
+    let mul = K2.wrapping_add(len as u64 * 2);
+    let a = fetch64(s, 0).wrapping_mul(K2);
+    let b = fetch64(s, 8);
+    let c = fetch64(s, len - 8).wrapping_mul(mul);
+    let d = fetch64(s, len - 16).wrapping_mul(K2);
+    let y = a
+        .wrapping_add(b)
+        .rotate_right(43)
+        .wrapping_add(c.rotate_right(30))
+        .wrapping_add(d);
+    let z = len_16_mul(
+        y,
+        a.wrapping_add(b.wrapping_add(K2).rotate_right(18))
+            .wrapping_add(c),
+        mul,
+    );
+    let e = fetch64(s, 16).wrapping_mul(mul);
+    let f = fetch64(s, 24);
+    let g = y.wrapping_add(fetch64(s, len - 32)).wrapping_mul(mul);
+    let h = z.wrapping_add(fetch64(s, len - 24)).wrapping_mul(mul);
+
+    len_16_mul(
+        e.wrapping_add(f)
+            .rotate_right(43)
+            .wrapping_add(g.rotate_right(30))
+            .wrapping_add(h),
+        e.wrapping_add(f.wrapping_add(a).rotate_right(18))
+            .wrapping_add(g),
+        mul,
+    )
+}
+
+/// Returns a 16-byte hash of `w`, `x`, `y`, `z`, `a` and `b`, quick and dirty.
+#[inline(always)]
+fn weak_len_32_with_seeds_vals(
+    w: u64,
+    x: u64,
+    y: u64,
+    z: u64,
+    mut a: u64,
+    mut b: u64,
+) -> (u64, u64) {
+    a = a.wrapping_add(w);
+    b = b.wrapping_add(a).wrapping_add(z).rotate_right(21);
+    let c = a;
+    a = a.wrapping_add(x).wrapping_add(y);
+    b = b.wrapping_add(a.rotate_right(44));
+
+    (a.wrapping_add(z), b.wrapping_add(c))
+}
+
+/// Returns a 16-byte hash of the first 32 bytes of `s`, `a` and `b`, quick and dirty.
+#[inline(always)]
+fn weak_len_32_with_seeds(s: &[u8], a: u64, b: u64) -> (u64, u64) {
+    weak_len_32_with_seeds_vals(
+        fetch64(s, 0),
+        fetch64(s, 8),
+        fetch64(s, 16),
+        fetch64(s, 24),
+        a,
+        b,
+    )
+}
+
+/// The 56 bytes of state of the loops of `farmhash64_na` and `farmhash128_cc_city`.
+struct State {
+    v: (u64, u64),
+    w: (u64, u64),
+    x: u64,
+    y: u64,
+    z: u64,
+}
+
+impl State {
+    /// Hashes a 64-byte chunk.
+    #[inline(always)]
+    fn update(&mut self, chunk: &[u8]) {
+        self.x = self
+            .x
+            .wrapping_add(self.y)
+            .wrapping_add(self.v.0)
+            .wrapping_add(fetch64(chunk, 8))
+            .rotate_right(37)
+            .wrapping_mul(K1);
+        self.y = self
+            .y
+            .wrapping_add(self.v.1)
+            .wrapping_add(fetch64(chunk, 48))
+            .rotate_right(42)
+            .wrapping_mul(K1);
+        self.x ^= self.w.1;
+        self.y = self
+            .y
+            .wrapping_add(self.v.0)
+            .wrapping_add(fetch64(chunk, 40));
+        self.z = self
+            .z
+            .wrapping_add(self.w.0)
+            .rotate_right(33)
+            .wrapping_mul(K1);
+        self.v = weak_len_32_with_seeds(
+            chunk,
+            self.v.1.wrapping_mul(K1),
+            self.x.wrapping_add(self.w.0),
+        );
+        self.w = weak_len_32_with_seeds(
+            &chunk[32..],
+            self.z.wrapping_add(self.w.1),
+            self.y.wrapping_add(fetch64(chunk, 16)),
+        );
+        mem::swap(&mut self.z, &mut self.x);
+    }
+}
+
+pub fn fingerprint64(s: &[u8]) -> u64 {
+    const SEED: u64 = 81;
+
+    let len = s.len();
+
+    if len <= 16 {
+        return na_len_0_to_16(s);
+    } else if len <= 32 {
+        return na_len_17_to_32(s);
+    } else if len <= 64 {
+        return na_len_33_to_64(s);
+    }
+
+    // for strings over 64 bytes we loop, internal state consists of 56 bytes: v, w, x, y, and z.
+    let y = SEED.wrapping_mul(K1).wrapping_add(113);
+    let mut state = State {
+        v: (0, 0),
+        w: (0, 0),
+        x: SEED.wrapping_mul(K2).wrapping_add(fetch64(s, 0)),
+        y,
+        z: smix(y.wrapping_mul(K2).wrapping_add(113)).wrapping_mul(K2),
+    };
+
+    // leave 1 to 64 bytes to process after the loop
+    for chunk in s[..((len - 1) / 64) * 64].chunks_exact(64) {
+        state.update(chunk);
+    }
+
+    let State {
+        mut v,
+        mut w,
+        mut x,
+        mut y,
+        mut z,
+    } = state;
+    let mul = K1.wrapping_add((z & 0xff) << 1);
+    // the last 64 bytes of input
+    let s = &s[len - 64..];
+
+    w.0 = w.0.wrapping_add(((len - 1) & 63) as u64);
+    v.0 = v.0.wrapping_add(w.0);
+    w.0 = w.0.wrapping_add(v.0);
+    x = x
+        .wrapping_add(y)
+        .wrapping_add(v.0)
+        .wrapping_add(fetch64(s, 8))
+        .rotate_right(37)
+        .wrapping_mul(mul);
+    y = y
+        .wrapping_add(v.1)
+        .wrapping_add(fetch64(s, 48))
+        .rotate_right(42)
+        .wrapping_mul(mul);
+    x ^= w.1.wrapping_mul(9);
+    y = y
+        .wrapping_add(v.0.wrapping_mul(9))
+        .wrapping_add(fetch64(s, 40));
+    z = z.wrapping_add(w.0).rotate_right(33).wrapping_mul(mul);
+    v = weak_len_32_with_seeds(s, v.1.wrapping_mul(mul), x.wrapping_add(w.0));
+    w = weak_len_32_with_seeds(
+        &s[32..],
+        z.wrapping_add(w.1),
+        y.wrapping_add(fetch64(s, 16)),
+    );
+    mem::swap(&mut z, &mut x);
+
+    len_16_mul(
+        len_16_mul(v.0, w.0, mul)
+            .wrapping_add(smix(y).wrapping_mul(K0))
+            .wrapping_add(z),
+        len_16_mul(v.1, w.1, mul).wrapping_add(x),
+        mul,
+    )
+}
+
+/// Returns a decent 128-bit hash for strings of any length, based on City and Murmur.
+fn cc_city_murmur(s: &[u8], seed: (u64, u64)) -> (u64, u64) {
+    let len = s.len();
+    let (mut a, mut b) = seed;
+    let mut c;
+    let mut d;
+
+    if len <= 16 {
+        a = smix(a.wrapping_mul(K1)).wrapping_mul(K1);
+        c = b.wrapping_mul(K1).wrapping_add(na_len_0_to_16(s));
+        d = smix(a.wrapping_add(if len >= 8 { fetch64(s, 0) } else { c }));
+    } else {
+        c = len_16(fetch64(s, len - 8).wrapping_add(K1), a);
+        d = len_16(
+            b.wrapping_add(len as u64),
+            c.wrapping_add(fetch64(s, len - 16)),
+        );
+        a = a.wrapping_add(d);
+
+        for i in (0..len - 16).step_by(16) {
+            a ^= smix(fetch64(s, i).wrapping_mul(K1)).wrapping_mul(K1);
+            a = a.wrapping_mul(K1);
+            b ^= a;
+            c ^= smix(fetch64(s, i + 8).wrapping_mul(K1)).wrapping_mul(K1);
+            c = c.wrapping_mul(K1);
+            d ^= c;
+        }
+    }
+
+    a = len_16(a, c);
+    b = len_16(d, b);
+
+    (a ^ b, len_16(b, a))
+}
+
+fn cc_city_with_seed(s: &[u8], seed: (u64, u64)) -> (u64, u64) {
+    let len = s.len();
+
+    if len < 128 {
+        return cc_city_murmur(s, seed);
+    }
+
+    // we expect len >= 128 to be the common case.  Keep 56 bytes of state:
+    // v, w, x, y, and z.
+    let (x, y) = seed;
+    let z = (len as u64).wrapping_mul(K1);
+    let v0 = (y ^ K1)
+        .rotate_right(49)
+        .wrapping_mul(K1)
+        .wrapping_add(fetch64(s, 0));
+    let mut state = State {
+        v: (
+            v0,
+            v0.rotate_right(42)
+                .wrapping_mul(K1)
+                .wrapping_add(fetch64(s, 8)),
+        ),
+        w: (
+            y.wrapping_add(z)
+                .rotate_right(35)
+                .wrapping_mul(K1)
+                .wrapping_add(x),
+            x.wrapping_add(fetch64(s, 88))
+                .rotate_right(53)
+                .wrapping_mul(K1),
+        ),
+        x,
+        y,
+        z,
+    };
+
+    // this is the same inner loop as `farmhash64_na`, manually unrolled.
+    let mut chunks = s.chunks_exact(128);
+
+    for chunk in &mut chunks {
+        state.update(&chunk[..64]);
+        state.update(&chunk[64..]);
+    }
+
+    let State {
+        mut v,
+        mut w,
+        mut x,
+        mut y,
+        mut z,
+    } = state;
+    let tail = chunks.remainder().len();
+
+    x = x.wrapping_add(v.0.wrapping_add(z).rotate_right(49).wrapping_mul(K0));
+    y = y.wrapping_mul(K0).wrapping_add(w.1.rotate_right(37));
+    z = z.wrapping_mul(K0).wrapping_add(w.0.rotate_right(27));
+    w.0 = w.0.wrapping_mul(9);
+    v.0 = v.0.wrapping_mul(K0);
+
+    // if 0 < tail < 128, hash up to 4 chunks of 32 bytes each from the end of s.
+    let mut tail_done = 0;
+
+    while tail_done < tail {
+        tail_done += 32;
+
+        let chunk = &s[len - tail_done..];
+
+        y = x
+            .wrapping_add(y)
+            .rotate_right(42)
+            .wrapping_mul(K0)
+            .wrapping_add(v.1);
+        w.0 = w.0.wrapping_add(fetch64(chunk, 16));
+        x = x.wrapping_mul(K0).wrapping_add(w.0);
+        z = z.wrapping_add(w.1).wrapping_add(fetch64(chunk, 0));
+        w.1 = w.1.wrapping_add(v.0);
+        v = weak_len_32_with_seeds(chunk, v.0.wrapping_add(z), v.1);
+        v.0 = v.0.wrapping_mul(K0);
+    }
+
+    // at this point our 56 bytes of state should contain more than
+    // enough information for a strong 128-bit hash.  We use two
+    // different 56-byte-to-8-byte hashes to get a 16-byte final result.
+    x = len_16(x, v.0);
+    y = len_16(y.wrapping_add(z), w.0);
+
+    (
+        len_16(x.wrapping_add(v.1), w.1).wrapping_add(y),
+        len_16(x.wrapping_add(w.1), y.wrapping_add(v.1)),
+    )
+}
+
+pub fn fingerprint128(s: &[u8]) -> u128 {
+    let (lo, hi) = if s.len() >= 16 {
+        cc_city_with_seed(&s[16..], (fetch64(s, 0), fetch64(s, 8).wrapping_add(K0)))
+    } else {
+        cc_city_with_seed(s, (K0, K1))
+    };
+
+    u128::from(lo) | (u128::from(hi) << 64)
+}
+
+#[inline(always)]
+pub fn fingerprint_u64(n: u64) -> u64 {
+    let mut b = n.wrapping_mul(K_MUL);
+    b ^= b >> 44;
+    b = b.wrapping_mul(K_MUL);
+    b ^= b >> 41;
+    b.wrapping_mul(K_MUL)
+}
+
+#[inline(always)]
+pub fn fingerprint_u128(n: u128) -> u64 {
+    let (lo, hi) = (n as u64, (n >> 64) as u64);
+    let mut a = (lo ^ hi).wrapping_mul(K_MUL);
+    a ^= a >> 47;
+    let mut b = (hi ^ a).wrapping_mul(K_MUL);
+    b ^= b >> 44;
+    b = b.wrapping_mul(K_MUL);
+    b ^= b >> 41;
+    b.wrapping_mul(K_MUL)
+}
@@ -0,0 +1,34 @@
+//! `FarmHash` fingerprints backed by the vendored C implementation.
+use core::mem;
+
+use crate::ffi;
+
+#[inline(always)]
+pub fn fingerprint32(bytes: &[u8]) -> u32 {
+    unsafe { ffi::farmhash_fingerprint32(bytes.as_ptr() as *const _, bytes.len()) }
+}
+
+#[inline(always)]
+pub fn fingerprint64(bytes: &[u8]) -> u64 {
+    unsafe { ffi::farmhash_fingerprint64(bytes.as_ptr() as *const _, bytes.len()) }
+}
+
+#[inline(always)]
+pub fn fingerprint128(bytes: &[u8]) -> u128 {
+    unsafe {
+        mem::transmute(ffi::farmhash_fingerprint128(
+            bytes.as_ptr() as *const _,
+            bytes.len(),
+        ))
+    }
+}
+
+#[inline(always)]
+pub fn fingerprint_u64(n: u64) -> u64 {
+    unsafe { ffi::farmhash_fingerprint_uint64(n) }
+}
+
+#[inline(always)]
+pub fn fingerprint_u128(n: u128) -> u64 {
+    unsafe { ffi::farmhash_fingerprint_uint128(mem::transmute(n)) }
+}
@@ -0,0 +1,1039 @@
+//! Filters built from 64-bit key fingerprints, more compact than the Bloom filters.
+//!
+//! - `CuckooFilter`, a dynamic filter with 16-bit fingerprints, supports deletion,
+//!   and `CuckooFilterView`, a read-only view of a serialized cuckoo filter.
+//! - `XorFilter`, a static xor filter, `Xor8` and `Xor16` take about 9.84 and 19.7 bits per key.
+//! - `BinaryFuseFilter`, a static binary fuse filter, `BinaryFuse8` and `BinaryFuse16`
+//!   take about 9 and 18 bits per key for large sets, and are faster to build.
+//!
+//! The filters take the 64-bit hashes of the keys, computed with any of the 64-bit hash functions,
+//! e.g. `farm::fingerprint64` or `xxh3::hash64`, which must be the same to build and query a filter.
+//!
+//! The static filters and `CuckooFilterView` are stored in their serialized format, a 32-byte header
+//! followed by the fingerprints in little endian, so a filter can be used from a memory-mapped file
+//! without copying or parsing the fingerprints.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(any(feature = "sys", feature = "pure-farm"))]
+//! # {
+//! use fasthash::{farm, filter::{BinaryFuse8, CuckooFilter}};
+//!
+//! let keys = ["apple", "banana", "cherry"];
+//! let hashes = keys.iter().map(farm::fingerprint64).collect::<Vec<_>>();
+//!
+//! let filter = BinaryFuse8::build(&hashes).unwrap();
+//!
+//! assert!(filter.contains(farm::fingerprint64("banana")));
+//!
+//! let bytes = filter.as_bytes();
+//! let filter = BinaryFuse8::from_bytes(bytes).unwrap();
+//!
+//! assert!(filter.contains(farm::fingerprint64("cherry")));
+//!
+//! let mut filter = CuckooFilter::new(1000);
+//!
+//! filter.insert(farm::fingerprint64("apple")).unwrap();
+//!
+//! assert!(filter.contains(farm::fingerprint64("apple")));
+//! assert!(filter.remove(farm::fingerprint64("apple")));
+//! assert!(!filter.contains(farm::fingerprint64("apple")));
+//! # }
+//! ```
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::BitXor;
+
+use crate::hasher::{mix64, GOLDEN_GAMMA};
+use crate::state::{self, Reader, Writer};
+
+/// The errors of building or deserializing the filters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The cuckoo filter is full.
+    Full,
+    /// The static filter can't be built from the keys.
+    ConstructionFailed,
+    /// The bytes are not a serialized filter of this kind.
+    InvalidFormat,
+    /// The serialized filter has an unsupported format version.
+    UnsupportedVersion(u8),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Full => f.write_str("filter is full"),
+            Error::ConstructionFailed => f.write_str("filter construction failed"),
+            Error::InvalidFormat => f.write_str("invalid filter format"),
+            Error::UnsupportedVersion(v) => write!(f, "unsupported filter format version {}", v),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<state::Error> for Error {
+    fn from(err: state::Error) -> Self {
+        match err {
+            state::Error::InvalidFormat => Error::InvalidFormat,
+            state::Error::UnsupportedVersion(v) => Error::UnsupportedVersion(v),
+        }
+    }
+}
+
+/// The magic number of the serialized filters.
+const MAGIC: &[u8; 4] = b"FHXF";
+/// The current version of the serialization format.
+pub const FORMAT_VERSION: u8 = 1;
+/// The size of the header: magic, version, kind, reserved, seed, number of keys and two parameters.
+const HEADER_SIZE: usize = 32;
+
+/// The kinds of serialized filters.
+const KIND_XOR: u8 = 0;
+const KIND_BINARY_FUSE: u8 = 2;
+const KIND_CUCKOO: u8 = 4;
+
+/// The number of attempts to build a static filter with a different seed.
+const MAX_ATTEMPTS: u64 = 100;
+
+/// The finalizer of `MurmurHash3`, mixes the key hash with the seed.
+#[inline(always)]
+fn mix(key: u64, seed: u64) -> u64 {
+    let mut h = key.wrapping_add(seed);
+    h = (h ^ (h >> 33)).wrapping_mul(0xff51_afd7_ed55_8ccd);
+    h = (h ^ (h >> 33)).wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    h ^ (h >> 33)
+}
+
+/// A step of `SplitMix64`, derives the seed of each attempt and the relocations.
+#[inline(always)]
+fn split_mix64(z: u64) -> u64 {
+    mix64(z.wrapping_add(GOLDEN_GAMMA))
+}
+
+/// Writes the header of a serialized filter, reserves `size` bytes for the fingerprints.
+fn write_header(kind: u8, seed: u64, keys: u64, params: [u32; 2], size: usize) -> Vec<u8> {
+    let mut w = Writer::header(MAGIC, FORMAT_VERSION, HEADER_SIZE - MAGIC.len() - 1 + size);
+
+    w.u8(kind)
+        .bytes(&[0, 0])
+        .u64(seed)
+        .u64(keys)
+        .u32(params[0])
+        .u32(params[1]);
+    w.into_bytes()
+}
+
+/// Reads the header of a serialized filter, returns the seed, number of keys and parameters.
+fn read_header(bytes: &[u8], kind: u8) -> Result<(u64, u64, [u32; 2]), Error> {
+    let mut r = Reader::header(bytes, MAGIC, FORMAT_VERSION)?;
+
+    if r.u8()? != kind || r.bytes(2)? != [0, 0] {
+        return Err(Error::InvalidFormat);
+    }
+
+    Ok((r.u64()?, r.u64()?, [r.u32()?, r.u32()?]))
+}
+
+/// The fingerprints stored in the static filters.
+pub trait Fingerprint: Copy + Default + Eq + BitXor<Output = Self> {
+    /// The size of a fingerprint in bytes.
+    const SIZE: usize;
+
+    /// Returns the fingerprint of a mixed hash.
+    fn from_hash(h: u64) -> Self;
+
+    /// Reads the fingerprint at `index` in little endian.
+    fn read(bytes: &[u8], index: usize) -> Self;
+
+    /// Appends the fingerprint in little endian.
+    fn write(self, bytes: &mut Vec<u8>);
+}
+
+impl Fingerprint for u8 {
+    const SIZE: usize = 1;
+
+    #[inline(always)]
+    fn from_hash(h: u64) -> Self {
+        (h ^ (h >> 32)) as u8
+    }
+
+    #[inline(always)]
+    fn read(bytes: &[u8], index: usize) -> Self {
+        bytes[index]
+    }
+
+    #[inline(always)]
+    fn write(self, bytes: &mut Vec<u8>) {
+        bytes.push(self)
+    }
+}
+
+impl Fingerprint for u16 {
+    const SIZE: usize = 2;
+
+    #[inline(always)]
+    fn from_hash(h: u64) -> Self {
+        (h ^ (h >> 32)) as u16
+    }
+
+    #[inline(always)]
+    fn read(bytes: &[u8], index: usize) -> Self {
+        u16::from_le_bytes([bytes[index * 2], bytes[index * 2 + 1]])
+    }
+
+    #[inline(always)]
+    fn write(self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.to_le_bytes())
+    }
+}
+
+/// Assigns the fingerprints of the keys to the slots by peeling the 3-hypergraph.
+///
+/// Returns `None` if the hypergraph has a cycle, and must be built with another seed.
+fn peel<F, P>(hashes: &[u64], slots: usize, seed: u64, positions: P) -> Option<Vec<F>>
+where
+    F: Fingerprint,
+    P: Fn(u64) -> [usize; 3],
+{
+    let mut counts = vec![0u32; slots];
+    let mut xors = vec![0u64; slots];
+
+    for &key in hashes {
+        let h = mix(key, seed);
+
+        for &p in &positions(h) {
+            counts[p] += 1;
+            xors[p] ^= h;
+        }
+    }
+
+    let mut queue = (0..slots).filter(|&p| counts[p] == 1).collect::<Vec<_>>();
+    let mut stack = Vec::with_capacity(hashes.len());
+
+    while let Some(p) = queue.pop() {
+        if counts[p] != 1 {
+            continue;
+        }
+
+        let h = xors[p];
+
+        stack.push((h, p));
+
+        for &q in &positions(h) {
+            counts[q] -= 1;
+            xors[q] ^= h;
+
+            if counts[q] == 1 {
+                queue.push(q);
+            }
+        }
+    }
+
+    if stack.len() != hashes.len() {
+        return None;
+    }
+
+    let mut fingerprints = vec![F::default(); slots];
+
+    for &(h, p) in stack.iter().rev() {
+        let [a, b, c] = positions(h);
+
+        // the slot `p` is still zero, it doesn't change the xor
+        fingerprints[p] = F::from_hash(h) ^ fingerprints[a] ^ fingerprints[b] ^ fingerprints[c];
+    }
+
+    Some(fingerprints)
+}
+
+/// Builds the fingerprints of the unique hashes with a new seed until the peeling succeeds.
+fn build<F, P>(hashes: &[u64], slots: usize, positions: P) -> Result<(u64, Vec<F>), Error>
+where
+    F: Fingerprint,
+    P: Fn(u64) -> [usize; 3],
+{
+    (0..MAX_ATTEMPTS)
+        .map(split_mix64)
+        .find_map(|seed| peel(hashes, slots, seed, &positions).map(|f| (seed, f)))
+        .ok_or(Error::ConstructionFailed)
+}
+
+/// Serializes the fingerprints after the header.
+fn to_bytes<F: Fingerprint>(kind: u8, seed: u64, keys: u64, params: [u32; 2], f: &[F]) -> Vec<u8> {
+    let mut bytes = write_header(
+        kind + F::SIZE as u8 - 1,
+        seed,
+        keys,
+        params,
+        f.len() * F::SIZE,
+    );
+
+    for &f in f {
+        f.write(&mut bytes);
+    }
+
+    bytes
+}
+
+macro_rules! impl_static_filter {
+    ($filter:ident, $kind:expr) => {
+        impl<F: Fingerprint> $filter<F> {
+            /// Builds the filter from the hashes of the keys, the duplicated hashes are ignored.
+            pub fn build(hashes: &[u64]) -> Result<Self, Error> {
+                let mut hashes = hashes.to_vec();
+
+                hashes.sort_unstable();
+                hashes.dedup();
+
+                let params = Self::params(hashes.len());
+                let (slots, positions) = Self::layout(params).unwrap();
+                let (seed, fingerprints) = build::<F, _>(&hashes, slots, positions)?;
+                let bytes = to_bytes($kind, seed, hashes.len() as u64, params, &fingerprints);
+
+                Ok($filter::from_bytes(bytes).unwrap())
+            }
+        }
+
+        impl<F: Fingerprint, B: AsRef<[u8]>> $filter<F, B> {
+            /// Wraps a serialized filter without copying it.
+            pub fn from_bytes(bytes: B) -> Result<Self, Error> {
+                let (seed, keys, params) = read_header(bytes.as_ref(), $kind + F::SIZE as u8 - 1)?;
+                let (slots, _) = Self::layout(params).ok_or(Error::InvalidFormat)?;
+
+                if bytes.as_ref().len() != HEADER_SIZE + slots * F::SIZE {
+                    return Err(Error::InvalidFormat);
+                }
+
+                Ok($filter {
+                    bytes,
+                    seed,
+                    keys,
+                    params,
+                    phantom: PhantomData,
+                })
+            }
+
+            /// Returns the serialized filter.
+            pub fn as_bytes(&self) -> &[u8] {
+                self.bytes.as_ref()
+            }
+
+            /// Consumes the filter, returns the serialized filter.
+            pub fn into_inner(self) -> B {
+                self.bytes
+            }
+
+            /// Returns the number of unique keys the filter was built from.
+            pub fn len(&self) -> usize {
+                self.keys as usize
+            }
+
+            /// Returns `true` if the filter was built from no key.
+            pub fn is_empty(&self) -> bool {
+                self.keys == 0
+            }
+
+            /// Returns the number of bits per key.
+            pub fn bits_per_key(&self) -> f64 {
+                (self.bytes.as_ref().len() - HEADER_SIZE) as f64 * 8.0 / self.keys.max(1) as f64
+            }
+
+            /// Returns `true` if the key may be in the set, `false` if it is definitely not.
+            #[inline(always)]
+            pub fn contains(&self, hash: u64) -> bool {
+                let h = mix(hash, self.seed);
+                let (_, positions) = Self::layout(self.params).unwrap();
+                let [a, b, c] = positions(h);
+                let fingerprints = &self.bytes.as_ref()[HEADER_SIZE..];
+
+                F::from_hash(h)
+                    == F::read(fingerprints, a)
+                        ^ F::read(fingerprints, b)
+                        ^ F::read(fingerprints, c)
+            }
+        }
+
+        impl<F, B: Clone> Clone for $filter<F, B> {
+            fn clone(&self) -> Self {
+                $filter {
+                    bytes: self.bytes.clone(),
+                    seed: self.seed,
+                    keys: self.keys,
+                    params: self.params,
+                    phantom: PhantomData,
+                }
+            }
+        }
+
+        impl<F, B: AsRef<[u8]>> fmt::Debug for $filter<F, B> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.debug_struct(stringify!($filter))
+                    .field("len", &self.keys)
+                    .field("size", &self.bytes.as_ref().len())
+                    .finish()
+            }
+        }
+    };
+}
+
+/// The xor filter, each key is the xor of the fingerprints in three blocks.
+///
+/// See "Xor Filters: Faster and Smaller Than Bloom and Cuckoo Filters"
+/// by Thomas Mueller Graf and Daniel Lemire.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(any(feature = "sys", feature = "pure-xxh3"))]
+/// # {
+/// use fasthash::{filter::Xor16, xxh3};
+///
+/// let hashes = (0..1000_u32).map(|i| xxh3::hash64(&i.to_le_bytes())).collect::<Vec<_>>();
+/// let filter = Xor16::build(&hashes).unwrap();
+///
+/// assert_eq!(filter.len(), 1000);
+/// assert!(hashes.iter().all(|&h| filter.contains(h)));
+/// assert!(!filter.contains(xxh3::hash64(b"hello")));
+/// # }
+/// ```
+pub struct XorFilter<F, B = Vec<u8>> {
+    bytes: B,
+    seed: u64,
+    keys: u64,
+    params: [u32; 2],
+    phantom: PhantomData<F>,
+}
+
+/// The xor filter with 8-bit fingerprints, a false positive rate of about 0.39%.
+pub type Xor8<B = Vec<u8>> = XorFilter<u8, B>;
+/// The xor filter with 16-bit fingerprints, a false positive rate of about 0.0015%.
+pub type Xor16<B = Vec<u8>> = XorFilter<u16, B>;
+
+impl_static_filter!(XorFilter, KIND_XOR);
+
+impl<F, B> XorFilter<F, B> {
+    /// Returns the block length for `keys` keys.
+    fn params(keys: usize) -> [u32; 2] {
+        [((32 + (keys as f64 * 1.23).ceil() as usize) / 3) as u32, 0]
+    }
+
+    /// Returns the number of fingerprints and the positions of a key.
+    #[inline(always)]
+    fn layout(params: [u32; 2]) -> Option<(usize, impl Fn(u64) -> [usize; 3])> {
+        let block_length = u64::from(params[0]);
+        let reduce = move |h: u64| (((h & 0xffff_ffff) * block_length) >> 32) as usize;
+
+        if block_length == 0 || params[1] != 0 {
+            return None;
+        }
+
+        Some((block_length as usize * 3, move |h: u64| {
+            [
+                reduce(h),
+                reduce(h.rotate_left(21)) + block_length as usize,
+                reduce(h.rotate_left(42)) + block_length as usize * 2,
+            ]
+        }))
+    }
+}
+
+/// The binary fuse filter, each key is the xor of the fingerprints in three consecutive segments.
+///
+/// See "Binary Fuse Filters: Fast and Smaller Than Xor Filters"
+/// by Thomas Mueller Graf and Daniel Lemire.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(any(feature = "sys", feature = "pure-xxh3"))]
+/// # {
+/// use fasthash::{filter::BinaryFuse16, xxh3};
+///
+/// let hashes = (0..1000_u32).map(|i| xxh3::hash64(&i.to_le_bytes())).collect::<Vec<_>>();
+/// let filter = BinaryFuse16::build(&hashes).unwrap();
+///
+/// assert!(hashes.iter().all(|&h| filter.contains(h)));
+/// assert!(!filter.contains(xxh3::hash64(b"hello")));
+/// # }
+/// ```
+pub struct BinaryFuseFilter<F, B = Vec<u8>> {
+    bytes: B,
+    seed: u64,
+    keys: u64,
+    params: [u32; 2],
+    phantom: PhantomData<F>,
+}
+
+/// The binary fuse filter with 8-bit fingerprints, a false positive rate of about 0.39%.
+pub type BinaryFuse8<B = Vec<u8>> = BinaryFuseFilter<u8, B>;
+/// The binary fuse filter with 16-bit fingerprints, a false positive rate of about 0.0015%.
+pub type BinaryFuse16<B = Vec<u8>> = BinaryFuseFilter<u16, B>;
+
+impl_static_filter!(BinaryFuseFilter, KIND_BINARY_FUSE);
+
+impl<F, B> BinaryFuseFilter<F, B> {
+    /// Returns the segment length and the number of segments for `keys` keys.
+    fn params(keys: usize) -> [u32; 2] {
+        let n = keys as f64;
+        let segment_length = if keys == 0 {
+            4
+        } else {
+            (1u32 << (n.ln() / 3.33f64.ln() + 2.25).floor() as u32).min(1 << 18)
+        };
+        let capacity = if keys > 1 {
+            (n * (0.875 + 0.25 * 1e6f64.ln() / n.ln()).max(1.125)).round() as usize
+        } else {
+            0
+        };
+        let segments = (capacity.max(1) - 1) / segment_length as usize + 1;
+
+        [segment_length, (segments.max(3) - 2) as u32]
+    }
+
+    /// Returns the number of fingerprints and the positions of a key.
+    #[inline(always)]
+    fn layout(params: [u32; 2]) -> Option<(usize, impl Fn(u64) -> [usize; 3])> {
+        let [segment_length, segments] = params;
+
+        if !segment_length.is_power_of_two() || segment_length > 1 << 18 || segments == 0 {
+            return None;
+        }
+
+        let segment_length = u64::from(segment_length);
+        let segment_count_length = u64::from(segments) * segment_length;
+        let mask = segment_length - 1;
+
+        Some((
+            (segment_count_length + 2 * segment_length) as usize,
+            move |h: u64| {
+                let h0 = ((u128::from(h) * u128::from(segment_count_length)) >> 64) as u64;
+                let h1 = (h0 + segment_length) ^ ((h >> 18) & mask);
+                let h2 = (h0 + 2 * segment_length) ^ (h & mask);
+
+                [h0 as usize, h1 as usize, h2 as usize]
+            },
+        ))
+    }
+}
+
+/// The number of fingerprints per bucket.
+const BUCKET_SIZE: usize = 4;
+/// The maximum number of relocations of an insertion.
+const MAX_KICKS: usize = 500;
+
+/// The bucket and the fingerprint kept aside when a cuckoo filter is full.
+type Victim = Option<(usize, u16)>;
+
+/// The cuckoo filter with 16-bit fingerprints in buckets of 4, supports deletion.
+///
+/// Each key has two candidate buckets, the second is derived from the first and the fingerprint,
+/// so a fingerprint can be relocated without the key.
+/// When both buckets are full, the insertion relocates up to 500 fingerprints,
+/// the last one is kept aside and the filter is full.
+///
+/// A key may be inserted several times, it must be removed as many times.
+///
+/// See "Cuckoo Filter: Practically Better Than Bloom"
+/// by Bin Fan, David G. Andersen, Michael Kaminsky and Michael D. Mitzenmacher.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(any(feature = "sys", feature = "pure-xxh3"))]
+/// # {
+/// use fasthash::{filter::CuckooFilter, xxh3};
+///
+/// let mut filter = CuckooFilter::new(1000);
+///
+/// filter.insert(xxh3::hash64(b"hello")).unwrap();
+///
+/// assert_eq!(filter.len(), 1);
+/// assert!(filter.contains(xxh3::hash64(b"hello")));
+///
+/// let filter = CuckooFilter::from_bytes(&filter.to_bytes()).unwrap();
+///
+/// assert!(filter.contains(xxh3::hash64(b"hello")));
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct CuckooFilter {
+    buckets: Vec<u16>,
+    len: usize,
+    victim: Victim,
+    rng: u64,
+}
+
+impl CuckooFilter {
+    /// Constructs a new filter for at least `capacity` keys at a load factor of 95%.
+    pub fn new(capacity: usize) -> Self {
+        let buckets = (capacity * 100 / 95 / BUCKET_SIZE + 1).next_power_of_two();
+
+        CuckooFilter {
+            buckets: vec![0; buckets * BUCKET_SIZE],
+            len: 0,
+            victim: None,
+            rng: 0,
+        }
+    }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there is no key.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of fingerprint slots.
+    pub fn capacity(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// Returns `true` if the last insertion failed to find a slot.
+    pub fn is_full(&self) -> bool {
+        self.victim.is_some()
+    }
+
+    /// Removes all the keys.
+    pub fn clear(&mut self) {
+        for f in self.buckets.iter_mut() {
+            *f = 0;
+        }
+
+        self.len = 0;
+        self.victim = None;
+    }
+
+    /// Returns the first bucket and the non-zero fingerprint of a key.
+    #[inline(always)]
+    fn index(&self, hash: u64) -> (usize, u16) {
+        cuckoo_index(hash, self.buckets.len() / BUCKET_SIZE)
+    }
+
+    /// Returns the other bucket of a fingerprint.
+    #[inline(always)]
+    fn alt_index(&self, index: usize, fingerprint: u16) -> usize {
+        cuckoo_alt_index(index, fingerprint, self.buckets.len() / BUCKET_SIZE)
+    }
+
+    #[inline(always)]
+    fn bucket(&mut self, index: usize) -> &mut [u16] {
+        &mut self.buckets[index * BUCKET_SIZE..(index + 1) * BUCKET_SIZE]
+    }
+
+    /// Puts the fingerprint in a free slot of the bucket.
+    #[inline(always)]
+    fn put(&mut self, index: usize, fingerprint: u16) -> bool {
+        self.bucket(index)
+            .iter_mut()
+            .find(|f| **f == 0)
+            .map(|f| *f = fingerprint)
+            .is_some()
+    }
+
+    /// Inserts a key by its hash.
+    ///
+    /// Returns `Error::Full` if the filter was already full,
+    /// the key is still inserted when this insertion fills the filter.
+    pub fn insert(&mut self, hash: u64) -> Result<(), Error> {
+        if self.victim.is_some() {
+            return Err(Error::Full);
+        }
+
+        let (i1, fingerprint) = self.index(hash);
+        let i2 = self.alt_index(i1, fingerprint);
+
+        self.len += 1;
+
+        if self.put(i1, fingerprint) || self.put(i2, fingerprint) {
+            return Ok(());
+        }
+
+        self.relocate(if self.rng & 1 == 0 { i1 } else { i2 }, fingerprint);
+
+        Ok(())
+    }
+
+    /// Relocates the fingerprints until one finds a free slot, keeps the last one aside.
+    fn relocate(&mut self, mut index: usize, mut fingerprint: u16) {
+        for _ in 0..MAX_KICKS {
+            self.rng = split_mix64(self.rng);
+
+            let slot = self.rng as usize % BUCKET_SIZE;
+
+            std::mem::swap(&mut fingerprint, &mut self.bucket(index)[slot]);
+            index = self.alt_index(index, fingerprint);
+
+            if self.put(index, fingerprint) {
+                return;
+            }
+        }
+
+        self.victim = Some((index, fingerprint));
+    }
+
+    /// Returns `true` if the key may be in the set, `false` if it is definitely not.
+    pub fn contains(&self, hash: u64) -> bool {
+        cuckoo_contains(
+            hash,
+            self.buckets.len() / BUCKET_SIZE,
+            self.victim,
+            |i, fingerprint| {
+                self.buckets[i * BUCKET_SIZE..(i + 1) * BUCKET_SIZE].contains(&fingerprint)
+            },
+        )
+    }
+
+    /// Removes a key by its hash, returns `false` if it was not found.
+    ///
+    /// Removing a key that was never inserted may remove another key with the same fingerprint.
+    pub fn remove(&mut self, hash: u64) -> bool {
+        let (i1, fingerprint) = self.index(hash);
+        let i2 = self.alt_index(i1, fingerprint);
+
+        if self
+            .victim
+            .filter(|&(i, f)| f == fingerprint && (i == i1 || i == i2))
+            .is_some()
+        {
+            self.victim = None;
+            self.len -= 1;
+            return true;
+        }
+
+        for &i in &[i1, i2] {
+            if let Some(f) = self.bucket(i).iter_mut().find(|f| **f == fingerprint) {
+                *f = 0;
+                self.len -= 1;
+
+                if let Some((i, f)) = self.victim.take() {
+                    if !self.put(i, f) {
+                        self.relocate(i, f);
+                    }
+                }
+
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Serializes the filter.
+    ///
+    /// The format is the magic `FHXF`, the format version, the filter kind, two reserved bytes,
+    /// the state of the relocations as `u64`, the number of keys as `u64`,
+    /// the bucket and the fingerprint kept aside as `u32`, or `0` if there is none,
+    /// followed by the fingerprints as `u16`, all in little endian.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let (index, fingerprint) = self.victim.unwrap_or((0, 0));
+        let mut bytes = write_header(
+            KIND_CUCKOO,
+            self.rng,
+            self.len as u64,
+            [index as u32, u32::from(fingerprint)],
+            self.buckets.len() * 2,
+        );
+
+        for f in &self.buckets {
+            bytes.extend_from_slice(&f.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    /// Deserializes a filter serialized by `to_bytes`.
+    ///
+    /// The fingerprints are copied to be updated, see `CuckooFilterView` to query them in place.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let (rng, len, victim) = read_cuckoo(bytes)?;
+
+        Ok(CuckooFilter {
+            buckets: bytes[HEADER_SIZE..]
+                .chunks(2)
+                .map(|f| u16::from_le_bytes([f[0], f[1]]))
+                .collect(),
+            len,
+            victim,
+            rng,
+        })
+    }
+}
+
+/// Returns the first bucket and the non-zero fingerprint of a key.
+#[inline(always)]
+fn cuckoo_index(hash: u64, buckets: usize) -> (usize, u16) {
+    let fingerprint = match hash as u16 {
+        0 => 1,
+        f => f,
+    };
+
+    ((hash >> 32) as usize & (buckets - 1), fingerprint)
+}
+
+/// Returns the other bucket of a fingerprint.
+#[inline(always)]
+fn cuckoo_alt_index(index: usize, fingerprint: u16, buckets: usize) -> usize {
+    (index ^ mix(u64::from(fingerprint), 0) as usize) & (buckets - 1)
+}
+
+/// Returns `true` if the fingerprint of the key is in one of its buckets or kept aside.
+#[inline(always)]
+fn cuckoo_contains<C>(hash: u64, buckets: usize, victim: Victim, bucket: C) -> bool
+where
+    C: Fn(usize, u16) -> bool,
+{
+    let (i1, fingerprint) = cuckoo_index(hash, buckets);
+    let i2 = cuckoo_alt_index(i1, fingerprint, buckets);
+
+    bucket(i1, fingerprint)
+        || bucket(i2, fingerprint)
+        || victim
+            .filter(|&(i, f)| f == fingerprint && (i == i1 || i == i2))
+            .is_some()
+}
+
+/// Checks a serialized cuckoo filter, returns the state of the relocations,
+/// the number of keys and the fingerprint kept aside.
+fn read_cuckoo(bytes: &[u8]) -> Result<(u64, usize, Victim), Error> {
+    let (rng, len, [index, fingerprint]) = read_header(bytes, KIND_CUCKOO)?;
+    let fingerprints = &bytes[HEADER_SIZE..];
+    let buckets = fingerprints.len() / 2 / BUCKET_SIZE;
+
+    if fingerprints.len() != buckets * 2 * BUCKET_SIZE
+        || !buckets.is_power_of_two()
+        || index as usize >= buckets
+        || fingerprint > 0xffff
+        || (fingerprint == 0 && index != 0)
+    {
+        return Err(Error::InvalidFormat);
+    }
+
+    Ok((
+        rng,
+        len as usize,
+        Some((index as usize, fingerprint as u16)).filter(|&(_, f)| f != 0),
+    ))
+}
+
+/// A read-only view of a serialized `CuckooFilter`, queries the fingerprints without copying them.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(any(feature = "sys", feature = "pure-xxh3"))]
+/// # {
+/// use fasthash::{filter::{CuckooFilter, CuckooFilterView}, xxh3};
+///
+/// let mut filter = CuckooFilter::new(1000);
+///
+/// filter.insert(xxh3::hash64(b"hello")).unwrap();
+///
+/// let bytes = filter.to_bytes();
+/// let view = CuckooFilterView::from_bytes(&bytes[..]).unwrap();
+///
+/// assert_eq!(view.len(), 1);
+/// assert!(view.contains(xxh3::hash64(b"hello")));
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct CuckooFilterView<B = Vec<u8>> {
+    bytes: B,
+    len: usize,
+    victim: Victim,
+}
+
+impl<B: AsRef<[u8]>> CuckooFilterView<B> {
+    /// Wraps a filter serialized by `CuckooFilter::to_bytes` without copying it.
+    pub fn from_bytes(bytes: B) -> Result<Self, Error> {
+        let (_, len, victim) = read_cuckoo(bytes.as_ref())?;
+
+        Ok(CuckooFilterView { bytes, len, victim })
+    }
+
+    /// Returns the serialized filter.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.bytes.as_ref()
+    }
+
+    /// Consumes the view, returns the serialized filter.
+    pub fn into_inner(self) -> B {
+        self.bytes
+    }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there is no key.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the key may be in the set, `false` if it is definitely not.
+    pub fn contains(&self, hash: u64) -> bool {
+        let fingerprints = &self.bytes.as_ref()[HEADER_SIZE..];
+
+        cuckoo_contains(
+            hash,
+            fingerprints.len() / 2 / BUCKET_SIZE,
+            self.victim,
+            |i, fingerprint| {
+                (i * BUCKET_SIZE..(i + 1) * BUCKET_SIZE)
+                    .any(|j| u16::read(fingerprints, j) == fingerprint)
+            },
+        )
+    }
+}
+
+impl<B: AsRef<[u8]>> fmt::Debug for CuckooFilterView<B> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CuckooFilterView")
+            .field("len", &self.len)
+            .field("size", &self.bytes.as_ref().len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sea::Hash64;
+    use crate::state::check_format;
+    use crate::FastHash;
+
+    fn hashes(keys: std::ops::Range<u32>) -> Vec<u64> {
+        keys.map(|i| Hash64::hash(i.to_le_bytes())).collect()
+    }
+
+    macro_rules! test_static_filter {
+        ($name:ident, $filter:ident, $fpp:expr, $bits:expr) => {
+            #[test]
+            fn $name() {
+                for &n in &[0, 1, 2, 10, 100, 10_000, 100_000] {
+                    let keys = hashes(0..n);
+                    let filter = <$filter>::build(&keys).unwrap();
+
+                    assert_eq!(filter.len(), n as usize);
+                    assert!(keys.iter().all(|&h| filter.contains(h)));
+
+                    let bytes = filter.as_bytes().to_vec();
+                    let view = <$filter<&[u8]>>::from_bytes(&bytes[..]).unwrap();
+
+                    assert!(keys.iter().all(|&h| view.contains(h)));
+
+                    if n == 100_000 {
+                        let fp = hashes(n..n * 2)
+                            .into_iter()
+                            .filter(|&h| filter.contains(h))
+                            .count();
+
+                        assert!((fp as f64 / n as f64) < $fpp, "fp = {}", fp);
+                        assert!(filter.bits_per_key() < $bits);
+                    }
+                }
+
+                let bytes = <$filter>::build(&hashes(0..100)).unwrap().into_inner();
+
+                assert_eq!(
+                    <$filter<&[u8]>>::from_bytes(&bytes[..10]).unwrap_err(),
+                    Error::InvalidFormat
+                );
+                check_format(&bytes, |bytes| {
+                    <$filter<&[u8]>>::from_bytes(bytes).map(drop)
+                });
+            }
+        };
+    }
+
+    test_static_filter!(test_xor8, Xor8, 0.006, 10.0);
+    test_static_filter!(test_xor16, Xor16, 0.0002, 20.0);
+    test_static_filter!(test_binary_fuse8, BinaryFuse8, 0.006, 10.0);
+    test_static_filter!(test_binary_fuse16, BinaryFuse16, 0.0002, 20.0);
+
+    #[test]
+    fn test_duplicated_keys() {
+        let mut keys = hashes(0..1000);
+
+        keys.extend(hashes(0..1000));
+
+        let filter = Xor8::build(&keys).unwrap();
+
+        assert_eq!(filter.len(), 1000);
+        assert!(keys.iter().all(|&h| filter.contains(h)));
+        assert!(BinaryFuse8::build(&keys).is_ok());
+    }
+
+    #[test]
+    fn test_cuckoo_filter() {
+        let keys = hashes(0..10_000);
+        let mut filter = CuckooFilter::new(keys.len());
+
+        for &h in &keys {
+            filter.insert(h).unwrap();
+        }
+
+        assert_eq!(filter.len(), keys.len());
+        assert!(keys.iter().all(|&h| filter.contains(h)));
+
+        let fp = hashes(10_000..20_000)
+            .into_iter()
+            .filter(|&h| filter.contains(h))
+            .count();
+
+        assert!(fp < 50, "fp = {}", fp);
+
+        let bytes = filter.to_bytes();
+        let copy = CuckooFilter::from_bytes(&bytes).unwrap();
+
+        assert_eq!(copy.buckets, filter.buckets);
+        assert_eq!(copy.len(), filter.len());
+
+        let view = CuckooFilterView::from_bytes(&bytes[..]).unwrap();
+
+        assert_eq!(view.len(), filter.len());
+        assert!(keys.iter().all(|&h| view.contains(h)));
+        assert_eq!(
+            hashes(10_000..20_000)
+                .into_iter()
+                .filter(|&h| view.contains(h))
+                .count(),
+            fp
+        );
+        check_format(&bytes, CuckooFilter::from_bytes);
+        check_format(&bytes, |bytes| {
+            CuckooFilterView::from_bytes(bytes).map(drop)
+        });
+
+        for &h in &keys[..5000] {
+            assert!(filter.remove(h));
+        }
+
+        assert_eq!(filter.len(), 5000);
+        assert!(keys[5000..].iter().all(|&h| filter.contains(h)));
+
+        // fill the filter until an insertion fails
+        let mut filter = CuckooFilter::new(100);
+        let keys = hashes(0..1000);
+        let inserted = keys
+            .iter()
+            .take_while(|&&h| filter.insert(h).is_ok())
+            .count();
+
+        assert!(filter.is_full());
+        assert_eq!(filter.len(), inserted);
+        assert!(keys[..inserted].iter().all(|&h| filter.contains(h)));
+
+        let view = CuckooFilterView::from_bytes(filter.to_bytes()).unwrap();
+
+        assert!(keys[..inserted].iter().all(|&h| view.contains(h)));
+        assert!(filter.remove(keys[0]));
+        assert!(!filter.is_full());
+        assert!(keys[1..inserted].iter().all(|&h| filter.contains(h)));
+    }
+}
@@ -1,9 +1,14 @@
-use core::cell::RefCell;
 use core::hash::{BuildHasher, Hasher};
-use core::marker::PhantomData;
+#[cfg(feature = "std")]
+use core::{cell::RefCell, marker::PhantomData};
+#[cfg(feature = "std")]
 use std::io;
 
+use alloc::vec::Vec;
+use core::fmt;
+
 use num_traits::PrimInt;
+#[cfg(feature = "std")]
 use xoroshiro128::{Rng, SeedableRng, Xoroshiro128Rng};
 
 /// Generate a good, portable, forever-fixed hash value
@@ -18,6 +23,18 @@ pub trait BuildHasherExt: BuildHasher {
 }
 
 /// Fast non-cryptographic hash functions
+///
+/// # Integer keys
+///
+/// `hash_u32` and `hash_u64` always return the same value as hashing the little endian bytes
+/// of the integer. `city::Hash64`, `murmur3::Hash32`, `xx::Hash32` and `xx::Hash64` compute it
+/// in Rust without calling into C, the other hash functions hash the bytes.
+///
+/// The buffered hashers keep the integers written by `write_u8` to `write_u128` in their inline
+/// buffer, without allocating. When the key is a single `u32` or `u64`, they hash it with
+/// `hash_u32` or `hash_u64`, any other key, e.g. a single `u8`, `u16` or `u128`, is hashed as bytes.
+/// The streaming hashers of `xx`, `xxh3`, `t1ha2`, `spooky` and `highway` feed an integer
+/// to their state as its native endian bytes, like the default `Hasher::write_u*` methods.
 pub trait FastHash: BuildHasherExt {
     /// The output hash generated value.
     type Hash: PrimInt;
@@ -32,6 +49,40 @@ pub trait FastHash: BuildHasherExt {
     fn hash<T: AsRef<[u8]>>(bytes: T) -> Self::Hash {
         Self::hash_with_seed(bytes, Default::default())
     }
+
+    /// Hash functions for a `u32` integer key.
+    ///
+    /// The result is the same as hashing `n.to_le_bytes()`,
+    /// some hash functions override it with a Rust implementation to skip the FFI call.
+    #[inline(always)]
+    fn hash_u32(n: u32) -> Self::Hash {
+        Self::hash(n.to_le_bytes())
+    }
+
+    /// Hash functions for a `u32` integer key with seed.
+    ///
+    /// The result is the same as hashing `n.to_le_bytes()` with the seed.
+    #[inline(always)]
+    fn hash_u32_with_seed(n: u32, seed: Self::Seed) -> Self::Hash {
+        Self::hash_with_seed(n.to_le_bytes(), seed)
+    }
+
+    /// Hash functions for a `u64` integer key.
+    ///
+    /// The result is the same as hashing `n.to_le_bytes()`,
+    /// some hash functions override it with a Rust implementation to skip the FFI call.
+    #[inline(always)]
+    fn hash_u64(n: u64) -> Self::Hash {
+        Self::hash(n.to_le_bytes())
+    }
+
+    /// Hash functions for a `u64` integer key with seed.
+    ///
+    /// The result is the same as hashing `n.to_le_bytes()` with the seed.
+    #[inline(always)]
+    fn hash_u64_with_seed(n: u64, seed: Self::Seed) -> Self::Hash {
+        Self::hash_with_seed(n.to_le_bytes(), seed)
+    }
 }
 
 /// Fast non-cryptographic hasher
@@ -40,7 +91,11 @@ where
     Self: Sized,
 {
     /// The seed to generate hash value.
+    #[cfg(feature = "std")]
     type Seed: Default + Copy + From<Seed>;
+    /// The seed to generate hash value.
+    #[cfg(not(feature = "std"))]
+    type Seed: Default + Copy;
 
     /// The output type
     type Output;
@@ -52,6 +107,7 @@ where
     }
 
     /// Constructs a new `FastHasher` with a random seed.
+    #[cfg(feature = "std")]
     fn with_random_seed() -> Self {
         Self::with_seed(Seed::gen().into())
     }
@@ -85,6 +141,7 @@ pub trait BufHasher: FastHasher + AsRef<[u8]> {
 }
 
 /// Hasher in the streaming mode without buffer
+#[cfg(feature = "std")]
 pub trait StreamHasher: FastHasher + Sized {
     /// Writes the stream into this hasher.
     fn write_stream<R: io::Read>(&mut self, r: &mut R) -> io::Result<usize> {
@@ -130,6 +187,49 @@ pub trait HasherExt: Hasher {
     fn finish_ext(&self) -> u128;
 }
 
+/// Hasher whose state can be saved midway and restored later.
+///
+/// A snapshot is a deep copy, e.g. to hash a common prefix once and branch for many suffixes.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(any(feature = "sys", feature = "pure-xxh3"))]
+/// # {
+/// use std::hash::Hasher;
+///
+/// use fasthash::{xxh3, FastHasher, Snapshot};
+///
+/// let mut h = xxh3::Hasher64::new();
+///
+/// h.write(b"hello ");
+///
+/// let prefix = h.snapshot();
+///
+/// h.write(b"world");
+/// assert_eq!(h.finish(), xxh3::hash64("hello world"));
+///
+/// h.restore(&prefix);
+/// h.write(b"rust");
+/// assert_eq!(h.finish(), xxh3::hash64("hello rust"));
+/// # }
+/// ```
+pub trait Snapshot: FastHasher + Clone {
+    /// Saves the state of the hasher, which continues independently of the snapshot.
+    #[inline(always)]
+    fn snapshot(&self) -> Self {
+        self.clone()
+    }
+
+    /// Restores the state of a snapshot, reusing the state of the hasher.
+    #[inline(always)]
+    fn restore(&mut self, snapshot: &Self) {
+        self.clone_from(snapshot)
+    }
+}
+
+impl<T: FastHasher + Clone> Snapshot for T {}
+
 /// Generate hash seeds
 ///
 /// It base on the same workflow from `std::collections::RandomState`
@@ -149,13 +249,18 @@ pub trait HasherExt: Hasher {
 /// # Examples
 ///
 /// ```rust
+/// # #[cfg(any(feature = "sys", feature = "pure-city"))]
+/// # {
 /// use fasthash::{Seed, city};
 ///
 /// city::hash128_with_seed(b"hello world", Seed::gen().into());
+/// # }
 /// ```
+#[cfg(feature = "std")]
 #[derive(Clone, Copy, Debug)]
 pub struct Seed(Xoroshiro128Rng);
 
+#[cfg(feature = "std")]
 impl Seed {
     #[inline(always)]
     fn new() -> Seed {
@@ -173,10 +278,18 @@ impl Seed {
             }))
         })
     }
+
+    /// Creates a seed from a fixed state, to reproduce the generated seeds.
+    #[cfg(feature = "quality")]
+    #[inline(always)]
+    pub(crate) fn with_state(state: [u64; 2]) -> Seed {
+        Seed(Xoroshiro128Rng::from_seed(state))
+    }
 }
 
 macro_rules! impl_from_seed {
     ($target:ty) => {
+        #[cfg(feature = "std")]
         impl From<Seed> for $target {
             #[inline(always)]
             fn from(seed: Seed) -> $target {
@@ -195,6 +308,7 @@ impl_from_seed!((u64, u64, u64, u64));
 impl_from_seed!([u64; 2]);
 impl_from_seed!([u64; 4]);
 
+#[cfg(feature = "std")]
 impl From<Seed> for u128 {
     #[inline(always)]
     fn from(seed: Seed) -> u128 {
@@ -213,6 +327,8 @@ impl From<Seed> for u128 {
 /// instances are unlikely to produce the same result for the same values.
 ///
 /// ```rust
+/// # #[cfg(any(feature = "sys", feature = "pure-city"))]
+/// # {
 /// use std::collections::HashMap;
 ///
 /// use fasthash::RandomState;
@@ -227,13 +343,16 @@ impl From<Seed> for u128 {
 /// map.insert(37, "b");
 /// assert_eq!(map.insert(37, "c"), Some("b"));
 /// assert_eq!(map[&37], "c");
+/// # }
 /// ```
+#[cfg(feature = "std")]
 #[derive(Clone)]
 pub struct RandomState<T: FastHash> {
     seed: Seed,
     phantom: PhantomData<T>,
 }
 
+#[cfg(feature = "std")]
 impl<T: FastHash> RandomState<T> {
     /// Constructs a new `RandomState` that is initialized with random keys.
     #[inline(always)]
@@ -245,6 +364,7 @@ impl<T: FastHash> RandomState<T> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<T: FastHash> BuildHasher for RandomState<T> {
     type Hasher = T::FastHasher;
 
@@ -254,6 +374,7 @@ impl<T: FastHash> BuildHasher for RandomState<T> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<T: FastHash> Default for RandomState<T> {
     #[inline(always)]
     fn default() -> Self {
@@ -264,7 +385,7 @@ impl<T: FastHash> Default for RandomState<T> {
 #[doc(hidden)]
 macro_rules! impl_build_hasher {
     ($hasher:ident, $hash:ident) => {
-        impl ::std::hash::BuildHasher for $hash {
+        impl ::core::hash::BuildHasher for $hash {
             type Hasher = $hasher;
 
             #[inline(always)]
@@ -290,6 +411,7 @@ where
 }
 
 #[doc(hidden)]
+#[cfg_attr(not(feature = "sys"), allow(unused_macros))]
 macro_rules! impl_digest {
     ($hasher:ident, $output:ident) => {
         #[cfg(feature = "digest")]
@@ -343,6 +465,35 @@ macro_rules! impl_digest {
     };
 }
 
+#[doc(hidden)]
+#[cfg_attr(
+    not(any(
+        feature = "sys",
+        feature = "pure-city",
+        feature = "pure-highway",
+        feature = "pure-t1ha",
+        feature = "pure-xxh3"
+    )),
+    allow(unused_macros)
+)]
+macro_rules! impl_serde {
+    ($hasher:ident) => {
+        #[cfg(feature = "serde")]
+        impl serde::Serialize for $hasher {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_bytes(&$crate::state::HasherState::to_bytes(self))
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de> serde::Deserialize<'de> for $hasher {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.deserialize_bytes($crate::state::StateVisitor::default())
+            }
+        }
+    };
+}
+
 cfg_if! {
     if #[cfg(feature = "digest")] {
         /// The `Digest` output type
@@ -365,20 +516,128 @@ cfg_if! {
     }
 }
 
+/// The number of bytes a `Buffer` keeps inline before spilling to the heap.
+pub const INLINE_CAPACITY: usize = 64;
+
+/// The buffer of the buffered hashers.
+///
+/// Short keys are kept in an inline array,
+/// it only allocates when the written bytes exceed `INLINE_CAPACITY`.
+#[doc(hidden)]
+#[derive(Clone)]
+pub enum Buffer {
+    Inline(usize, [u8; INLINE_CAPACITY]),
+    Heap(Vec<u8>),
+}
+
+#[cfg_attr(not(feature = "sys"), allow(dead_code))]
+impl Buffer {
+    #[inline(always)]
+    pub fn with_capacity(capacity: usize) -> Self {
+        if capacity <= INLINE_CAPACITY {
+            Buffer::Inline(0, [0; INLINE_CAPACITY])
+        } else {
+            Buffer::Heap(Vec::with_capacity(capacity))
+        }
+    }
+
+    #[inline(always)]
+    pub fn extend_from_slice(&mut self, bytes: &[u8]) {
+        match self {
+            Buffer::Inline(len, buf) if *len + bytes.len() <= INLINE_CAPACITY => {
+                buf[*len..*len + bytes.len()].copy_from_slice(bytes);
+                *len += bytes.len();
+            }
+            Buffer::Inline(len, buf) => {
+                let mut v = Vec::with_capacity((*len + bytes.len()).max(INLINE_CAPACITY * 2));
+                v.extend_from_slice(&buf[..*len]);
+                v.extend_from_slice(bytes);
+                *self = Buffer::Heap(v);
+            }
+            Buffer::Heap(v) => v.extend_from_slice(bytes),
+        }
+    }
+
+    #[cfg(test)]
+    fn spilled(&self) -> bool {
+        match self {
+            Buffer::Inline(..) => false,
+            Buffer::Heap(_) => true,
+        }
+    }
+}
+
+impl AsRef<[u8]> for Buffer {
+    #[inline(always)]
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Buffer::Inline(len, buf) => &buf[..*len],
+            Buffer::Heap(v) => v,
+        }
+    }
+}
+
+impl fmt::Debug for Buffer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.as_ref()).finish()
+    }
+}
+
+/// The increment of `SplitMix64`, the fractional part of the golden ratio.
+pub(crate) const GOLDEN_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// The finalizer of `SplitMix64`, a bijective 64-bit mixer.
+#[inline(always)]
+pub(crate) fn mix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Derives a 32-bit seed from an index, `index * 0x9e3779b9 + 0x7f4a7c15`,
+/// the two halves of `GOLDEN_GAMMA`.
+#[cfg(feature = "std")]
+#[inline(always)]
+pub(crate) fn index_seed(index: u32) -> u32 {
+    index
+        .wrapping_mul((GOLDEN_GAMMA >> 32) as u32)
+        .wrapping_add(GOLDEN_GAMMA as u32)
+}
+
+/// `_mm_crc32_u64` in software, the CRC-32C of `v` continued from the low 32 bits of `crc`.
+#[cfg(any(
+    feature = "pure-city",
+    all(feature = "runtime", any(target_arch = "x86", target_arch = "x86_64"))
+))]
+pub(crate) fn crc32c_u64(crc: u64, v: u64) -> u64 {
+    let mut crc = crc as u32;
+
+    for &b in &v.to_le_bytes() {
+        crc ^= u32::from(b);
+
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0x82f6_3b78 & (crc & 1).wrapping_neg());
+        }
+    }
+
+    u64::from(crc)
+}
+
 pub trait TrivialHasher: FastHasher {
     fn finalize(&self) -> Self::Output;
 }
 
+/// Implements a buffering hasher of a hash function, `$name` is the stable name of its exported state.
 #[doc(hidden)]
 #[macro_export]
 macro_rules! trivial_hasher {
-    ($(#[$meta:meta])* $hasher:ident ( $hash:ident ) -> $output:ident) => {
-        /// An implementation of `std::hash::Hasher`.
+    ($(#[$meta:meta])* $hasher:ident ( $hash:ident ) -> $output:ident, $name:literal) => {
+        /// An implementation of `core::hash::Hasher`.
         #[derive(Clone, Debug)]
         $(#[$meta])*
         pub struct $hasher {
             seed: Option<<$hash as $crate::hasher::FastHash>::Seed>,
-            bytes: Vec<u8>,
+            bytes: $crate::hasher::Buffer,
         }
 
         impl Default for $hasher {
@@ -390,15 +649,38 @@ macro_rules! trivial_hasher {
         impl $crate::hasher::TrivialHasher for $hasher {
             #[inline(always)]
             fn finalize(&self) -> $output {
-                self.seed
-                    .map_or_else(
-                        || $hash::hash(&self.bytes),
-                        |seed| $hash::hash_with_seed(&self.bytes, seed),
-                    )
+                use ::core::convert::TryInto;
+
+                // a single integer key takes the integer path of the hash function,
+                // which returns the same value as hashing its bytes
+                let bytes = self.bytes.as_ref();
+
+                match (bytes.len(), self.seed) {
+                    (4, seed) => {
+                        let n = u32::from_le_bytes(bytes.try_into().unwrap());
+
+                        seed.map_or_else(
+                            || $hash::hash_u32(n),
+                            |seed| $hash::hash_u32_with_seed(n, seed),
+                        )
+                    }
+                    (8, seed) => {
+                        let n = u64::from_le_bytes(bytes.try_into().unwrap());
+
+                        seed.map_or_else(
+                            || $hash::hash_u64(n),
+                            |seed| $hash::hash_u64_with_seed(n, seed),
+                        )
+                    }
+                    (_, seed) => seed.map_or_else(
+                        || $hash::hash(bytes),
+                        |seed| $hash::hash_with_seed(bytes, seed),
+                    ),
+                }
             }
         }
 
-        impl ::std::hash::Hasher for $hasher {
+        impl ::core::hash::Hasher for $hasher {
             #[inline(always)]
             fn finish(&self) -> u64 {
                 use crate::hasher::TrivialHasher;
@@ -410,6 +692,36 @@ macro_rules! trivial_hasher {
             fn write(&mut self, bytes: &[u8]) {
                 self.bytes.extend_from_slice(bytes)
             }
+
+            #[inline(always)]
+            fn write_u8(&mut self, n: u8) {
+                self.bytes.extend_from_slice(&[n])
+            }
+
+            #[inline(always)]
+            fn write_u16(&mut self, n: u16) {
+                self.bytes.extend_from_slice(&n.to_ne_bytes())
+            }
+
+            #[inline(always)]
+            fn write_u32(&mut self, n: u32) {
+                self.bytes.extend_from_slice(&n.to_ne_bytes())
+            }
+
+            #[inline(always)]
+            fn write_u64(&mut self, n: u64) {
+                self.bytes.extend_from_slice(&n.to_ne_bytes())
+            }
+
+            #[inline(always)]
+            fn write_u128(&mut self, n: u128) {
+                self.bytes.extend_from_slice(&n.to_ne_bytes())
+            }
+
+            #[inline(always)]
+            fn write_usize(&mut self, n: usize) {
+                self.bytes.extend_from_slice(&n.to_ne_bytes())
+            }
         }
 
         impl $crate::hasher::FastHasher for $hasher {
@@ -418,19 +730,25 @@ macro_rules! trivial_hasher {
 
             #[inline(always)]
             fn new() -> Self {
-                <Self as $crate::hasher::BufHasher>::with_capacity_and_seed(64, None)
+                <Self as $crate::hasher::BufHasher>::with_capacity_and_seed(
+                    $crate::hasher::INLINE_CAPACITY,
+                    None,
+                )
             }
 
             #[inline(always)]
             fn with_seed(seed: Self::Seed) -> Self {
-                <Self as $crate::hasher::BufHasher>::with_capacity_and_seed(64, Some(seed))
+                <Self as $crate::hasher::BufHasher>::with_capacity_and_seed(
+                    $crate::hasher::INLINE_CAPACITY,
+                    Some(seed),
+                )
             }
         }
 
-        impl ::std::convert::AsRef<[u8]> for $hasher {
+        impl ::core::convert::AsRef<[u8]> for $hasher {
             #[inline(always)]
             fn as_ref(&self) -> &[u8] {
-                &self.bytes
+                self.bytes.as_ref()
             }
         }
 
@@ -439,17 +757,45 @@ macro_rules! trivial_hasher {
             fn with_capacity_and_seed(capacity: usize, seed: Option<Self::Seed>) -> Self {
                 $hasher {
                     seed: seed,
-                    bytes: Vec::with_capacity(capacity),
+                    bytes: $crate::hasher::Buffer::with_capacity(capacity),
                 }
             }
         }
 
+        impl $crate::state::HasherState for $hasher {
+            fn to_bytes(&self) -> alloc::vec::Vec<u8> {
+                let bytes = self.bytes.as_ref();
+                let mut w = $crate::state::Writer::new($name);
+
+                w.seed(self.seed).u64(bytes.len() as u64).bytes(bytes);
+                w.into_bytes()
+            }
+
+            fn from_bytes(bytes: &[u8]) -> Result<Self, $crate::state::Error> {
+                use ::core::convert::TryFrom;
+
+                let mut r = $crate::state::Reader::new(bytes, $name)?;
+                let seed = r.seed()?;
+                let len = usize::try_from(r.u64()?).map_err(|_| $crate::state::Error::InvalidFormat)?;
+                let bytes = r.bytes(len)?;
+
+                r.finish()?;
+
+                let mut h = <Self as $crate::hasher::BufHasher>::with_capacity_and_seed(len, seed);
+
+                h.bytes.extend_from_slice(bytes);
+
+                Ok(h)
+            }
+        }
+
         impl_build_hasher!($hasher, $hash);
         impl_digest!($hasher, $output);
+        impl_serde!($hasher);
     };
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use std::collections::HashMap;
     use std::convert::Into;
@@ -496,6 +842,117 @@ mod tests {
         assert!(u1 != (u2 >> 64) as u64);
     }
 
+    #[test]
+    fn test_inline_buffer() {
+        use crate::hasher::{Buffer, INLINE_CAPACITY};
+
+        let data = (0..=255).collect::<Vec<u8>>();
+
+        let mut buf = Buffer::with_capacity(INLINE_CAPACITY);
+        buf.extend_from_slice(&data[..40]);
+        buf.extend_from_slice(&data[40..64]);
+        assert!(!buf.spilled());
+        assert_eq!(buf.as_ref(), &data[..64]);
+
+        buf.extend_from_slice(&data[64..65]);
+        assert!(buf.spilled());
+        assert_eq!(buf.as_ref(), &data[..65]);
+
+        assert!(Buffer::with_capacity(INLINE_CAPACITY + 1).spilled());
+
+        #[cfg(feature = "sys")]
+        {
+            use std::hash::Hasher;
+
+            for len in &[0, 1, 63, 64, 65, 128, 256] {
+                let mut h = city::Hasher64::new();
+
+                for chunk in data[..*len].chunks(7) {
+                    h.write(chunk);
+                }
+
+                assert_eq!(h.len(), *len);
+                assert_eq!(h.finish(), city::hash64(&data[..*len]));
+            }
+        }
+    }
+
+    macro_rules! test_integer_keys {
+        [ $( $hash:path ),* ] => {
+            $( {
+                for &n in &[0_u64, 1, 123, 0xdead_beef, 0x0123_4567_89ab_cdef, u64::MAX] {
+                    let seed = <$hash as FastHash>::Seed::default();
+
+                    assert_eq!(<$hash>::hash_u64(n), <$hash>::hash(n.to_le_bytes()));
+                    assert_eq!(
+                        <$hash>::hash_u64_with_seed(n, seed),
+                        <$hash>::hash_with_seed(n.to_le_bytes(), seed)
+                    );
+                    assert_eq!(
+                        <$hash>::hash_u32(n as u32),
+                        <$hash>::hash((n as u32).to_le_bytes())
+                    );
+                    assert_eq!(
+                        <$hash>::hash_u32_with_seed(n as u32, seed),
+                        <$hash>::hash_with_seed((n as u32).to_le_bytes(), seed)
+                    );
+                }
+            } )*
+        }
+    }
+
+    #[cfg(feature = "sys")]
+    macro_rules! test_integer_writes {
+        [ $( $hash:path ),* ] => {
+            $( {
+                use std::hash::{BuildHasher, Hash, Hasher};
+
+                for &n in &[0_u64, 1, 123, 0xdead_beef, 0x0123_4567_89ab_cdef, u64::MAX] {
+                    let mut h = $hash.build_hasher();
+                    n.hash(&mut h);
+                    assert_eq!(h.finish(), <$hash>::hash(n.to_ne_bytes()) as u64);
+
+                    let mut h = $hash.build_hasher();
+                    (n as u32).hash(&mut h);
+                    assert_eq!(h.finish(), <$hash>::hash((n as u32).to_ne_bytes()) as u64);
+
+                    // the other widths are hashed as bytes
+                    let mut h = $hash.build_hasher();
+                    (n as u8).hash(&mut h);
+                    assert_eq!(h.finish(), <$hash>::hash([n as u8]) as u64);
+
+                    let mut h = $hash.build_hasher();
+                    (n as u16).hash(&mut h);
+                    assert_eq!(h.finish(), <$hash>::hash((n as u16).to_ne_bytes()) as u64);
+
+                    let k = u128::from(n) << 64 | 1;
+                    let mut h = $hash.build_hasher();
+                    k.hash(&mut h);
+                    assert_eq!(h.finish(), <$hash>::hash(k.to_ne_bytes()) as u64);
+                }
+            } )*
+        }
+    }
+
+    #[test]
+    fn test_integer_keys() {
+        #[cfg(feature = "sys")]
+        {
+            test_integer_keys![city::Hash32, city::Hash64, city::Hash128];
+            test_integer_keys![farm::Hash32, farm::Hash64, farm::Hash128];
+            test_integer_keys![murmur3::Hash32, murmur3::Hash128_x86, murmur3::Hash128_x64];
+            test_integer_keys![mum::Hash64, t1ha1::Hash64Le, xx::Hash32, xx::Hash64];
+
+            test_integer_writes![city::Hash32, city::Hash64, city::Hash128];
+            test_integer_writes![farm::Hash64, murmur3::Hash32, murmur3::Hash128_x64];
+            test_integer_writes![xx::Hash32, xx::Hash64, spooky::Hash64];
+        }
+
+        test_integer_keys![sea::Hash64];
+        #[cfg(any(feature = "sys", feature = "pure-xxh3"))]
+        test_integer_keys![xxh3::Hash64, xxh3::Hash128];
+    }
+
     macro_rules! test_hashmap_with_fixed_state {
         ($hash:path) => {
             let mut map = HashMap::with_hasher($hash);
@@ -534,46 +991,53 @@ mod tests {
 
     #[test]
     fn test_hashmap_with_hashers() {
-        test_hashmap_with_hashers![city::Hash32, city::Hash64, city::Hash128];
-        #[cfg(any(feature = "sse42", target_feature = "sse4.2"))]
-        test_hashmap_with_hashers![city::crc::Hash128];
-        test_hashmap_with_hashers![farm::Hash32, farm::Hash64, farm::Hash128];
-        test_hashmap_with_hashers![lookup3::Hash32];
-        test_hashmap_with_hashers![
-            metro::Hash64_1,
-            metro::Hash64_2,
-            metro::Hash128_1,
-            metro::Hash128_2
-        ];
-        #[cfg(any(feature = "sse42", target_feature = "sse4.2"))]
-        test_hashmap_with_hashers![
-            metro::crc::Hash64_1,
-            metro::crc::Hash64_2,
-            metro::crc::Hash128_1,
-            metro::crc::Hash128_2
-        ];
-
-        test_hashmap_with_hashers![mum::Hash64];
-        test_hashmap_with_hashers![murmur::Hash32, murmur::Hash32Aligned];
-        test_hashmap_with_hashers![
-            murmur2::Hash32,
-            murmur2::Hash32A,
-            murmur2::Hash32Neutral,
-            murmur2::Hash32Aligned,
-            murmur2::Hash64_x64,
-            murmur2::Hash64_x86
-        ];
-        test_hashmap_with_hashers![murmur3::Hash32, murmur3::Hash128_x86, murmur3::Hash128_x64];
+        #[cfg(feature = "sys")]
+        {
+            test_hashmap_with_hashers![city::Hash32, city::Hash64, city::Hash128];
+            #[cfg(any(feature = "sse42", target_feature = "sse4.2"))]
+            test_hashmap_with_hashers![city::crc::Hash128];
+            test_hashmap_with_hashers![farm::Hash32, farm::Hash64, farm::Hash128];
+            test_hashmap_with_hashers![highway::Hash64, highway::Hash128];
+            test_hashmap_with_hashers![lookup3::Hash32];
+            test_hashmap_with_hashers![
+                metro::Hash64_1,
+                metro::Hash64_2,
+                metro::Hash128_1,
+                metro::Hash128_2
+            ];
+            #[cfg(any(feature = "sse42", target_feature = "sse4.2"))]
+            test_hashmap_with_hashers![
+                metro::crc::Hash64_1,
+                metro::crc::Hash64_2,
+                metro::crc::Hash128_1,
+                metro::crc::Hash128_2
+            ];
+
+            test_hashmap_with_hashers![mum::Hash64];
+            test_hashmap_with_hashers![murmur::Hash32, murmur::Hash32Aligned];
+            test_hashmap_with_hashers![
+                murmur2::Hash32,
+                murmur2::Hash32A,
+                murmur2::Hash32Neutral,
+                murmur2::Hash32Aligned,
+                murmur2::Hash64_x64,
+                murmur2::Hash64_x86
+            ];
+            test_hashmap_with_hashers![murmur3::Hash32, murmur3::Hash128_x86, murmur3::Hash128_x64];
+            test_hashmap_with_hashers![spooky::Hash32, spooky::Hash64, spooky::Hash128];
+            test_hashmap_with_hashers![
+                t1ha0::Hash64,
+                t1ha1::Hash64Le,
+                t1ha1::Hash64Be,
+                t1ha2::Hash64AtOnce,
+                t1ha2::Hash128AtOnce
+            ];
+
+            test_hashmap_with_hashers![xx::Hash32, xx::Hash64];
+        }
+
         test_hashmap_with_hashers![sea::Hash64];
-        test_hashmap_with_hashers![spooky::Hash32, spooky::Hash64, spooky::Hash128];
-        test_hashmap_with_hashers![
-            t1ha0::Hash64,
-            t1ha1::Hash64Le,
-            t1ha1::Hash64Be,
-            t1ha2::Hash64AtOnce,
-            t1ha2::Hash128AtOnce
-        ];
-
-        test_hashmap_with_hashers![xx::Hash32, xx::Hash64];
+        #[cfg(any(feature = "sys", feature = "pure-xxh3"))]
+        test_hashmap_with_hashers![xxh3::Hash64, xxh3::Hash128];
     }
 }
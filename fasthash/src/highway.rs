@@ -13,16 +13,172 @@
 //! cost. The result is more than twice as fast as SipTreeHash.
 //!
 //! We also provide an SSE4.1 version (80% as fast for large inputs and 95% as fast
-//! for short inputs), an implementation for VSX on POWER and a portable version
-//! (10% as fast). A third-party ARM implementation is referenced below.
+//! for short inputs) and a portable version (10% as fast). A third-party ARM
+//! implementation is referenced below.
 //!
 //! Statistical analyses and preliminary cryptanalysis are given in
 //! https://arxiv.org/abs/1612.06257.
-use crate::FastHash;
+//!
+//! By default it is backed by the vendored C++ implementation, the `pure-highway` feature
+//! switches to a pure Rust port which gives bit for bit the same results and doesn't need
+//! a C++ compiler. The port picks its SSE4.1 or AVX2 code path at runtime.
+//!
+//! The streaming hashers of the C++ implementation, `Hasher64`, `Hasher128` and `Hasher256`,
+//! are built on the `HighwayHashCat` API of its portable C version, so they always run the
+//! `Portable` target whatever `Target::active` returns. The port streams with the active target.
+//!
+//! # Example
+//!
+//! ```
+//! use std::hash::Hasher;
+//! use std::io::Cursor;
+//!
+//! use fasthash::{highway, FastHasher, StreamHasher};
+//!
+//! let mut h = highway::Hasher64::with_seed([1, 2, 3, 4]);
+//!
+//! // hash a stream incrementally without loading it into memory
+//! h.write_stream(&mut Cursor::new("hello world")).unwrap();
+//!
+//! assert_eq!(h.finish(), highway::hash64_with_seed("hello world", [1, 2, 3, 4]));
+//! ```
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::Hasher;
+
+#[cfg(feature = "std")]
+use crate::hasher::StreamHasher;
+use crate::hasher::{FastHash, FastHasher, TrivialHasher};
+use crate::state::{Error, HasherState, Reader, Writer};
+
+#[cfg(feature = "pure-highway")]
+mod pure;
+#[cfg(all(feature = "sys", any(test, not(feature = "pure-highway"))))]
+mod sys;
+
+cfg_if! {
+    if #[cfg(feature = "pure-highway")] {
+        use self::pure as imp;
+    } else {
+        use self::sys as imp;
+    }
+}
 
 /// 256-bit secret key that should remain unknown to attackers.
 /// We recommend initializing it to a random value.
-pub type Seed = ffi::HHKey;
+pub type Seed = [u64; 4];
+
+macro_rules! impl_cat_state {
+    ($hasher:ident, $name:literal) => {
+        impl HasherState for $hasher {
+            fn to_bytes(&self) -> Vec<u8> {
+                cat_to_bytes($name, &self.0)
+            }
+
+            fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+                cat_from_bytes(bytes, $name).map($hasher)
+            }
+        }
+
+        impl_serde!($hasher);
+    };
+}
+
+macro_rules! impl_with_target {
+    ($hasher:ident) => {
+        impl $hasher {
+            /// Creates a streaming hasher with the implementation of a particular target.
+            ///
+            /// Returns `None` if the target is not available on the current CPU, the C++
+            /// implementation only streams with the `Portable` target.
+            pub fn with_target(seed: Seed, target: Target) -> Option<Self> {
+                if !target.is_available() {
+                    return None;
+                }
+
+                imp::State::with_target(seed, target).map($hasher)
+            }
+        }
+    };
+}
+
+/// The instruction set targets of `HighwayHash` implementations.
+///
+/// # Example
+///
+/// ```
+/// use fasthash::highway::{self, Hash64, Target};
+///
+/// let target = Target::active();
+///
+/// println!("HighwayHash running on the {} target", target);
+///
+/// assert!(target.is_available());
+/// assert!(Target::Portable.is_available());
+///
+/// assert_eq!(
+///     Hash64::hash_with_target("hello world", [1, 2, 3, 4], Target::Portable),
+///     Some(highway::hash64_with_seed("hello world", [1, 2, 3, 4]))
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Target {
+    /// The portable implementation, available on all platforms.
+    Portable,
+    /// The SSE4.1 implementation on x86.
+    SSE41,
+    /// The AVX2 implementation on x86.
+    AVX2,
+}
+
+impl Target {
+    /// All the targets, in the order of preference from the slowest.
+    pub const ALL: [Target; 3] = [Target::Portable, Target::SSE41, Target::AVX2];
+
+    /// Returns the fastest target available on the current CPU,
+    /// which is the one used by `Hash64`, `Hash128` and `Hash256`.
+    pub fn active() -> Target {
+        Target::ALL
+            .iter()
+            .rev()
+            .cloned()
+            .find(|target| target.is_available())
+            .unwrap_or(Target::Portable)
+    }
+
+    /// Returns all the targets available on the current CPU.
+    pub fn available() -> Vec<Target> {
+        Target::ALL
+            .iter()
+            .cloned()
+            .filter(|target| target.is_available())
+            .collect()
+    }
+
+    /// Returns `true` if the target was compiled in and is supported by the current CPU.
+    pub fn is_available(self) -> bool {
+        match self {
+            Target::Portable => true,
+            Target::SSE41 => imp::has_sse41(),
+            Target::AVX2 => imp::has_avx2(),
+        }
+    }
+
+    /// Returns the name of the target.
+    pub fn name(self) -> &'static str {
+        match self {
+            Target::Portable => "Portable",
+            Target::SSE41 => "SSE41",
+            Target::AVX2 => "AVX2",
+        }
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
 
 /// `HighwayHash` 64-bit hash functions for a byte array.
 ///
@@ -96,6 +252,52 @@ pub fn hash128_with_seed<T: AsRef<[u8]>>(v: T, seed: Seed) -> u128 {
     Hash128::hash_with_seed(v, seed)
 }
 
+/// `HighwayHash` 256-bit hash functions for a byte array.
+///
+/// # Example
+///
+/// ```
+/// use fasthash::highway;
+///
+/// assert_eq!(
+///     highway::hash256("hello world"),
+///     [
+///         1203621404206046668,
+///         7983057632324745539,
+///         14245526413442055123,
+///         11387424667110708774
+///     ]
+/// );
+/// ```
+#[inline(always)]
+pub fn hash256<T: AsRef<[u8]>>(v: T) -> [u64; 4] {
+    Hash256::hash(v)
+}
+
+/// `HighwayHash` 256-bit hash function for a byte array.
+///
+/// For convenience, a 256-bit seed is also hashed into the result.
+///
+/// # Example
+///
+/// ```
+/// use fasthash::highway;
+///
+/// assert_eq!(
+///     highway::hash256_with_seed("hello world", [1, 2, 3, 4]),
+///     [
+///         10597102072441266416,
+///         14289288226045296482,
+///         16798962741462603234,
+///         17891137339754239124
+///     ]
+/// );
+/// ```
+#[inline(always)]
+pub fn hash256_with_seed<T: AsRef<[u8]>>(v: T, seed: Seed) -> [u64; 4] {
+    Hash256::hash_with_seed(v, seed)
+}
+
 /// An implementation of `std::hash::Hasher`.
 ///
 /// # Example
@@ -117,37 +319,90 @@ impl FastHash for Hash64 {
 
     #[inline(always)]
     fn hash_with_seed<T: AsRef<[u8]>>(bytes: T, seed: Self::Seed) -> Self::Hash {
-        let bytes = bytes.as_ref();
-
-        unsafe {
-            ffi::HighwayHash64(
-                seed.as_ptr() as *mut _,
-                bytes.as_ptr() as *const _,
-                bytes.len() as u64,
-            )
-        }
+        imp::hash64(bytes.as_ref(), seed)
     }
 }
 
-trivial_hasher! {
-    /// # Example
-    ///
-    /// ```
-    /// use std::hash::Hasher;
-    ///
-    /// use fasthash::{highway::Hasher64, FastHasher};
-    ///
-    /// let mut h = Hasher64::new();
-    ///
-    /// h.write(b"hello");
-    /// assert_eq!(h.finish(), 16088634173958985784);
+impl Hash64 {
+    /// Hash functions for a byte array with the implementation of a particular target.
     ///
-    /// h.write(b"world");
-    /// assert_eq!(h.finish(), 14621305948273251148);
-    /// ```
-    Hasher64(Hash64) -> u64
+    /// Returns `None` if the target is not available on the current CPU.
+    pub fn hash_with_target<T: AsRef<[u8]>>(bytes: T, seed: Seed, target: Target) -> Option<u64> {
+        if !target.is_available() {
+            return None;
+        }
+
+        imp::hash64_with_target(bytes.as_ref(), seed, target)
+    }
+}
+
+/// An implementation of `std::hash::Hasher`.
+///
+/// It hashes the input incrementally with the streaming API of `HighwayHash`,
+/// instead of buffering the whole input.
+///
+/// # Example
+///
+/// ```
+/// use std::hash::Hasher;
+///
+/// use fasthash::{highway::Hasher64, FastHasher};
+///
+/// let mut h = Hasher64::new();
+///
+/// h.write(b"hello");
+/// assert_eq!(h.finish(), 16088634173958985784);
+///
+/// h.write(b"world");
+/// assert_eq!(h.finish(), 14621305948273251148);
+/// ```
+#[derive(Clone)]
+pub struct Hasher64(imp::State);
+
+impl Default for Hasher64 {
+    #[inline(always)]
+    fn default() -> Self {
+        Hasher64::new()
+    }
+}
+
+impl Hasher for Hasher64 {
+    #[inline(always)]
+    fn finish(&self) -> u64 {
+        self.finalize()
+    }
+
+    #[inline(always)]
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.append(bytes)
+    }
+}
+
+impl FastHasher for Hasher64 {
+    type Seed = Seed;
+    type Output = u64;
+
+    #[inline(always)]
+    fn with_seed(seed: Seed) -> Self {
+        Hasher64(imp::State::new(seed))
+    }
+}
+
+impl TrivialHasher for Hasher64 {
+    #[inline(always)]
+    fn finalize(&self) -> u64 {
+        self.0.finish64()
+    }
 }
 
+#[cfg(feature = "std")]
+impl StreamHasher for Hasher64 {}
+
+impl_with_target!(Hasher64);
+impl_build_hasher!(Hasher64, Hash64);
+impl_digest!(Hasher64, u64);
+impl_cat_state!(Hasher64, "fasthash::highway::Hasher64");
+
 /// An implementation of `std::hash::Hasher`.
 ///
 /// # Example
@@ -169,37 +424,480 @@ impl FastHash for Hash128 {
 
     #[inline(always)]
     fn hash_with_seed<T: AsRef<[u8]>>(bytes: T, seed: Self::Seed) -> Self::Hash {
-        let bytes = bytes.as_ref();
-        let mut hash: ffi::HHResult128 = [0; 2];
-
-        unsafe {
-            ffi::HighwayHash128(
-                seed.as_ptr() as *mut _,
-                bytes.as_ptr() as *const _,
-                bytes.len() as u64,
-                &mut hash,
-            )
+        imp::hash128(bytes.as_ref(), seed)
+    }
+}
+
+impl Hash128 {
+    /// Hash functions for a byte array with the implementation of a particular target.
+    ///
+    /// Returns `None` if the target is not available on the current CPU.
+    pub fn hash_with_target<T: AsRef<[u8]>>(bytes: T, seed: Seed, target: Target) -> Option<u128> {
+        if !target.is_available() {
+            return None;
         }
 
-        u128::from(hash[0]) + (u128::from(hash[1]) << 64)
+        imp::hash128_with_target(bytes.as_ref(), seed, target)
     }
 }
 
-trivial_hasher! {
-    /// # Example
-    ///
-    /// ```
-    /// use std::hash::Hasher;
-    ///
-    /// use fasthash::{highway::Hasher128, FastHasher, HasherExt};
-    ///
-    /// let mut h = Hasher128::new();
-    ///
-    /// h.write(b"hello");
-    /// assert_eq!(h.finish_ext(), 25004695140143629173192629076022730068);
+/// An implementation of `std::hash::Hasher`.
+///
+/// It hashes the input incrementally with the streaming API of `HighwayHash`,
+/// instead of buffering the whole input.
+///
+/// # Example
+///
+/// ```
+/// use std::hash::Hasher;
+///
+/// use fasthash::{highway::Hasher128, FastHasher, HasherExt};
+///
+/// let mut h = Hasher128::new();
+///
+/// h.write(b"hello");
+/// assert_eq!(h.finish_ext(), 25004695140143629173192629076022730068);
+///
+/// h.write(b"world");
+/// assert_eq!(h.finish_ext(), 11585459712122041444150834631428357454);
+/// ```
+#[derive(Clone)]
+pub struct Hasher128(imp::State);
+
+impl Default for Hasher128 {
+    #[inline(always)]
+    fn default() -> Self {
+        Hasher128::new()
+    }
+}
+
+impl Hasher for Hasher128 {
+    #[inline(always)]
+    fn finish(&self) -> u64 {
+        self.finalize() as u64
+    }
+
+    #[inline(always)]
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.append(bytes)
+    }
+}
+
+impl FastHasher for Hasher128 {
+    type Seed = Seed;
+    type Output = u128;
+
+    #[inline(always)]
+    fn with_seed(seed: Seed) -> Self {
+        Hasher128(imp::State::new(seed))
+    }
+}
+
+impl TrivialHasher for Hasher128 {
+    #[inline(always)]
+    fn finalize(&self) -> u128 {
+        self.0.finish128()
+    }
+}
+
+#[cfg(feature = "std")]
+impl StreamHasher for Hasher128 {}
+
+impl_with_target!(Hasher128);
+impl_build_hasher!(Hasher128, Hash128);
+impl_digest!(Hasher128, u128);
+impl_cat_state!(Hasher128, "fasthash::highway::Hasher128");
+
+/// `HighwayHash` 256-bit hash functions
+///
+/// The 256-bit result doesn't fit in a primitive integer, so `Hash256`
+/// provides the `hash` and `hash_with_seed` functions without implementing `FastHash`.
+///
+/// # Example
+///
+/// ```
+/// use fasthash::highway;
+///
+/// assert_eq!(
+///     highway::Hash256::hash_with_seed("hello world", [1, 2, 3, 4]),
+///     [
+///         10597102072441266416,
+///         14289288226045296482,
+///         16798962741462603234,
+///         17891137339754239124
+///     ]
+/// );
+/// ```
+#[derive(Clone)]
+pub struct Hash256;
+
+impl Hash256 {
+    /// Hash functions for a byte array.
+    #[inline(always)]
+    pub fn hash<T: AsRef<[u8]>>(bytes: T) -> [u64; 4] {
+        Self::hash_with_seed(bytes, Default::default())
+    }
+
+    /// Hash functions for a byte array.
+    /// For convenience, a seed is also hashed into the result.
+    #[inline(always)]
+    pub fn hash_with_seed<T: AsRef<[u8]>>(bytes: T, seed: Seed) -> [u64; 4] {
+        imp::hash256(bytes.as_ref(), seed)
+    }
+
+    /// Hash functions for a byte array with the implementation of a particular target.
     ///
-    /// h.write(b"world");
-    /// assert_eq!(h.finish_ext(), 11585459712122041444150834631428357454);
-    /// ```
-    Hasher128(Hash128) -> u128
+    /// Returns `None` if the target is not available on the current CPU.
+    pub fn hash_with_target<T: AsRef<[u8]>>(
+        bytes: T,
+        seed: Seed,
+        target: Target,
+    ) -> Option<[u64; 4]> {
+        if !target.is_available() {
+            return None;
+        }
+
+        imp::hash256_with_target(bytes.as_ref(), seed, target)
+    }
+}
+
+/// An implementation of `std::hash::Hasher` with 256-bit result.
+///
+/// It hashes the input incrementally with the streaming API of `HighwayHash`,
+/// instead of buffering the whole input.
+///
+/// # Example
+///
+/// ```
+/// use std::hash::Hasher;
+/// use std::io::Cursor;
+///
+/// use fasthash::{highway::{self, Hasher256}, FastHasher, StreamHasher};
+///
+/// let mut h = Hasher256::with_seed([1, 2, 3, 4]);
+///
+/// h.write_stream(&mut Cursor::new("hello world")).unwrap();
+///
+/// assert_eq!(h.finish256(), highway::hash256_with_seed("hello world", [1, 2, 3, 4]));
+/// ```
+#[derive(Clone)]
+pub struct Hasher256(imp::State);
+
+impl Hasher256 {
+    /// Completes a round of hashing, producing the 256-bit hash value.
+    #[inline(always)]
+    pub fn finish256(&self) -> [u64; 4] {
+        self.finalize()
+    }
+}
+
+impl Default for Hasher256 {
+    #[inline(always)]
+    fn default() -> Self {
+        Hasher256::new()
+    }
+}
+
+impl Hasher for Hasher256 {
+    #[inline(always)]
+    fn finish(&self) -> u64 {
+        self.finalize()[0]
+    }
+
+    #[inline(always)]
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.append(bytes)
+    }
+}
+
+impl FastHasher for Hasher256 {
+    type Seed = Seed;
+    type Output = [u64; 4];
+
+    #[inline(always)]
+    fn with_seed(seed: Seed) -> Self {
+        Hasher256(imp::State::new(seed))
+    }
+}
+
+impl TrivialHasher for Hasher256 {
+    #[inline(always)]
+    fn finalize(&self) -> [u64; 4] {
+        self.0.finish256()
+    }
+}
+
+#[cfg(feature = "std")]
+impl StreamHasher for Hasher256 {}
+
+impl_with_target!(Hasher256);
+impl_build_hasher!(Hasher256, Hash256);
+impl_cat_state!(Hasher256, "fasthash::highway::Hasher256");
+
+/// The progress of a streaming state, the same for both implementations.
+struct Progress {
+    v0: [u64; 4],
+    v1: [u64; 4],
+    mul0: [u64; 4],
+    mul1: [u64; 4],
+    packet: [u8; 32],
+    num: usize,
+}
+
+fn cat_to_bytes(name: &str, state: &imp::State) -> Vec<u8> {
+    let progress = state.progress();
+    let mut w = Writer::new(name);
+
+    w.words(&progress.v0)
+        .words(&progress.v1)
+        .words(&progress.mul0)
+        .words(&progress.mul1)
+        .bytes(&progress.packet)
+        .u32(progress.num as u32);
+    w.into_bytes()
+}
+
+fn cat_from_bytes(bytes: &[u8], name: &str) -> Result<imp::State, Error> {
+    let mut r = Reader::new(bytes, name)?;
+    let mut progress = Progress {
+        v0: [0; 4],
+        v1: [0; 4],
+        mul0: [0; 4],
+        mul1: [0; 4],
+        packet: [0; 32],
+        num: 0,
+    };
+
+    r.words(&mut progress.v0)?;
+    r.words(&mut progress.v1)?;
+    r.words(&mut progress.mul0)?;
+    r.words(&mut progress.mul1)?;
+    progress.packet.copy_from_slice(r.bytes(32)?);
+
+    // the packet is hashed as soon as it's full
+    progress.num = match r.u32()? {
+        n if n < 32 => n as usize,
+        _ => return Err(Error::InvalidFormat),
+    };
+
+    r.finish()?;
+
+    let mut state = imp::State::new(Seed::default());
+
+    state.set_progress(&progress);
+
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_targets() {
+        let active = Target::active();
+
+        assert!(active.is_available());
+        assert!(Target::available().contains(&active));
+        assert!(Target::available().contains(&Target::Portable));
+
+        let data = (0..=255).collect::<Vec<u8>>();
+
+        for target in Target::available() {
+            for len in 0..data.len() {
+                let seed = [len as u64, 2, 3, 4];
+
+                assert_eq!(
+                    Hash64::hash_with_target(&data[..len], seed, target),
+                    Some(Hash64::hash_with_seed(&data[..len], seed)),
+                    "target {} with {} bytes",
+                    target,
+                    len
+                );
+                assert_eq!(
+                    Hash128::hash_with_target(&data[..len], seed, target),
+                    Some(Hash128::hash_with_seed(&data[..len], seed)),
+                    "target {} with {} bytes",
+                    target,
+                    len
+                );
+                assert_eq!(
+                    Hash256::hash_with_target(&data[..len], seed, target),
+                    Some(Hash256::hash_with_seed(&data[..len], seed)),
+                    "target {} with {} bytes",
+                    target,
+                    len
+                );
+            }
+        }
+
+        for target in Target::ALL.iter().cloned() {
+            if !target.is_available() {
+                assert_eq!(Hash64::hash_with_target(&data, [0; 4], target), None);
+                assert_eq!(Hash128::hash_with_target(&data, [0; 4], target), None);
+                assert_eq!(Hash256::hash_with_target(&data, [0; 4], target), None);
+                assert!(Hasher256::with_target([0; 4], target).is_none());
+            }
+        }
+    }
+
+    #[test]
+    fn test_streaming_targets() {
+        let data = (0..=255).collect::<Vec<u8>>();
+
+        // the portable target always streams
+        assert!(Hasher64::with_target([1, 2, 3, 4], Target::Portable).is_some());
+
+        for target in Target::available() {
+            if let Some(mut h) = Hasher256::with_target([1, 2, 3, 4], target) {
+                h.write(&data);
+
+                assert_eq!(
+                    h.finish256(),
+                    hash256_with_seed(&data, [1, 2, 3, 4]),
+                    "target {}",
+                    target
+                );
+            }
+        }
+    }
+
+    /// The pure Rust port against the C++ implementation.
+    #[cfg(all(feature = "sys", feature = "pure-highway"))]
+    mod compat {
+        use super::super::{pure, sys, Target};
+
+        const SEEDS: [[u64; 4]; 3] = [
+            [0; 4],
+            [1, 2, 3, 4],
+            [
+                0x9E37_79B1_85EB_CA87,
+                0xC2B2_AE3D_27D4_EB4F,
+                0x1656_67B1_9E37_79F9,
+                0x85EB_CA77_C2B2_AE63,
+            ],
+        ];
+
+        fn sample(len: usize) -> Vec<u8> {
+            let mut x = 0x9E37_79B1_u64;
+
+            (0..len)
+                .map(|_| {
+                    x = x.wrapping_mul(0x9E37_79B1_85EB_CA87);
+                    (x >> 56) as u8
+                })
+                .collect()
+        }
+
+        #[test]
+        fn test_one_shot() {
+            let data = sample(1024);
+
+            for len in 0..=data.len() {
+                let v = &data[..len];
+
+                for &seed in &SEEDS {
+                    assert_eq!(
+                        pure::hash64(v, seed),
+                        sys::hash64(v, seed),
+                        "hash64, len={}",
+                        len
+                    );
+                    assert_eq!(
+                        pure::hash128(v, seed),
+                        sys::hash128(v, seed),
+                        "hash128, len={}",
+                        len
+                    );
+                    assert_eq!(
+                        pure::hash256(v, seed),
+                        sys::hash256(v, seed),
+                        "hash256, len={}",
+                        len
+                    );
+                }
+            }
+        }
+
+        #[test]
+        fn test_targets() {
+            let data = sample(300);
+
+            // the port has every code path of the C++ implementation
+            assert!(!sys::has_sse41() || pure::has_sse41());
+            assert!(!sys::has_avx2() || pure::has_avx2());
+
+            for target in Target::available() {
+                for len in 0..=data.len() {
+                    let v = &data[..len];
+
+                    for &seed in &SEEDS {
+                        assert_eq!(
+                            pure::hash64_with_target(v, seed, target),
+                            sys::hash64_with_target(v, seed, target),
+                            "target {} with {} bytes",
+                            target,
+                            len
+                        );
+                        assert_eq!(
+                            pure::hash128_with_target(v, seed, target),
+                            sys::hash128_with_target(v, seed, target),
+                            "target {} with {} bytes",
+                            target,
+                            len
+                        );
+                        assert_eq!(
+                            pure::hash256_with_target(v, seed, target),
+                            sys::hash256_with_target(v, seed, target),
+                            "target {} with {} bytes",
+                            target,
+                            len
+                        );
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn test_streaming() {
+            let data = sample(1024);
+
+            for target in Target::available() {
+                for &step in &[1, 7, 31, 32, 33, 100] {
+                    // the streaming C++ implementation is portable only
+                    let mut h = pure::State::with_target(SEEDS[2], target).unwrap();
+                    let mut expected = sys::State::with_target(SEEDS[2], Target::Portable).unwrap();
+
+                    for chunk in data.chunks(step) {
+                        h.append(chunk);
+                        expected.append(chunk);
+
+                        assert_eq!(h.finish64(), expected.finish64(), "target {}", target);
+                        assert_eq!(h.finish128(), expected.finish128(), "target {}", target);
+                        assert_eq!(h.finish256(), expected.finish256(), "target {}", target);
+                    }
+
+                    // the progress of one implementation resumes in the other
+                    let mut resumed = pure::State::with_target(SEEDS[0], target).unwrap();
+                    let mut resumed_sys = sys::State::new(SEEDS[0]);
+
+                    resumed.set_progress(&expected.progress());
+                    resumed_sys.set_progress(&h.progress());
+
+                    resumed.append(&data[..step]);
+                    resumed_sys.append(&data[..step]);
+                    h.append(&data[..step]);
+                    expected.append(&data[..step]);
+
+                    assert_eq!(
+                        resumed.finish256(),
+                        expected.finish256(),
+                        "target {}",
+                        target
+                    );
+                    assert_eq!(resumed_sys.finish256(), h.finish256(), "target {}", target);
+                }
+            }
+        }
+    }
 }
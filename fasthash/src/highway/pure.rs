@@ -0,0 +1,434 @@
+//! HighwayHash implemented in Rust, bit for bit compatible with the vendored C++ implementation.
+//!
+//! The packets are hashed with SSE4.1 or AVX2 when the CPU supports them, the remainder
+//! and the finalization are shared with the portable code path.
+use core::convert::TryInto;
+
+use super::{Progress, Seed, Target};
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod avx2;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod sse41;
+
+const PACKET_SIZE: usize = 32;
+
+const INIT_MUL0: [u64; 4] = [
+    0xdbe6_d5d5_fe4c_ce2f,
+    0xa409_3822_299f_31d0,
+    0x1319_8a2e_0370_7344,
+    0x243f_6a88_85a3_08d3,
+];
+const INIT_MUL1: [u64; 4] = [
+    0x3bd3_9e10_cb0e_f593,
+    0xc0ac_f169_b5f1_8a8c,
+    0xbe54_66cf_34e9_0c6c,
+    0x4528_21e6_38d0_1377,
+];
+
+#[inline(always)]
+pub fn hash64(bytes: &[u8], key: Seed) -> u64 {
+    let target = active();
+
+    Lanes::digest(bytes, key, target).finalize64(target)
+}
+
+#[inline(always)]
+pub fn hash128(bytes: &[u8], key: Seed) -> u128 {
+    let target = active();
+
+    Lanes::digest(bytes, key, target).finalize128(target)
+}
+
+#[inline(always)]
+pub fn hash256(bytes: &[u8], key: Seed) -> [u64; 4] {
+    let target = active();
+
+    Lanes::digest(bytes, key, target).finalize256(target)
+}
+
+pub fn hash64_with_target(bytes: &[u8], key: Seed, target: Target) -> Option<u64> {
+    Some(Lanes::digest(bytes, key, target).finalize64(target))
+}
+
+pub fn hash128_with_target(bytes: &[u8], key: Seed, target: Target) -> Option<u128> {
+    Some(Lanes::digest(bytes, key, target).finalize128(target))
+}
+
+pub fn hash256_with_target(bytes: &[u8], key: Seed, target: Target) -> Option<[u64; 4]> {
+    Some(Lanes::digest(bytes, key, target).finalize256(target))
+}
+
+cfg_if! {
+    if #[cfg(all(feature = "std", any(target_arch = "x86", target_arch = "x86_64")))] {
+        #[inline(always)]
+        pub fn has_sse41() -> bool {
+            is_x86_feature_detected!("sse4.1")
+        }
+
+        #[inline(always)]
+        pub fn has_avx2() -> bool {
+            is_x86_feature_detected!("avx2")
+        }
+    } else if #[cfg(any(target_arch = "x86", target_arch = "x86_64"))] {
+        // the CPU can't be probed without `std`, trust the build configuration
+        #[inline(always)]
+        pub fn has_sse41() -> bool {
+            cfg!(target_feature = "sse4.1")
+        }
+
+        #[inline(always)]
+        pub fn has_avx2() -> bool {
+            cfg!(target_feature = "avx2")
+        }
+    } else {
+        #[inline(always)]
+        pub fn has_sse41() -> bool {
+            false
+        }
+
+        #[inline(always)]
+        pub fn has_avx2() -> bool {
+            false
+        }
+    }
+}
+
+/// The fastest code path supported by the CPU.
+#[inline(always)]
+fn active() -> Target {
+    if has_avx2() {
+        Target::AVX2
+    } else if has_sse41() {
+        Target::SSE41
+    } else {
+        Target::Portable
+    }
+}
+
+/// The four lanes of the vectors of the hash state.
+#[derive(Clone, Copy)]
+struct Lanes {
+    v0: [u64; 4],
+    v1: [u64; 4],
+    mul0: [u64; 4],
+    mul1: [u64; 4],
+}
+
+impl Lanes {
+    fn new(key: Seed) -> Self {
+        let mut lanes = Lanes {
+            v0: [0; 4],
+            v1: [0; 4],
+            mul0: INIT_MUL0,
+            mul1: INIT_MUL1,
+        };
+
+        for i in 0..4 {
+            lanes.v0[i] = INIT_MUL0[i] ^ key[i];
+            lanes.v1[i] = INIT_MUL1[i] ^ key[i].rotate_left(32);
+        }
+
+        lanes
+    }
+
+    /// Hashes the whole input.
+    #[inline(always)]
+    fn digest(bytes: &[u8], key: Seed, target: Target) -> Self {
+        let mut lanes = Lanes::new(key);
+        let (packets, remainder) = bytes.split_at(bytes.len() & !(PACKET_SIZE - 1));
+
+        lanes.update_packets(packets, target);
+
+        if !remainder.is_empty() {
+            lanes.update_remainder(remainder);
+        }
+
+        lanes
+    }
+
+    /// Hashes the packets, `packets` is a multiple of the packet size.
+    #[inline(always)]
+    fn update_packets(&mut self, packets: &[u8], target: Target) {
+        if packets.is_empty() {
+            return;
+        }
+
+        match target {
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Target::AVX2 => unsafe { avx2::update_packets(self, packets) },
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Target::SSE41 => unsafe { sse41::update_packets(self, packets) },
+            _ => {
+                for packet in packets.chunks_exact(PACKET_SIZE) {
+                    self.update_packet(packet);
+                }
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn update_packet(&mut self, packet: &[u8]) {
+        let mut lanes = [0; 4];
+
+        for (lane, bytes) in lanes.iter_mut().zip(packet.chunks_exact(8)) {
+            *lane = u64::from_le_bytes(bytes.try_into().unwrap());
+        }
+
+        self.update(lanes);
+    }
+
+    #[inline(always)]
+    fn update(&mut self, lanes: [u64; 4]) {
+        for (i, &lane) in lanes.iter().enumerate() {
+            self.v1[i] = self.v1[i].wrapping_add(self.mul0[i].wrapping_add(lane));
+            self.mul0[i] ^= (self.v1[i] & 0xffff_ffff).wrapping_mul(self.v0[i] >> 32);
+            self.v0[i] = self.v0[i].wrapping_add(self.mul1[i]);
+            self.mul1[i] ^= (self.v0[i] & 0xffff_ffff).wrapping_mul(self.v1[i] >> 32);
+        }
+
+        for &(hi, lo) in &[(1, 0), (3, 2)] {
+            let (add1, add0) = zipper_merge(self.v1[hi], self.v1[lo]);
+
+            self.v0[hi] = self.v0[hi].wrapping_add(add1);
+            self.v0[lo] = self.v0[lo].wrapping_add(add0);
+        }
+        for &(hi, lo) in &[(1, 0), (3, 2)] {
+            let (add1, add0) = zipper_merge(self.v0[hi], self.v0[lo]);
+
+            self.v1[hi] = self.v1[hi].wrapping_add(add1);
+            self.v1[lo] = self.v1[lo].wrapping_add(add0);
+        }
+    }
+
+    /// Hashes the last partial packet, shorter than the packet size.
+    fn update_remainder(&mut self, bytes: &[u8]) {
+        let size = bytes.len();
+        let size_mod4 = size & 3;
+        let remainder = size & !3;
+        let mut packet = [0; PACKET_SIZE];
+
+        for i in 0..4 {
+            self.v0[i] = self.v0[i].wrapping_add(((size as u64) << 32) + size as u64);
+            self.v1[i] = rotate32_by(size as u32, self.v1[i]);
+        }
+
+        packet[..remainder].copy_from_slice(&bytes[..remainder]);
+
+        if size & 16 != 0 {
+            packet[28..].copy_from_slice(&bytes[size - 4..]);
+        } else if size_mod4 != 0 {
+            packet[16] = bytes[remainder];
+            packet[17] = bytes[remainder + (size_mod4 >> 1)];
+            packet[18] = bytes[remainder + size_mod4 - 1];
+        }
+
+        self.update_packet(&packet);
+    }
+
+    #[inline(always)]
+    fn permute_and_update(&mut self, rounds: usize, target: Target) {
+        match target {
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Target::AVX2 => unsafe { avx2::permute_and_update(self, rounds) },
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Target::SSE41 => unsafe { sse41::permute_and_update(self, rounds) },
+            _ => {
+                for _ in 0..rounds {
+                    let v0 = self.v0;
+
+                    self.update([
+                        v0[2].rotate_left(32),
+                        v0[3].rotate_left(32),
+                        v0[0].rotate_left(32),
+                        v0[1].rotate_left(32),
+                    ]);
+                }
+            }
+        }
+    }
+
+    fn finalize64(mut self, target: Target) -> u64 {
+        self.permute_and_update(4, target);
+
+        self.v0[0]
+            .wrapping_add(self.v1[0])
+            .wrapping_add(self.mul0[0])
+            .wrapping_add(self.mul1[0])
+    }
+
+    fn finalize128(mut self, target: Target) -> u128 {
+        self.permute_and_update(6, target);
+
+        let lo = self.v0[0]
+            .wrapping_add(self.mul0[0])
+            .wrapping_add(self.v1[2])
+            .wrapping_add(self.mul1[2]);
+        let hi = self.v0[1]
+            .wrapping_add(self.mul0[1])
+            .wrapping_add(self.v1[3])
+            .wrapping_add(self.mul1[3]);
+
+        u128::from(lo) + (u128::from(hi) << 64)
+    }
+
+    fn finalize256(mut self, target: Target) -> [u64; 4] {
+        self.permute_and_update(10, target);
+
+        let (lo, hi) = modular_reduction(
+            self.v1[1].wrapping_add(self.mul1[1]),
+            self.v1[0].wrapping_add(self.mul1[0]),
+            self.v0[1].wrapping_add(self.mul0[1]),
+            self.v0[0].wrapping_add(self.mul0[0]),
+        );
+        let (lo2, hi2) = modular_reduction(
+            self.v1[3].wrapping_add(self.mul1[3]),
+            self.v1[2].wrapping_add(self.mul1[2]),
+            self.v0[3].wrapping_add(self.mul0[3]),
+            self.v0[2].wrapping_add(self.mul0[2]),
+        );
+
+        [lo, hi, lo2, hi2]
+    }
+}
+
+/// Shuffles the bytes of `v1:v0` in the order of the multiplications, returned as `(v1, v0)`.
+#[inline(always)]
+fn zipper_merge(v1: u64, v0: u64) -> (u64, u64) {
+    let add0 = (((v0 & 0xff00_0000) | (v1 & 0x00ff_0000_0000)) >> 24)
+        | (((v0 & 0xff00_0000_0000) | (v1 & 0x00ff_0000_0000_0000)) >> 16)
+        | (v0 & 0x00ff_0000)
+        | ((v0 & 0xff00) << 32)
+        | ((v1 & 0xff00_0000_0000_0000) >> 8)
+        | (v0 << 56);
+    let add1 = (((v1 & 0xff00_0000) | (v0 & 0x00ff_0000_0000)) >> 24)
+        | (v1 & 0x00ff_0000)
+        | ((v1 & 0xff00_0000_0000) >> 16)
+        | ((v1 & 0xff00) << 24)
+        | ((v0 & 0x00ff_0000_0000_0000) >> 8)
+        | ((v1 & 0xff) << 48)
+        | (v0 & 0xff00_0000_0000_0000);
+
+    (add1, add0)
+}
+
+/// Rotates both 32-bit halves of `v` left by `count`.
+#[inline(always)]
+fn rotate32_by(count: u32, v: u64) -> u64 {
+    let lo = (v as u32).rotate_left(count);
+    let hi = ((v >> 32) as u32).rotate_left(count);
+
+    u64::from(lo) | (u64::from(hi) << 32)
+}
+
+/// Reduces the 256-bit `a3:a2:a1:a0` to 128 bits, returned as `(lo, hi)`.
+#[inline(always)]
+fn modular_reduction(a3_unmasked: u64, a2: u64, a1: u64, a0: u64) -> (u64, u64) {
+    let a3 = a3_unmasked & 0x3fff_ffff_ffff_ffff;
+
+    let hi = a1 ^ ((a3 << 1) | (a2 >> 63)) ^ ((a3 << 2) | (a2 >> 62));
+    let lo = a0 ^ (a2 << 1) ^ (a2 << 2);
+
+    (lo, hi)
+}
+
+/// The streaming state of the `HighwayHashCat` API.
+#[derive(Clone, Copy)]
+pub struct State {
+    lanes: Lanes,
+    packet: [u8; PACKET_SIZE],
+    num: usize,
+    target: Target,
+}
+
+impl State {
+    #[inline(always)]
+    pub fn new(key: Seed) -> Self {
+        State {
+            lanes: Lanes::new(key),
+            packet: [0; PACKET_SIZE],
+            num: 0,
+            target: active(),
+        }
+    }
+
+    /// Hashes with the code path of the target, which must be available.
+    #[inline(always)]
+    pub fn with_target(key: Seed, target: Target) -> Option<Self> {
+        Some(State {
+            target,
+            ..State::new(key)
+        })
+    }
+
+    pub fn append(&mut self, mut bytes: &[u8]) {
+        if self.num != 0 {
+            let num_add = bytes.len().min(PACKET_SIZE - self.num);
+
+            self.packet[self.num..self.num + num_add].copy_from_slice(&bytes[..num_add]);
+            self.num += num_add;
+            bytes = &bytes[num_add..];
+
+            if self.num == PACKET_SIZE {
+                let packet = self.packet;
+
+                self.lanes.update_packets(&packet, self.target);
+                self.num = 0;
+            }
+        }
+
+        let (packets, remainder) = bytes.split_at(bytes.len() & !(PACKET_SIZE - 1));
+
+        self.lanes.update_packets(packets, self.target);
+
+        self.packet[..remainder.len()].copy_from_slice(remainder);
+        self.num += remainder.len();
+    }
+
+    #[inline(always)]
+    pub fn finish64(&self) -> u64 {
+        self.last_lanes().finalize64(self.target)
+    }
+
+    #[inline(always)]
+    pub fn finish128(&self) -> u128 {
+        self.last_lanes().finalize128(self.target)
+    }
+
+    #[inline(always)]
+    pub fn finish256(&self) -> [u64; 4] {
+        self.last_lanes().finalize256(self.target)
+    }
+
+    /// Hashes the buffered bytes without changing the state.
+    #[inline(always)]
+    fn last_lanes(&self) -> Lanes {
+        let mut lanes = self.lanes;
+
+        if self.num != 0 {
+            lanes.update_remainder(&self.packet[..self.num]);
+        }
+
+        lanes
+    }
+
+    pub fn progress(&self) -> Progress {
+        Progress {
+            v0: self.lanes.v0,
+            v1: self.lanes.v1,
+            mul0: self.lanes.mul0,
+            mul1: self.lanes.mul1,
+            packet: self.packet,
+            num: self.num,
+        }
+    }
+
+    pub fn set_progress(&mut self, progress: &Progress) {
+        self.lanes.v0 = progress.v0;
+        self.lanes.v1 = progress.v1;
+        self.lanes.mul0 = progress.mul0;
+        self.lanes.mul1 = progress.mul1;
+        self.packet = progress.packet;
+        self.num = progress.num;
+    }
+}
@@ -0,0 +1,98 @@
+//! The AVX2 code path, each vector is held in a single register.
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+use super::{Lanes, PACKET_SIZE};
+
+struct Registers {
+    v0: __m256i,
+    v1: __m256i,
+    mul0: __m256i,
+    mul1: __m256i,
+}
+
+impl Registers {
+    #[inline(always)]
+    unsafe fn load(lanes: &Lanes) -> Self {
+        Registers {
+            v0: load(&lanes.v0),
+            v1: load(&lanes.v1),
+            mul0: load(&lanes.mul0),
+            mul1: load(&lanes.mul1),
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn store(&self, lanes: &mut Lanes) {
+        store(self.v0, &mut lanes.v0);
+        store(self.v1, &mut lanes.v1);
+        store(self.mul0, &mut lanes.mul0);
+        store(self.mul1, &mut lanes.mul1);
+    }
+
+    #[inline(always)]
+    unsafe fn update(&mut self, packet: __m256i) {
+        self.v1 = _mm256_add_epi64(self.v1, _mm256_add_epi64(self.mul0, packet));
+        self.mul0 = _mm256_xor_si256(
+            self.mul0,
+            _mm256_mul_epu32(self.v1, _mm256_srli_epi64(self.v0, 32)),
+        );
+        self.v0 = _mm256_add_epi64(self.v0, self.mul1);
+        self.mul1 = _mm256_xor_si256(
+            self.mul1,
+            _mm256_mul_epu32(self.v0, _mm256_srli_epi64(self.v1, 32)),
+        );
+        self.v0 = _mm256_add_epi64(self.v0, zipper_merge(self.v1));
+        self.v1 = _mm256_add_epi64(self.v1, zipper_merge(self.v0));
+    }
+}
+
+#[inline(always)]
+unsafe fn load(lanes: &[u64; 4]) -> __m256i {
+    _mm256_loadu_si256(lanes.as_ptr() as *const _)
+}
+
+#[inline(always)]
+unsafe fn store(v: __m256i, lanes: &mut [u64; 4]) {
+    _mm256_storeu_si256(lanes.as_mut_ptr() as *mut _, v)
+}
+
+#[inline(always)]
+unsafe fn zipper_merge(v: __m256i) -> __m256i {
+    _mm256_shuffle_epi8(
+        v,
+        _mm256_set_epi64x(
+            0x0708_0609_0D0A_040B,
+            0x000F_010E_0502_0C03,
+            0x0708_0609_0D0A_040B,
+            0x000F_010E_0502_0C03,
+        ),
+    )
+}
+
+#[target_feature(enable = "avx2")]
+pub unsafe fn update_packets(lanes: &mut Lanes, packets: &[u8]) {
+    let mut r = Registers::load(lanes);
+
+    for packet in packets.chunks_exact(PACKET_SIZE) {
+        r.update(_mm256_loadu_si256(packet.as_ptr() as *const _));
+    }
+
+    r.store(lanes);
+}
+
+#[target_feature(enable = "avx2")]
+pub unsafe fn permute_and_update(lanes: &mut Lanes, rounds: usize) {
+    let mut r = Registers::load(lanes);
+
+    for _ in 0..rounds {
+        // swaps the 128-bit halves and the 32-bit halves of each lane
+        let permuted = _mm256_shuffle_epi32(_mm256_permute4x64_epi64(r.v0, 0x4E), 0xB1);
+
+        r.update(permuted);
+    }
+
+    r.store(lanes);
+}
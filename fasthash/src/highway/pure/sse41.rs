@@ -0,0 +1,120 @@
+//! The SSE4.1 code path, the lanes 0-1 and 2-3 of each vector are held in two registers.
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+use super::{Lanes, PACKET_SIZE};
+
+struct Registers {
+    v0l: __m128i,
+    v0h: __m128i,
+    v1l: __m128i,
+    v1h: __m128i,
+    mul0l: __m128i,
+    mul0h: __m128i,
+    mul1l: __m128i,
+    mul1h: __m128i,
+}
+
+impl Registers {
+    #[inline(always)]
+    unsafe fn load(lanes: &Lanes) -> Self {
+        Registers {
+            v0l: load(&lanes.v0[..2]),
+            v0h: load(&lanes.v0[2..]),
+            v1l: load(&lanes.v1[..2]),
+            v1h: load(&lanes.v1[2..]),
+            mul0l: load(&lanes.mul0[..2]),
+            mul0h: load(&lanes.mul0[2..]),
+            mul1l: load(&lanes.mul1[..2]),
+            mul1h: load(&lanes.mul1[2..]),
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn store(&self, lanes: &mut Lanes) {
+        store(self.v0l, &mut lanes.v0[..2]);
+        store(self.v0h, &mut lanes.v0[2..]);
+        store(self.v1l, &mut lanes.v1[..2]);
+        store(self.v1h, &mut lanes.v1[2..]);
+        store(self.mul0l, &mut lanes.mul0[..2]);
+        store(self.mul0h, &mut lanes.mul0[2..]);
+        store(self.mul1l, &mut lanes.mul1[..2]);
+        store(self.mul1h, &mut lanes.mul1[2..]);
+    }
+
+    #[inline(always)]
+    unsafe fn update(&mut self, packet_l: __m128i, packet_h: __m128i) {
+        self.v1l = _mm_add_epi64(self.v1l, _mm_add_epi64(self.mul0l, packet_l));
+        self.v1h = _mm_add_epi64(self.v1h, _mm_add_epi64(self.mul0h, packet_h));
+        self.mul0l = _mm_xor_si128(
+            self.mul0l,
+            _mm_mul_epu32(self.v1l, _mm_srli_epi64(self.v0l, 32)),
+        );
+        self.mul0h = _mm_xor_si128(
+            self.mul0h,
+            _mm_mul_epu32(self.v1h, _mm_srli_epi64(self.v0h, 32)),
+        );
+        self.v0l = _mm_add_epi64(self.v0l, self.mul1l);
+        self.v0h = _mm_add_epi64(self.v0h, self.mul1h);
+        self.mul1l = _mm_xor_si128(
+            self.mul1l,
+            _mm_mul_epu32(self.v0l, _mm_srli_epi64(self.v1l, 32)),
+        );
+        self.mul1h = _mm_xor_si128(
+            self.mul1h,
+            _mm_mul_epu32(self.v0h, _mm_srli_epi64(self.v1h, 32)),
+        );
+        self.v0l = _mm_add_epi64(self.v0l, zipper_merge(self.v1l));
+        self.v0h = _mm_add_epi64(self.v0h, zipper_merge(self.v1h));
+        self.v1l = _mm_add_epi64(self.v1l, zipper_merge(self.v0l));
+        self.v1h = _mm_add_epi64(self.v1h, zipper_merge(self.v0h));
+    }
+}
+
+#[inline(always)]
+unsafe fn load(lanes: &[u64]) -> __m128i {
+    _mm_loadu_si128(lanes.as_ptr() as *const _)
+}
+
+#[inline(always)]
+unsafe fn store(v: __m128i, lanes: &mut [u64]) {
+    _mm_storeu_si128(lanes.as_mut_ptr() as *mut _, v)
+}
+
+#[inline(always)]
+unsafe fn zipper_merge(v: __m128i) -> __m128i {
+    _mm_shuffle_epi8(
+        v,
+        _mm_set_epi64x(0x0708_0609_0D0A_040B, 0x000F_010E_0502_0C03),
+    )
+}
+
+#[target_feature(enable = "sse4.1")]
+pub unsafe fn update_packets(lanes: &mut Lanes, packets: &[u8]) {
+    let mut r = Registers::load(lanes);
+
+    for packet in packets.chunks_exact(PACKET_SIZE) {
+        let ptr = packet.as_ptr() as *const __m128i;
+
+        r.update(_mm_loadu_si128(ptr), _mm_loadu_si128(ptr.add(1)));
+    }
+
+    r.store(lanes);
+}
+
+#[target_feature(enable = "sse4.1")]
+pub unsafe fn permute_and_update(lanes: &mut Lanes, rounds: usize) {
+    let mut r = Registers::load(lanes);
+
+    for _ in 0..rounds {
+        // swaps the 128-bit halves and the 32-bit halves of each lane
+        let permuted_l = _mm_shuffle_epi32(r.v0h, 0xB1);
+        let permuted_h = _mm_shuffle_epi32(r.v0l, 0xB1);
+
+        r.update(permuted_l, permuted_h);
+    }
+
+    r.store(lanes);
+}
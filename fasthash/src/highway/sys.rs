@@ -0,0 +1,249 @@
+//! HighwayHash backed by the vendored C++ implementation.
+use core::mem;
+
+use super::{Progress, Seed, Target};
+
+#[inline(always)]
+pub fn hash64(bytes: &[u8], key: Seed) -> u64 {
+    unsafe {
+        ffi::HighwayHash64(
+            key.as_ptr() as *mut _,
+            bytes.as_ptr() as *const _,
+            bytes.len() as u64,
+        )
+    }
+}
+
+#[inline(always)]
+pub fn hash128(bytes: &[u8], key: Seed) -> u128 {
+    let mut hash: ffi::HHResult128 = [0; 2];
+
+    unsafe {
+        ffi::HighwayHash128(
+            key.as_ptr() as *mut _,
+            bytes.as_ptr() as *const _,
+            bytes.len() as u64,
+            &mut hash,
+        )
+    }
+
+    u128::from(hash[0]) + (u128::from(hash[1]) << 64)
+}
+
+#[inline(always)]
+pub fn hash256(bytes: &[u8], key: Seed) -> [u64; 4] {
+    let mut hash: ffi::HHResult256 = [0; 4];
+
+    unsafe {
+        ffi::HighwayHash256(
+            key.as_ptr() as *mut _,
+            bytes.as_ptr() as *const _,
+            bytes.len() as u64,
+            &mut hash,
+        )
+    }
+
+    hash
+}
+
+pub fn hash64_with_target(bytes: &[u8], key: Seed, target: Target) -> Option<u64> {
+    let key = key.as_ptr() as *mut _;
+    let data = bytes.as_ptr() as *const _;
+    let size = bytes.len() as u64;
+
+    unsafe {
+        match target {
+            Target::Portable => Some(ffi::HighwayHash64_TargetPortable(key, data, size)),
+            #[cfg(all(
+                any(target_arch = "x86", target_arch = "x86_64"),
+                any(feature = "sse41", target_feature = "sse4.1")
+            ))]
+            Target::SSE41 => Some(ffi::HighwayHash64_TargetSSE41(key, data, size)),
+            #[cfg(all(
+                any(target_arch = "x86", target_arch = "x86_64"),
+                any(feature = "avx2", target_feature = "avx2")
+            ))]
+            Target::AVX2 => Some(ffi::HighwayHash64_TargetAVX2(key, data, size)),
+            // unreachable when both SIMD targets are built
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+}
+
+/// The `HH_TARGET_*` bit of the target.
+fn target_bits(target: Target) -> u32 {
+    match target {
+        Target::Portable => 1,
+        Target::SSE41 => 2,
+        Target::AVX2 => 4,
+    }
+}
+
+pub fn hash128_with_target(bytes: &[u8], key: Seed, target: Target) -> Option<u128> {
+    let mut hash = [0u64; 2];
+
+    let built = unsafe {
+        ffi::HighwayHash128_Target(
+            target_bits(target),
+            key.as_ptr(),
+            bytes.as_ptr() as *const _,
+            bytes.len() as u64,
+            hash.as_mut_ptr(),
+        )
+    };
+
+    if built {
+        Some(u128::from(hash[0]) + (u128::from(hash[1]) << 64))
+    } else {
+        None
+    }
+}
+
+pub fn hash256_with_target(bytes: &[u8], key: Seed, target: Target) -> Option<[u64; 4]> {
+    let mut hash = [0u64; 4];
+
+    let built = unsafe {
+        ffi::HighwayHash256_Target(
+            target_bits(target),
+            key.as_ptr(),
+            bytes.as_ptr() as *const _,
+            bytes.len() as u64,
+            hash.as_mut_ptr(),
+        )
+    };
+
+    if built {
+        Some(hash)
+    } else {
+        None
+    }
+}
+
+cfg_if! {
+    if #[cfg(all(
+        feature = "std",
+        any(target_arch = "x86", target_arch = "x86_64"),
+        any(feature = "sse41", target_feature = "sse4.1")
+    ))] {
+        #[inline(always)]
+        pub fn has_sse41() -> bool {
+            is_x86_feature_detected!("sse4.1")
+        }
+    } else if #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        any(feature = "sse41", target_feature = "sse4.1")
+    ))] {
+        // the CPU can't be probed without `std`, trust the build configuration
+        #[inline(always)]
+        pub fn has_sse41() -> bool {
+            true
+        }
+    } else {
+        #[inline(always)]
+        pub fn has_sse41() -> bool {
+            false
+        }
+    }
+}
+
+cfg_if! {
+    if #[cfg(all(
+        feature = "std",
+        any(target_arch = "x86", target_arch = "x86_64"),
+        any(feature = "avx2", target_feature = "avx2")
+    ))] {
+        #[inline(always)]
+        pub fn has_avx2() -> bool {
+            is_x86_feature_detected!("avx2")
+        }
+    } else if #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        any(feature = "avx2", target_feature = "avx2")
+    ))] {
+        // the CPU can't be probed without `std`, trust the build configuration
+        #[inline(always)]
+        pub fn has_avx2() -> bool {
+            true
+        }
+    } else {
+        #[inline(always)]
+        pub fn has_avx2() -> bool {
+            false
+        }
+    }
+}
+
+/// The streaming state of the `HighwayHashCat` API.
+#[derive(Clone, Copy)]
+pub struct State(ffi::HighwayHashCat);
+
+impl State {
+    #[inline(always)]
+    pub fn new(key: Seed) -> Self {
+        unsafe {
+            let mut state: ffi::HighwayHashCat = mem::zeroed();
+
+            ffi::HighwayHashCatStart(key.as_ptr(), &mut state);
+
+            State(state)
+        }
+    }
+
+    /// The `HighwayHashCat` API only has a portable implementation.
+    #[inline(always)]
+    pub fn with_target(key: Seed, target: Target) -> Option<Self> {
+        match target {
+            Target::Portable => Some(State::new(key)),
+            _ => None,
+        }
+    }
+
+    #[inline(always)]
+    pub fn append(&mut self, bytes: &[u8]) {
+        unsafe { ffi::HighwayHashCatAppend(bytes.as_ptr(), bytes.len(), &mut self.0) }
+    }
+
+    #[inline(always)]
+    pub fn finish64(&self) -> u64 {
+        unsafe { ffi::HighwayHashCatFinish64(&self.0) }
+    }
+
+    #[inline(always)]
+    pub fn finish128(&self) -> u128 {
+        let mut hash: ffi::HHResult128 = [0; 2];
+
+        unsafe { ffi::HighwayHashCatFinish128(&self.0, hash.as_mut_ptr()) }
+
+        u128::from(hash[0]) + (u128::from(hash[1]) << 64)
+    }
+
+    #[inline(always)]
+    pub fn finish256(&self) -> [u64; 4] {
+        let mut hash: ffi::HHResult256 = [0; 4];
+
+        unsafe { ffi::HighwayHashCatFinish256(&self.0, hash.as_mut_ptr()) }
+
+        hash
+    }
+
+    pub fn progress(&self) -> Progress {
+        Progress {
+            v0: self.0.state.v0,
+            v1: self.0.state.v1,
+            mul0: self.0.state.mul0,
+            mul1: self.0.state.mul1,
+            packet: self.0.packet,
+            num: self.0.num as usize,
+        }
+    }
+
+    pub fn set_progress(&mut self, progress: &Progress) {
+        self.0.state.v0 = progress.v0;
+        self.0.state.v1 = progress.v1;
+        self.0.state.mul0 = progress.mul0;
+        self.0.state.mul1 = progress.mul1;
+        self.0.packet = progress.packet;
+        self.0.num = progress.num as _;
+    }
+}
@@ -0,0 +1,548 @@
+//! HyperLogLog++ cardinality estimator.
+//!
+//! HyperLogLog estimates the number of distinct keys with a fixed amount of memory,
+//! `2^p` registers for the precision `p` give a standard error of about `1.04 / sqrt(2^p)`.
+//!
+//! Like HyperLogLog++, it uses a 64-bit hash, so there is no large range correction,
+//! and starts with a sparse representation with precision 25,
+//! which is much more accurate for small cardinalities,
+//! then converts to the dense registers when the sparse list grows larger than them.
+//! Small cardinalities of the dense registers use linear counting,
+//! the empirical bias correction of the paper is not applied.
+//!
+//! The keys are hashed with a 64-bit `FastHash` function,
+//! so the estimator shares the hash values with the fingerprints already stored,
+//! `insert_hash` takes such a hash value directly.
+//!
+//! # Example
+//!
+//! ```
+//! use fasthash::{hll::HyperLogLog, sea};
+//!
+//! let mut a = HyperLogLog::<sea::Hash64>::new(14);
+//! let mut b = HyperLogLog::<sea::Hash64>::new(14);
+//!
+//! for i in 0..10_000_u32 {
+//!     a.insert(i.to_le_bytes());
+//!     b.insert((i + 5_000).to_le_bytes());
+//! }
+//!
+//! a.merge(&b).unwrap();
+//!
+//! let n = a.count();
+//!
+//! assert!(n > 14_500 && n < 15_500, "n = {}", n);
+//!
+//! let a = HyperLogLog::<sea::Hash64>::from_bytes(&a.to_bytes()).unwrap();
+//!
+//! assert_eq!(a.count(), n);
+//! ```
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::hasher::FastHash;
+use crate::state::{self, Reader, Writer};
+
+/// The minimum precision.
+pub const MIN_PRECISION: u8 = 4;
+/// The maximum precision.
+pub const MAX_PRECISION: u8 = 18;
+/// The precision of the sparse representation.
+const SPARSE_PRECISION: u8 = 25;
+
+/// The errors of merging or deserializing the estimators.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The estimators have a different precision.
+    Incompatible,
+    /// The bytes are not a serialized estimator.
+    InvalidFormat,
+    /// The serialized estimator has an unsupported format version.
+    UnsupportedVersion(u8),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Incompatible => f.write_str("incompatible precision"),
+            Error::InvalidFormat => f.write_str("invalid HyperLogLog format"),
+            Error::UnsupportedVersion(v) => {
+                write!(f, "unsupported HyperLogLog format version {}", v)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<state::Error> for Error {
+    fn from(err: state::Error) -> Self {
+        match err {
+            state::Error::InvalidFormat => Error::InvalidFormat,
+            state::Error::UnsupportedVersion(v) => Error::UnsupportedVersion(v),
+        }
+    }
+}
+
+/// The magic number of the serialized estimators.
+const MAGIC: &[u8; 4] = b"FHLL";
+/// The current version of the serialization format.
+pub const FORMAT_VERSION: u8 = 1;
+
+const SPARSE: u8 = 0;
+const DENSE: u8 = 1;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Repr {
+    /// Sorted entries of the 25-bit index and the 6-bit rank.
+    Sparse(Vec<u32>),
+    /// The `2^p` registers.
+    Dense(Vec<u8>),
+}
+
+/// Encode a hash into a sparse entry.
+#[inline(always)]
+fn sparse_entry(hash: u64) -> u32 {
+    let index = (hash >> (64 - SPARSE_PRECISION)) as u32;
+    let rank = ((hash << SPARSE_PRECISION) | (1 << (SPARSE_PRECISION - 1))).leading_zeros() + 1;
+
+    (index << 6) | rank
+}
+
+/// Returns the register index and rank of a hash.
+#[inline(always)]
+fn dense_entry(hash: u64, p: u8) -> (usize, u8) {
+    let index = (hash >> (64 - p)) as usize;
+    let rank = ((hash << p) | (1 << (p - 1))).leading_zeros() + 1;
+
+    (index, rank as u8)
+}
+
+/// Decode a sparse entry into the register index and rank with the precision `p`.
+#[inline(always)]
+fn decode_sparse(entry: u32, p: u8) -> (usize, u8) {
+    let index = entry >> 6;
+    let rank = (entry & 0x3f) as u8;
+    let extra = SPARSE_PRECISION - p;
+    let low = index & ((1 << extra) - 1);
+
+    let rank = if low == 0 {
+        rank + extra
+    } else {
+        (low.leading_zeros() - (32 - u32::from(extra)) + 1) as u8
+    };
+
+    ((index >> extra) as usize, rank)
+}
+
+/// Estimate the cardinality with linear counting of `m` buckets and `zeros` empty buckets.
+#[inline(always)]
+fn linear_counting(m: f64, zeros: f64) -> f64 {
+    m * (m / zeros).ln()
+}
+
+/// The HyperLogLog++ cardinality estimator.
+pub struct HyperLogLog<H> {
+    precision: u8,
+    repr: Repr,
+    phantom: PhantomData<H>,
+}
+
+impl<H> HyperLogLog<H>
+where
+    H: FastHash<Hash = u64>,
+{
+    /// Constructs a new estimator with `2^precision` registers, starting in the sparse mode.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the precision is not between `MIN_PRECISION` and `MAX_PRECISION`.
+    pub fn new(precision: u8) -> Self {
+        assert!(
+            (MIN_PRECISION..=MAX_PRECISION).contains(&precision),
+            "precision must be between {} and {}",
+            MIN_PRECISION,
+            MAX_PRECISION
+        );
+
+        HyperLogLog {
+            precision,
+            repr: Repr::Sparse(Vec::new()),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Constructs a new estimator with `2^precision` registers, in the dense mode.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the precision is not between `MIN_PRECISION` and `MAX_PRECISION`.
+    pub fn dense(precision: u8) -> Self {
+        let mut hll = Self::new(precision);
+
+        hll.convert_to_dense();
+        hll
+    }
+
+    /// Returns the precision.
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    /// Returns `true` if the estimator is in the sparse mode.
+    pub fn is_sparse(&self) -> bool {
+        match self.repr {
+            Repr::Sparse(_) => true,
+            Repr::Dense(_) => false,
+        }
+    }
+
+    /// Returns `true` if no key was inserted.
+    pub fn is_empty(&self) -> bool {
+        match self.repr {
+            Repr::Sparse(ref entries) => entries.is_empty(),
+            Repr::Dense(ref registers) => registers.iter().all(|&r| r == 0),
+        }
+    }
+
+    /// Removes all the keys, back to the sparse mode.
+    pub fn clear(&mut self) {
+        self.repr = Repr::Sparse(Vec::new());
+    }
+
+    /// Inserts a key.
+    #[inline(always)]
+    pub fn insert<T: AsRef<[u8]>>(&mut self, key: T) {
+        self.insert_hash(H::hash(key))
+    }
+
+    /// Inserts the hash value of a key, it must come from the same `FastHash` function.
+    pub fn insert_hash(&mut self, hash: u64) {
+        let p = self.precision;
+
+        match self.repr {
+            Repr::Sparse(_) => self.insert_sparse(sparse_entry(hash)),
+            Repr::Dense(ref mut registers) => {
+                let (index, rank) = dense_entry(hash, p);
+
+                registers[index] = registers[index].max(rank);
+            }
+        }
+    }
+
+    /// Inserts a sparse entry, converts to the dense mode if the sparse list is too large.
+    fn insert_sparse(&mut self, entry: u32) {
+        if let Repr::Sparse(ref mut entries) = self.repr {
+            match entries.binary_search_by_key(&(entry >> 6), |e| e >> 6) {
+                Ok(i) => entries[i] = entries[i].max(entry),
+                Err(i) => entries.insert(i, entry),
+            }
+
+            if entries.len() * 4 > 1 << self.precision {
+                self.convert_to_dense();
+            }
+        }
+    }
+
+    /// Converts the estimator to the dense mode.
+    fn convert_to_dense(&mut self) {
+        let p = self.precision;
+
+        if let Repr::Sparse(ref entries) = self.repr {
+            let mut registers = vec![0; 1 << p];
+
+            for &entry in entries {
+                let (index, rank) = decode_sparse(entry, p);
+
+                registers[index] = registers[index].max(rank);
+            }
+
+            self.repr = Repr::Dense(registers);
+        }
+    }
+
+    /// Returns the estimated number of distinct keys.
+    pub fn estimate(&self) -> f64 {
+        match self.repr {
+            Repr::Sparse(ref entries) => {
+                let m = (1_u64 << SPARSE_PRECISION) as f64;
+
+                linear_counting(m, m - entries.len() as f64)
+            }
+            Repr::Dense(ref registers) => {
+                let m = registers.len() as f64;
+                let alpha = match registers.len() {
+                    16 => 0.673,
+                    32 => 0.697,
+                    64 => 0.709,
+                    _ => 0.7213 / (1.0 + 1.079 / m),
+                };
+                let sum = registers
+                    .iter()
+                    .map(|&r| 2.0_f64.powi(-i32::from(r)))
+                    .sum::<f64>();
+                let estimate = alpha * m * m / sum;
+                let zeros = registers.iter().filter(|&&r| r == 0).count();
+
+                if zeros > 0 && estimate <= 2.5 * m {
+                    linear_counting(m, zeros as f64)
+                } else {
+                    estimate
+                }
+            }
+        }
+    }
+
+    /// Returns the estimated number of distinct keys, rounded.
+    pub fn count(&self) -> u64 {
+        self.estimate().round() as u64
+    }
+
+    /// Merges another estimator, the result estimates the union of the keys.
+    pub fn merge(&mut self, other: &Self) -> Result<(), Error> {
+        if self.precision != other.precision {
+            return Err(Error::Incompatible);
+        }
+
+        match other.repr {
+            Repr::Sparse(ref entries) if self.is_sparse() => {
+                let p = self.precision;
+
+                for &entry in entries {
+                    // the sparse list may be converted to the dense mode while merging
+                    match self.repr {
+                        Repr::Sparse(_) => self.insert_sparse(entry),
+                        Repr::Dense(ref mut registers) => {
+                            let (index, rank) = decode_sparse(entry, p);
+
+                            registers[index] = registers[index].max(rank);
+                        }
+                    }
+                }
+            }
+            _ => {
+                let mut other = other.clone();
+
+                self.convert_to_dense();
+                other.convert_to_dense();
+
+                if let (Repr::Dense(ref mut registers), Repr::Dense(ref others)) =
+                    (&mut self.repr, &other.repr)
+                {
+                    for (r, o) in registers.iter_mut().zip(others.iter()) {
+                        *r = (*r).max(*o);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Serializes the estimator.
+    ///
+    /// The format is the magic `FHLL`, the format version, the precision and the representation,
+    /// followed by the number of entries as `u32` and the `u32` entries in the sparse mode,
+    /// or the `2^p` registers in the dense mode, all in little endian.
+    /// The hash function is not recorded, it must be the same to deserialize.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::header(MAGIC, FORMAT_VERSION, 2 + (1 << self.precision));
+
+        w.u8(self.precision);
+
+        match self.repr {
+            Repr::Sparse(ref entries) => {
+                w.u8(SPARSE).u32(entries.len() as u32);
+
+                for &entry in entries {
+                    w.u32(entry);
+                }
+            }
+            Repr::Dense(ref registers) => {
+                w.u8(DENSE).bytes(registers);
+            }
+        }
+
+        w.into_bytes()
+    }
+
+    /// Deserializes an estimator serialized by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let mut r = Reader::header(bytes, MAGIC, FORMAT_VERSION)?;
+        let precision = r.u8()?;
+
+        if !(MIN_PRECISION..=MAX_PRECISION).contains(&precision) {
+            return Err(Error::InvalidFormat);
+        }
+
+        let repr = match r.u8()? {
+            SPARSE => {
+                let len = r.u32()? as usize;
+
+                if r.rest().len() != len * 4 {
+                    return Err(Error::InvalidFormat);
+                }
+
+                let entries = (0..len).map(|_| r.u32()).collect::<Result<Vec<_>, _>>()?;
+
+                if entries.windows(2).any(|w| w[0] >> 6 >= w[1] >> 6)
+                    || entries
+                        .iter()
+                        .any(|&e| e & 0x3f == 0 || e & 0x3f > 64 - u32::from(SPARSE_PRECISION) + 1)
+                {
+                    return Err(Error::InvalidFormat);
+                }
+
+                Repr::Sparse(entries)
+            }
+            DENSE => {
+                let registers = r.bytes(1 << precision)?;
+
+                if registers.iter().any(|&rank| rank > 64 - precision + 1) {
+                    return Err(Error::InvalidFormat);
+                }
+
+                Repr::Dense(registers.to_vec())
+            }
+            _ => return Err(Error::InvalidFormat),
+        };
+
+        r.finish()?;
+
+        Ok(HyperLogLog {
+            precision,
+            repr,
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<H> Clone for HyperLogLog<H> {
+    fn clone(&self) -> Self {
+        HyperLogLog {
+            precision: self.precision,
+            repr: self.repr.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<H> fmt::Debug for HyperLogLog<H> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("HyperLogLog")
+            .field("precision", &self.precision)
+            .field("repr", &self.repr)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sea::Hash64;
+    use crate::state::check_format;
+
+    fn build(hll: &mut HyperLogLog<Hash64>, keys: std::ops::Range<u32>) {
+        for i in keys {
+            hll.insert(i.to_le_bytes());
+        }
+    }
+
+    #[test]
+    fn test_estimate() {
+        for &p in &[MIN_PRECISION, 10, 14, MAX_PRECISION] {
+            let error = 1.04 / f64::from(1_u32 << p).sqrt();
+
+            for &n in &[0_u32, 1, 10, 100, 1000, 10_000, 100_000] {
+                let mut hll = HyperLogLog::<Hash64>::new(p);
+
+                build(&mut hll, 0..n);
+
+                let estimate = hll.estimate();
+                let tolerance = (f64::from(n) * error * 4.0).max(1.0);
+
+                assert!(
+                    (estimate - f64::from(n)).abs() <= tolerance,
+                    "p = {}, n = {}, estimate = {}",
+                    p,
+                    n,
+                    estimate
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_sparse_to_dense() {
+        for &p in &[MIN_PRECISION, 12, MAX_PRECISION] {
+            let mut sparse = HyperLogLog::<Hash64>::new(p);
+            let mut dense = HyperLogLog::<Hash64>::dense(p);
+
+            build(&mut sparse, 0..100);
+            build(&mut dense, 0..100);
+
+            sparse.convert_to_dense();
+
+            assert_eq!(sparse.repr, dense.repr);
+        }
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut a = HyperLogLog::<Hash64>::new(12);
+        let mut b = HyperLogLog::<Hash64>::new(12);
+        let mut c = HyperLogLog::<Hash64>::new(12);
+
+        build(&mut a, 0..500);
+        build(&mut b, 250..750);
+        build(&mut c, 0..750);
+
+        let mut ab = a.clone();
+        ab.merge(&b).unwrap();
+        assert!(ab.is_sparse());
+        assert_eq!(ab.repr, c.repr);
+
+        build(&mut a, 500..100_000);
+        build(&mut c, 750..100_000);
+        assert!(!a.is_sparse());
+
+        a.merge(&b).unwrap();
+        assert_eq!(a.repr, c.repr);
+
+        b.merge(&c).unwrap();
+        assert_eq!(b.repr, c.repr);
+
+        let mut a = HyperLogLog::<Hash64>::new(10);
+        let mut b = HyperLogLog::<Hash64>::new(10);
+        let mut c = HyperLogLog::<Hash64>::new(10);
+
+        build(&mut a, 0..200);
+        build(&mut b, 200..400);
+        build(&mut c, 0..400);
+        assert!(a.is_sparse() && b.is_sparse() && !c.is_sparse());
+
+        a.merge(&b).unwrap();
+        assert!(!a.is_sparse());
+        assert_eq!(a.repr, c.repr);
+
+        assert_eq!(
+            a.merge(&HyperLogLog::new(13)).unwrap_err(),
+            Error::Incompatible
+        );
+    }
+
+    #[test]
+    fn test_serialize() {
+        let mut hll = HyperLogLog::<Hash64>::new(10);
+
+        for &n in &[0, 100, 10_000] {
+            build(&mut hll, 0..n);
+
+            let bytes = hll.to_bytes();
+            let decoded = HyperLogLog::<Hash64>::from_bytes(&bytes).unwrap();
+
+            assert_eq!(decoded.repr, hll.repr);
+            check_format(&bytes, HyperLogLog::<Hash64>::from_bytes);
+        }
+    }
+}
@@ -3,6 +3,8 @@
 //! # Example
 //!
 //! ```rust
+//! # #[cfg(feature = "sys")]
+//! # {
 //! use std::hash::{Hash, Hasher};
 //!
 //! use fasthash::{metro, MetroHasher};
@@ -16,6 +18,7 @@
 //! let h = metro::hash64(b"hello world\xff");
 //!
 //! assert_eq!(h, hash(&"hello world"));
+//! # }
 //! ```
 //!
 //! By default, `HashMap` uses a hashing algorithm selected to
@@ -29,15 +32,17 @@
 //! ```rust
 //! use std::collections::HashSet;
 //!
-//! use fasthash::spooky::Hash128;
+//! use fasthash::sea::Hash64;
 //!
-//! let mut set = HashSet::with_hasher(Hash128);
+//! let mut set = HashSet::with_hasher(Hash64);
 //! set.insert(2);
 //! ```
 //!
 //! Or use `RandomState<CityHash64>` with a random seed.
 //!
 //! ```rust
+//! # #[cfg(any(feature = "sys", feature = "pure-city"))]
+//! # {
 //! use std::collections::HashMap;
 //!
 //! use fasthash::{city, RandomState};
@@ -51,13 +56,32 @@
 //! map.insert(37, "b");
 //! assert_eq!(map.insert(37, "c"), Some("b"));
 //! assert_eq!(map[&37], "c");
+//! # }
 //! ```
+//!
+//! The default `native` feature probes the build machine and enables its SIMD instructions,
+//! which may crash with `SIGILL` on an older CPU. Build with the `runtime` feature instead
+//! to compile in every SIMD variant and select them with `is_x86_feature_detected!`,
+//! the hash values and root aliases like `MetroHasher` stay the same across machines.
+//! The runtime dispatch is only done on x86_64, the other targets build the portable code
+//! and the SIMD variants enabled by the features or target features.
+//!
+//! The crate is `no_std` compatible with `alloc` when the default `std` feature is disabled,
+//! the `FastHash` and `FastHasher` traits and the one-shot hash functions stay available,
+//! while `RandomState`, `Seed` and `StreamHasher` need an OS RNG or `std::io`.
+//! The C based hashes behind the `sys` feature still link the C/C++ runtime,
+//! use `sea`, `pure-city`, `pure-highway`, `pure-t1ha`, `pure-xxh3` or the `pure-farm`
+//! fingerprints on a bare metal target.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![warn(missing_docs)]
 
+extern crate alloc;
 #[macro_use]
 extern crate cfg_if;
+#[cfg(all(feature = "sys", any(test, not(feature = "pure-t1ha"))))]
 #[macro_use]
 extern crate lazy_static;
+#[cfg(feature = "sys")]
 extern crate fasthash_sys as ffi;
 
 cfg_if! {
@@ -70,51 +94,87 @@ cfg_if! {
 
 #[macro_use]
 mod hasher;
+cfg_if! {
+    if #[cfg(feature = "sys")] {
+        pub mod lookup3;
+        pub mod metro;
+        pub mod mum;
+        pub mod murmur;
+        pub mod murmur2;
+        pub mod murmur3;
+        pub mod spooky;
+        pub mod xx;
+    }
+}
+pub mod bloom;
+#[cfg(any(feature = "sys", feature = "pure-city"))]
 pub mod city;
+#[cfg(any(feature = "sys", feature = "pure-farm"))]
 pub mod farm;
+#[cfg(feature = "std")]
+pub mod filter;
+#[cfg(any(feature = "sys", feature = "pure-highway"))]
 pub mod highway;
-pub mod lookup3;
-pub mod metro;
-pub mod mum;
-pub mod murmur;
-pub mod murmur2;
-pub mod murmur3;
+#[cfg(feature = "std")]
+pub mod hll;
+#[cfg(feature = "std")]
+pub mod mphf;
+#[cfg(feature = "quality")]
+pub mod quality;
+pub mod rolling;
 pub mod sea;
-pub mod spooky;
+pub mod shard;
+#[cfg(feature = "std")]
+pub mod similarity;
+#[cfg(feature = "std")]
+pub mod sketch;
+pub mod state;
+#[cfg(any(feature = "sys", feature = "pure-t1ha"))]
 pub mod t1ha;
-pub mod xx;
+#[cfg(any(feature = "sys", feature = "pure-xxh3"))]
 pub mod xxh3;
 
-pub use crate::hasher::{
-    BufHasher, FastHash, FastHasher, Fingerprint, HasherExt, RandomState, Seed, StreamHasher,
-};
+pub use crate::hasher::{BufHasher, FastHash, FastHasher, Fingerprint, HasherExt, Snapshot};
+#[cfg(feature = "std")]
+pub use crate::hasher::{RandomState, Seed, StreamHasher};
+pub use crate::state::HasherState;
 
-pub use crate::farm::{Hasher128 as FarmHasherExt, Hasher64 as FarmHasher};
-pub use crate::lookup3::Hasher32 as Lookup3Hasher;
-pub use crate::mum::Hasher64 as MumHasher;
-pub use crate::murmur::Hasher32 as MurmurHasher;
-pub use crate::murmur3::Hasher32 as Murmur3Hasher;
 #[doc(no_inline)]
 pub use crate::sea::Hasher64 as SeaHasher;
-pub use crate::spooky::{Hasher128 as SpookyHasherExt, Hasher64 as SpookyHasher};
+
+#[cfg(any(feature = "sys", feature = "pure-t1ha"))]
 pub use crate::t1ha::{t1ha0, t1ha1, t1ha2};
+#[cfg(any(feature = "sys", feature = "pure-t1ha"))]
 pub use crate::t1ha2::{Hasher128 as T1haHasherExt, Hasher128 as T1haHasher};
-pub use crate::xx::Hasher64 as XXHasher;
-cfg_if! {
-    if #[cfg(target_pointer_width = "64")] {
-        pub use crate::murmur2::Hasher64_x64 as Murmur2Hasher;
-        pub use crate::murmur3::Hasher128_x64 as Murmur3HasherExt;
-    } else {
-        pub use murmur2::Hasher64_x86 as Murmur2Hasher;
-        pub use murmur3::Hasher128_x86 as Murmur3HasherExt;
-    }
-}
+
 cfg_if! {
-    if #[cfg(any(feature = "sse42", target_feature = "sse4.2"))] {
-        pub use crate::city::{Hasher64 as CityHasher, crc::Hasher128 as CityHasherExt};
-        pub use crate::metro::{crc::Hasher128_1 as MetroHasherExt, crc::Hasher64_1 as MetroHasher};
-    } else {
-        pub use city::{Hasher128 as CityHasherExt, Hasher64 as CityHasher};
-        pub use metro::{Hasher128_1 as MetroHasherExt, Hasher64_1 as MetroHasher};
+    if #[cfg(feature = "sys")] {
+        pub use crate::farm::{Hasher128 as FarmHasherExt, Hasher64 as FarmHasher};
+        pub use crate::lookup3::Hasher32 as Lookup3Hasher;
+        pub use crate::mum::Hasher64 as MumHasher;
+        pub use crate::murmur::Hasher32 as MurmurHasher;
+        pub use crate::murmur3::Hasher32 as Murmur3Hasher;
+        pub use crate::spooky::{Hasher128 as SpookyHasherExt, Hasher64 as SpookyHasher};
+        pub use crate::xx::Hasher64 as XXHasher;
+        cfg_if! {
+            if #[cfg(target_pointer_width = "64")] {
+                pub use crate::murmur2::Hasher64_x64 as Murmur2Hasher;
+                pub use crate::murmur3::Hasher128_x64 as Murmur3HasherExt;
+            } else {
+                pub use murmur2::Hasher64_x86 as Murmur2Hasher;
+                pub use murmur3::Hasher128_x86 as Murmur3HasherExt;
+            }
+        }
+        cfg_if! {
+            if #[cfg(all(not(feature = "runtime"), any(feature = "sse42", target_feature = "sse4.2")))] {
+                pub use crate::city::{Hasher64 as CityHasher, crc::Hasher128 as CityHasherExt};
+                pub use crate::metro::{crc::Hasher128_1 as MetroHasherExt, crc::Hasher64_1 as MetroHasher};
+            } else {
+                pub use city::{Hasher128 as CityHasherExt, Hasher64 as CityHasher};
+                pub use metro::{Hasher128_1 as MetroHasherExt, Hasher64_1 as MetroHasher};
+            }
+        }
+    } else if #[cfg(feature = "pure-city")] {
+        pub use crate::city::{Hasher128 as CityHasherExt, Hasher64 as CityHasher};
     }
 }
@@ -23,7 +23,7 @@
 //! assert_eq!(h, hash(&"hello world") as u32);
 //! ```
 //!
-use std::os::raw::c_void;
+use core::ffi::c_void;
 
 use crate::ffi;
 
@@ -75,7 +75,7 @@ trivial_hasher! {
     /// h.write(b"world");
     /// assert_eq!(h.finish(), 1392336737);
     /// ```
-    Hasher32(Hash32) -> u32
+    Hasher32(Hash32) -> u32, "fasthash::lookup3::Hasher32"
 }
 
 /// `Lookup3` 32-bit hash functions for a byte array.
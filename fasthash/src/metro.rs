@@ -45,6 +45,13 @@ use crate::ffi;
 
 use crate::hasher::FastHash;
 
+#[cfg(all(
+    feature = "runtime",
+    any(target_arch = "x86", target_arch = "x86_64"),
+    any(feature = "sse42", target_feature = "sse4.2")
+))]
+mod soft;
+
 /// `MetroHash` 64-bit hash functions
 ///
 /// # Example
@@ -96,7 +103,7 @@ trivial_hasher! {
     /// h.write(b"world");
     /// assert_eq!(h.finish(), 4615394705531318333);
     /// ```
-    Hasher64_1(Hash64_1) -> u64
+    Hasher64_1(Hash64_1) -> u64, "fasthash::metro::Hasher64_1"
 }
 
 /// `MetroHash` 64-bit hash functions
@@ -150,7 +157,7 @@ trivial_hasher! {
     /// h.write(b"world");
     /// assert_eq!(h.finish(), 13816693401637061492);
     /// ```
-    Hasher64_2(Hash64_2) -> u64
+    Hasher64_2(Hash64_2) -> u64, "fasthash::metro::Hasher64_2"
 }
 
 /// `MetroHash` 128-bit hash functions
@@ -213,7 +220,7 @@ trivial_hasher! {
     /// h.write(b"world");
     /// assert_eq!(h.finish_ext(), 168124756093089300765778527570074281113);
     /// ```
-    Hasher128_1(Hash128_1) -> u128
+    Hasher128_1(Hash128_1) -> u128, "fasthash::metro::Hasher128_1"
 }
 
 /// `MetroHash` 128-bit hash functions
@@ -276,10 +283,13 @@ trivial_hasher! {
     /// h.write(b"world");
     /// assert_eq!(h.finish_ext(), 296295343271043311657399689121923046467);
     /// ```
-    Hasher128_2(Hash128_2) -> u128
+    Hasher128_2(Hash128_2) -> u128, "fasthash::metro::Hasher128_2"
 }
 
 /// hash functions using HW CRC instruction.
+///
+/// With the `runtime` feature, they fall back to a software CRC
+/// if the CPU does not support SSE 4.2.
 #[cfg(any(feature = "sse42", target_feature = "sse4.2"))]
 pub mod crc {
     use crate::FastHash;
@@ -307,6 +317,14 @@ pub mod crc {
 
         #[inline(always)]
         fn hash_with_seed<T: AsRef<[u8]>>(bytes: T, seed: u32) -> u64 {
+            #[cfg(all(feature = "runtime", any(target_arch = "x86", target_arch = "x86_64")))]
+            {
+                // the CRC variants are always built in `runtime` mode, fall back to the Rust port
+                if !is_x86_feature_detected!("sse4.2") {
+                    return super::soft::hash64crc_1(bytes.as_ref(), seed);
+                }
+            }
+
             let mut hash = 0_u64;
 
             unsafe {
@@ -338,7 +356,7 @@ pub mod crc {
         /// h.write(b"world");
         /// assert_eq!(h.finish(), 15512397028293617890);
         /// ```
-        Hasher64_1(Hash64_1) -> u64
+        Hasher64_1(Hash64_1) -> u64, "fasthash::metro::crc::Hasher64_1"
     }
 
     /// `MetroHash` 64-bit hash functions using HW CRC instruction.
@@ -364,6 +382,14 @@ pub mod crc {
 
         #[inline(always)]
         fn hash_with_seed<T: AsRef<[u8]>>(bytes: T, seed: u32) -> u64 {
+            #[cfg(all(feature = "runtime", any(target_arch = "x86", target_arch = "x86_64")))]
+            {
+                // the CRC variants are always built in `runtime` mode, fall back to the Rust port
+                if !is_x86_feature_detected!("sse4.2") {
+                    return super::soft::hash64crc_2(bytes.as_ref(), seed);
+                }
+            }
+
             let mut hash = 0_u64;
 
             unsafe {
@@ -395,7 +421,7 @@ pub mod crc {
         /// h.write(b"world");
         /// assert_eq!(h.finish(), 11309399771810154329);
         /// ```
-        Hasher64_2(Hash64_2) -> u64
+        Hasher64_2(Hash64_2) -> u64, "fasthash::metro::crc::Hasher64_2"
     }
 
     /// `MetroHash` 128-bit hash functions using HW CRC instruction.
@@ -427,6 +453,14 @@ pub mod crc {
 
         #[inline(always)]
         fn hash_with_seed<T: AsRef<[u8]>>(bytes: T, seed: u32) -> u128 {
+            #[cfg(all(feature = "runtime", any(target_arch = "x86", target_arch = "x86_64")))]
+            {
+                // the CRC variants are always built in `runtime` mode, fall back to the Rust port
+                if !is_x86_feature_detected!("sse4.2") {
+                    return super::soft::hash128crc_1(bytes.as_ref(), seed);
+                }
+            }
+
             let mut hash = 0;
 
             unsafe {
@@ -458,7 +492,7 @@ pub mod crc {
         /// h.write(b"world");
         /// assert_eq!(h.finish_ext(), 330807979290440384643858402038145360287);
         /// ```
-        Hasher128_1(Hash128_1) -> u128
+        Hasher128_1(Hash128_1) -> u128, "fasthash::metro::crc::Hasher128_1"
     }
 
     /// `MetroHash` 128-bit hash functions using HW CRC instruction.
@@ -490,6 +524,14 @@ pub mod crc {
 
         #[inline(always)]
         fn hash_with_seed<T: AsRef<[u8]>>(bytes: T, seed: u32) -> u128 {
+            #[cfg(all(feature = "runtime", any(target_arch = "x86", target_arch = "x86_64")))]
+            {
+                // the CRC variants are always built in `runtime` mode, fall back to the Rust port
+                if !is_x86_feature_detected!("sse4.2") {
+                    return super::soft::hash128crc_2(bytes.as_ref(), seed);
+                }
+            }
+
             let mut hash = 0;
 
             unsafe {
@@ -521,12 +563,12 @@ pub mod crc {
         /// h.write(b"world");
         /// assert_eq!(h.finish_ext(), 332348429832512530891646387991260171468);
         /// ```
-        Hasher128_2(Hash128_2) -> u128
+        Hasher128_2(Hash128_2) -> u128, "fasthash::metro::crc::Hasher128_2"
     }
 }
 
 cfg_if! {
-    if #[cfg(any(feature = "sse42", target_feature = "sse4.2"))] {
+    if #[cfg(all(not(feature = "runtime"), any(feature = "sse42", target_feature = "sse4.2")))] {
         /// `MetroHash` 64-bit hash function for a byte array using HW CRC instruction.
         #[inline(always)]
         pub fn hash64<T: AsRef<[u8]>>(v: T) -> u64 {
@@ -580,3 +622,65 @@ cfg_if! {
         }
     }
 }
+
+#[cfg(all(
+    test,
+    feature = "runtime",
+    any(target_arch = "x86", target_arch = "x86_64"),
+    any(feature = "sse42", target_feature = "sse4.2")
+))]
+mod tests {
+    use super::{crc, soft};
+    use crate::FastHash;
+
+    fn sample(len: usize) -> Vec<u8> {
+        let mut x = 0x9E37_79B1_u64;
+
+        (0..len)
+            .map(|_| {
+                x = x.wrapping_mul(0x9E37_79B1_85EB_CA87);
+                (x >> 56) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_soft_crc() {
+        if !is_x86_feature_detected!("sse4.2") {
+            return;
+        }
+
+        let data = sample(300);
+
+        for len in 0..=data.len() {
+            let v = &data[..len];
+
+            for &seed in &[0, 123, 0x9E37_79B1] {
+                assert_eq!(
+                    soft::hash64crc_1(v, seed),
+                    crc::Hash64_1::hash_with_seed(v, seed),
+                    "hash64crc_1, len={}",
+                    len
+                );
+                assert_eq!(
+                    soft::hash64crc_2(v, seed),
+                    crc::Hash64_2::hash_with_seed(v, seed),
+                    "hash64crc_2, len={}",
+                    len
+                );
+                assert_eq!(
+                    soft::hash128crc_1(v, seed),
+                    crc::Hash128_1::hash_with_seed(v, seed),
+                    "hash128crc_1, len={}",
+                    len
+                );
+                assert_eq!(
+                    soft::hash128crc_2(v, seed),
+                    crc::Hash128_2::hash_with_seed(v, seed),
+                    "hash128crc_2, len={}",
+                    len
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//! `MetroHash` CRC variants with a software CRC-32C,
+//! bit for bit compatible with the vendored C++ implementation.
+//!
+//! The CRC variants are always built in `runtime` mode, they use this port
+//! if the CPU does not support SSE 4.2.
+use core::convert::TryInto;
+
+use crate::hasher::crc32c_u64;
+
+#[inline(always)]
+fn read_u64(v: &[u8]) -> u64 {
+    u64::from_le_bytes(v[..8].try_into().unwrap())
+}
+
+#[inline(always)]
+fn read_u32(v: &[u8]) -> u64 {
+    u64::from(u32::from_le_bytes(v[..4].try_into().unwrap()))
+}
+
+#[inline(always)]
+fn read_u16(v: &[u8]) -> u64 {
+    u64::from(u16::from_le_bytes(v[..2].try_into().unwrap()))
+}
+
+const K64_1: [u64; 4] = [0xC83A_91E1, 0x8648_DBDB, 0x7BDE_C03B, 0x2F58_70A5];
+const K64_2: [u64; 4] = [0xD6D0_18F5, 0xA2AA_033B, 0x6299_2FC1, 0x30BC_5B29];
+
+/// `metrohash64crc_1`
+pub fn hash64crc_1(bytes: &[u8], seed: u32) -> u64 {
+    metrohash64crc(bytes, seed, K64_1)
+}
+
+/// `metrohash64crc_2`
+pub fn hash64crc_2(bytes: &[u8], seed: u32) -> u64 {
+    metrohash64crc(bytes, seed, K64_2)
+}
+
+#[inline(always)]
+fn metrohash64crc(bytes: &[u8], seed: u32, k: [u64; 4]) -> u64 {
+    let [k0, k1, k2, k3] = k;
+    let mut hash = u64::from(seed)
+        .wrapping_add(k2)
+        .wrapping_mul(k0)
+        .wrapping_add(bytes.len() as u64);
+    let mut s = bytes;
+
+    if s.len() >= 32 {
+        let mut v = [hash; 4];
+
+        while s.len() >= 32 {
+            for (i, v) in v.iter_mut().enumerate() {
+                *v ^= crc32c_u64(*v, read_u64(&s[i * 8..]));
+            }
+            s = &s[32..];
+        }
+
+        v[2] ^= (v[0].wrapping_add(v[3]).wrapping_mul(k0).wrapping_add(v[1]))
+            .rotate_right(33)
+            .wrapping_mul(k1);
+        v[3] ^= (v[1].wrapping_add(v[2]).wrapping_mul(k1).wrapping_add(v[0]))
+            .rotate_right(33)
+            .wrapping_mul(k0);
+        v[0] ^= (v[0].wrapping_add(v[2]).wrapping_mul(k0).wrapping_add(v[3]))
+            .rotate_right(33)
+            .wrapping_mul(k1);
+        v[1] ^= (v[1].wrapping_add(v[3]).wrapping_mul(k1).wrapping_add(v[2]))
+            .rotate_right(33)
+            .wrapping_mul(k0);
+        hash = hash.wrapping_add(v[0] ^ v[1]);
+    }
+
+    if s.len() >= 16 {
+        let mut v0 = hash.wrapping_add(read_u64(s).wrapping_mul(k0));
+        v0 = v0.rotate_right(33).wrapping_mul(k1);
+        let mut v1 = hash.wrapping_add(read_u64(&s[8..]).wrapping_mul(k1));
+        v1 = v1.rotate_right(33).wrapping_mul(k2);
+        v0 ^= v0.wrapping_mul(k0).rotate_right(35).wrapping_add(v1);
+        v1 ^= v1.wrapping_mul(k3).rotate_right(35).wrapping_add(v0);
+        hash = hash.wrapping_add(v1);
+        s = &s[16..];
+    }
+
+    if s.len() >= 8 {
+        hash = hash.wrapping_add(read_u64(s).wrapping_mul(k3));
+        hash ^= hash.rotate_right(33).wrapping_mul(k1);
+        s = &s[8..];
+    }
+
+    if s.len() >= 4 {
+        hash ^= crc32c_u64(hash, read_u32(s));
+        hash ^= hash.rotate_right(15).wrapping_mul(k1);
+        s = &s[4..];
+    }
+
+    if s.len() >= 2 {
+        hash ^= crc32c_u64(hash, read_u16(s));
+        hash ^= hash.rotate_right(13).wrapping_mul(k1);
+        s = &s[2..];
+    }
+
+    if !s.is_empty() {
+        hash ^= crc32c_u64(hash, u64::from(s[0]));
+        hash ^= hash.rotate_right(25).wrapping_mul(k1);
+    }
+
+    hash ^= hash.rotate_right(33);
+    hash = hash.wrapping_mul(k0);
+    hash ^= hash.rotate_right(33);
+
+    hash
+}
+
+/// The rotations of a `metrohash128crc` variant.
+struct Rotations {
+    mix: [u32; 2],
+    tail16: [u32; 2],
+    tail8: [u32; 2],
+    tail4: u32,
+    tail2: u32,
+    tail1: u32,
+    finalize: [u32; 2],
+}
+
+const K128_1: [u64; 4] = [0xC83A_91E1, 0x8648_DBDB, 0x7BDE_C03B, 0x2F58_70A5];
+const R128_1: Rotations = Rotations {
+    mix: [34, 37],
+    tail16: [34, 30],
+    tail8: [36, 23],
+    tail4: 19,
+    tail2: 13,
+    tail1: 17,
+    finalize: [11, 26],
+};
+
+const K128_2: [u64; 4] = [0xEE78_3E2F, 0xAD07_C493, 0x797A_90BB, 0x2E4B_2E1B];
+const R128_2: Rotations = Rotations {
+    mix: [12, 19],
+    tail16: [41, 10],
+    tail8: [34, 22],
+    tail4: 14,
+    tail2: 15,
+    tail1: 18,
+    finalize: [15, 27],
+};
+
+/// `metrohash128crc_1`
+pub fn hash128crc_1(bytes: &[u8], seed: u32) -> u128 {
+    metrohash128crc(bytes, seed, K128_1, &R128_1)
+}
+
+/// `metrohash128crc_2`
+pub fn hash128crc_2(bytes: &[u8], seed: u32) -> u128 {
+    metrohash128crc(bytes, seed, K128_2, &R128_2)
+}
+
+#[inline(always)]
+fn metrohash128crc(bytes: &[u8], seed: u32, k: [u64; 4], r: &Rotations) -> u128 {
+    let [k0, k1, k2, k3] = k;
+    let seed = u64::from(seed);
+    let len = bytes.len() as u64;
+    let mut v = [
+        seed.wrapping_sub(k0).wrapping_mul(k3).wrapping_add(len),
+        seed.wrapping_add(k1).wrapping_mul(k2).wrapping_add(len),
+        0,
+        0,
+    ];
+    let mut s = bytes;
+
+    if s.len() >= 32 {
+        v[2] = seed.wrapping_add(k0).wrapping_mul(k2).wrapping_add(len);
+        v[3] = seed.wrapping_sub(k1).wrapping_mul(k3).wrapping_add(len);
+
+        while s.len() >= 32 {
+            for (i, v) in v.iter_mut().enumerate() {
+                *v ^= crc32c_u64(*v, read_u64(&s[i * 8..]));
+            }
+            s = &s[32..];
+        }
+
+        v[2] ^= (v[0].wrapping_add(v[3]).wrapping_mul(k0).wrapping_add(v[1]))
+            .rotate_right(r.mix[0])
+            .wrapping_mul(k1);
+        v[3] ^= (v[1].wrapping_add(v[2]).wrapping_mul(k1).wrapping_add(v[0]))
+            .rotate_right(r.mix[1])
+            .wrapping_mul(k0);
+        v[0] ^= (v[0].wrapping_add(v[2]).wrapping_mul(k0).wrapping_add(v[3]))
+            .rotate_right(r.mix[0])
+            .wrapping_mul(k1);
+        v[1] ^= (v[1].wrapping_add(v[3]).wrapping_mul(k1).wrapping_add(v[2]))
+            .rotate_right(r.mix[1])
+            .wrapping_mul(k0);
+    }
+
+    if s.len() >= 16 {
+        v[0] = v[0].wrapping_add(read_u64(s).wrapping_mul(k2));
+        v[0] = v[0].rotate_right(r.tail16[0]).wrapping_mul(k3);
+        v[1] = v[1].wrapping_add(read_u64(&s[8..]).wrapping_mul(k2));
+        v[1] = v[1].rotate_right(r.tail16[0]).wrapping_mul(k3);
+        v[0] ^= (v[0].wrapping_mul(k2).wrapping_add(v[1]))
+            .rotate_right(r.tail16[1])
+            .wrapping_mul(k1);
+        v[1] ^= (v[1].wrapping_mul(k3).wrapping_add(v[0]))
+            .rotate_right(r.tail16[1])
+            .wrapping_mul(k0);
+        s = &s[16..];
+    }
+
+    if s.len() >= 8 {
+        v[0] = v[0].wrapping_add(read_u64(s).wrapping_mul(k2));
+        v[0] = v[0].rotate_right(r.tail8[0]).wrapping_mul(k3);
+        v[0] ^= (v[0].wrapping_mul(k2).wrapping_add(v[1]))
+            .rotate_right(r.tail8[1])
+            .wrapping_mul(k1);
+        s = &s[8..];
+    }
+
+    if s.len() >= 4 {
+        v[1] ^= crc32c_u64(v[0], read_u32(s));
+        v[1] ^= (v[1].wrapping_mul(k3).wrapping_add(v[0]))
+            .rotate_right(r.tail4)
+            .wrapping_mul(k0);
+        s = &s[4..];
+    }
+
+    if s.len() >= 2 {
+        v[0] ^= crc32c_u64(v[1], read_u16(s));
+        v[0] ^= (v[0].wrapping_mul(k2).wrapping_add(v[1]))
+            .rotate_right(r.tail2)
+            .wrapping_mul(k1);
+        s = &s[2..];
+    }
+
+    if !s.is_empty() {
+        v[1] ^= crc32c_u64(v[0], u64::from(s[0]));
+        v[1] ^= (v[1].wrapping_mul(k3).wrapping_add(v[0]))
+            .rotate_right(r.tail1)
+            .wrapping_mul(k0);
+    }
+
+    for _ in 0..2 {
+        v[0] = v[0]
+            .wrapping_add((v[0].wrapping_mul(k0).wrapping_add(v[1])).rotate_right(r.finalize[0]));
+        v[1] = v[1]
+            .wrapping_add((v[1].wrapping_mul(k1).wrapping_add(v[0])).rotate_right(r.finalize[1]));
+    }
+
+    u128::from(v[1]) << 64 | u128::from(v[0])
+}
@@ -0,0 +1,440 @@
+//! Minimal perfect hash functions, map `n` static keys to distinct indexes in `0..n`.
+//!
+//! `Mphf` is built with the BBHash algorithm: each level is a bit array of `gamma` bits per key,
+//! the keys hashed to a unique bit of a level are placed, the colliding keys go to the next level.
+//! The index of a key is the number of placed keys before its bit, counted in constant time
+//! with a rank table of one `u64` per 512 bits.
+//!
+//! Each level hashes the keys with a `FastHash` function and a different seed,
+//! derived from the level, see `Mphf::seed`.
+//! With the default `gamma` of 2, the function takes about 3.7 bits per key.
+//!
+//! See "Fast and scalable minimal perfect hashing for massive key sets"
+//! by Antoine Limasset, Guillaume Rizk, Rayan Chikhi and Pierre Peterlongo.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(any(feature = "sys", feature = "pure-xxh3"))]
+//! # {
+//! use fasthash::{mphf::Mphf, xxh3};
+//!
+//! let keys = ["apple", "banana", "cherry", "durian"];
+//! let mphf = Mphf::<xxh3::Hash64>::new(&keys).unwrap();
+//!
+//! let mut indexes = keys
+//!     .iter()
+//!     .map(|key| mphf.index_of(key).unwrap())
+//!     .collect::<Vec<_>>();
+//!
+//! indexes.sort();
+//!
+//! assert_eq!(indexes, vec![0, 1, 2, 3]);
+//!
+//! let mphf = Mphf::<xxh3::Hash64>::from_bytes(&mphf.to_bytes()).unwrap();
+//!
+//! assert_eq!(mphf.len(), 4);
+//! # }
+//! ```
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::hasher::{index_seed, FastHash};
+use crate::state::{self, Reader, Writer};
+
+/// The errors of building or deserializing the functions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The keys collide at every level, they are most likely duplicated.
+    DuplicateKeys,
+    /// The bytes are not a serialized function.
+    InvalidFormat,
+    /// The serialized function has an unsupported format version.
+    UnsupportedVersion(u8),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::DuplicateKeys => f.write_str("duplicate keys"),
+            Error::InvalidFormat => f.write_str("invalid function format"),
+            Error::UnsupportedVersion(v) => write!(f, "unsupported function format version {}", v),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<state::Error> for Error {
+    fn from(err: state::Error) -> Self {
+        match err {
+            state::Error::InvalidFormat => Error::InvalidFormat,
+            state::Error::UnsupportedVersion(v) => Error::UnsupportedVersion(v),
+        }
+    }
+}
+
+/// The magic number of the serialized functions.
+const MAGIC: &[u8; 4] = b"FHPH";
+/// The current version of the serialization format.
+pub const FORMAT_VERSION: u8 = 1;
+
+/// The default number of bits per remaining key at each level.
+pub const DEFAULT_GAMMA: f64 = 2.0;
+/// The maximum number of levels, the keys left after the last level are duplicated.
+const MAX_LEVELS: usize = 64;
+/// The number of `u64` words per rank entry.
+const RANK_WORDS: usize = 8;
+
+/// The minimal perfect hash function of a set of static keys.
+pub struct Mphf<H: FastHash> {
+    bits: Vec<u64>,
+    /// The offset in bits and the size in bits of each level.
+    levels: Vec<(usize, usize)>,
+    ranks: Vec<u64>,
+    len: usize,
+    seeds: Vec<H::Seed>,
+    phantom: PhantomData<H>,
+}
+
+impl<H> Mphf<H>
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+    H::Seed: From<u32>,
+{
+    /// Builds the function of unique keys with the default `gamma`.
+    pub fn new<T: AsRef<[u8]>>(keys: &[T]) -> Result<Self, Error> {
+        Self::with_gamma(keys, DEFAULT_GAMMA)
+    }
+
+    /// Builds the function of unique keys with `gamma` bits per remaining key at each level.
+    ///
+    /// A larger `gamma` takes more space but is faster to build and query.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `gamma` is less than 1.
+    pub fn with_gamma<T: AsRef<[u8]>>(keys: &[T], gamma: f64) -> Result<Self, Error> {
+        assert!(gamma >= 1.0, "gamma must be at least 1");
+
+        let mut remaining = keys.iter().map(AsRef::as_ref).collect::<Vec<&[u8]>>();
+        let mut bits = Vec::new();
+        let mut levels = Vec::new();
+        let mut seeds = Vec::new();
+
+        while !remaining.is_empty() {
+            if levels.len() == MAX_LEVELS {
+                return Err(Error::DuplicateKeys);
+            }
+
+            let seed = H::Seed::from(Self::seed(levels.len() as u32));
+            let size = (((remaining.len() as f64 * gamma).ceil() as usize - 1) / 64 + 1) * 64;
+            let mut hits = vec![0u64; size / 64];
+            let mut collisions = vec![0u64; size / 64];
+
+            for key in &remaining {
+                let pos = position::<H>(key, seed, size);
+                let (word, mask) = (pos / 64, 1 << (pos % 64));
+
+                if collisions[word] & mask != 0 {
+                    continue;
+                }
+                if hits[word] & mask != 0 {
+                    hits[word] &= !mask;
+                    collisions[word] |= mask;
+                } else {
+                    hits[word] |= mask;
+                }
+            }
+
+            remaining.retain(|key| {
+                let pos = position::<H>(key, seed, size);
+
+                hits[pos / 64] & (1 << (pos % 64)) == 0
+            });
+
+            levels.push((bits.len() * 64, size));
+            seeds.push(seed);
+            bits.extend_from_slice(&hits);
+        }
+
+        Ok(Mphf {
+            ranks: ranks(&bits),
+            bits,
+            levels,
+            len: keys.len(),
+            seeds,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Returns the seed of a level, derived from its index.
+    #[inline(always)]
+    pub fn seed(level: u32) -> u32 {
+        index_seed(level)
+    }
+
+    /// Returns the index in `0..len` of a key of the set.
+    ///
+    /// The index of a key not in the set is either `None` or the index of another key.
+    pub fn index_of<T: AsRef<[u8]>>(&self, key: T) -> Option<usize> {
+        let key = key.as_ref();
+
+        self.levels
+            .iter()
+            .zip(self.seeds.iter())
+            .find_map(|(&(offset, size), &seed)| {
+                let pos = offset + position::<H>(key, seed, size);
+
+                if self.bits[pos / 64] & (1 << (pos % 64)) != 0 {
+                    Some(self.rank(pos))
+                } else {
+                    None
+                }
+            })
+    }
+
+    /// Returns the number of set bits before `pos`.
+    #[inline(always)]
+    fn rank(&self, pos: usize) -> usize {
+        let word = pos / 64;
+        let block = word / RANK_WORDS;
+        let before = self.bits[block * RANK_WORDS..word]
+            .iter()
+            .map(|w| w.count_ones())
+            .sum::<u32>();
+        let mask = (1u64 << (pos % 64)) - 1;
+
+        self.ranks[block] as usize
+            + before as usize
+            + (self.bits[word] & mask).count_ones() as usize
+    }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the function was built from no key.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of levels.
+    pub fn levels(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Returns the number of bits per key, including the rank table.
+    pub fn bits_per_key(&self) -> f64 {
+        (self.bits.len() + self.ranks.len()) as f64 * 64.0 / self.len.max(1) as f64
+    }
+
+    /// Serializes the function.
+    ///
+    /// The format is the magic `FHPH`, the format version, three reserved bytes,
+    /// the number of keys as `u64`, the number of levels as `u32`,
+    /// the size in bits of each level as `u64`, followed by the bits of the levels as `u64` words,
+    /// all in little endian.
+    /// The hash function is not recorded, it must be the same to deserialize.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::header(
+            MAGIC,
+            FORMAT_VERSION,
+            3 + 8 + 4 + self.levels.len() * 8 + self.bits.len() * 8,
+        );
+
+        w.bytes(&[0; 3])
+            .u64(self.len as u64)
+            .u32(self.levels.len() as u32);
+
+        for &(_, size) in &self.levels {
+            w.u64(size as u64);
+        }
+
+        w.words(&self.bits);
+        w.into_bytes()
+    }
+
+    /// Deserializes a function serialized by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let mut r = Reader::header(bytes, MAGIC, FORMAT_VERSION)?;
+
+        if r.bytes(3)? != [0; 3] {
+            return Err(Error::InvalidFormat);
+        }
+
+        let len = r.u64()? as usize;
+        let num_levels = r.u32()? as usize;
+
+        if num_levels > MAX_LEVELS {
+            return Err(Error::InvalidFormat);
+        }
+
+        let mut levels = Vec::with_capacity(num_levels);
+        let mut offset = 0;
+
+        for _ in 0..num_levels {
+            let size = r.u64()? as usize;
+
+            if size == 0 || size / 64 * 64 != size {
+                return Err(Error::InvalidFormat);
+            }
+
+            levels.push((offset, size));
+            offset += size;
+        }
+
+        if r.rest().len() * 8 != offset {
+            return Err(Error::InvalidFormat);
+        }
+
+        let mut bits = vec![0; offset / 64];
+
+        r.words(&mut bits)?;
+        r.finish()?;
+
+        if bits.iter().map(|w| w.count_ones() as usize).sum::<usize>() != len {
+            return Err(Error::InvalidFormat);
+        }
+
+        Ok(Mphf {
+            ranks: ranks(&bits),
+            bits,
+            levels,
+            len,
+            seeds: (0..num_levels as u32)
+                .map(|level| Self::seed(level).into())
+                .collect(),
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<H: FastHash> Clone for Mphf<H> {
+    fn clone(&self) -> Self {
+        Mphf {
+            bits: self.bits.clone(),
+            levels: self.levels.clone(),
+            ranks: self.ranks.clone(),
+            len: self.len,
+            seeds: self.seeds.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<H: FastHash> fmt::Debug for Mphf<H> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Mphf")
+            .field("len", &self.len)
+            .field("levels", &self.levels.len())
+            .field("bits", &(self.bits.len() * 64))
+            .finish()
+    }
+}
+
+/// Hashes the key with the seed of a level to a bit in `0..size`.
+#[inline(always)]
+fn position<H>(key: &[u8], seed: H::Seed, size: usize) -> usize
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+{
+    let h: u128 = H::hash_with_seed(key, seed).into();
+    let h = h as u64 ^ (h >> 64) as u64;
+
+    ((u128::from(h) * size as u128) >> 64) as usize
+}
+
+/// Returns the number of set bits before each block of `RANK_WORDS` words.
+fn ranks(bits: &[u64]) -> Vec<u64> {
+    bits.chunks(RANK_WORDS)
+        .scan(0, |rank, block| {
+            let before = *rank;
+
+            *rank += block.iter().map(|w| u64::from(w.count_ones())).sum::<u64>();
+
+            Some(before)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hasher::FastHash;
+    use crate::state::check_format;
+
+    /// A 64-bit hash with a 32-bit seed, `sea::Hash64` has a tuple seed.
+    struct Hash64;
+
+    impl FastHash for Hash64 {
+        type Hash = u64;
+        type Seed = u32;
+
+        fn hash_with_seed<T: AsRef<[u8]>>(bytes: T, seed: u32) -> u64 {
+            let seed = u64::from(seed);
+
+            crate::sea::Hash64::hash_with_seed(bytes, (seed, seed, seed, seed))
+        }
+    }
+
+    impl std::hash::BuildHasher for Hash64 {
+        type Hasher = crate::sea::Hasher64;
+
+        fn build_hasher(&self) -> Self::Hasher {
+            Default::default()
+        }
+    }
+
+    impl crate::hasher::BuildHasherExt for Hash64 {
+        type FastHasher = crate::sea::Hasher64;
+    }
+
+    #[test]
+    fn test_mphf() {
+        for &n in &[0, 1, 2, 10, 1000, 100_000] {
+            let keys = (0..n).map(|i: u32| i.to_le_bytes()).collect::<Vec<_>>();
+            let mphf = Mphf::<Hash64>::new(&keys).unwrap();
+            let mut seen = vec![false; n as usize];
+
+            assert_eq!(mphf.len(), n as usize);
+
+            for key in &keys {
+                let i = mphf.index_of(key).unwrap();
+
+                assert!(!seen[i]);
+                seen[i] = true;
+            }
+
+            if n == 100_000 {
+                assert!(mphf.bits_per_key() < 4.0, "bits = {}", mphf.bits_per_key());
+            }
+
+            let copy = Mphf::<Hash64>::from_bytes(&mphf.to_bytes()).unwrap();
+
+            assert!(keys
+                .iter()
+                .all(|key| copy.index_of(key) == mphf.index_of(key)));
+        }
+
+        let mphf = Mphf::<Hash64>::with_gamma(&["a", "b", "c"], 5.0).unwrap();
+
+        assert_eq!(mphf.levels(), 1);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            Mphf::<Hash64>::new(&["a", "b", "a"]).unwrap_err(),
+            Error::DuplicateKeys
+        );
+
+        check_format(
+            &Mphf::<Hash64>::new(&["a", "b", "c"]).unwrap().to_bytes(),
+            Mphf::<Hash64>::from_bytes,
+        );
+    }
+}
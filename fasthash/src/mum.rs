@@ -53,7 +53,7 @@
 //! ```
 //!
 #![allow(non_camel_case_types)]
-use std::os::raw::c_void;
+use core::ffi::c_void;
 
 use crate::ffi;
 
@@ -105,7 +105,7 @@ trivial_hasher! {
     /// h.write(b"world");
     /// assert_eq!(h.finish(), 9122204010978352975);
     /// ```
-    Hasher64(Hash64) -> u64
+    Hasher64(Hash64) -> u64, "fasthash::mum::Hasher64"
 }
 
 /// `MumHash` 64-bit hash functions for a byte array.
@@ -40,7 +40,7 @@
 //! assert_eq!(h, hash(&"hello world") as u32);
 //! ```
 //!
-use std::os::raw::c_void;
+use core::ffi::c_void;
 
 use crate::ffi;
 
@@ -92,7 +92,7 @@ trivial_hasher! {
     /// h.write(b"world");
     /// assert_eq!(h.finish(), 567127608);
     /// ```
-    Hasher32(Hash32) -> u32
+    Hasher32(Hash32) -> u32, "fasthash::murmur::Hasher32"
 }
 
 /// `MurmurHash` 32-bit aligned hash functions
@@ -141,7 +141,7 @@ trivial_hasher! {
     /// h.write(b"world");
     /// assert_eq!(h.finish(), 567127608);
     /// ```
-    Hasher32Aligned(Hash32Aligned) -> u32
+    Hasher32Aligned(Hash32Aligned) -> u32, "fasthash::murmur::Hasher32Aligned"
 }
 
 /// `MurmurHash` 32-bit hash functions for a byte array.
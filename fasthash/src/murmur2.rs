@@ -64,7 +64,7 @@
 //! ```
 //!
 #![allow(non_camel_case_types)]
-use std::os::raw::c_void;
+use core::ffi::c_void;
 
 use crate::ffi;
 
@@ -116,7 +116,7 @@ trivial_hasher! {
     /// h.write(b"world");
     /// assert_eq!(h.finish(), 2155944146);
     /// ```
-    Hasher32(Hash32) -> u32
+    Hasher32(Hash32) -> u32, "fasthash::murmur2::Hasher32"
 }
 
 /// `MurmurHash2A` 32-bit hash functions
@@ -165,7 +165,7 @@ trivial_hasher! {
     /// h.write(b"world");
     /// assert_eq!(h.finish(), 403945221);
     /// ```
-    Hasher32A(Hash32A) -> u32
+    Hasher32A(Hash32A) -> u32, "fasthash::murmur2::Hasher32A"
 }
 
 /// `MurmurHash2` 32-bit neutral hash functions for the (slower) endian-neutral implementation
@@ -214,7 +214,7 @@ trivial_hasher! {
     /// h.write(b"world");
     /// assert_eq!(h.finish(), 2155944146);
     /// ```
-    Hasher32Neutral(Hash32Neutral) -> u32
+    Hasher32Neutral(Hash32Neutral) -> u32, "fasthash::murmur2::Hasher32Neutral"
 }
 
 /// `MurmurHash2` 32-bit aligned hash functions for the little-endian aligned-read-only implementation
@@ -263,7 +263,7 @@ trivial_hasher! {
     /// h.write(b"world");
     /// assert_eq!(h.finish(), 2155944146);
     /// ```
-    Hasher32Aligned(Hash32Aligned) -> u32
+    Hasher32Aligned(Hash32Aligned) -> u32, "fasthash::murmur2::Hasher32Aligned"
 }
 
 /// `MurmurHash2` 64-bit hash functions for 64-bit processors
@@ -315,7 +315,7 @@ trivial_hasher! {
     /// h.write(b"world");
     /// assert_eq!(h.finish(), 2139823713852166039);
     /// ```
-    Hasher64_x64(Hash64_x64) -> u64
+    Hasher64_x64(Hash64_x64) -> u64, "fasthash::murmur2::Hasher64_x64"
 }
 
 /// `MurmurHash2` 64-bit hash functions for 32-bit processors
@@ -367,7 +367,7 @@ trivial_hasher! {
     /// h.write(b"world");
     /// assert_eq!(h.finish(), 14017254558097603378);
     /// ```
-    Hasher64_x86(Hash64_x86) -> u64
+    Hasher64_x86(Hash64_x86) -> u64, "fasthash::murmur2::Hasher64_x86"
 }
 
 /// `MurmurHash2` 32-bit hash functions for a byte array.
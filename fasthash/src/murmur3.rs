@@ -30,7 +30,7 @@
 //! ```
 //!
 #![allow(non_camel_case_types)]
-use std::os::raw::c_void;
+use core::ffi::c_void;
 
 use crate::ffi;
 
@@ -47,9 +47,46 @@ use crate::hasher::FastHash;
 /// assert_eq!(Hash32::hash_with_seed(b"hello", 123), 1573043710);
 /// assert_eq!(Hash32::hash(b"helloworld"), 2687965642);
 /// ```
+///
+/// The integer keys are hashed in Rust without calling into C,
+/// with the same result as hashing their little endian bytes.
+///
+/// ```
+/// use fasthash::{murmur3::Hash32, FastHash};
+///
+/// assert_eq!(Hash32::hash_u32(123), Hash32::hash(123_u32.to_le_bytes()));
+/// assert_eq!(
+///     Hash32::hash_u64_with_seed(123, 456),
+///     Hash32::hash_with_seed(123_u64.to_le_bytes(), 456)
+/// );
+/// ```
 #[derive(Clone)]
 pub struct Hash32;
 
+/// Mix a 4-byte block into the `MurmurHash3_x86_32` state.
+#[inline(always)]
+fn mix_k1(h1: u32, k1: u32) -> u32 {
+    let k1 = k1
+        .wrapping_mul(0xcc9e_2d51)
+        .rotate_left(15)
+        .wrapping_mul(0x1b87_3593);
+
+    (h1 ^ k1)
+        .rotate_left(13)
+        .wrapping_mul(5)
+        .wrapping_add(0xe654_6b64)
+}
+
+/// Finalization mix of `MurmurHash3_x86_32`.
+#[inline(always)]
+fn fmix32(mut h: u32) -> u32 {
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2_ae35);
+    h ^ (h >> 16)
+}
+
 impl FastHash for Hash32 {
     type Hash = u32;
     type Seed = u32;
@@ -69,6 +106,26 @@ impl FastHash for Hash32 {
             hash
         }
     }
+
+    #[inline(always)]
+    fn hash_u32(n: u32) -> u32 {
+        Self::hash_u32_with_seed(n, 0)
+    }
+
+    #[inline(always)]
+    fn hash_u32_with_seed(n: u32, seed: u32) -> u32 {
+        fmix32(mix_k1(seed, n) ^ 4)
+    }
+
+    #[inline(always)]
+    fn hash_u64(n: u64) -> u32 {
+        Self::hash_u64_with_seed(n, 0)
+    }
+
+    #[inline(always)]
+    fn hash_u64_with_seed(n: u64, seed: u32) -> u32 {
+        fmix32(mix_k1(mix_k1(seed, n as u32), (n >> 32) as u32) ^ 8)
+    }
 }
 
 trivial_hasher! {
@@ -87,7 +144,7 @@ trivial_hasher! {
     /// h.write(b"world");
     /// assert_eq!(h.finish(), 2687965642);
     /// ```
-    Hasher32(Hash32) -> u32
+    Hasher32(Hash32) -> u32, "fasthash::murmur3::Hasher32"
 }
 
 /// `MurmurHash3` 128-bit hash functions for 32-bit processors
@@ -150,7 +207,7 @@ trivial_hasher! {
     /// h.write(b"world");
     /// assert_eq!(h.finish_ext(), 83212725615010754952022132390053357814);
     /// ```
-    Hasher128_x86(Hash128_x86) -> u128
+    Hasher128_x86(Hash128_x86) -> u128, "fasthash::murmur3::Hasher128_x86"
 }
 
 /// `MurmurHash3` 128-bit hash functions for 64-bit processors
@@ -213,7 +270,7 @@ trivial_hasher! {
     /// h.write(b"world");
     /// assert_eq!(h.finish_ext(), 216280293825344914020777844322685271162);
     /// ```
-    Hasher128_x64(Hash128_x64) -> u128
+    Hasher128_x64(Hash128_x64) -> u128, "fasthash::murmur3::Hasher128_x64"
 }
 
 /// `MurmurHash3` 32-bit hash functions for a byte array.
@@ -0,0 +1,852 @@
+//! SMHasher-style quality tests of the hash functions.
+//!
+//! The tests run against any `FastHash` implementation, from the raw output of its one-shot functions,
+//! so they also cover the wrappers, e.g. the conversion of a C struct into `u128`.
+//!
+//! - **avalanche**, flipping any input bit should flip every output bit with a probability of 1/2.
+//! - **bic**, the bit independence criterion, the output bits should flip independently of each other.
+//! - **sparse**, the keys with only a few bits set shouldn't collide.
+//! - **cyclic**, the keys repeating a short random block shouldn't collide.
+//! - **two-bytes**, the keys with up to two non-zero bytes shouldn't collide.
+//! - **differential**, flipping a few bits of a key shouldn't give the same hash.
+//! - **seed**, a key hashed with different seeds shouldn't collide and every output bit should be balanced.
+//! - **distribution**, the hashes of some key shapes should spread evenly over the buckets
+//!   of every window of output bits.
+//!
+//! The collisions are also counted in the low and high 32 bits of the wider hashes,
+//! like a hash table or a filter would truncate them.
+//!
+//! Every test computes a p-value, the probability of a result at least as bad from a random function,
+//! corrected for the number of cells or windows it looked at, and fails when it is below `Config::alpha`.
+//! The keys and seeds come from a fixed pseudo random sequence, so the results are reproducible.
+//!
+//! The module is a test harness rather than a hash function, it is behind the `quality` feature.
+//!
+//! Some algorithms fail by design, e.g. the vendored CityHash 1.0 and FarmHash fail the avalanche
+//! test on some key lengths, `CityHash64` mixes the last word of the 9 to 16 byte keys into its
+//! hash as is, and XXH3 returns the same hash of an empty key for any seed.
+//!
+//! # Example
+//!
+//! ```
+//! use fasthash::{quality, sea};
+//!
+//! let report = quality::run::<sea::Hash64>(&quality::Config::quick());
+//!
+//! println!("{}", report);
+//!
+//! assert!(report.passed());
+//! ```
+//!
+//! `collisions` and `distribution` take the keys, to compare the algorithms on your own key shapes.
+//!
+//! ```
+//! use fasthash::{quality, sea};
+//!
+//! let config = quality::Config::quick();
+//! let keys = (0..10_000).map(|i| format!("user:{:08}", i));
+//! let outcome = quality::distribution::<sea::Hash64, _, _>(&config, "user ids", keys);
+//!
+//! assert!(outcome.passed, "{}", outcome);
+//! ```
+use std::f64;
+use std::fmt;
+use std::mem;
+
+use xoroshiro128::{Rng, SeedableRng, Xoroshiro128Rng};
+
+use crate::hasher::{FastHash, Seed};
+
+/// The sizes of the tests.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+    /// The random keys of the avalanche and BIC tests, a tenth of them for the differential test.
+    pub reps: usize,
+    /// The maximum keys of the sparse, cyclic, seed and distribution tests.
+    pub keys: usize,
+    /// The longest keys of the two-bytes test, the lengths are multiples of 4.
+    pub two_bytes_len: usize,
+    /// The significance level, a test fails when its p-value is below it.
+    pub alpha: f64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            reps: 10_000,
+            keys: 1 << 20,
+            two_bytes_len: 12,
+            alpha: 1e-6,
+        }
+    }
+}
+
+impl Config {
+    /// A smaller configuration, which runs in a few seconds in a debug build.
+    pub fn quick() -> Self {
+        Config {
+            reps: 500,
+            keys: 20_000,
+            two_bytes_len: 4,
+            ..Default::default()
+        }
+    }
+}
+
+/// The outcome of a test.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Outcome {
+    /// The name of the test.
+    pub test: &'static str,
+    /// The keys of the test, e.g. `16 bytes`.
+    pub case: String,
+    /// The worst result found.
+    pub summary: String,
+    /// The probability of a result at least as bad from a random function.
+    pub p_value: f64,
+    /// Whether the p-value is above the significance level.
+    pub passed: bool,
+}
+
+impl Outcome {
+    /// Creates the outcome of the worst candidate, a `(p-value, summary)` pair.
+    fn worst(
+        config: &Config,
+        test: &'static str,
+        case: String,
+        candidates: Vec<(f64, String)>,
+    ) -> Self {
+        let n = candidates.len();
+        let (p_value, summary) =
+            candidates
+                .into_iter()
+                .fold((f64::INFINITY, String::new()), |worst, candidate| {
+                    if candidate.0 < worst.0 {
+                        candidate
+                    } else {
+                        worst
+                    }
+                });
+        let p_value = at_least_once(p_value, n);
+
+        Outcome {
+            test,
+            case,
+            summary,
+            p_value,
+            passed: p_value >= config.alpha,
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {:<12} {:<16} {} (p = {:.2e})",
+            if self.passed { "PASS" } else { "FAIL" },
+            self.test,
+            self.case,
+            self.summary,
+            self.p_value
+        )
+    }
+}
+
+/// The outcomes of all the tests of a hash function.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    /// The name of the hash function.
+    pub name: &'static str,
+    /// The outcomes of the tests.
+    pub outcomes: Vec<Outcome>,
+}
+
+impl Report {
+    /// Returns `true` if all the tests passed.
+    pub fn passed(&self) -> bool {
+        self.outcomes.iter().all(|outcome| outcome.passed)
+    }
+
+    /// Returns the failed tests.
+    pub fn failures(&self) -> impl Iterator<Item = &Outcome> {
+        self.outcomes.iter().filter(|outcome| !outcome.passed)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", self.name)?;
+
+        for outcome in &self.outcomes {
+            writeln!(f, "{}", outcome)?;
+        }
+
+        write!(
+            f,
+            "{}/{} tests passed",
+            self.outcomes.len() - self.failures().count(),
+            self.outcomes.len()
+        )
+    }
+}
+
+/// Runs all the tests.
+pub fn run<H>(config: &Config) -> Report
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+    H::Seed: From<Seed>,
+{
+    let mut outcomes = Vec::new();
+
+    for &len in &[4, 8, 16, 32, 64] {
+        outcomes.push(avalanche::<H>(config, len));
+    }
+
+    outcomes.push(bic::<H>(config, 8));
+
+    for &len in &[4, 8, 16, 32, 64] {
+        // as many bits as the keys allow
+        let max_bits = (1..=len * 8)
+            .take_while(|&bits| sparse_keys(len * 8, bits) <= config.keys as f64)
+            .last()
+            .unwrap_or(1);
+
+        outcomes.push(sparse::<H>(config, len, max_bits));
+    }
+
+    for &cycle in &[4, 5, 6, 7, 8, 12, 16] {
+        outcomes.push(cyclic::<H>(config, cycle, 8));
+    }
+
+    for len in (4..=config.two_bytes_len).step_by(4) {
+        outcomes.push(two_bytes::<H>(config, len));
+    }
+
+    outcomes.push(differential::<H>(config, 8, 3));
+    outcomes.push(differential::<H>(config, 16, 2));
+
+    for key in &[&b""[..], b"a", b"hello world", &[0; 64]] {
+        outcomes.push(seed::<H>(config, key));
+    }
+
+    let mut rng = rng();
+
+    outcomes.push(distribution::<H, _, _>(
+        config,
+        "sequential u32",
+        (0..config.keys as u32).map(u32::to_le_bytes),
+    ));
+    outcomes.push(distribution::<H, _, _>(
+        config,
+        "sequential text",
+        (0..config.keys).map(|i| format!("key{}", i)),
+    ));
+    outcomes.push(distribution::<H, _, _>(
+        config,
+        "random 16 bytes",
+        (0..config.keys).map(|_| rng.gen::<[u8; 16]>()),
+    ));
+
+    Report {
+        name: std::any::type_name::<H>(),
+        outcomes,
+    }
+}
+
+/// Tests the avalanche of the random keys of `len` bytes.
+pub fn avalanche<H>(config: &Config, len: usize) -> Outcome
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+{
+    let bits = hash_bits::<H>();
+    let mut flips = vec![0u32; len * 8 * bits];
+    let mut rng = rng();
+    let mut key = vec![0; len];
+
+    for _ in 0..config.reps {
+        rng.fill_bytes(&mut key);
+
+        let h = hash::<H>(&key);
+
+        for (i, row) in flips.chunks_mut(bits).enumerate() {
+            key[i / 8] ^= 1 << (i % 8);
+            let mut d = h ^ hash::<H>(&key);
+            key[i / 8] ^= 1 << (i % 8);
+
+            while d != 0 {
+                row[d.trailing_zeros() as usize] += 1;
+                d &= d - 1;
+            }
+        }
+    }
+
+    let n = config.reps as f64;
+    let bias = flips
+        .iter()
+        .map(|&c| (f64::from(c) / n * 2.0 - 1.0).abs())
+        .fold(0.0, f64::max);
+
+    Outcome::worst(
+        config,
+        "avalanche",
+        format!("{} bytes", len),
+        vec![(
+            max_normal(bias * n.sqrt(), flips.len()),
+            format!("worst bias {:.2}%", bias * 100.0),
+        )],
+    )
+}
+
+/// Tests the bit independence of the random keys of `len` bytes.
+pub fn bic<H>(config: &Config, len: usize) -> Outcome
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+{
+    let bits = hash_bits::<H>();
+    let mut both = vec![0u32; len * 8 * bits * bits];
+    let mut rng = rng();
+    let mut key = vec![0; len];
+
+    for _ in 0..config.reps {
+        rng.fill_bytes(&mut key);
+
+        let h = hash::<H>(&key);
+
+        for (i, pairs) in both.chunks_mut(bits * bits).enumerate() {
+            key[i / 8] ^= 1 << (i % 8);
+            let mut d = h ^ hash::<H>(&key);
+            key[i / 8] ^= 1 << (i % 8);
+
+            while d != 0 {
+                let j = d.trailing_zeros() as usize;
+
+                d &= d - 1;
+
+                let mut rest = d;
+
+                while rest != 0 {
+                    pairs[j * bits + rest.trailing_zeros() as usize] += 1;
+                    rest &= rest - 1;
+                }
+            }
+        }
+    }
+
+    // both bits of a pair flip with a probability of 1/4
+    let n = config.reps as f64;
+    let mut bias = 0.0;
+    let mut cells = 0;
+
+    for pairs in both.chunks(bits * bits) {
+        for j in 0..bits {
+            for &c in &pairs[j * bits + j + 1..(j + 1) * bits] {
+                bias = f64::max(bias, (f64::from(c) / n * 4.0 - 1.0).abs());
+                cells += 1;
+            }
+        }
+    }
+
+    Outcome::worst(
+        config,
+        "bic",
+        format!("{} bytes", len),
+        vec![(
+            max_normal(bias * n / 4.0 / (n * 3.0 / 16.0).sqrt(), cells),
+            format!("worst bias {:.2}%", bias * 100.0),
+        )],
+    )
+}
+
+/// Tests the collisions of the keys of `len` bytes with up to `max_bits` bits set.
+pub fn sparse<H>(config: &Config, len: usize, max_bits: usize) -> Outcome
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+{
+    let mut hashes = Vec::new();
+
+    for_each_sparse(&mut vec![0; len], 0, max_bits, &mut |key| {
+        hashes.push(hash::<H>(key))
+    });
+
+    collision_outcome::<H>(
+        config,
+        "sparse",
+        format!("{} bytes, {} bits", len, max_bits),
+        hashes,
+    )
+}
+
+/// Tests the collisions of the keys repeating a random block of `cycle` bytes `reps` times.
+///
+/// # Panics
+///
+/// Panics if `cycle` is larger than 16 bytes.
+pub fn cyclic<H>(config: &Config, cycle: usize, reps: usize) -> Outcome
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+{
+    assert!(cycle <= 16, "cycle too long");
+
+    let mut rng = rng();
+    let mask = if cycle < 16 {
+        (1 << (cycle * 8)) - 1
+    } else {
+        !0
+    };
+    let mut blocks = (0..config.keys)
+        .map(|_| (u128::from(rng.gen::<u64>()) << 64 | u128::from(rng.gen::<u64>())) & mask)
+        .collect::<Vec<_>>();
+
+    // the short blocks are not unique by chance
+    blocks.sort_unstable();
+    blocks.dedup();
+
+    let hashes = blocks
+        .into_iter()
+        .map(|block| hash::<H>(&block.to_le_bytes()[..cycle].repeat(reps)))
+        .collect();
+
+    collision_outcome::<H>(
+        config,
+        "cyclic",
+        format!("{} bytes x {}", cycle, reps),
+        hashes,
+    )
+}
+
+/// Tests the collisions of the keys of `len` bytes with up to two non-zero bytes.
+pub fn two_bytes<H>(config: &Config, len: usize) -> Outcome
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+{
+    let mut key = vec![0; len];
+    let mut hashes = vec![hash::<H>(&key)];
+
+    for i in 0..len {
+        for a in 1..=255 {
+            key[i] = a;
+            hashes.push(hash::<H>(&key));
+
+            for j in i + 1..len {
+                for b in 1..=255 {
+                    key[j] = b;
+                    hashes.push(hash::<H>(&key));
+                }
+                key[j] = 0;
+            }
+        }
+        key[i] = 0;
+    }
+
+    collision_outcome::<H>(config, "two-bytes", format!("{} bytes", len), hashes)
+}
+
+/// Tests the random keys of `len` bytes against their differences in up to `max_bits` bits.
+pub fn differential<H>(config: &Config, len: usize, max_bits: usize) -> Outcome
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+{
+    let bits = hash_bits::<H>();
+    let mut rng = rng();
+    let mut key = vec![0; len];
+    let mut pairs = 0;
+    let mut same = [0; 3];
+
+    for _ in 0..(config.reps / 10).max(1) {
+        rng.fill_bytes(&mut key);
+
+        let h = hash::<H>(&key);
+        let mut first = true;
+
+        // flipping the bits of the key itself, the first key is unchanged
+        for_each_sparse(&mut key, 0, max_bits, &mut |key| {
+            if first {
+                first = false;
+                return;
+            }
+
+            let d = h ^ hash::<H>(key);
+
+            pairs += 1;
+            same[0] += (d == 0) as usize;
+            same[1] += (d & 0xffff_ffff == 0) as usize;
+            same[2] += (d >> (bits - 32) & 0xffff_ffff == 0) as usize;
+        });
+    }
+
+    let widths = truncations(bits);
+    let candidates = widths
+        .iter()
+        .zip(&same)
+        .map(|(&(width, name), &count)| {
+            let expected = pairs as f64 * (-(width as f64)).exp2();
+
+            (
+                poisson_sf(count, expected),
+                format!("{} same hashes{}, expected {:.2}", count, name, expected),
+            )
+        })
+        .collect();
+
+    Outcome::worst(
+        config,
+        "differential",
+        format!("{} bytes, {} bits", len, max_bits),
+        candidates,
+    )
+}
+
+/// Tests a key hashed with the random seeds.
+pub fn seed<H>(config: &Config, key: &[u8]) -> Outcome
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+    H::Seed: From<Seed>,
+{
+    let bits = hash_bits::<H>();
+    let mut rng = rng();
+    // the 32-bit seeds would collide by chance
+    let hashes = (0..config.keys.min(1 << 16))
+        .map(|_| H::hash_with_seed(key, Seed::with_state(rng.gen()).into()).into())
+        .collect::<Vec<u128>>();
+
+    let n = hashes.len() as f64;
+    let ones = (0..bits)
+        .map(|i| hashes.iter().filter(|&&h| h >> i & 1 == 1).count())
+        .collect::<Vec<_>>();
+    let bias = ones
+        .iter()
+        .map(|&c| (c as f64 / n * 2.0 - 1.0).abs())
+        .fold(0.0, f64::max);
+
+    let mut candidates = collisions_of(hashes, bits);
+
+    candidates.push((
+        max_normal(bias * n.sqrt(), bits),
+        format!("worst bit bias {:.2}%", bias * 100.0),
+    ));
+
+    Outcome::worst(config, "seed", format!("{} bytes", key.len()), candidates)
+}
+
+/// Tests the collisions of the keys.
+pub fn collisions<H, I, T>(config: &Config, case: &str, keys: I) -> Outcome
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+    I: IntoIterator<Item = T>,
+    T: AsRef<[u8]>,
+{
+    let hashes = keys
+        .into_iter()
+        .map(|key| hash::<H>(key.as_ref()))
+        .collect();
+
+    collision_outcome::<H>(config, "collisions", case.to_owned(), hashes)
+}
+
+/// Tests how the keys spread over the buckets of every window of output bits.
+///
+/// The buckets are indexed by `log2(n / 16)` bits, so each holds 16 keys on average.
+pub fn distribution<H, I, T>(config: &Config, case: &str, keys: I) -> Outcome
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+    I: IntoIterator<Item = T>,
+    T: AsRef<[u8]>,
+{
+    let bits = hash_bits::<H>();
+    let hashes = keys
+        .into_iter()
+        .map(|key| hash::<H>(key.as_ref()))
+        .collect::<Vec<_>>();
+    let n = hashes.len();
+    let width = (64 - (n as u64 / 16).leading_zeros() as usize)
+        .saturating_sub(1)
+        .clamp(1, bits.min(20));
+    let mask = (1 << width) - 1;
+    let mut buckets = vec![0u32; 1 << width];
+    let expected = n as f64 / buckets.len() as f64;
+    let df = (buckets.len() - 1) as f64;
+    let mut worst = (f64::NEG_INFINITY, 0);
+
+    for offset in 0..=bits - width {
+        buckets.iter_mut().for_each(|c| *c = 0);
+
+        for h in &hashes {
+            buckets[(h >> offset) as usize & mask] += 1;
+        }
+
+        let chi2 = buckets
+            .iter()
+            .map(|&c| (f64::from(c) - expected).powi(2) / expected)
+            .sum::<f64>();
+        let z = (chi2 - df) / (2.0 * df).sqrt();
+
+        if z > worst.0 {
+            worst = (z, offset);
+        }
+    }
+
+    Outcome::worst(
+        config,
+        "distribution",
+        case.to_owned(),
+        vec![(
+            at_least_once(normal_sf(worst.0), bits - width + 1),
+            format!(
+                "worst chi-square z {:.2} in bits {}..{}",
+                worst.0,
+                worst.1,
+                worst.1 + width
+            ),
+        )],
+    )
+}
+
+fn rng() -> Xoroshiro128Rng {
+    Xoroshiro128Rng::from_seed([0x9e37_79b9_7f4a_7c15, 0xbf58_476d_1ce4_e5b9])
+}
+
+fn hash_bits<H: FastHash>() -> usize {
+    mem::size_of::<H::Hash>() * 8
+}
+
+fn hash<H>(key: &[u8]) -> u128
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+{
+    H::hash(key).into()
+}
+
+/// Calls `f` with every key setting up to `bits` more bits from the `start` bit.
+fn for_each_sparse<F: FnMut(&[u8])>(key: &mut [u8], start: usize, bits: usize, f: &mut F) {
+    f(key);
+
+    if bits > 0 {
+        for i in start..key.len() * 8 {
+            key[i / 8] ^= 1 << (i % 8);
+            for_each_sparse(key, i + 1, bits - 1, f);
+            key[i / 8] ^= 1 << (i % 8);
+        }
+    }
+}
+
+/// The number of keys of `n` bits with up to `bits` bits set.
+fn sparse_keys(n: usize, bits: usize) -> f64 {
+    let mut choose = 1.0;
+    let mut total = 1.0;
+
+    for k in 1..=bits {
+        choose *= (n + 1 - k) as f64 / k as f64;
+        total += choose;
+    }
+
+    total
+}
+
+/// The checked widths of a hash, the full hash and the low and high 32 bits of the wider hashes.
+fn truncations(bits: usize) -> Vec<(usize, &'static str)> {
+    if bits > 32 {
+        vec![
+            (bits, ""),
+            (32, " in the low 32 bits"),
+            (32, " in the high 32 bits"),
+        ]
+    } else {
+        vec![(bits, "")]
+    }
+}
+
+fn collision_outcome<H: FastHash>(
+    config: &Config,
+    test: &'static str,
+    case: String,
+    hashes: Vec<u128>,
+) -> Outcome {
+    Outcome::worst(config, test, case, collisions_of(hashes, hash_bits::<H>()))
+}
+
+fn collisions_of(hashes: Vec<u128>, bits: usize) -> Vec<(f64, String)> {
+    let n = hashes.len() as f64;
+    let pairs = n * (n - 1.0) / 2.0;
+
+    truncations(bits)
+        .into_iter()
+        .enumerate()
+        .map(|(i, (width, name))| {
+            let mut hashes = match i {
+                0 => hashes.clone(),
+                1 => hashes.iter().map(|h| h & 0xffff_ffff).collect(),
+                _ => hashes.iter().map(|h| h >> (bits - 32)).collect(),
+            };
+
+            hashes.sort_unstable();
+            hashes.dedup();
+
+            let count = n as usize - hashes.len();
+            let expected = pairs * (-(width as f64)).exp2();
+
+            (
+                poisson_sf(count, expected),
+                format!("{} collisions{}, expected {:.2}", count, name, expected),
+            )
+        })
+        .collect()
+}
+
+/// The probability that an event of probability `p` happens at least once in `n` trials.
+fn at_least_once(p: f64, n: usize) -> f64 {
+    -(n as f64 * (-p.min(1.0)).ln_1p()).exp_m1()
+}
+
+/// The p-value of the largest deviation `z` of `n` normal variables.
+fn max_normal(z: f64, n: usize) -> f64 {
+    at_least_once(2.0 * normal_sf(z.abs()), n)
+}
+
+/// The probability that a standard normal variable is larger than `z`.
+fn normal_sf(z: f64) -> f64 {
+    0.5 * erfc(z / f64::consts::SQRT_2)
+}
+
+/// The complementary error function, with a relative error below `1.2e-7`.
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let r = t
+        * (-z * z - 1.265_512_23
+            + t * (1.000_023_68
+                + t * (0.374_091_96
+                    + t * (0.096_784_18
+                        + t * (-0.186_288_06
+                            + t * (0.278_868_07
+                                + t * (-1.135_203_98
+                                    + t * (1.488_515_87
+                                        + t * (-0.822_152_23 + t * 0.170_872_77)))))))))
+            .exp();
+
+    if x >= 0.0 {
+        r
+    } else {
+        2.0 - r
+    }
+}
+
+/// The probability that a Poisson variable of `mean` is at least `count`.
+fn poisson_sf(count: usize, mean: f64) -> f64 {
+    if count == 0 {
+        return 1.0;
+    }
+    if mean > 500.0 {
+        return normal_sf((count as f64 - 0.5 - mean) / mean.sqrt());
+    }
+
+    let mut term = (-mean).exp();
+    let mut cdf = 0.0;
+
+    for i in 1..=count {
+        cdf += term;
+        term *= mean / i as f64;
+    }
+
+    if count as f64 <= mean {
+        return (1.0 - cdf).max(0.0);
+    }
+
+    // sums the upper tail, which is too small to subtract from 1
+    let mut sf = 0.0;
+    let mut i = count;
+
+    while term > sf * 1e-17 {
+        sf += term;
+        i += 1;
+        term *= mean / i as f64;
+    }
+
+    sf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_statistics() {
+        assert!((erfc(0.0) - 1.0).abs() < 1e-7);
+        assert!((erfc(1.0) - 0.157_299_207).abs() < 1e-7);
+        assert!((erfc(-1.0) - 1.842_700_793).abs() < 1e-7);
+        assert!((normal_sf(6.0) / 9.865_876e-10 - 1.0).abs() < 1e-5);
+
+        assert_eq!(poisson_sf(0, 3.0), 1.0);
+        assert!((poisson_sf(1, 3.0) - (1.0 - (-3.0f64).exp())).abs() < 1e-12);
+        assert!((poisson_sf(3, 1e-10) / (1e-30 / 6.0) - 1.0).abs() < 1e-6);
+        assert!(poisson_sf(1_000, 1e-10) == 0.0);
+        assert!((poisson_sf(1_000, 1_000.0) - 0.5).abs() < 0.01);
+
+        assert!((at_least_once(1e-9, 1_000) - 1e-6).abs() < 1e-12);
+        assert_eq!(at_least_once(1.0, 3), 1.0);
+        assert_eq!(sparse_keys(32, 2), 1.0 + 32.0 + 496.0);
+
+        let mut keys = 0;
+
+        for_each_sparse(&mut [0; 4], 0, 2, &mut |_| keys += 1);
+
+        assert_eq!(keys, 529);
+    }
+
+    #[test]
+    fn test_detect() {
+        use crate::sea::Hash64;
+
+        let config = Config::quick();
+        let keys = (0..10_000).map(|i: u32| (i % 5_000).to_le_bytes());
+        let outcome = collisions::<Hash64, _, _>(&config, "duplicated", keys);
+
+        assert!(!outcome.passed, "{}", outcome);
+        assert!(
+            outcome.summary.starts_with("5000 collisions"),
+            "{}",
+            outcome
+        );
+
+        let keys = (0..10_000).map(|i: u32| (i % 5_000).to_le_bytes());
+        let outcome = distribution::<Hash64, _, _>(&config, "duplicated", keys);
+
+        assert!(!outcome.passed, "{}", outcome);
+    }
+
+    #[test]
+    fn test_quality() {
+        let report = run::<crate::sea::Hash64>(&Config::quick());
+
+        assert!(report.passed(), "{}", report);
+        assert_eq!(report.outcomes.len(), 28);
+    }
+
+    #[cfg(feature = "sys")]
+    #[test]
+    fn test_quality_wrappers() {
+        use crate::{farm, metro, murmur3};
+
+        let config = Config::quick();
+
+        for report in [
+            run::<farm::Hash128>(&config),
+            run::<metro::Hash128_1>(&config),
+            run::<murmur3::Hash32>(&config),
+            run::<murmur3::Hash128_x64>(&config),
+        ] {
+            assert!(report.passed(), "{}", report);
+        }
+    }
+}
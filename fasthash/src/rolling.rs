@@ -0,0 +1,609 @@
+//! Rolling hash functions and content-defined chunking.
+//!
+//! A rolling hash is updated one byte at a time over a sliding window,
+//! which makes it cheap to find the cut points of content-defined chunks:
+//! an insertion or deletion only moves the boundaries around it,
+//! so the unchanged chunks keep the same fingerprint for deduplication.
+//!
+//! - `RabinKarp`, polynomial hash over a fixed window.
+//! - `Buzhash`, cyclic polynomial hash over a fixed window.
+//! - `Gear`, the hash used by `FastCDC`, the window is implied by the 64-bit state.
+//!
+//! With the `std` feature, `Chunker` splits any `io::Read` into `FastCDC` chunks
+//! and fingerprints each of them with a `FastHash` function.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(any(feature = "sys", feature = "pure-xxh3"))]
+//! # {
+//! use std::io::Cursor;
+//!
+//! use fasthash::{rolling::Chunker, xxh3, FastHash};
+//!
+//! let data = (0..100_000_u64).map(|i| (i * i >> 7) as u8).collect::<Vec<_>>();
+//!
+//! let chunks = Chunker::<_, xxh3::Hash64>::new(Cursor::new(&data))
+//!     .collect::<Result<Vec<_>, _>>()
+//!     .unwrap();
+//!
+//! assert_eq!(chunks.iter().map(|c| c.length).sum::<usize>(), data.len());
+//!
+//! for chunk in chunks {
+//!     let start = chunk.offset as usize;
+//!     let bytes = &data[start..start + chunk.length];
+//!
+//!     assert_eq!(chunk.fingerprint, xxh3::Hash64::hash(bytes));
+//! }
+//! # }
+//! ```
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::marker::PhantomData;
+#[cfg(feature = "std")]
+use std::io;
+
+#[cfg(feature = "std")]
+use crate::hasher::FastHash;
+use crate::hasher::{mix64, GOLDEN_GAMMA};
+
+/// The default seed of the byte tables used by `Buzhash` and `Gear`.
+pub const DEFAULT_SEED: u64 = 0x2545_f491_4f6c_dd1d;
+
+/// A hash function updated one byte at a time over a sliding window.
+pub trait RollingHash {
+    /// Slides the window forward by one byte, returns the hash of the new window.
+    fn roll(&mut self, byte: u8) -> u64;
+
+    /// Returns the hash of the current window.
+    fn hash(&self) -> u64;
+
+    /// Clears the window.
+    fn reset(&mut self);
+
+    /// Rolls all the bytes into the window, returns the hash of the last window.
+    #[inline(always)]
+    fn update(&mut self, bytes: &[u8]) -> u64 {
+        for &b in bytes {
+            self.roll(b);
+        }
+
+        self.hash()
+    }
+}
+
+/// Build a table of 256 random values from `seed` with `SplitMix64`,
+/// the same seed always gives the same table.
+fn byte_table(seed: u64) -> [u64; 256] {
+    let mut table = [0; 256];
+    let mut state = seed;
+
+    for v in table.iter_mut() {
+        state = state.wrapping_add(GOLDEN_GAMMA);
+        *v = mix64(state);
+    }
+
+    table
+}
+
+/// The bytes in the sliding window of `RabinKarp` and `Buzhash`.
+#[derive(Clone, Debug)]
+struct Window {
+    bytes: Vec<u8>,
+    pos: usize,
+    full: bool,
+}
+
+impl Window {
+    fn new(size: usize) -> Self {
+        assert!(size > 0, "window size must be positive");
+
+        Window {
+            bytes: alloc::vec![0; size],
+            pos: 0,
+            full: false,
+        }
+    }
+
+    /// Push a byte into the window, returns the byte that left it.
+    #[inline(always)]
+    fn push(&mut self, byte: u8) -> Option<u8> {
+        let out = if self.full {
+            Some(self.bytes[self.pos])
+        } else {
+            None
+        };
+
+        self.bytes[self.pos] = byte;
+        self.pos += 1;
+
+        if self.pos == self.bytes.len() {
+            self.pos = 0;
+            self.full = true;
+        }
+
+        out
+    }
+
+    #[inline(always)]
+    fn reset(&mut self) {
+        self.pos = 0;
+        self.full = false;
+    }
+}
+
+/// `Rabin-Karp` polynomial rolling hash over a fixed window.
+///
+/// The hash of a window `b[0..n]` is `b[0] * B^(n-1) + ... + b[n-1]` modulo `2^64`.
+///
+/// # Example
+///
+/// ```
+/// use fasthash::rolling::{RabinKarp, RollingHash};
+///
+/// let mut h = RabinKarp::new(4);
+///
+/// assert_eq!(h.update(b"hello world"), RabinKarp::new(4).update(b"orld"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct RabinKarp {
+    window: Window,
+    pow: u64,
+    hash: u64,
+}
+
+impl RabinKarp {
+    /// The multiplier of the polynomial.
+    pub const BASE: u64 = 0x0000_0100_0000_01b3;
+
+    /// Constructs a new `RabinKarp` over a window of `size` bytes.
+    pub fn new(size: usize) -> Self {
+        RabinKarp {
+            window: Window::new(size),
+            pow: (0..size).fold(1_u64, |pow, _| pow.wrapping_mul(Self::BASE)),
+            hash: 0,
+        }
+    }
+}
+
+impl RollingHash for RabinKarp {
+    #[inline(always)]
+    fn roll(&mut self, byte: u8) -> u64 {
+        self.hash = self
+            .hash
+            .wrapping_mul(Self::BASE)
+            .wrapping_add(u64::from(byte));
+
+        if let Some(out) = self.window.push(byte) {
+            self.hash = self
+                .hash
+                .wrapping_sub(u64::from(out).wrapping_mul(self.pow));
+        }
+
+        self.hash
+    }
+
+    #[inline(always)]
+    fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    #[inline(always)]
+    fn reset(&mut self) {
+        self.window.reset();
+        self.hash = 0;
+    }
+}
+
+/// `Buzhash`, a cyclic polynomial rolling hash over a fixed window.
+///
+/// Each byte is mapped to a random value, the hash of a window is
+/// the XOR of the values rotated by their distance to the end of the window.
+///
+/// # Example
+///
+/// ```
+/// use fasthash::rolling::{Buzhash, RollingHash};
+///
+/// let mut h = Buzhash::new(4);
+///
+/// assert_eq!(h.update(b"hello world"), Buzhash::new(4).update(b"orld"));
+/// ```
+#[derive(Clone)]
+pub struct Buzhash {
+    table: [u64; 256],
+    window: Window,
+    hash: u64,
+}
+
+impl Buzhash {
+    /// Constructs a new `Buzhash` over a window of `size` bytes.
+    pub fn new(size: usize) -> Self {
+        Self::with_seed(size, DEFAULT_SEED)
+    }
+
+    /// Constructs a new `Buzhash` over a window of `size` bytes, the byte table is built from `seed`.
+    pub fn with_seed(size: usize, seed: u64) -> Self {
+        Buzhash {
+            table: byte_table(seed),
+            window: Window::new(size),
+            hash: 0,
+        }
+    }
+}
+
+impl RollingHash for Buzhash {
+    #[inline(always)]
+    fn roll(&mut self, byte: u8) -> u64 {
+        self.hash = self.hash.rotate_left(1) ^ self.table[byte as usize];
+
+        if let Some(out) = self.window.push(byte) {
+            self.hash ^= self.table[out as usize].rotate_left(self.window.bytes.len() as u32 % 64);
+        }
+
+        self.hash
+    }
+
+    #[inline(always)]
+    fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    #[inline(always)]
+    fn reset(&mut self) {
+        self.window.reset();
+        self.hash = 0;
+    }
+}
+
+/// `Gear` rolling hash, as used by `FastCDC`.
+///
+/// Every byte shifts the state left by one bit, so the top bits of the hash
+/// depend on the last 64 bytes and a byte falls out of the window by itself.
+///
+/// # Example
+///
+/// ```
+/// use fasthash::rolling::{Gear, RollingHash};
+///
+/// let data = (0..128).collect::<Vec<u8>>();
+///
+/// assert_eq!(Gear::new().update(&data), Gear::new().update(&data[64..]));
+/// ```
+#[derive(Clone)]
+pub struct Gear {
+    table: [u64; 256],
+    hash: u64,
+}
+
+impl Default for Gear {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Gear {
+    /// The number of bytes a `Gear` hash depends on.
+    pub const WINDOW_SIZE: usize = 64;
+
+    /// Constructs a new `Gear` hash.
+    pub fn new() -> Self {
+        Self::with_seed(DEFAULT_SEED)
+    }
+
+    /// Constructs a new `Gear` hash, the byte table is built from `seed`.
+    pub fn with_seed(seed: u64) -> Self {
+        Gear {
+            table: byte_table(seed),
+            hash: 0,
+        }
+    }
+}
+
+impl RollingHash for Gear {
+    #[inline(always)]
+    fn roll(&mut self, byte: u8) -> u64 {
+        self.hash = (self.hash << 1).wrapping_add(self.table[byte as usize]);
+        self.hash
+    }
+
+    #[inline(always)]
+    fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    #[inline(always)]
+    fn reset(&mut self) {
+        self.hash = 0;
+    }
+}
+
+/// A content-defined chunk found by `Chunker`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Chunk<T> {
+    /// The offset of the chunk in the stream.
+    pub offset: u64,
+    /// The length of the chunk in bytes.
+    pub length: usize,
+    /// The hash value of the chunk.
+    pub fingerprint: T,
+}
+
+/// The default minimum chunk size of `Chunker`.
+pub const MIN_CHUNK_SIZE: usize = 2 * 1024;
+/// The default average chunk size of `Chunker`.
+pub const AVG_CHUNK_SIZE: usize = 8 * 1024;
+/// The default maximum chunk size of `Chunker`.
+pub const MAX_CHUNK_SIZE: usize = 64 * 1024;
+
+/// The normalization level of `FastCDC`,
+/// the mask has more bits before the average size and less bits after it.
+#[cfg(feature = "std")]
+const NORMALIZATION: u32 = 2;
+
+/// Returns a mask of the `bits` most significant bits,
+/// they depend on the whole window of the `Gear` hash.
+#[cfg(feature = "std")]
+#[inline(always)]
+fn top_bits(bits: u32) -> u64 {
+    match bits {
+        0 => 0,
+        bits if bits >= 64 => !0,
+        bits => !0 << (64 - bits),
+    }
+}
+
+/// A `FastCDC` content-defined chunker.
+///
+/// It reads the stream with a `Gear` hash and cuts a chunk when the masked hash is zero,
+/// the chunks are between the minimum and maximum size, and around the average size.
+/// Each chunk is fingerprinted with the `FastHash` function `H`.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(any(feature = "sys", feature = "pure-t1ha"))]
+/// # {
+/// use std::io::Cursor;
+///
+/// use fasthash::{rolling::Chunker, t1ha2};
+///
+/// let data = (0..100_000_u64).map(|i| (i * i >> 7) as u8).collect::<Vec<_>>();
+///
+/// let reader = Cursor::new(&data);
+///
+/// for chunk in Chunker::<_, t1ha2::Hash64AtOnce>::with_sizes(reader, 1024, 4096, 16384) {
+///     let chunk = chunk.unwrap();
+///
+///     assert!(chunk.length <= 16384);
+/// }
+/// # }
+/// ```
+#[cfg(feature = "std")]
+pub struct Chunker<R, H: FastHash> {
+    reader: R,
+    gear: Gear,
+    min_size: usize,
+    avg_size: usize,
+    max_size: usize,
+    mask_s: u64,
+    mask_l: u64,
+    buf: Vec<u8>,
+    offset: u64,
+    eof: bool,
+    phantom: PhantomData<H>,
+}
+
+#[cfg(feature = "std")]
+impl<R: io::Read, H: FastHash> Chunker<R, H> {
+    /// Constructs a new `Chunker` with the default chunk sizes.
+    pub fn new(reader: R) -> Self {
+        Self::with_sizes(reader, MIN_CHUNK_SIZE, AVG_CHUNK_SIZE, MAX_CHUNK_SIZE)
+    }
+
+    /// Constructs a new `Chunker` with the minimum, average and maximum chunk sizes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sizes are not `0 < min_size <= avg_size <= max_size`.
+    pub fn with_sizes(reader: R, min_size: usize, avg_size: usize, max_size: usize) -> Self {
+        assert!(
+            0 < min_size && min_size <= avg_size && avg_size <= max_size,
+            "chunk sizes must be 0 < min <= avg <= max"
+        );
+
+        let bits = (avg_size as f64).log2().round() as u32;
+
+        Chunker {
+            reader,
+            gear: Gear::new(),
+            min_size,
+            avg_size,
+            max_size,
+            mask_s: top_bits(bits + NORMALIZATION),
+            mask_l: top_bits(bits.saturating_sub(NORMALIZATION)),
+            buf: Vec::with_capacity(max_size),
+            offset: 0,
+            eof: false,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Replaces the `Gear` hash, to use a different byte table.
+    pub fn with_gear(mut self, gear: Gear) -> Self {
+        self.gear = gear;
+        self
+    }
+
+    /// Consumes the chunker, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Fill the buffer with up to `max_size` bytes.
+    fn fill_buf(&mut self) -> io::Result<()> {
+        while !self.eof && self.buf.len() < self.max_size {
+            let len = self.buf.len();
+
+            self.buf.resize(self.max_size, 0);
+
+            match self.reader.read(&mut self.buf[len..]) {
+                Ok(n) => {
+                    self.buf.truncate(len + n);
+                    self.eof = n == 0;
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => self.buf.truncate(len),
+                Err(e) => {
+                    self.buf.truncate(len);
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Find the length of the next chunk in the buffer.
+    fn cut_point(&mut self) -> usize {
+        let len = self.buf.len().min(self.max_size);
+
+        if len <= self.min_size {
+            return len;
+        }
+
+        let normal = self.avg_size.min(len);
+
+        self.gear.reset();
+
+        for i in self.min_size..len {
+            let mask = if i < normal { self.mask_s } else { self.mask_l };
+
+            if self.gear.roll(self.buf[i]) & mask == 0 {
+                return i + 1;
+            }
+        }
+
+        len
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: io::Read, H: FastHash> Iterator for Chunker<R, H> {
+    type Item = io::Result<Chunk<H::Hash>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Err(err) = self.fill_buf() {
+            return Some(Err(err));
+        }
+
+        if self.buf.is_empty() {
+            return None;
+        }
+
+        let length = self.cut_point();
+        let chunk = Chunk {
+            offset: self.offset,
+            length,
+            fingerprint: H::hash(&self.buf[..length]),
+        };
+
+        self.buf.drain(..length);
+        self.offset += length as u64;
+
+        Some(Ok(chunk))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(len: usize) -> Vec<u8> {
+        let mut state = 0x1234_5678_u64;
+
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect()
+    }
+
+    fn check_window<H: RollingHash, F: Fn() -> H>(new: F, size: usize) {
+        let data = sample(1000);
+        let mut h = new();
+
+        for (i, &b) in data.iter().enumerate() {
+            let start = (i + 1).saturating_sub(size);
+
+            assert_eq!(h.roll(b), new().update(&data[start..=i]), "i = {}", i);
+        }
+
+        h.reset();
+        assert_eq!(h.update(&data[..size]), new().update(&data[..size]));
+    }
+
+    #[test]
+    fn test_rolling_window() {
+        for &size in &[1, 2, 7, 48, 64, 100] {
+            check_window(|| RabinKarp::new(size), size);
+            check_window(|| Buzhash::new(size), size);
+        }
+
+        check_window(Gear::new, Gear::WINDOW_SIZE);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_chunker() {
+        use std::io::Cursor;
+
+        use crate::sea;
+
+        let data = sample(1 << 20);
+        let chunks = Chunker::<_, sea::Hash64>::new(Cursor::new(&data))
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        let mut offset = 0;
+
+        for (i, chunk) in chunks.iter().enumerate() {
+            assert_eq!(chunk.offset, offset as u64);
+            assert!(chunk.length <= MAX_CHUNK_SIZE);
+            assert!(chunk.length >= MIN_CHUNK_SIZE || i == chunks.len() - 1);
+            assert_eq!(
+                chunk.fingerprint,
+                sea::Hash64::hash(&data[offset..offset + chunk.length])
+            );
+
+            offset += chunk.length;
+        }
+
+        assert_eq!(offset, data.len());
+
+        let avg = data.len() / chunks.len();
+
+        assert!(
+            avg > AVG_CHUNK_SIZE / 2 && avg < AVG_CHUNK_SIZE * 2,
+            "avg = {}",
+            avg
+        );
+
+        // an insertion only changes the chunks around it
+        let mut shifted = b"inserted".to_vec();
+        shifted.extend_from_slice(&data);
+
+        let fingerprints = Chunker::<_, sea::Hash64>::new(Cursor::new(&shifted))
+            .map(|chunk| chunk.unwrap().fingerprint)
+            .collect::<Vec<_>>();
+
+        let shared = chunks
+            .iter()
+            .filter(|chunk| fingerprints.contains(&chunk.fingerprint))
+            .count();
+
+        assert!(shared + 2 >= chunks.len(), "{} of {}", shared, chunks.len());
+    }
+}
@@ -25,11 +25,15 @@
 //! assert_eq!(hash(&"hello world"), 1198299633807023012);
 //! ```
 //!
+//! `Hasher64` is the `SeaHasher` of seahash, which doesn't expose its state,
+//! so it can't be exported with `HasherState` nor cloned for a `Snapshot`.
 use seahash;
 
 pub use seahash::{hash as hash64, hash_seeded as hash64_with_seeds, SeaHasher as Hasher64};
 
-use crate::hasher::{FastHash, FastHasher, StreamHasher};
+#[cfg(feature = "std")]
+use crate::hasher::StreamHasher;
+use crate::hasher::{FastHash, FastHasher};
 
 /// `SeaHash` 64-bit hash functions
 ///
@@ -80,6 +84,7 @@ impl FastHasher for Hasher64 {
     }
 }
 
+#[cfg(feature = "std")]
 impl StreamHasher for Hasher64 {}
 
 #[cfg(test)]
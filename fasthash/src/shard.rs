@@ -0,0 +1,543 @@
+//! Consistent hashing, map the keys to shards with minimal disruption when the shards change.
+//!
+//! - `Jump`, Jump consistent hash, maps a key to one of `n` numbered buckets.
+//! - `Rendezvous`, highest random weight hashing over a set of named nodes.
+//! - `Maglev`, Google's Maglev lookup table over a set of named nodes.
+//! - `Ring`, the classic hash ring with virtual nodes.
+//!
+//! Every algorithm is generic over the `FastHash` function used to hash the keys and nodes.
+//! The placement only depends on the hash values and the constants in this module,
+//! so it is stable across platforms, processes and versions of this crate.
+//!
+//! # Example
+//!
+//! ```
+//! use fasthash::{sea, shard::{Jump, Rendezvous}};
+//!
+//! let jump = Jump::<sea::Hash64>::new(16);
+//!
+//! assert!(jump.shard("user-42") < 16);
+//!
+//! let nodes = Rendezvous::<sea::Hash64, _>::new(vec!["node-a", "node-b", "node-c"]);
+//!
+//! assert_eq!(nodes.node("user-42"), nodes.node("user-42"));
+//! ```
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::hasher::{mix64, FastHash};
+
+/// Hash the bytes with `H` and mix the result into 64 bits.
+#[inline(always)]
+fn hash64<H, T>(bytes: T) -> u64
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+    T: AsRef<[u8]>,
+{
+    let h: u128 = H::hash(bytes).into();
+
+    mix64(h as u64 ^ (h >> 64) as u64)
+}
+
+/// Jump consistent hash, maps a 64-bit key to a bucket in `0..buckets`.
+///
+/// When the number of buckets grows from `n` to `n + 1`,
+/// only `1 / (n + 1)` of the keys move, all of them to the new bucket.
+///
+/// See "A Fast, Minimal Memory, Consistent Hash Algorithm" by John Lamping and Eric Veach.
+///
+/// # Panics
+///
+/// Panics if `buckets` is zero.
+///
+/// # Example
+///
+/// ```
+/// use fasthash::shard::jump_consistent_hash;
+///
+/// assert_eq!(jump_consistent_hash(0, 10), 0);
+/// assert_eq!(jump_consistent_hash(123_456_789, 10), 7);
+/// ```
+pub fn jump_consistent_hash(mut key: u64, buckets: u32) -> u32 {
+    assert!(buckets > 0, "the number of buckets must be positive");
+
+    let mut b = -1_i64;
+    let mut j = 0_i64;
+
+    while j < i64::from(buckets) {
+        b = j;
+        key = key.wrapping_mul(2_862_933_555_777_941_757).wrapping_add(1);
+        j = ((b + 1) as f64 * ((1_u64 << 31) as f64 / ((key >> 33) + 1) as f64)) as i64;
+    }
+
+    b as u32
+}
+
+/// Jump consistent hash over `n` numbered buckets.
+///
+/// # Example
+///
+/// ```
+/// use fasthash::{sea, shard::Jump};
+///
+/// let jump = Jump::<sea::Hash64>::new(10);
+///
+/// assert_eq!(jump.shard("hello"), 9);
+/// assert_eq!(jump.shard("world"), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Jump<H> {
+    buckets: u32,
+    phantom: PhantomData<H>,
+}
+
+impl<H> Jump<H>
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+{
+    /// Constructs a new `Jump` over `buckets` buckets.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buckets` is zero.
+    pub fn new(buckets: u32) -> Self {
+        assert!(buckets > 0, "the number of buckets must be positive");
+
+        Jump {
+            buckets,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the number of buckets.
+    pub fn buckets(&self) -> u32 {
+        self.buckets
+    }
+
+    /// Returns the bucket of the key.
+    #[inline(always)]
+    pub fn shard<T: AsRef<[u8]>>(&self, key: T) -> u32 {
+        jump_consistent_hash(hash64::<H, _>(key), self.buckets)
+    }
+}
+
+/// Rendezvous or highest random weight (HRW) hashing.
+///
+/// Every node gets a weight for the key, and the key goes to the node with the highest weight.
+/// Removing a node only moves the keys of that node.
+///
+/// # Example
+///
+/// ```
+/// use fasthash::{sea, shard::Rendezvous};
+///
+/// let nodes = Rendezvous::<sea::Hash64, _>::new(vec!["node-a", "node-b", "node-c"]);
+///
+/// assert_eq!(nodes.node("hello"), Some(&"node-c"));
+/// assert_eq!(nodes.top("hello", 2).collect::<Vec<_>>(), vec![&"node-c", &"node-a"]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Rendezvous<H, N> {
+    nodes: Vec<(u64, N)>,
+    phantom: PhantomData<H>,
+}
+
+impl<H, N> Rendezvous<H, N>
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+    N: AsRef<[u8]>,
+{
+    /// Constructs a new `Rendezvous` over the nodes, each node is identified by its bytes.
+    pub fn new<I: IntoIterator<Item = N>>(nodes: I) -> Self {
+        Rendezvous {
+            nodes: nodes
+                .into_iter()
+                .map(|node| (hash64::<H, _>(&node), node))
+                .collect(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Adds a node.
+    pub fn add(&mut self, node: N) {
+        self.nodes.push((hash64::<H, _>(&node), node));
+    }
+
+    /// Removes the node with the same bytes, returns it if it was found.
+    pub fn remove<T: AsRef<[u8]>>(&mut self, node: T) -> Option<N> {
+        let pos = self
+            .nodes
+            .iter()
+            .position(|(_, n)| n.as_ref() == node.as_ref())?;
+
+        Some(self.nodes.remove(pos).1)
+    }
+
+    /// Returns the nodes.
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.nodes.iter().map(|(_, node)| node)
+    }
+
+    /// Returns the node of the key, or `None` if there are no nodes.
+    pub fn node<T: AsRef<[u8]>>(&self, key: T) -> Option<&N> {
+        let key = hash64::<H, _>(key);
+
+        self.nodes
+            .iter()
+            .max_by_key(|(h, node)| (Self::weight(key, *h), node.as_ref()))
+            .map(|(_, node)| node)
+    }
+
+    /// Returns the `n` nodes with the highest weights for the key, in order, for replication.
+    pub fn top<T: AsRef<[u8]>>(&self, key: T, n: usize) -> impl Iterator<Item = &N> {
+        let key = hash64::<H, _>(key);
+        let mut nodes = self
+            .nodes
+            .iter()
+            .map(|(h, node)| (Self::weight(key, *h), node))
+            .collect::<Vec<_>>();
+
+        nodes.sort_by(|(w1, n1), (w2, n2)| (w2, n2.as_ref()).cmp(&(w1, n1.as_ref())));
+        nodes.into_iter().take(n).map(|(_, node)| node)
+    }
+
+    #[inline(always)]
+    fn weight(key: u64, node: u64) -> u64 {
+        mix64(key ^ node.rotate_left(32))
+    }
+}
+
+/// Maglev consistent hashing with a lookup table.
+///
+/// Each node fills the table slots in the order of its own permutation,
+/// so the nodes get almost the same number of slots and the lookup is a single index.
+/// The table size should be a prime much larger than the number of nodes.
+///
+/// See "Maglev: A Fast and Reliable Software Network Load Balancer".
+///
+/// # Example
+///
+/// ```
+/// use fasthash::{sea, shard::Maglev};
+///
+/// let maglev = Maglev::<sea::Hash64, _>::new(vec!["node-a", "node-b", "node-c"]);
+///
+/// assert_eq!(maglev.node("hello"), Some(&"node-c"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Maglev<H, N> {
+    nodes: Vec<N>,
+    table: Vec<usize>,
+    phantom: PhantomData<H>,
+}
+
+impl<H, N> Maglev<H, N>
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+    N: AsRef<[u8]>,
+{
+    /// The default size of the lookup table.
+    pub const TABLE_SIZE: usize = 65537;
+
+    /// Constructs a new `Maglev` over the nodes with the default table size.
+    pub fn new<I: IntoIterator<Item = N>>(nodes: I) -> Self {
+        Self::with_table_size(nodes, Self::TABLE_SIZE)
+    }
+
+    /// Constructs a new `Maglev` over the nodes with the table size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `table_size` is not a prime.
+    pub fn with_table_size<I: IntoIterator<Item = N>>(nodes: I, table_size: usize) -> Self {
+        assert!(is_prime(table_size), "the table size must be a prime");
+
+        let nodes = nodes.into_iter().collect::<Vec<_>>();
+        let table = Self::populate(&nodes, table_size);
+
+        Maglev {
+            nodes,
+            table,
+            phantom: PhantomData,
+        }
+    }
+
+    fn populate(nodes: &[N], size: usize) -> Vec<usize> {
+        const EMPTY: usize = !0;
+
+        if nodes.is_empty() {
+            return Vec::new();
+        }
+
+        let m = size as u64;
+        let perms = nodes
+            .iter()
+            .map(|node| {
+                let h = hash64::<H, _>(node);
+                let offset = h % m;
+                let skip = mix64(h) % (m - 1) + 1;
+
+                (offset, skip)
+            })
+            .collect::<Vec<_>>();
+
+        let mut next = alloc::vec![0_u64; nodes.len()];
+        let mut table = alloc::vec![EMPTY; size];
+        let mut filled = 0;
+
+        loop {
+            for (i, &(offset, skip)) in perms.iter().enumerate() {
+                let mut c = (offset + next[i] * skip) % m;
+
+                while table[c as usize] != EMPTY {
+                    next[i] += 1;
+                    c = (offset + next[i] * skip) % m;
+                }
+
+                table[c as usize] = i;
+                next[i] += 1;
+                filled += 1;
+
+                if filled == size {
+                    return table;
+                }
+            }
+        }
+    }
+
+    /// Returns the nodes.
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    /// Returns the lookup table, each slot is an index in the nodes.
+    pub fn table(&self) -> &[usize] {
+        &self.table
+    }
+
+    /// Returns the index of the key's node, or `None` if there are no nodes.
+    #[inline(always)]
+    pub fn shard<T: AsRef<[u8]>>(&self, key: T) -> Option<usize> {
+        if self.table.is_empty() {
+            None
+        } else {
+            Some(self.table[(hash64::<H, _>(key) % self.table.len() as u64) as usize])
+        }
+    }
+
+    /// Returns the node of the key, or `None` if there are no nodes.
+    #[inline(always)]
+    pub fn node<T: AsRef<[u8]>>(&self, key: T) -> Option<&N> {
+        self.shard(key).map(|i| &self.nodes[i])
+    }
+}
+
+fn is_prime(n: usize) -> bool {
+    n >= 2 && (2..).take_while(|i| i * i <= n).all(|i| n / i * i != n)
+}
+
+/// A consistent hash ring with virtual nodes.
+///
+/// Each node is placed on the ring at `replicas` points,
+/// a key goes to the node of the first point at or after its hash.
+///
+/// # Example
+///
+/// ```
+/// use fasthash::{sea, shard::Ring};
+///
+/// let mut ring = Ring::<sea::Hash64, _>::new(100);
+///
+/// ring.add("node-a");
+/// ring.add("node-b");
+/// ring.add("node-c");
+///
+/// assert_eq!(ring.node("hello"), Some(&"node-c"));
+///
+/// ring.remove("node-c");
+///
+/// assert_eq!(ring.node("hello"), Some(&"node-a"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Ring<H, N> {
+    replicas: u32,
+    points: BTreeMap<u64, N>,
+    phantom: PhantomData<H>,
+}
+
+impl<H, N> Ring<H, N>
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+    N: AsRef<[u8]> + Clone,
+{
+    /// Constructs a new empty `Ring`, each node will be placed at `replicas` points.
+    pub fn new(replicas: u32) -> Self {
+        Ring {
+            replicas,
+            points: BTreeMap::new(),
+            phantom: PhantomData,
+        }
+    }
+
+    fn points(&self, node: &[u8]) -> impl Iterator<Item = u64> {
+        let h = hash64::<H, _>(node);
+
+        (0..self.replicas).map(move |i| mix64(h ^ mix64(u64::from(i))))
+    }
+
+    /// Adds a node at its virtual points.
+    pub fn add(&mut self, node: N) {
+        for point in self.points(node.as_ref()) {
+            self.points.insert(point, node.clone());
+        }
+    }
+
+    /// Removes the node with the same bytes from its virtual points.
+    pub fn remove<T: AsRef<[u8]>>(&mut self, node: T) {
+        for point in self.points(node.as_ref()) {
+            if self
+                .points
+                .get(&point)
+                .filter(|n| n.as_ref() == node.as_ref())
+                .is_some()
+            {
+                self.points.remove(&point);
+            }
+        }
+    }
+
+    /// Returns `true` if the ring has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Returns the node of the key, or `None` if the ring is empty.
+    pub fn node<T: AsRef<[u8]>>(&self, key: T) -> Option<&N> {
+        let key = hash64::<H, _>(key);
+
+        self.points
+            .range(key..)
+            .next()
+            .or_else(|| self.points.iter().next())
+            .map(|(_, node)| node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sea::Hash64;
+
+    const KEYS: u32 = 10_000;
+
+    fn keys() -> impl Iterator<Item = [u8; 4]> {
+        (0..KEYS).map(|i| i.to_le_bytes())
+    }
+
+    #[test]
+    fn test_jump() {
+        for buckets in 1..20 {
+            let before = Jump::<Hash64>::new(buckets);
+            let after = Jump::<Hash64>::new(buckets + 1);
+            let mut moved = 0;
+
+            for key in keys() {
+                let (b, a) = (before.shard(key), after.shard(key));
+
+                assert!(b < buckets);
+
+                if a != b {
+                    assert_eq!(a, buckets);
+                    moved += 1;
+                }
+            }
+
+            let expected = KEYS / (buckets + 1);
+
+            assert!(
+                moved > expected / 2 && moved < expected * 2,
+                "moved = {}",
+                moved
+            );
+        }
+    }
+
+    #[test]
+    fn test_rendezvous() {
+        let mut nodes = Rendezvous::<Hash64, _>::new((0..10).map(|i| format!("node-{}", i)));
+        let before = keys()
+            .map(|key| nodes.node(key).cloned())
+            .collect::<Vec<_>>();
+
+        assert_eq!(nodes.remove("node-3"), Some("node-3".to_owned()));
+        assert_eq!(nodes.remove("node-3"), None);
+
+        for (key, before) in keys().zip(before) {
+            let after = nodes.node(key).cloned();
+
+            if before.as_deref() != Some("node-3") {
+                assert_eq!(after, before);
+            }
+
+            assert_eq!(nodes.top(key, 1).next(), after.as_ref());
+        }
+    }
+
+    #[test]
+    fn test_maglev() {
+        let nodes = (0..10).map(|i| format!("node-{}", i)).collect::<Vec<_>>();
+        let maglev = Maglev::<Hash64, _>::with_table_size(nodes.clone(), 1009);
+        let mut slots = vec![0; nodes.len()];
+
+        for &i in maglev.table() {
+            slots[i] += 1;
+        }
+
+        assert!(slots.iter().all(|&n| n == 100 || n == 101), "{:?}", slots);
+
+        let removed = Maglev::<Hash64, _>::with_table_size(
+            nodes.iter().filter(|n| *n != "node-3").cloned(),
+            1009,
+        );
+        let moved = keys()
+            .filter(|key| {
+                let node = maglev.node(key).unwrap();
+
+                node != "node-3" && removed.node(key) != Some(node)
+            })
+            .count();
+
+        assert!(moved < KEYS as usize / 10, "moved = {}", moved);
+        assert_eq!(Maglev::<Hash64, String>::new(vec![]).node("key"), None);
+    }
+
+    #[test]
+    fn test_ring() {
+        let mut ring = Ring::<Hash64, _>::new(100);
+
+        assert!(ring.is_empty());
+        assert_eq!(ring.node("key"), None);
+
+        for i in 0..10 {
+            ring.add(format!("node-{}", i));
+        }
+
+        let before = keys()
+            .map(|key| ring.node(key).cloned())
+            .collect::<Vec<_>>();
+
+        ring.remove("node-3");
+
+        for (key, before) in keys().zip(before) {
+            if before.as_deref() != Some("node-3") {
+                assert_eq!(ring.node(key), before.as_ref());
+            }
+        }
+    }
+}
@@ -0,0 +1,746 @@
+//! Similarity signatures, estimate how similar two sets of items are from short signatures.
+//!
+//! - `MinHash`, the classic MinHash with one seeded hash function per permutation.
+//! - `OnePermutation`, one-permutation MinHash with optimal densification, hashes each item once.
+//! - `BBitSignature`, b-bit MinHash, keeps the lowest `b` bits of each MinHash value.
+//! - `SimHash`, Charikar's SimHash, a 64-bit fingerprint compared with the Hamming distance.
+//! - `Lsh` and `LshIndex`, locality sensitive hashing over the bands of a MinHash signature.
+//!
+//! Every signature is generic over the `FastHash` function used to hash the items.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(any(feature = "sys", feature = "pure-city"))]
+//! # {
+//! use fasthash::{city, similarity::MinHash};
+//!
+//! let minhash = MinHash::<city::Hash64>::new(128);
+//!
+//! let a = minhash.signature("the quick brown fox jumps over the lazy dog".split(' '));
+//! let b = minhash.signature("the quick brown fox jumps over the lazy cat".split(' '));
+//!
+//! let j = a.jaccard(&b);
+//!
+//! assert!(j > 0.6 && j < 0.9);
+//! # }
+//! ```
+use std::collections::{HashMap, HashSet};
+use std::f64::consts::PI;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use crate::hasher::{index_seed, mix64, FastHash};
+
+const EMPTY: u64 = !0;
+
+/// Fold a hash value into 64 bits.
+#[inline(always)]
+fn fold(h: u128) -> u64 {
+    h as u64 ^ (h >> 64) as u64
+}
+
+/// A MinHash signature, the minimum hash value of each permutation or bin.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Signature(Vec<u64>);
+
+impl Signature {
+    /// Returns the values of the signature.
+    pub fn values(&self) -> &[u64] {
+        &self.0
+    }
+
+    /// Returns the number of values in the signature.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the signature was computed from an empty set.
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|&v| v == EMPTY)
+    }
+
+    /// Estimates the Jaccard similarity with another signature of the same kind and length.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the signatures have a different length.
+    pub fn jaccard(&self, other: &Signature) -> f64 {
+        assert_eq!(
+            self.len(),
+            other.len(),
+            "signatures must have the same length"
+        );
+
+        let matches = self
+            .0
+            .iter()
+            .zip(other.0.iter())
+            .filter(|(a, b)| a == b)
+            .count();
+
+        matches as f64 / self.len() as f64
+    }
+
+    /// Keeps the lowest `bits` bits of each value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits` is not in `1..=32`.
+    pub fn b_bit(&self, bits: u32) -> BBitSignature {
+        assert!((1..=32).contains(&bits), "bits must be in 1..=32");
+
+        let mut words = vec![0u64; (self.len() * bits as usize - 1) / 64 + 1];
+        let mask = (1 << bits) - 1;
+
+        for (i, &v) in self.0.iter().enumerate() {
+            let bit = i * bits as usize;
+            let v = v & mask;
+
+            words[bit / 64] |= v << (bit % 64);
+
+            if bit % 64 + bits as usize > 64 {
+                words[bit / 64 + 1] |= v >> (64 - bit % 64);
+            }
+        }
+
+        BBitSignature {
+            bits,
+            len: self.len(),
+            words,
+        }
+    }
+}
+
+/// A b-bit MinHash signature, packs the lowest `b` bits of each MinHash value.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(any(feature = "sys", feature = "pure-city"))]
+/// # {
+/// use fasthash::{city, similarity::MinHash};
+///
+/// let minhash = MinHash::<city::Hash64>::new(256);
+///
+/// let a = minhash.signature((0..100_u32).map(u32::to_le_bytes)).b_bit(2);
+/// let b = minhash.signature((50..150_u32).map(u32::to_le_bytes)).b_bit(2);
+///
+/// assert_eq!(a.len(), 256);
+/// assert_eq!(a.words().len(), 8);
+///
+/// let j = a.jaccard(&b);
+///
+/// assert!(j > 0.2 && j < 0.5);
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BBitSignature {
+    bits: u32,
+    len: usize,
+    words: Vec<u64>,
+}
+
+impl BBitSignature {
+    /// Returns the number of bits of each value.
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    /// Returns the number of values in the signature.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the signature has no value.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the packed values.
+    pub fn words(&self) -> &[u64] {
+        &self.words
+    }
+
+    /// Returns the value at `index`.
+    pub fn get(&self, index: usize) -> u64 {
+        assert!(index < self.len, "index out of bounds");
+
+        let bit = index * self.bits as usize;
+        let mut v = self.words[bit / 64] >> (bit % 64);
+
+        if bit % 64 + self.bits as usize > 64 {
+            v |= self.words[bit / 64 + 1] << (64 - bit % 64);
+        }
+
+        v & ((1 << self.bits) - 1)
+    }
+
+    /// Estimates the Jaccard similarity with another signature of the same bits and length.
+    ///
+    /// Two different values collide on `b` bits with probability `2^-b`,
+    /// the estimate corrects the rate of matching values for these collisions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the signatures have a different bits or length.
+    pub fn jaccard(&self, other: &BBitSignature) -> f64 {
+        assert!(
+            self.bits == other.bits && self.len == other.len,
+            "signatures must have the same bits and length"
+        );
+
+        let matches = (0..self.len)
+            .filter(|&i| self.get(i) == other.get(i))
+            .count();
+        let p = matches as f64 / self.len as f64;
+        let c = 1.0 / (1u64 << self.bits) as f64;
+
+        ((p - c) / (1.0 - c)).clamp(0.0, 1.0)
+    }
+}
+
+/// The classic MinHash, hashes each item once per permutation with a different seed.
+///
+/// The seed of each permutation is derived from its index, see `MinHash::seed`.
+pub struct MinHash<H: FastHash> {
+    seeds: Vec<H::Seed>,
+    phantom: PhantomData<H>,
+}
+
+impl<H> MinHash<H>
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+    H::Seed: From<u32>,
+{
+    /// Constructs a new MinHash of `num_perm` permutations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_perm` is zero.
+    pub fn new(num_perm: usize) -> Self {
+        Self::with_seeds((0..num_perm as u32).map(|i| Self::seed(i).into()))
+    }
+}
+
+impl<H> MinHash<H>
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+{
+    /// Constructs a new MinHash with a permutation for each seed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no seed.
+    pub fn with_seeds<I: IntoIterator<Item = H::Seed>>(seeds: I) -> Self {
+        let seeds = seeds.into_iter().collect::<Vec<_>>();
+
+        assert!(!seeds.is_empty(), "num_perm must be positive");
+
+        MinHash {
+            seeds,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the default seed of a permutation, derived from its index.
+    #[inline(always)]
+    pub fn seed(index: u32) -> u32 {
+        index_seed(index)
+    }
+
+    /// Returns the number of permutations.
+    pub fn num_perm(&self) -> usize {
+        self.seeds.len()
+    }
+
+    /// Computes the signature of a set of items.
+    pub fn signature<I, T>(&self, items: I) -> Signature
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        let mut values = vec![EMPTY; self.seeds.len()];
+
+        for item in items {
+            let item = item.as_ref();
+
+            for (v, &seed) in values.iter_mut().zip(self.seeds.iter()) {
+                *v = (*v).min(mix64(fold(H::hash_with_seed(item, seed).into())));
+            }
+        }
+
+        Signature(values)
+    }
+}
+
+impl<H: FastHash> Clone for MinHash<H> {
+    fn clone(&self) -> Self {
+        MinHash {
+            seeds: self.seeds.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// One-permutation MinHash, hashes each item once and keeps the minimum of each bin.
+///
+/// The empty bins are filled with the optimal densification,
+/// each empty bin copies a non-empty bin picked by a fixed random probe sequence.
+///
+/// See "Optimal Densification for Fast and Accurate Minwise Hashing" by Anshumali Shrivastava.
+///
+/// # Example
+///
+/// ```
+/// use fasthash::{sea, similarity::OnePermutation};
+///
+/// let oph = OnePermutation::<sea::Hash64>::new(128);
+///
+/// let a = oph.signature((0..1000_u32).map(u32::to_le_bytes));
+/// let b = oph.signature((500..1500_u32).map(u32::to_le_bytes));
+///
+/// let j = a.jaccard(&b);
+///
+/// assert!(j > 0.2 && j < 0.5);
+/// ```
+pub struct OnePermutation<H> {
+    bins: usize,
+    phantom: PhantomData<H>,
+}
+
+impl<H> OnePermutation<H>
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+{
+    /// Constructs a new one-permutation MinHash of `bins` bins.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bins` is zero.
+    pub fn new(bins: usize) -> Self {
+        assert!(bins > 0, "bins must be positive");
+
+        OnePermutation {
+            bins,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the number of bins.
+    pub fn bins(&self) -> usize {
+        self.bins
+    }
+
+    /// Computes the signature of a set of items.
+    pub fn signature<I, T>(&self, items: I) -> Signature
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        let mut values = vec![EMPTY; self.bins];
+
+        for item in items {
+            let h = mix64(fold(H::hash(item).into()));
+            let bin = ((u128::from(h) * self.bins as u128) >> 64) as usize;
+
+            values[bin] = values[bin].min(h);
+        }
+
+        if values.iter().all(|&v| v == EMPTY) {
+            return Signature(values);
+        }
+
+        let filled = values.clone();
+
+        for (i, v) in values.iter_mut().enumerate() {
+            if *v != EMPTY {
+                continue;
+            }
+
+            for attempt in 1.. {
+                let probe = mix64(((i as u64) << 32) | attempt);
+                let j = ((u128::from(probe) * self.bins as u128) >> 64) as usize;
+
+                if filled[j] != EMPTY {
+                    *v = filled[j];
+                    break;
+                }
+            }
+        }
+
+        Signature(values)
+    }
+}
+
+impl<H> Clone for OnePermutation<H> {
+    fn clone(&self) -> Self {
+        OnePermutation {
+            bins: self.bins,
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// Charikar's SimHash, a 64-bit fingerprint where similar sets differ in few bits.
+///
+/// # Example
+///
+/// ```
+/// use fasthash::{sea, similarity::{hamming, SimHash}};
+///
+/// let simhash = SimHash::<sea::Hash64>::new();
+///
+/// let a = simhash.fingerprint("the quick brown fox jumps over the lazy dog".split(' '));
+/// let b = simhash.fingerprint("the quick brown fox jumps over the lazy cat".split(' '));
+/// let c = simhash.fingerprint("lorem ipsum dolor sit amet consectetur adipiscing elit".split(' '));
+///
+/// assert!(hamming(a, b) < hamming(a, c));
+/// ```
+pub struct SimHash<H> {
+    phantom: PhantomData<H>,
+}
+
+impl<H> Default for SimHash<H> {
+    fn default() -> Self {
+        SimHash {
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<H> SimHash<H>
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+{
+    /// Constructs a new SimHash.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Computes the fingerprint of a set of items, each with the same weight.
+    pub fn fingerprint<I, T>(&self, items: I) -> u64
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        self.weighted(items.into_iter().map(|item| (item, 1.0)))
+    }
+
+    /// Computes the fingerprint of a set of weighted items.
+    pub fn weighted<I, T>(&self, items: I) -> u64
+    where
+        I: IntoIterator<Item = (T, f64)>,
+        T: AsRef<[u8]>,
+    {
+        let mut weights = [0f64; 64];
+
+        for (item, weight) in items {
+            let h = mix64(fold(H::hash(item).into()));
+
+            for (bit, w) in weights.iter_mut().enumerate() {
+                if h & (1 << bit) == 0 {
+                    *w -= weight;
+                } else {
+                    *w += weight;
+                }
+            }
+        }
+
+        weights
+            .iter()
+            .enumerate()
+            .filter(|(_, &w)| w > 0.0)
+            .fold(0, |fp, (bit, _)| fp | (1 << bit))
+    }
+}
+
+/// Returns the number of different bits between two fingerprints.
+#[inline(always)]
+pub fn hamming(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// Estimates the cosine similarity of two SimHash fingerprints.
+#[inline(always)]
+pub fn cosine(a: u64, b: u64) -> f64 {
+    (PI * f64::from(hamming(a, b)) / 64.0).cos()
+}
+
+/// Locality sensitive hashing, splits a MinHash signature in `bands` bands of `rows` values.
+///
+/// Two signatures share at least one band key with probability `1 - (1 - j^rows)^bands`,
+/// for a Jaccard similarity `j`.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(any(feature = "sys", feature = "pure-xxh3"))]
+/// # {
+/// use fasthash::{similarity::{Lsh, MinHash}, xxh3};
+///
+/// let lsh = Lsh::<xxh3::Hash64>::new(16, 8);
+///
+/// assert!((lsh.threshold() - 0.707).abs() < 0.001);
+/// assert!(lsh.probability(0.9) > 0.99);
+/// assert!(lsh.probability(0.3) < 0.01);
+///
+/// let minhash = MinHash::<xxh3::Hash64>::new(lsh.bands() * lsh.rows());
+/// let sig = minhash.signature(&["a", "b", "c"]);
+///
+/// assert_eq!(lsh.keys(&sig).len(), 16);
+/// # }
+/// ```
+pub struct Lsh<H> {
+    bands: usize,
+    rows: usize,
+    phantom: PhantomData<H>,
+}
+
+impl<H> Lsh<H>
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+{
+    /// Constructs a new LSH of `bands` bands of `rows` values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bands` or `rows` is zero.
+    pub fn new(bands: usize, rows: usize) -> Self {
+        assert!(bands > 0 && rows > 0, "bands and rows must be positive");
+
+        Lsh {
+            bands,
+            rows,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the number of bands.
+    pub fn bands(&self) -> usize {
+        self.bands
+    }
+
+    /// Returns the number of values per band.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the probability that two sets of Jaccard similarity `j` share a band.
+    pub fn probability(&self, j: f64) -> f64 {
+        1.0 - (1.0 - j.powi(self.rows as i32)).powi(self.bands as i32)
+    }
+
+    /// Returns the approximate similarity where the probability rises the fastest.
+    pub fn threshold(&self) -> f64 {
+        (1.0 / self.bands as f64).powf(1.0 / self.rows as f64)
+    }
+
+    /// Hashes each band of the signature into a key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the signature is shorter than `bands * rows`.
+    pub fn keys(&self, signature: &Signature) -> Vec<u64> {
+        assert!(
+            signature.len() >= self.bands * self.rows,
+            "signature must have at least bands * rows values"
+        );
+
+        signature
+            .values()
+            .chunks(self.rows)
+            .take(self.bands)
+            .map(|band| {
+                let bytes = band
+                    .iter()
+                    .flat_map(|v| v.to_le_bytes().to_vec())
+                    .collect::<Vec<_>>();
+
+                fold(H::hash(bytes).into())
+            })
+            .collect()
+    }
+}
+
+impl<H> Clone for Lsh<H> {
+    fn clone(&self) -> Self {
+        Lsh {
+            bands: self.bands,
+            rows: self.rows,
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// An index of MinHash signatures, finds the candidates that share a band.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(any(feature = "sys", feature = "pure-xxh3"))]
+/// # {
+/// use fasthash::{similarity::{Lsh, LshIndex, MinHash}, xxh3};
+///
+/// let minhash = MinHash::<xxh3::Hash64>::new(128);
+/// let mut index = LshIndex::new(Lsh::<xxh3::Hash64>::new(32, 4));
+///
+/// index.insert("fox", &minhash.signature("the quick brown fox jumps over the lazy dog".split(' ')));
+/// index.insert("lorem", &minhash.signature("lorem ipsum dolor sit amet".split(' ')));
+///
+/// let query = minhash.signature("the quick brown fox jumps over the lazy cat".split(' '));
+///
+/// assert_eq!(index.query(&query), vec![&"fox"]);
+/// # }
+/// ```
+pub struct LshIndex<K, H> {
+    lsh: Lsh<H>,
+    tables: Vec<HashMap<u64, Vec<K>>>,
+}
+
+impl<K, H> LshIndex<K, H>
+where
+    K: Hash + Eq,
+    H: FastHash,
+    H::Hash: Into<u128>,
+{
+    /// Constructs a new empty index.
+    pub fn new(lsh: Lsh<H>) -> Self {
+        LshIndex {
+            tables: (0..lsh.bands()).map(|_| HashMap::new()).collect(),
+            lsh,
+        }
+    }
+
+    /// Returns the LSH of the index.
+    pub fn lsh(&self) -> &Lsh<H> {
+        &self.lsh
+    }
+
+    /// Inserts a key with its signature.
+    pub fn insert(&mut self, key: K, signature: &Signature)
+    where
+        K: Clone,
+    {
+        for (table, band) in self.tables.iter_mut().zip(self.lsh.keys(signature)) {
+            table.entry(band).or_insert_with(Vec::new).push(key.clone());
+        }
+    }
+
+    /// Returns the keys sharing at least one band with the signature, in insertion order per band.
+    pub fn query(&self, signature: &Signature) -> Vec<&K> {
+        let mut seen = HashSet::new();
+        let mut candidates = Vec::new();
+
+        for (table, band) in self.tables.iter().zip(self.lsh.keys(signature)) {
+            for key in table.get(&band).into_iter().flatten() {
+                if seen.insert(key) {
+                    candidates.push(key);
+                }
+            }
+        }
+
+        candidates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sea::Hash64;
+
+    /// The seeds of `sea::Hash64` derived from the 32-bit seeds.
+    fn seeds(num_perm: u32) -> Vec<(u64, u64, u64, u64)> {
+        (0..num_perm)
+            .map(|i| {
+                let seed = u64::from(MinHash::<Hash64>::seed(i));
+
+                (seed, seed, seed, seed)
+            })
+            .collect()
+    }
+
+    /// Two sets of 1000 items with a Jaccard similarity of `common / (2000 - common)`.
+    fn sets(common: u32) -> (Vec<[u8; 4]>, Vec<[u8; 4]>) {
+        let a = (0..1000_u32).map(|i| i.to_le_bytes()).collect();
+        let b = (1000 - common..2000 - common)
+            .map(|i| i.to_le_bytes())
+            .collect();
+
+        (a, b)
+    }
+
+    #[test]
+    fn test_minhash() {
+        let minhash = MinHash::<Hash64>::with_seeds(seeds(256));
+        let oph = OnePermutation::<Hash64>::new(256);
+
+        for &common in &[0, 200, 500, 800, 1000] {
+            let (a, b) = sets(common);
+            let j = f64::from(common) / f64::from(2000 - common);
+
+            for (sa, sb) in [
+                (minhash.signature(&a), minhash.signature(&b)),
+                (oph.signature(&a), oph.signature(&b)),
+            ] {
+                assert_eq!(sa.len(), 256);
+                assert!(!sa.is_empty());
+                assert!((sa.jaccard(&sb) - j).abs() < 0.1);
+
+                for &bits in &[1, 2, 8, 32] {
+                    let (ba, bb) = (sa.b_bit(bits), sb.b_bit(bits));
+
+                    for i in 0..256 {
+                        assert_eq!(ba.get(i), sa.values()[i] & ((1 << bits) - 1));
+                    }
+
+                    assert!((ba.jaccard(&bb) - j).abs() < 0.2);
+                }
+            }
+        }
+
+        assert!(minhash.signature(Vec::<&[u8]>::new()).is_empty());
+        assert!(oph.signature(Vec::<&[u8]>::new()).is_empty());
+
+        // the densification fills every bin of a sparse set
+        let sig = oph.signature([b"a", b"b"]);
+
+        assert!(sig.values().iter().all(|&v| v != EMPTY));
+    }
+
+    #[test]
+    fn test_simhash() {
+        let simhash = SimHash::<Hash64>::new();
+        let (a, b) = sets(900);
+        let (fa, fb) = (simhash.fingerprint(&a), simhash.fingerprint(&b));
+
+        assert!(hamming(fa, fb) < 16);
+        assert!(cosine(fa, fb) > 0.7);
+        assert_eq!(hamming(fa, !fa), 64);
+        assert_eq!(
+            simhash.fingerprint(&a[..1]),
+            mix64(fold(Hash64::hash(a[0]).into()))
+        );
+    }
+
+    #[test]
+    fn test_lsh() {
+        let minhash = MinHash::<Hash64>::with_seeds(seeds(128));
+        let mut index = LshIndex::new(Lsh::<Hash64>::new(32, 4));
+
+        for &common in &[0, 100, 900] {
+            let (_, b) = sets(common);
+
+            index.insert(common, &minhash.signature(&b));
+        }
+
+        let (a, _) = sets(0);
+
+        assert_eq!(index.query(&minhash.signature(&a)), vec![&900]);
+    }
+}
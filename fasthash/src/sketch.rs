@@ -0,0 +1,531 @@
+//! Frequency sketches, estimate how many times the keys were seen.
+//!
+//! - `CountMinSketch`, a `depth x width` matrix of counters, never underestimates a key.
+//! - `SpaceSaving`, tracks the top-k heavy hitters with a fixed number of counters.
+//!
+//! The `depth` rows of a `CountMinSketch` are hashed with one `FastHash` function
+//! and a different seed per row, derived from the row index, see `CountMinSketch::seed`.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(any(feature = "sys", feature = "pure-xxh3"))]
+//! # {
+//! use fasthash::{sketch::{CountMinSketch, SpaceSaving}, xxh3, RandomState};
+//!
+//! let mut cms = CountMinSketch::<xxh3::Hash64>::new(1024, 4);
+//! let mut top = SpaceSaving::<_, RandomState<xxh3::Hash64>>::new(16);
+//!
+//! for i in 0..1000_u32 {
+//!     let key = format!("key-{}", i % 10 * i % 7);
+//!
+//!     cms.add(&key, 1);
+//!     top.add(key, 1);
+//! }
+//!
+//! let (key, counter) = top.top(1)[0];
+//!
+//! assert_eq!(key, "key-0");
+//! assert!(cms.estimate(key) >= counter.count - counter.error);
+//! # }
+//! ```
+use std::collections::hash_map::{self, HashMap};
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+
+use crate::hasher::{index_seed, FastHash};
+
+/// The errors of merging the sketches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The sketches have a different size or different seeds.
+    Incompatible,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Incompatible => f.write_str("incompatible sketches"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// The Count-Min sketch.
+///
+/// With `width = ceil(e / epsilon)` and `depth = ceil(ln(1 / delta))`,
+/// the estimate is at most `epsilon * total` above the real count with probability `1 - delta`.
+///
+/// With the conservative update, a key only increments the counters below its new estimate,
+/// which reduces the overestimation but the sketches can no longer be merged exactly.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(any(feature = "sys", feature = "pure-xxh3"))]
+/// # {
+/// use fasthash::{sketch::CountMinSketch, xxh3};
+///
+/// let mut cms = CountMinSketch::<xxh3::Hash64>::with_error(0.001, 0.01).conservative();
+///
+/// cms.add("hello", 3);
+/// cms.add("world", 2);
+/// cms.add("hello", 1);
+///
+/// assert_eq!(cms.estimate("hello"), 4);
+/// assert_eq!(cms.estimate("world"), 2);
+/// assert_eq!(cms.total(), 6);
+/// # }
+/// ```
+pub struct CountMinSketch<H: FastHash> {
+    width: usize,
+    seeds: Vec<H::Seed>,
+    counters: Vec<u64>,
+    total: u64,
+    conservative: bool,
+    phantom: PhantomData<H>,
+}
+
+impl<H> CountMinSketch<H>
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+    H::Seed: From<u32>,
+{
+    /// Constructs a new sketch of `depth` rows and `width` counters per row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` or `depth` is zero.
+    pub fn new(width: usize, depth: usize) -> Self {
+        Self::with_seeds(width, (0..depth as u32).map(|row| Self::seed(row).into()))
+    }
+
+    /// Constructs a new sketch for the error `epsilon` with probability `delta`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `epsilon` or `delta` is not in `(0, 1)`.
+    pub fn with_error(epsilon: f64, delta: f64) -> Self {
+        assert!(epsilon > 0.0 && epsilon < 1.0, "epsilon must be in (0, 1)");
+        assert!(delta > 0.0 && delta < 1.0, "delta must be in (0, 1)");
+
+        let width = (std::f64::consts::E / epsilon).ceil() as usize;
+        let depth = (1.0 / delta).ln().ceil().max(1.0) as usize;
+
+        Self::new(width, depth)
+    }
+}
+
+impl<H> CountMinSketch<H>
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+{
+    /// Constructs a new sketch of `width` counters per row, with a row for each seed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero or there is no seed.
+    pub fn with_seeds<I: IntoIterator<Item = H::Seed>>(width: usize, seeds: I) -> Self {
+        let seeds = seeds.into_iter().collect::<Vec<_>>();
+
+        assert!(width > 0, "width must be positive");
+        assert!(!seeds.is_empty(), "depth must be positive");
+
+        CountMinSketch {
+            width,
+            counters: vec![0; width * seeds.len()],
+            seeds,
+            total: 0,
+            conservative: false,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the default seed of a row, derived from its index.
+    #[inline(always)]
+    pub fn seed(row: u32) -> u32 {
+        index_seed(row)
+    }
+
+    /// Enables the conservative update.
+    pub fn conservative(mut self) -> Self {
+        self.conservative = true;
+        self
+    }
+
+    /// Returns `true` if the conservative update is enabled.
+    pub fn is_conservative(&self) -> bool {
+        self.conservative
+    }
+
+    /// Returns the number of counters per row.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of rows.
+    pub fn depth(&self) -> usize {
+        self.seeds.len()
+    }
+
+    /// Returns the sum of all the counts added.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Resets all the counters.
+    pub fn clear(&mut self) {
+        for c in self.counters.iter_mut() {
+            *c = 0;
+        }
+
+        self.total = 0;
+    }
+
+    /// Returns the index of the key's counter in a row.
+    #[inline(always)]
+    fn cell(&self, row: usize, key: &[u8]) -> usize {
+        let h: u128 = H::hash_with_seed(key, self.seeds[row]).into();
+
+        row * self.width + ((h as u64 ^ (h >> 64) as u64) % self.width as u64) as usize
+    }
+
+    /// Adds `count` occurrences of the key.
+    pub fn add<T: AsRef<[u8]>>(&mut self, key: T, count: u64) {
+        let key = key.as_ref();
+
+        self.total = self.total.saturating_add(count);
+
+        if self.conservative {
+            let estimate = self.estimate(key).saturating_add(count);
+
+            for row in 0..self.seeds.len() {
+                let i = self.cell(row, key);
+
+                self.counters[i] = self.counters[i].max(estimate);
+            }
+        } else {
+            for row in 0..self.seeds.len() {
+                let i = self.cell(row, key);
+
+                self.counters[i] = self.counters[i].saturating_add(count);
+            }
+        }
+    }
+
+    /// Returns the estimated count of the key, never less than the real count.
+    pub fn estimate<T: AsRef<[u8]>>(&self, key: T) -> u64 {
+        let key = key.as_ref();
+
+        (0..self.seeds.len())
+            .map(|row| self.counters[self.cell(row, key)])
+            .min()
+            .unwrap_or_default()
+    }
+
+    /// Adds the counters of another sketch with the same size and seeds.
+    ///
+    /// The estimates stay upper bounds after merging conservative sketches,
+    /// but are not as tight as a conservative sketch of the whole stream.
+    pub fn merge(&mut self, other: &Self) -> Result<(), Error>
+    where
+        H::Seed: PartialEq,
+    {
+        if self.width != other.width || self.seeds != other.seeds {
+            return Err(Error::Incompatible);
+        }
+
+        for (c, o) in self.counters.iter_mut().zip(other.counters.iter()) {
+            *c = c.saturating_add(*o);
+        }
+
+        self.total = self.total.saturating_add(other.total);
+
+        Ok(())
+    }
+}
+
+impl<H: FastHash> Clone for CountMinSketch<H> {
+    fn clone(&self) -> Self {
+        CountMinSketch {
+            width: self.width,
+            seeds: self.seeds.clone(),
+            counters: self.counters.clone(),
+            total: self.total,
+            conservative: self.conservative,
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// The counter of a key tracked by `SpaceSaving`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Counter {
+    /// The estimated count, never less than the real count.
+    pub count: u64,
+    /// The maximum overestimation, the real count is at least `count - error`.
+    pub error: u64,
+}
+
+/// The Space-Saving heavy hitters algorithm.
+///
+/// It keeps at most `capacity` counters, a new key replaces the key with the minimum count.
+/// Every key with a real count above `total / capacity` is guaranteed to be tracked.
+///
+/// # Example
+///
+/// ```
+/// use fasthash::{sea, sketch::SpaceSaving, RandomState};
+///
+/// let mut top = SpaceSaving::<_, RandomState<sea::Hash64>>::new(2);
+///
+/// top.add("a", 5);
+/// top.add("b", 3);
+/// top.add("c", 1);
+///
+/// assert_eq!(top.get(&"a").unwrap().count, 5);
+/// assert_eq!(top.get(&"b"), None);
+/// assert_eq!(top.get(&"c").unwrap().count, 4);
+/// assert_eq!(top.get(&"c").unwrap().error, 3);
+/// ```
+#[derive(Clone, Debug)]
+pub struct SpaceSaving<K, S = hash_map::RandomState> {
+    capacity: usize,
+    counters: HashMap<K, Counter, S>,
+    total: u64,
+}
+
+impl<K, S> SpaceSaving<K, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher + Default,
+{
+    /// Constructs a new tracker with `capacity` counters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_hasher(capacity, S::default())
+    }
+}
+
+impl<K, S> SpaceSaving<K, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Constructs a new tracker with `capacity` counters, the keys are hashed with `hasher`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn with_hasher(capacity: usize, hasher: S) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+
+        SpaceSaving {
+            capacity,
+            counters: HashMap::with_capacity_and_hasher(capacity, hasher),
+            total: 0,
+        }
+    }
+
+    /// Returns the maximum number of tracked keys.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of tracked keys.
+    pub fn len(&self) -> usize {
+        self.counters.len()
+    }
+
+    /// Returns `true` if no key is tracked.
+    pub fn is_empty(&self) -> bool {
+        self.counters.is_empty()
+    }
+
+    /// Returns the sum of all the counts added.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Adds `count` occurrences of the key.
+    ///
+    /// Replacing a key scans all the counters, so `capacity` should stay small.
+    pub fn add(&mut self, key: K, count: u64) {
+        self.total = self.total.saturating_add(count);
+
+        if let Some(counter) = self.counters.get_mut(&key) {
+            counter.count = counter.count.saturating_add(count);
+            return;
+        }
+
+        let counter = if self.counters.len() < self.capacity {
+            Counter { count, error: 0 }
+        } else {
+            let min = self
+                .counters
+                .values()
+                .min_by_key(|c| c.count)
+                .cloned()
+                .unwrap();
+            let mut evicted = false;
+
+            self.counters.retain(|_, c| {
+                let keep = evicted || c.count != min.count;
+                evicted |= !keep;
+                keep
+            });
+
+            Counter {
+                count: min.count.saturating_add(count),
+                error: min.count,
+            }
+        };
+
+        self.counters.insert(key, counter);
+    }
+
+    /// Returns the counter of a tracked key.
+    pub fn get(&self, key: &K) -> Option<Counter> {
+        self.counters.get(key).cloned()
+    }
+
+    /// Returns up to `n` tracked keys with the highest counts, in descending order.
+    pub fn top(&self, n: usize) -> Vec<(&K, Counter)> {
+        let mut top = self
+            .counters
+            .iter()
+            .map(|(k, c)| (k, *c))
+            .collect::<Vec<_>>();
+
+        top.sort_by(|(_, a), (_, b)| b.count.cmp(&a.count).then(a.error.cmp(&b.error)));
+        top.truncate(n);
+        top
+    }
+
+    /// Returns the tracked keys whose real count is guaranteed to be above `threshold`.
+    pub fn heavy_hitters(&self, threshold: u64) -> Vec<(&K, Counter)> {
+        let mut top = self.top(self.counters.len());
+
+        top.retain(|(_, c)| c.count - c.error > threshold);
+        top
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sea::Hash64;
+    use crate::RandomState;
+
+    /// The counts of a skewed stream, key `i` occurs `1000 / (i + 1)` times.
+    fn stream() -> Vec<(u32, u64)> {
+        (0..1000).map(|i| (i, 1000 / (u64::from(i) + 1))).collect()
+    }
+
+    #[test]
+    fn test_count_min() {
+        // sea::Hash64 has a tuple seed, expand the derived 32-bit seeds
+        let seeds = (0..4_u32)
+            .map(|row| {
+                let seed = u64::from(CountMinSketch::<Hash64>::seed(row));
+
+                (seed, seed, seed, seed)
+            })
+            .collect::<Vec<_>>();
+        let mut standard = CountMinSketch::<Hash64>::with_seeds(256, seeds.clone());
+        let mut conservative =
+            CountMinSketch::<Hash64>::with_seeds(256, seeds.clone()).conservative();
+
+        assert_eq!(standard.depth(), 4);
+        assert_eq!(CountMinSketch::<Hash64>::seed(0), 0x7f4a_7c15);
+
+        for &(key, count) in &stream() {
+            for _ in 0..count {
+                standard.add(key.to_le_bytes(), 1);
+                conservative.add(key.to_le_bytes(), 1);
+            }
+        }
+
+        let total = stream().iter().map(|&(_, c)| c).sum::<u64>();
+
+        assert_eq!(standard.total(), total);
+
+        let mut errors = 0;
+
+        for &(key, count) in &stream() {
+            let s = standard.estimate(key.to_le_bytes());
+            let c = conservative.estimate(key.to_le_bytes());
+
+            assert!(s >= count && c >= count && c <= s);
+
+            if s > count + total * 3 / 256 {
+                errors += 1;
+            }
+        }
+
+        assert!(errors < 50, "errors = {}", errors);
+
+        let mut a = CountMinSketch::<Hash64>::with_seeds(256, seeds.clone());
+        let mut b = a.clone();
+
+        a.add("hello", 3);
+        b.add("hello", 4);
+        a.merge(&b).unwrap();
+
+        assert_eq!(a.estimate("hello"), 7);
+        assert_eq!(a.total(), 7);
+
+        assert_eq!(
+            a.merge(&CountMinSketch::with_seeds(128, seeds.clone()))
+                .unwrap_err(),
+            Error::Incompatible
+        );
+        assert_eq!(
+            a.merge(&CountMinSketch::with_seeds(
+                256,
+                seeds.iter().rev().copied()
+            ))
+            .unwrap_err(),
+            Error::Incompatible
+        );
+
+        a.clear();
+        assert_eq!(a.estimate("hello"), 0);
+    }
+
+    #[test]
+    fn test_space_saving() {
+        let mut top = SpaceSaving::<u32, RandomState<Hash64>>::new(50);
+
+        for round in 0..1000 {
+            for &(key, count) in &stream() {
+                if round < count {
+                    top.add(key, 1);
+                }
+            }
+        }
+
+        assert_eq!(top.len(), 50);
+
+        for (key, counter) in top.top(10) {
+            let count = stream()[*key as usize].1;
+
+            assert!(counter.count >= count && counter.count - counter.error <= count);
+        }
+
+        let heavy = top
+            .heavy_hitters(top.total() / 50)
+            .into_iter()
+            .map(|(k, _)| *k)
+            .collect::<Vec<_>>();
+
+        assert_eq!(heavy[..3], [0, 1, 2]);
+    }
+}
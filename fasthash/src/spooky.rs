@@ -44,13 +44,19 @@
 //! assert_eq!(h as u64, hash(&"hello world"));
 //! ```
 //!
-use std::hash::Hasher;
-use std::os::raw::c_void;
-use std::ptr::NonNull;
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use core::ffi::c_void;
+use core::hash::Hasher;
+use core::mem;
+use core::ptr::NonNull;
 
 use crate::ffi;
 
-use crate::hasher::{FastHash, FastHasher, HasherExt, StreamHasher};
+#[cfg(feature = "std")]
+use crate::hasher::StreamHasher;
+use crate::hasher::{FastHash, FastHasher, HasherExt};
+use crate::state::{Error, HasherState, Reader, Writer};
 
 /// `SpookyHash` 32-bit hash functions
 ///
@@ -104,7 +110,7 @@ trivial_hasher! {
     /// h.write(b"world");
     /// assert_eq!(h.finish(), 3874077464);
     /// ```
-    Hasher32(Hash32) -> u32
+    Hasher32(Hash32) -> u32, "fasthash::spooky::Hasher32"
 }
 
 /// `SpookyHash` 64-bit hash functions
@@ -159,7 +165,7 @@ trivial_hasher! {
     /// h.write(b"world");
     /// assert_eq!(h.finish(), 18412934266828208920);
     /// ```
-    Hasher64(Hash64) -> u64
+    Hasher64(Hash64) -> u64, "fasthash::spooky::Hasher64"
 }
 
 /// `SpookyHash` 128-bit hash functions
@@ -224,8 +230,7 @@ impl FastHash for Hash128 {
 /// h.write(b"world");
 /// assert_eq!(h.finish_ext(), 339658686066216790682429200470429822413);
 /// ```
-#[derive(Clone)]
-pub struct Hasher128(NonNull<c_void>);
+pub struct Hasher128(NonNull<c_void>, (u64, u64));
 
 impl Default for Hasher128 {
     fn default() -> Self {
@@ -240,6 +245,26 @@ impl Drop for Hasher128 {
     }
 }
 
+impl Clone for Hasher128 {
+    fn clone(&self) -> Self {
+        unsafe {
+            let h = ffi::SpookyHasherNew();
+
+            ffi::SpookyHasherCopy(h, self.0.as_ptr());
+
+            Hasher128(NonNull::new_unchecked(h), self.1)
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        unsafe {
+            ffi::SpookyHasherCopy(self.0.as_ptr(), source.0.as_ptr());
+        }
+
+        self.1 = source.1;
+    }
+}
+
 impl Hasher for Hasher128 {
     #[inline(always)]
     fn finish(&self) -> u64 {
@@ -261,8 +286,8 @@ impl Hasher for Hasher128 {
 impl HasherExt for Hasher128 {
     #[inline(always)]
     fn finish_ext(&self) -> u128 {
-        let mut hi = 0_u64;
-        let mut lo = 0_u64;
+        // `Final` hashes a short message with the seed passed in, instead of the one of `Init`
+        let (mut hi, mut lo) = self.1;
 
         unsafe {
             ffi::SpookyHasherFinal(self.0.as_ptr(), &mut hi, &mut lo);
@@ -283,14 +308,66 @@ impl FastHasher for Hasher128 {
 
             ffi::SpookyHasherInit(h, seed.0, seed.1);
 
-            Hasher128(NonNull::new_unchecked(h))
+            Hasher128(NonNull::new_unchecked(h), seed)
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl StreamHasher for Hasher128 {}
 
+impl HasherState for Hasher128 {
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut state: ffi::SpookyHasherState = unsafe { mem::zeroed() };
+
+        unsafe { ffi::SpookyHasherGetState(self.0.as_ptr(), &mut state) }
+
+        let mut w = Writer::new("fasthash::spooky::Hasher128");
+
+        w.seed(self.1);
+
+        // the unhashed data, in the order of the bytes
+        for n in &state.data[..] {
+            w.bytes(&n.to_ne_bytes());
+        }
+
+        w.words(&state.state)
+            .u64(state.length as u64)
+            .u8(state.remainder);
+        w.into_bytes()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let mut r = Reader::new(bytes, "fasthash::spooky::Hasher128")?;
+        let mut state: ffi::SpookyHasherState = unsafe { mem::zeroed() };
+        let seed = r.seed()?;
+
+        for n in &mut state.data[..] {
+            *n = u64::from_ne_bytes(r.bytes(8)?.try_into().unwrap());
+        }
+
+        r.words(&mut state.state)?;
+
+        state.length = r.u64()?.try_into().map_err(|_| Error::InvalidFormat)?;
+        state.remainder = r.u8()?;
+
+        // the data is hashed as soon as the buffer is full
+        if usize::from(state.remainder) >= mem::size_of_val(&state.data) {
+            return Err(Error::InvalidFormat);
+        }
+
+        r.finish()?;
+
+        let h = Self::with_seed(seed);
+
+        unsafe { ffi::SpookyHasherSetState(h.0.as_ptr(), &state) }
+
+        Ok(h)
+    }
+}
+
 impl_build_hasher!(Hasher128, Hash128);
+impl_serde!(Hasher128);
 
 /// `SpookyHash` 32-bit hash functions for a byte array.
 #[inline(always)]
@@ -330,3 +407,27 @@ pub fn hash128<T: AsRef<[u8]>>(v: T) -> u128 {
 pub fn hash128_with_seed<T: AsRef<[u8]>>(v: T, seed: u128) -> u128 {
     Hash128::hash_with_seed(v, seed)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_hasher128() {
+        let data = (0..500).map(|n| n as u8).collect::<Vec<_>>();
+
+        // `Final` takes the short message path below 192 bytes
+        for &len in &[0, 5, 191, 192, 500] {
+            let mut h = Hasher128::with_seed((123, 456));
+
+            h.write(&data[..len]);
+
+            assert_eq!(
+                h.finish_ext(),
+                Hash128::hash_with_seed(&data[..len], 123 << 64 | 456),
+                "{} bytes",
+                len
+            );
+        }
+    }
+}
@@ -0,0 +1,427 @@
+//! Exports and imports the states of the streaming hashers.
+//!
+//! A hasher can be exported midway with `to_bytes`, e.g. to persist a partial upload between requests,
+//! and resumed later with `from_bytes`, in another process or on another machine.
+//!
+//! The format is versioned and endian-stable, the magic number, the format version and the name of
+//! the hasher, followed by its state with the integers in little endian. The state only depends on
+//! the hash function, the `pure-highway`, `pure-t1ha` and `pure-xxh3` ports resume the states of
+//! the C and C++ implementations and vice versa.
+//!
+//! The hasher is named by its public path, e.g. `fasthash::xxh3::Hasher64`, which stays the same
+//! if the module moves.
+//!
+//! The states are not encrypted, the seed and a custom secret of `xxh3` are exported as is, they must
+//! be kept as confidential as the key of the hasher.
+//!
+//! With the `serde` feature, the hashers implement `Serialize` and `Deserialize` with these bytes.
+//!
+//! `sea::Hasher64` is not supported, seahash doesn't expose the state of its `SeaHasher`.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(any(feature = "sys", feature = "pure-xxh3"))]
+//! # {
+//! use std::hash::Hasher;
+//!
+//! use fasthash::{xxh3, FastHasher, HasherState};
+//!
+//! let mut h = xxh3::Hasher64::new();
+//!
+//! h.write(b"hello ");
+//!
+//! let bytes = h.to_bytes();
+//! let mut h = xxh3::Hasher64::from_bytes(&bytes).unwrap();
+//!
+//! h.write(b"world");
+//! assert_eq!(h.finish(), xxh3::hash64("hello world"));
+//! # }
+//! ```
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use core::fmt;
+
+/// The errors of importing a state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The bytes are not an exported state of this hasher.
+    InvalidFormat,
+    /// The exported state has an unsupported format version.
+    UnsupportedVersion(u8),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidFormat => f.write_str("invalid hasher state format"),
+            Error::UnsupportedVersion(v) => {
+                write!(f, "unsupported hasher state format version {}", v)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// The magic number of the exported states.
+const MAGIC: &[u8; 4] = b"FHST";
+/// The current version of the state format.
+pub const FORMAT_VERSION: u8 = 1;
+
+/// Streaming hasher whose state can be exported and imported.
+pub trait HasherState: Sized {
+    /// Exports the state of the hasher, which continues independently of it.
+    fn to_bytes(&self) -> Vec<u8>;
+
+    /// Imports a state exported by `to_bytes` of the same hasher.
+    fn from_bytes(bytes: &[u8]) -> Result<Self, Error>;
+}
+
+/// Writes an exported state.
+#[doc(hidden)]
+pub struct Writer(Vec<u8>);
+
+impl Writer {
+    /// Writes the header of the state of the hasher `name`.
+    pub fn new(name: &str) -> Self {
+        let mut w = Writer::header(MAGIC, FORMAT_VERSION, 1 + name.len());
+
+        w.u8(name.len() as u8).bytes(name.as_bytes());
+        w
+    }
+
+    /// Writes the magic number and the format version of a serialized format,
+    /// reserves `capacity` bytes for the rest.
+    pub fn header(magic: &[u8; 4], version: u8, capacity: usize) -> Self {
+        let mut bytes = Vec::with_capacity(magic.len() + 1 + capacity);
+
+        bytes.extend_from_slice(magic);
+        bytes.push(version);
+
+        Writer(bytes)
+    }
+
+    pub fn u8(&mut self, n: u8) -> &mut Self {
+        self.0.push(n);
+        self
+    }
+
+    pub fn u32(&mut self, n: u32) -> &mut Self {
+        self.0.extend_from_slice(&n.to_le_bytes());
+        self
+    }
+
+    pub fn u64(&mut self, n: u64) -> &mut Self {
+        self.0.extend_from_slice(&n.to_le_bytes());
+        self
+    }
+
+    pub fn words(&mut self, words: &[u64]) -> &mut Self {
+        for &w in words {
+            self.u64(w);
+        }
+        self
+    }
+
+    /// Writes the bytes as is, their length is known to the reader.
+    pub fn bytes(&mut self, bytes: &[u8]) -> &mut Self {
+        self.0.extend_from_slice(bytes);
+        self
+    }
+
+    pub fn seed<S: StateSeed>(&mut self, seed: S) -> &mut Self {
+        seed.write(self);
+        self
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+/// Reads an exported state.
+#[doc(hidden)]
+pub struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    /// Reads the header of the state of the hasher `name`.
+    pub fn new(bytes: &'a [u8], name: &str) -> Result<Self, Error> {
+        let mut r = Reader::header(bytes, MAGIC, FORMAT_VERSION)?;
+        let len = r.u8()?;
+
+        if r.bytes(usize::from(len))? != name.as_bytes() {
+            return Err(Error::InvalidFormat);
+        }
+
+        Ok(r)
+    }
+
+    /// Checks the magic number and the format version of a serialized format.
+    pub fn header(bytes: &'a [u8], magic: &[u8; 4], version: u8) -> Result<Self, Error> {
+        if bytes.len() < magic.len() + 1 || &bytes[..magic.len()] != magic {
+            return Err(Error::InvalidFormat);
+        }
+        if bytes[magic.len()] != version {
+            return Err(Error::UnsupportedVersion(bytes[magic.len()]));
+        }
+
+        Ok(Reader(&bytes[magic.len() + 1..]))
+    }
+
+    pub fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub fn u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    pub fn u64(&mut self) -> Result<u64, Error> {
+        Ok(u64::from_le_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+
+    pub fn words(&mut self, words: &mut [u64]) -> Result<(), Error> {
+        for w in words {
+            *w = self.u64()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.0.len() < len {
+            return Err(Error::InvalidFormat);
+        }
+
+        let (bytes, rest) = self.0.split_at(len);
+
+        self.0 = rest;
+
+        Ok(bytes)
+    }
+
+    pub fn seed<S: StateSeed>(&mut self) -> Result<S, Error> {
+        S::read(self)
+    }
+
+    /// Returns the bytes left to read.
+    pub fn rest(&self) -> &'a [u8] {
+        self.0
+    }
+
+    /// Checks that the whole state was read.
+    pub fn finish(self) -> Result<(), Error> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidFormat)
+        }
+    }
+}
+
+/// The seeds of the exported states.
+#[doc(hidden)]
+pub trait StateSeed: Sized {
+    fn write(self, w: &mut Writer);
+
+    fn read(r: &mut Reader) -> Result<Self, Error>;
+}
+
+impl StateSeed for u32 {
+    fn write(self, w: &mut Writer) {
+        w.u32(self);
+    }
+
+    fn read(r: &mut Reader) -> Result<Self, Error> {
+        r.u32()
+    }
+}
+
+impl StateSeed for u64 {
+    fn write(self, w: &mut Writer) {
+        w.u64(self);
+    }
+
+    fn read(r: &mut Reader) -> Result<Self, Error> {
+        r.u64()
+    }
+}
+
+impl StateSeed for u128 {
+    fn write(self, w: &mut Writer) {
+        w.u64(self as u64).u64((self >> 64) as u64);
+    }
+
+    fn read(r: &mut Reader) -> Result<Self, Error> {
+        Ok(u128::from(r.u64()?) | u128::from(r.u64()?) << 64)
+    }
+}
+
+impl StateSeed for (u64, u64) {
+    fn write(self, w: &mut Writer) {
+        w.u64(self.0).u64(self.1);
+    }
+
+    fn read(r: &mut Reader) -> Result<Self, Error> {
+        Ok((r.u64()?, r.u64()?))
+    }
+}
+
+impl<S: StateSeed> StateSeed for Option<S> {
+    fn write(self, w: &mut Writer) {
+        match self {
+            Some(seed) => {
+                w.u8(1);
+                seed.write(w);
+            }
+            None => {
+                w.u8(0);
+            }
+        }
+    }
+
+    fn read(r: &mut Reader) -> Result<Self, Error> {
+        match r.u8()? {
+            0 => Ok(None),
+            1 => S::read(r).map(Some),
+            _ => Err(Error::InvalidFormat),
+        }
+    }
+}
+
+/// Deserializes a state from bytes, or a sequence of bytes for the formats without them.
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub struct StateVisitor<T>(core::marker::PhantomData<T>);
+
+#[cfg(feature = "serde")]
+impl<T> Default for StateVisitor<T> {
+    fn default() -> Self {
+        StateVisitor(core::marker::PhantomData)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: HasherState> serde::de::Visitor<'de> for StateVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an exported hasher state")
+    }
+
+    fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<T, E> {
+        T::from_bytes(v).map_err(E::custom)
+    }
+
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<T, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+
+        while let Some(b) = seq.next_element()? {
+            bytes.push(b);
+        }
+
+        T::from_bytes(&bytes).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Checks that `from_bytes` accepts the serialized `bytes`, but rejects them truncated,
+/// with a trailing byte or with the next format version.
+#[cfg(test)]
+pub(crate) fn check_format<T, E, F>(bytes: &[u8], from_bytes: F)
+where
+    E: From<Error> + PartialEq + fmt::Debug,
+    F: Fn(&[u8]) -> Result<T, E>,
+{
+    assert!(from_bytes(bytes).is_ok());
+    assert_eq!(from_bytes(&[]).err(), Some(Error::InvalidFormat.into()));
+    assert_eq!(
+        from_bytes(&bytes[..bytes.len() - 1]).err(),
+        Some(Error::InvalidFormat.into()),
+        "truncated"
+    );
+    assert_eq!(
+        from_bytes(&[bytes, &[0]].concat()).err(),
+        Some(Error::InvalidFormat.into()),
+        "trailing bytes"
+    );
+
+    let mut bytes = bytes.to_vec();
+
+    bytes[4] += 1;
+
+    assert_eq!(
+        from_bytes(&bytes).err(),
+        Some(Error::UnsupportedVersion(bytes[4]).into())
+    );
+}
+
+#[cfg(all(test, feature = "sys"))]
+mod tests {
+    use std::hash::Hasher;
+
+    use super::*;
+    use crate::{city, spooky, t1ha2, xx, FastHasher};
+
+    fn resume<T: HasherState + Hasher>(mut h: T) -> u64 {
+        h.write(b"hello ");
+
+        let mut h = T::from_bytes(&h.to_bytes()).unwrap();
+
+        h.write(b"world");
+        h.finish()
+    }
+
+    #[test]
+    fn test_resume() {
+        assert_eq!(
+            resume(xx::Hasher64::with_seed(123)),
+            xx::hash64_with_seed("hello world", 123)
+        );
+        assert_eq!(resume(city::Hasher64::new()), city::hash64("hello world"));
+        assert_eq!(
+            resume(spooky::Hasher128::with_seed((1, 2))),
+            spooky::hash128_with_seed("hello world", 1 << 64 | 2) as u64
+        );
+    }
+
+    #[test]
+    fn test_invalid_state() {
+        let mut h = xx::Hasher32::new();
+
+        h.write(b"hello");
+
+        let bytes = h.to_bytes();
+
+        assert_eq!(&bytes[..4], MAGIC);
+        // the names don't depend on the module paths
+        assert_eq!(&bytes[6..6 + bytes[5] as usize], b"fasthash::xx::Hasher32");
+        check_format(&bytes, xx::Hasher32::from_bytes);
+        assert_eq!(
+            xx::Hasher64::from_bytes(&bytes).err(),
+            Some(Error::InvalidFormat),
+            "another hasher"
+        );
+        assert_eq!(
+            t1ha2::Hasher128::from_bytes(&t1ha2::Hasher128::new().to_bytes()[..60]).err(),
+            Some(Error::InvalidFormat)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let mut h = xx::Hasher64::new();
+
+        h.write(b"hello ");
+
+        let json = serde_json::to_string(&h).unwrap();
+        let mut h: xx::Hasher64 = serde_json::from_str(&json).unwrap();
+
+        h.write(b"world");
+
+        assert_eq!(h.finish(), xx::hash64("hello world"));
+        assert!(serde_json::from_str::<xx::Hasher32>(&json).is_err());
+    }
+}
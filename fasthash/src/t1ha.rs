@@ -28,6 +28,13 @@
 //! This implementation of t1ha requires modern GNU C compatible compiler,
 //! includes Clang/LLVM; or MSVC++ 14.0 (Visual Studio 2015).
 //!
+//! By default it is backed by the vendored C implementation, the `pure-t1ha` feature
+//! switches to a pure Rust port which gives bit for bit the same results and doesn't need
+//! a C compiler. The port picks the AES-NI variants of `t1ha0` at runtime.
+//! Without `std` it can't probe the CPU and picks them from the target features instead,
+//! so a `no_std` build of the port may hash with another `t1ha0` variant than a `std` build
+//! on the same CPU. Use `t1ha1` or `t1ha2` for hashes which are stable across builds.
+//!
 //! # Example
 //!
 //! ```
@@ -48,6 +55,19 @@
 //!
 use crate::hasher::FastHash;
 
+#[cfg(feature = "pure-t1ha")]
+mod pure;
+#[cfg(all(feature = "sys", any(test, not(feature = "pure-t1ha"))))]
+mod sys;
+
+cfg_if! {
+    if #[cfg(feature = "pure-t1ha")] {
+        use self::pure as imp;
+    } else {
+        use self::sys as imp;
+    }
+}
+
 ///
 /// t1ha2 = 64 and 128-bit, SLIGHTLY MORE ATTENTION FOR QUALITY AND STRENGTH.
 ///
@@ -60,11 +80,15 @@ use crate::hasher::FastHash;
 ///      Provides streaming mode and 128-bit result.
 ///
 pub mod t1ha2 {
-    use std::hash::Hasher;
-    use std::mem;
-    use std::ptr;
+    use alloc::vec::Vec;
+    use core::hash::Hasher;
+
+    #[cfg(feature = "std")]
+    use crate::hasher::StreamHasher;
+    use crate::hasher::{FastHash, FastHasher, HasherExt};
+    use crate::state::{Error, HasherState, Reader, Writer};
 
-    use crate::hasher::{FastHash, FastHasher, HasherExt, StreamHasher};
+    use super::{imp, Progress};
 
     /// The at-once variant with 64-bit result
     ///
@@ -95,13 +119,7 @@ pub mod t1ha2 {
 
         #[inline(always)]
         fn hash_with_seed<T: AsRef<[u8]>>(bytes: T, seed: u64) -> u64 {
-            unsafe {
-                ffi::t1ha2_atonce(
-                    bytes.as_ref().as_ptr() as *const _,
-                    bytes.as_ref().len(),
-                    seed,
-                )
-            }
+            imp::t1ha2_atonce(bytes.as_ref(), seed)
         }
     }
 
@@ -134,18 +152,7 @@ pub mod t1ha2 {
 
         #[inline(always)]
         fn hash_with_seed<T: AsRef<[u8]>>(bytes: T, seed: u64) -> u128 {
-            let mut hi = 0;
-
-            let lo = unsafe {
-                ffi::t1ha2_atonce128(
-                    &mut hi,
-                    bytes.as_ref().as_ptr() as *const _,
-                    bytes.as_ref().len(),
-                    seed,
-                )
-            };
-
-            u128::from(hi).wrapping_shl(64) + u128::from(lo)
+            imp::t1ha2_atonce128(bytes.as_ref(), seed)
         }
     }
 
@@ -169,10 +176,10 @@ pub mod t1ha2 {
     /// assert_eq!(h.finish(), 11611394885310216856);
     ///
     /// h.write(b"world");
-    /// assert_eq!(h.finish_ext(), 189154943715293976030023582550666960629);
+    /// assert_eq!(h.finish_ext(), 62530754400354913447849696898716656679);
     /// ```
     #[derive(Clone)]
-    pub struct Hasher128(ptr::NonNull<ffi::t1ha_context_t>);
+    pub struct Hasher128(imp::State);
 
     impl Default for Hasher128 {
         fn default() -> Self {
@@ -180,32 +187,21 @@ pub mod t1ha2 {
         }
     }
 
-    impl Drop for Hasher128 {
-        fn drop(&mut self) {
-            unsafe { mem::drop(Box::from_raw(self.0.as_ptr())) }
-        }
-    }
-
     impl Hasher for Hasher128 {
         #[inline(always)]
         fn write(&mut self, bytes: &[u8]) {
-            unsafe {
-                ffi::t1ha2_update(self.0.as_ptr(), bytes.as_ptr() as *const _, bytes.len());
-            }
+            self.0.update(bytes)
         }
 
         #[inline(always)]
         fn finish(&self) -> u64 {
-            unsafe { ffi::t1ha2_final(self.0.as_ptr(), ptr::null_mut()) }
+            self.0.finish64()
         }
     }
 
     impl HasherExt for Hasher128 {
         fn finish_ext(&self) -> u128 {
-            let mut hi = 0;
-            let lo = unsafe { ffi::t1ha2_final(self.0.as_ptr(), &mut hi) };
-
-            (u128::from(hi) << 64) + u128::from(lo)
+            self.0.finish128()
         }
     }
 
@@ -215,21 +211,57 @@ pub mod t1ha2 {
 
         #[inline(always)]
         fn with_seed(seed: (u64, u64)) -> Self {
-            unsafe {
-                let ctx: ptr::NonNull<ffi::t1ha_context_t> =
-                    ptr::NonNull::new_unchecked(Box::into_raw(Box::new(mem::zeroed())));
-
-                ffi::t1ha2_init(ctx.as_ptr(), seed.0, seed.1);
-
-                Hasher128(ctx)
-            }
+            Hasher128(imp::State::new(seed.0, seed.1))
         }
     }
 
+    #[cfg(feature = "std")]
     impl StreamHasher for Hasher128 {}
 
+    impl HasherState for Hasher128 {
+        fn to_bytes(&self) -> Vec<u8> {
+            let progress = self.0.progress();
+            let mut w = Writer::new("fasthash::t1ha::t1ha2::Hasher128");
+
+            w.words(&progress.state)
+                .bytes(&progress.buffer)
+                .u64(progress.partial as u64)
+                .u64(progress.total);
+            w.into_bytes()
+        }
+
+        fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+            let mut r = Reader::new(bytes, "fasthash::t1ha::t1ha2::Hasher128")?;
+            let mut progress = Progress {
+                state: [0; 4],
+                buffer: [0; 32],
+                partial: 0,
+                total: 0,
+            };
+
+            r.words(&mut progress.state)?;
+            progress.buffer.copy_from_slice(r.bytes(32)?);
+
+            // the partial block is always shorter than the buffer
+            progress.partial = match r.u64()? {
+                n if n < 32 => n as usize,
+                _ => return Err(Error::InvalidFormat),
+            };
+            progress.total = r.u64()?;
+
+            r.finish()?;
+
+            let mut h = Self::new();
+
+            h.0.set_progress(&progress);
+
+            Ok(h)
+        }
+    }
+
     impl_build_hasher!(Hasher128, Hash64AtOnce);
     impl_build_hasher!(Hasher128, Hash128AtOnce);
+    impl_serde!(Hasher128);
 }
 
 ///
@@ -249,6 +281,8 @@ pub mod t1ha2 {
 pub mod t1ha1 {
     use crate::hasher::FastHash;
 
+    use super::imp;
+
     cfg_if! {
         if #[cfg(target_endian = "little")] {
             pub use self::{Hasher64Le as Hasher64, Hash64Le as Hash64};
@@ -280,13 +314,7 @@ pub mod t1ha1 {
 
         #[inline(always)]
         fn hash_with_seed<T: AsRef<[u8]>>(bytes: T, seed: u64) -> u64 {
-            unsafe {
-                ffi::t1ha1_le(
-                    bytes.as_ref().as_ptr() as *const _,
-                    bytes.as_ref().len(),
-                    seed,
-                )
-            }
+            imp::t1ha1_le(bytes.as_ref(), seed)
         }
     }
 
@@ -306,7 +334,7 @@ pub mod t1ha1 {
         /// h.write(b"world");
         /// assert_eq!(h.finish(), 16997942636322422782);
         /// ```
-        Hasher64Le(Hash64Le) -> u64
+        Hasher64Le(Hash64Le) -> u64, "fasthash::t1ha::t1ha1::Hasher64Le"
     }
 
     /// `T1Hash` 64-bit hash functions for 64-bit big-endian platforms.
@@ -332,13 +360,7 @@ pub mod t1ha1 {
 
         #[inline(always)]
         fn hash_with_seed<T: AsRef<[u8]>>(bytes: T, seed: u64) -> u64 {
-            unsafe {
-                ffi::t1ha1_be(
-                    bytes.as_ref().as_ptr() as *const _,
-                    bytes.as_ref().len(),
-                    seed,
-                )
-            }
+            imp::t1ha1_be(bytes.as_ref(), seed)
         }
     }
 
@@ -358,7 +380,7 @@ pub mod t1ha1 {
         /// h.write(b"world");
         /// assert_eq!(h.finish(), 15825971635414726702);
         /// ```
-        Hasher64Be(Hash64Be) -> u64
+        Hasher64Be(Hash64Be) -> u64, "fasthash::t1ha::t1ha1::Hasher64Be"
     }
 }
 
@@ -371,12 +393,13 @@ pub mod t1ha1 {
 ///      for the current processor. For instance, on IA32 (x86) actual function
 ///      will be selected in runtime, depending on current CPU capabilities
 ///
+/// Without `std`, the `pure-t1ha` port selects the function from the target features
+/// it is built with, e.g. `-C target-feature=+aes,+avx2`, rather than the current CPU.
+///
 pub mod t1ha0 {
     use crate::hasher::FastHash;
 
-    lazy_static! {
-        static ref T1HA0: ffi::t1ha0_function_t = unsafe { ffi::t1ha0_resolve() };
-    }
+    use super::imp;
 
     /// `T1Hash` 64-bit hash functions.
     ///
@@ -401,13 +424,7 @@ pub mod t1ha0 {
 
         #[inline(always)]
         fn hash_with_seed<T: AsRef<[u8]>>(bytes: T, seed: u64) -> u64 {
-            unsafe {
-                T1HA0.unwrap_or(ffi::t1ha0_64)(
-                    bytes.as_ref().as_ptr() as *const _,
-                    bytes.as_ref().len(),
-                    seed,
-                )
-            }
+            imp::t1ha0(bytes.as_ref(), seed)
         }
     }
 
@@ -427,7 +444,7 @@ pub mod t1ha0 {
         /// h.write(b"world");
         /// assert_eq!(h.finish(), 15302361616348747620);
         /// ```
-        Hasher64(Hash64) -> u64
+        Hasher64(Hash64) -> u64, "fasthash::t1ha::t1ha0::Hasher64"
     }
 }
 
@@ -443,3 +460,190 @@ pub fn hash64<T: AsRef<[u8]>>(v: T) -> u64 {
 pub fn hash64_with_seed<T: AsRef<[u8]>>(v: T, seed: u64) -> u64 {
     t1ha2::Hash64AtOnce::hash_with_seed(v, seed)
 }
+
+/// The progress of a streaming `t1ha2` state, the same for both implementations.
+struct Progress {
+    state: [u64; 4],
+    buffer: [u8; 32],
+    partial: usize,
+    total: u64,
+}
+
+/// The pure Rust port against the C implementation.
+#[cfg(all(test, feature = "sys", feature = "pure-t1ha"))]
+mod tests {
+    use super::{pure, sys};
+
+    const SEEDS: [u64; 3] = [0, 123, 0x9E37_79B1_85EB_CA87];
+
+    fn sample(len: usize) -> Vec<u8> {
+        let mut x = 0x9E37_79B1_u64;
+
+        (0..len)
+            .map(|_| {
+                x = x.wrapping_mul(0x9E37_79B1_85EB_CA87);
+                (x >> 56) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_t1ha2() {
+        let data = sample(1024);
+
+        for len in 0..=data.len() {
+            let v = &data[..len];
+
+            for &seed in &SEEDS {
+                assert_eq!(
+                    pure::t1ha2_atonce(v, seed),
+                    sys::t1ha2_atonce(v, seed),
+                    "t1ha2_atonce, len={}",
+                    len
+                );
+                assert_eq!(
+                    pure::t1ha2_atonce128(v, seed),
+                    sys::t1ha2_atonce128(v, seed),
+                    "t1ha2_atonce128, len={}",
+                    len
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_t1ha1() {
+        let data = sample(1024);
+
+        for len in 0..=data.len() {
+            let v = &data[..len];
+
+            for &seed in &SEEDS {
+                assert_eq!(
+                    pure::t1ha1_le(v, seed),
+                    sys::t1ha1_le(v, seed),
+                    "t1ha1_le, len={}",
+                    len
+                );
+                assert_eq!(
+                    pure::t1ha1_be(v, seed),
+                    sys::t1ha1_be(v, seed),
+                    "t1ha1_be, len={}",
+                    len
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_t1ha0() {
+        let data = sample(1024);
+
+        for len in 0..=data.len() {
+            let v = &data[..len];
+
+            for &seed in &SEEDS {
+                assert_eq!(pure::t1ha0(v, seed), sys::t1ha0(v, seed), "len={}", len);
+            }
+        }
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[test]
+    fn test_t1ha0_ia32aes() {
+        if !pure::has_aes() {
+            return;
+        }
+
+        let data = sample(1024);
+
+        for len in 0..=data.len() {
+            let v = &data[..len];
+
+            for &seed in &SEEDS {
+                #[cfg(feature = "aes")]
+                assert_eq!(
+                    unsafe { pure::t1ha0_ia32aes_noavx(v, seed) },
+                    sys::t1ha0_ia32aes_noavx(v, seed),
+                    "t1ha0_ia32aes_noavx, len={}",
+                    len
+                );
+                #[cfg(feature = "avx")]
+                {
+                    if pure::has_avx() {
+                        assert_eq!(
+                            unsafe { pure::t1ha0_ia32aes_avx(v, seed) },
+                            sys::t1ha0_ia32aes_avx(v, seed),
+                            "t1ha0_ia32aes_avx, len={}",
+                            len
+                        );
+                    }
+                }
+                #[cfg(feature = "avx2")]
+                {
+                    if pure::has_avx2() {
+                        assert_eq!(
+                            unsafe { pure::t1ha0_ia32aes_avx2(v, seed) },
+                            sys::t1ha0_ia32aes_avx2(v, seed),
+                            "t1ha0_ia32aes_avx2, len={}",
+                            len
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    // the 32-bit variants aren't exported by the C library, compare with its digest instead
+    #[test]
+    fn test_t1ha0_32() {
+        let data = sample(256);
+        let expected = [
+            (0x3E17_F99E_D2C8_8802, 0x63BE_3188_E365_FF0C),
+            (0x6D57_AD75_0CF4_F7FF, 0x79E0_5110_2599_06B0),
+            (0xAC07_5A21_B55B_59C3, 0xAA04_CA05_AEDC_EDF5),
+        ];
+
+        for (&seed, &(le, be)) in SEEDS.iter().zip(expected.iter()) {
+            let digest = |f: fn(&[u8], u64) -> u64| {
+                (0..=data.len()).fold(0u64, |h, len| h.rotate_left(5) ^ f(&data[..len], seed))
+            };
+
+            assert_eq!(digest(pure::t1ha0_32le), le, "t1ha0_32le, seed={}", seed);
+            assert_eq!(digest(pure::t1ha0_32be), be, "t1ha0_32be, seed={}", seed);
+        }
+    }
+
+    #[test]
+    fn test_streaming() {
+        let data = sample(1024);
+
+        for &step in &[1, 7, 31, 32, 33, 100] {
+            let mut h = pure::State::new(SEEDS[2], SEEDS[1]);
+            let mut expected = sys::State::new(SEEDS[2], SEEDS[1]);
+
+            for chunk in data.chunks(step) {
+                h.update(chunk);
+                expected.update(chunk);
+
+                assert_eq!(h.finish64(), expected.finish64(), "step={}", step);
+                assert_eq!(h.finish128(), expected.finish128(), "step={}", step);
+            }
+
+            // the progress of one implementation resumes in the other
+            let mut resumed = pure::State::new(SEEDS[0], SEEDS[0]);
+            let mut resumed_sys = sys::State::new(SEEDS[0], SEEDS[0]);
+
+            resumed.set_progress(&expected.progress());
+            resumed_sys.set_progress(&h.progress());
+
+            resumed.update(&data[..step]);
+            resumed_sys.update(&data[..step]);
+            h.update(&data[..step]);
+            expected.update(&data[..step]);
+
+            assert_eq!(resumed.finish128(), expected.finish128(), "step={}", step);
+            assert_eq!(resumed_sys.finish128(), h.finish128(), "step={}", step);
+        }
+    }
+}
@@ -0,0 +1,465 @@
+//! `t1ha` implemented in Rust, bit for bit compatible with the vendored C implementation.
+//!
+//! `t1ha0` selects its implementation like `t1ha0_resolve`, the AES-NI variants when the CPU
+//! supports them, otherwise `t1ha1` on 64-bit targets and the 32-bit `t1ha0` on the others.
+use core::convert::TryInto;
+
+use super::Progress;
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod aes;
+#[cfg(any(all(test, feature = "sys"), target_pointer_width = "32"))]
+mod t1ha0_32;
+
+const PRIME_0: u64 = 0xEC99_BF0D_8372_CAAB;
+const PRIME_1: u64 = 0x8243_4FE9_0EDC_EF39;
+const PRIME_2: u64 = 0xD4F0_6DB9_9D67_BE4B;
+const PRIME_3: u64 = 0xBD9C_ACC2_2C6E_9571;
+const PRIME_4: u64 = 0x9C06_FAF4_D023_E3AB;
+const PRIME_5: u64 = 0xC060_724A_8424_F345;
+const PRIME_6: u64 = 0xCB5A_F53A_E3AA_AC31;
+
+/// The byte order of the words read by the hash functions.
+trait Endian {
+    fn fetch64(v: &[u8]) -> u64;
+
+    /// Reads the last 1 to 8 bytes as a 64-bit word.
+    fn tail64(v: &[u8]) -> u64;
+}
+
+struct Le;
+
+impl Endian for Le {
+    #[inline(always)]
+    fn fetch64(v: &[u8]) -> u64 {
+        u64::from_le_bytes(v[..8].try_into().unwrap())
+    }
+
+    #[inline(always)]
+    fn tail64(v: &[u8]) -> u64 {
+        v.iter().rev().fold(0, |r, &b| r << 8 | u64::from(b))
+    }
+}
+
+struct Be;
+
+impl Endian for Be {
+    #[inline(always)]
+    fn fetch64(v: &[u8]) -> u64 {
+        u64::from_be_bytes(v[..8].try_into().unwrap())
+    }
+
+    #[inline(always)]
+    fn tail64(v: &[u8]) -> u64 {
+        v.iter().fold(0, |r, &b| r << 8 | u64::from(b))
+    }
+}
+
+/// Returns the low and high halves of the full 128-bit product.
+#[inline(always)]
+fn mul_64x64_128(a: u64, b: u64) -> (u64, u64) {
+    let r = u128::from(a) * u128::from(b);
+
+    (r as u64, (r >> 64) as u64)
+}
+
+/// xor high and low parts of full 128-bit product
+#[inline(always)]
+fn mux64(v: u64, prime: u64) -> u64 {
+    let (l, h) = mul_64x64_128(v, prime);
+
+    l ^ h
+}
+
+#[inline(always)]
+fn mixup64(a: &mut u64, b: &mut u64, v: u64, prime: u64) {
+    let (l, h) = mul_64x64_128(b.wrapping_add(v), prime);
+
+    *a ^= l;
+    *b = b.wrapping_add(h);
+}
+
+#[inline(always)]
+fn final64(a: u64, b: u64) -> u64 {
+    let x = a.wrapping_add(b.rotate_right(41)).wrapping_mul(PRIME_0);
+    let y = a.rotate_right(23).wrapping_add(b).wrapping_mul(PRIME_6);
+
+    mux64(x ^ y, PRIME_5)
+}
+
+/// Hashes the last 0 to 32 bytes into `a` and `b`, and returns the 64-bit result.
+#[inline(always)]
+fn tail_ab(mut a: u64, mut b: u64, mut v: &[u8]) -> u64 {
+    let len = v.len();
+
+    if len > 24 {
+        mixup64(&mut a, &mut b, Le::fetch64(v), PRIME_4);
+        v = &v[8..];
+    }
+    if len > 16 {
+        mixup64(&mut b, &mut a, Le::fetch64(v), PRIME_3);
+        v = &v[8..];
+    }
+    if len > 8 {
+        mixup64(&mut a, &mut b, Le::fetch64(v), PRIME_2);
+        v = &v[8..];
+    }
+    if len > 0 {
+        mixup64(&mut b, &mut a, Le::tail64(v), PRIME_1);
+    }
+
+    final64(a, b)
+}
+
+/// The 256-bit state of `t1ha2`.
+#[derive(Clone, Copy)]
+struct State256 {
+    a: u64,
+    b: u64,
+    c: u64,
+    d: u64,
+}
+
+impl State256 {
+    #[inline(always)]
+    fn new(x: u64, y: u64) -> Self {
+        State256 {
+            a: x,
+            b: y,
+            c: y.rotate_right(23).wrapping_add(!x),
+            d: (!y).wrapping_add(x.rotate_right(19)),
+        }
+    }
+
+    /// Hashes a 32-byte block.
+    #[inline(always)]
+    fn update(&mut self, v: &[u8]) {
+        let w0 = Le::fetch64(v);
+        let w1 = Le::fetch64(&v[8..]);
+        let w2 = Le::fetch64(&v[16..]);
+        let w3 = Le::fetch64(&v[24..]);
+
+        let d02 = w0.wrapping_add(w2.wrapping_add(self.d).rotate_right(56));
+        let c13 = w1.wrapping_add(w3.wrapping_add(self.c).rotate_right(19));
+        self.d ^= self.b.wrapping_add(w1.rotate_right(38));
+        self.c ^= self.a.wrapping_add(w0.rotate_right(57));
+        self.b ^= PRIME_6.wrapping_mul(c13.wrapping_add(w2));
+        self.a ^= PRIME_5.wrapping_mul(d02.wrapping_add(w3));
+    }
+
+    /// Hashes the 32-byte blocks of `data`, and returns the remainder.
+    #[inline(always)]
+    fn update_blocks<'a>(&mut self, data: &'a [u8]) -> &'a [u8] {
+        let mut blocks = data.chunks_exact(32);
+
+        for block in &mut blocks {
+            self.update(block);
+        }
+
+        blocks.remainder()
+    }
+
+    #[inline(always)]
+    fn squash(&mut self) {
+        self.a ^= PRIME_6.wrapping_mul(self.c.wrapping_add(self.d.rotate_right(23)));
+        self.b ^= PRIME_5.wrapping_mul(self.c.rotate_right(19).wrapping_add(self.d));
+    }
+
+    /// Hashes the last 0 to 32 bytes into all the lanes, and returns the 128-bit result.
+    #[inline(always)]
+    fn tail_abcd(self, mut v: &[u8]) -> u128 {
+        let State256 {
+            mut a,
+            mut b,
+            mut c,
+            mut d,
+        } = self;
+        let len = v.len();
+
+        if len > 24 {
+            mixup64(&mut a, &mut d, Le::fetch64(v), PRIME_4);
+            v = &v[8..];
+        }
+        if len > 16 {
+            mixup64(&mut b, &mut a, Le::fetch64(v), PRIME_3);
+            v = &v[8..];
+        }
+        if len > 8 {
+            mixup64(&mut c, &mut b, Le::fetch64(v), PRIME_2);
+            v = &v[8..];
+        }
+        if len > 0 {
+            mixup64(&mut d, &mut c, Le::tail64(v), PRIME_1);
+        }
+
+        // final128
+        mixup64(&mut a, &mut b, c.rotate_right(41) ^ d, PRIME_0);
+        mixup64(&mut b, &mut c, d.rotate_right(23) ^ a, PRIME_6);
+        mixup64(&mut c, &mut d, a.rotate_right(19) ^ b, PRIME_5);
+        mixup64(&mut d, &mut a, b.rotate_right(31) ^ c, PRIME_4);
+
+        u128::from(c.wrapping_add(d)) << 64 | u128::from(a ^ b)
+    }
+}
+
+pub fn t1ha2_atonce(data: &[u8], seed: u64) -> u64 {
+    let mut s = State256::new(seed, data.len() as u64);
+    let mut tail = data;
+
+    if data.len() > 32 {
+        tail = s.update_blocks(data);
+        s.squash();
+    }
+
+    tail_ab(s.a, s.b, tail)
+}
+
+pub fn t1ha2_atonce128(data: &[u8], seed: u64) -> u128 {
+    let mut s = State256::new(seed, data.len() as u64);
+    let mut tail = data;
+
+    if data.len() > 32 {
+        tail = s.update_blocks(data);
+    }
+
+    s.tail_abcd(tail)
+}
+
+/// The streaming state of `t1ha2`, the same as `t1ha_context_t` without the heap allocation.
+#[derive(Clone)]
+pub struct State {
+    state: State256,
+    buffer: [u8; 32],
+    partial: usize,
+    total: u64,
+}
+
+impl State {
+    #[inline(always)]
+    pub fn new(seed_x: u64, seed_y: u64) -> Self {
+        State {
+            state: State256::new(seed_x, seed_y),
+            buffer: [0; 32],
+            partial: 0,
+            total: 0,
+        }
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.total = self.total.wrapping_add(data.len() as u64);
+
+        if self.partial > 0 {
+            let chunk = data.len().min(32 - self.partial);
+
+            self.buffer[self.partial..self.partial + chunk].copy_from_slice(&data[..chunk]);
+            self.partial += chunk;
+
+            if self.partial < 32 {
+                return;
+            }
+
+            self.partial = 0;
+            data = &data[chunk..];
+            self.state.update(&self.buffer);
+        }
+
+        let rest = self.state.update_blocks(data);
+
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.partial = rest.len();
+    }
+
+    /// Pads a copy of the state with the total length, like `t1ha2_final`.
+    #[inline(always)]
+    fn padded(&self) -> Self {
+        let mut padded = self.clone();
+        let bits = (self.total << 3) ^ (1 << 63);
+
+        padded.update(&bits.to_le_bytes());
+        padded
+    }
+
+    #[inline(always)]
+    pub fn finish64(&self) -> u64 {
+        let mut padded = self.padded();
+
+        padded.state.squash();
+
+        tail_ab(
+            padded.state.a,
+            padded.state.b,
+            &padded.buffer[..padded.partial],
+        )
+    }
+
+    #[inline(always)]
+    pub fn finish128(&self) -> u128 {
+        let padded = self.padded();
+
+        padded.state.tail_abcd(&padded.buffer[..padded.partial])
+    }
+
+    pub fn progress(&self) -> Progress {
+        Progress {
+            state: [self.state.a, self.state.b, self.state.c, self.state.d],
+            buffer: self.buffer,
+            partial: self.partial,
+            total: self.total,
+        }
+    }
+
+    pub fn set_progress(&mut self, progress: &Progress) {
+        let [a, b, c, d] = progress.state;
+
+        self.state = State256 { a, b, c, d };
+        self.buffer = progress.buffer;
+        self.partial = progress.partial;
+        self.total = progress.total;
+    }
+}
+
+/// xor-mul-xor mixer
+#[inline(always)]
+fn mix64(v: u64, p: u64) -> u64 {
+    let v = v.wrapping_mul(p);
+
+    v ^ v.rotate_right(41)
+}
+
+#[inline(always)]
+fn final_weak_avalanche(a: u64, b: u64) -> u64 {
+    mux64(a.wrapping_add(b).rotate_right(17), PRIME_4).wrapping_add(mix64(a ^ b, PRIME_0))
+}
+
+#[inline(always)]
+fn t1ha1<E: Endian>(data: &[u8], seed: u64) -> u64 {
+    let len = data.len() as u64;
+    let mut a = seed;
+    let mut b = len;
+    let mut v = data;
+
+    if data.len() > 32 {
+        let mut c = len.rotate_right(17).wrapping_add(seed);
+        let mut d = len ^ seed.rotate_right(17);
+        let mut blocks = data.chunks_exact(32);
+
+        for block in &mut blocks {
+            let w0 = E::fetch64(block);
+            let w1 = E::fetch64(&block[8..]);
+            let w2 = E::fetch64(&block[16..]);
+            let w3 = E::fetch64(&block[24..]);
+
+            let d02 = w0 ^ w2.wrapping_add(d).rotate_right(17);
+            let c13 = w1 ^ w3.wrapping_add(c).rotate_right(17);
+            d = d.wrapping_sub(b ^ w1.rotate_right(31));
+            c = c.wrapping_add(a ^ w0.rotate_right(41));
+            b ^= PRIME_0.wrapping_mul(c13.wrapping_add(w2));
+            a ^= PRIME_1.wrapping_mul(d02.wrapping_add(w3));
+        }
+
+        a ^= PRIME_6.wrapping_mul(c.rotate_right(17).wrapping_add(d));
+        b ^= PRIME_5.wrapping_mul(c.wrapping_add(d.rotate_right(17)));
+        v = blocks.remainder();
+    }
+
+    let len = v.len();
+
+    if len > 24 {
+        b = b.wrapping_add(mux64(E::fetch64(v), PRIME_4));
+        v = &v[8..];
+    }
+    if len > 16 {
+        a = a.wrapping_add(mux64(E::fetch64(v), PRIME_3));
+        v = &v[8..];
+    }
+    if len > 8 {
+        b = b.wrapping_add(mux64(E::fetch64(v), PRIME_2));
+        v = &v[8..];
+    }
+    if len > 0 {
+        a = a.wrapping_add(mux64(E::tail64(v), PRIME_1));
+    }
+
+    final_weak_avalanche(a, b)
+}
+
+pub fn t1ha1_le(data: &[u8], seed: u64) -> u64 {
+    t1ha1::<Le>(data, seed)
+}
+
+pub fn t1ha1_be(data: &[u8], seed: u64) -> u64 {
+    t1ha1::<Be>(data, seed)
+}
+
+cfg_if! {
+    if #[cfg(all(target_pointer_width = "64", target_endian = "little"))] {
+        use self::t1ha1_le as t1ha0_fallback;
+    } else if #[cfg(target_pointer_width = "64")] {
+        use self::t1ha1_be as t1ha0_fallback;
+    } else if #[cfg(target_endian = "little")] {
+        use self::t1ha0_32::t1ha0_32le as t1ha0_fallback;
+    } else {
+        use self::t1ha0_32::t1ha0_32be as t1ha0_fallback;
+    }
+}
+
+cfg_if! {
+    if #[cfg(all(feature = "std", any(target_arch = "x86", target_arch = "x86_64")))] {
+        #[inline(always)]
+        pub fn has_aes() -> bool {
+            is_x86_feature_detected!("aes")
+        }
+
+        #[inline(always)]
+        pub fn has_avx() -> bool {
+            is_x86_feature_detected!("avx")
+        }
+
+        #[inline(always)]
+        pub fn has_avx2() -> bool {
+            is_x86_feature_detected!("avx2")
+        }
+    } else if #[cfg(any(target_arch = "x86", target_arch = "x86_64"))] {
+        // the CPU can't be probed without `std`, trust the build configuration,
+        // so `t1ha0` may select another variant than a `std` build on the same CPU
+        #[inline(always)]
+        pub fn has_aes() -> bool {
+            cfg!(target_feature = "aes")
+        }
+
+        #[inline(always)]
+        pub fn has_avx() -> bool {
+            cfg!(target_feature = "avx")
+        }
+
+        #[inline(always)]
+        pub fn has_avx2() -> bool {
+            cfg!(target_feature = "avx2")
+        }
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub use self::aes::{t1ha0_ia32aes_avx, t1ha0_ia32aes_avx2, t1ha0_ia32aes_noavx};
+#[cfg(all(test, feature = "sys"))]
+pub use self::t1ha0_32::{t1ha0_32be, t1ha0_32le};
+
+#[inline(always)]
+pub fn t1ha0(data: &[u8], seed: u64) -> u64 {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if has_aes() {
+            return unsafe {
+                if has_avx2() {
+                    t1ha0_ia32aes_avx2(data, seed)
+                } else if has_avx() {
+                    t1ha0_ia32aes_avx(data, seed)
+                } else {
+                    t1ha0_ia32aes_noavx(data, seed)
+                }
+            };
+        }
+    }
+
+    t1ha0_fallback(data, seed)
+}
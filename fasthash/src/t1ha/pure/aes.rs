@@ -0,0 +1,152 @@
+//! The AES-NI code paths of `t1ha0`.
+//!
+//! The `noavx` and `avx` variants share the same algorithm and only differ by the encoding
+//! of the instructions, the `avx2` variant uses a different one.
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+use super::{tail_ab, PRIME_0, PRIME_1, PRIME_5, PRIME_6};
+
+#[inline(always)]
+unsafe fn load(v: &[u8]) -> __m128i {
+    _mm_loadu_si128(v[..16].as_ptr() as *const __m128i)
+}
+
+/// Returns the low and high lanes.
+#[inline(always)]
+unsafe fn lanes(x: __m128i) -> (u64, u64) {
+    let mut r = [0u64; 2];
+
+    _mm_storeu_si128(r.as_mut_ptr() as *mut __m128i, x);
+
+    (r[0], r[1])
+}
+
+#[inline(always)]
+unsafe fn t1ha0_ia32aes_a(data: &[u8], seed: u64) -> u64 {
+    let len = data.len();
+    let mut a = seed;
+    let mut b = len as u64;
+    let mut v = data;
+
+    if len > 32 {
+        let mut x = _mm_set_epi64x(a as i64, b as i64);
+        let mut y = _mm_aesenc_si128(x, _mm_set_epi64x(PRIME_5 as i64, PRIME_6 as i64));
+
+        while v.len() >= 128 {
+            let v0y = _mm_aesenc_si128(load(v), y);
+            let v2x6 = _mm_aesenc_si128(load(&v[32..]), _mm_xor_si128(x, load(&v[96..])));
+            let v45_67 = _mm_xor_si128(
+                _mm_aesenc_si128(load(&v[64..]), load(&v[80..])),
+                _mm_add_epi64(load(&v[96..]), load(&v[112..])),
+            );
+
+            let v0y7_1 = _mm_aesdec_si128(_mm_sub_epi64(load(&v[112..]), v0y), load(&v[16..]));
+            let v2x6_3 = _mm_aesenc_si128(v2x6, load(&v[48..]));
+
+            x = _mm_aesenc_si128(v45_67, _mm_add_epi64(x, y));
+            y = _mm_aesenc_si128(v2x6_3, _mm_xor_si128(v0y7_1, load(&v[80..])));
+            v = &v[128..];
+        }
+
+        if len & 64 != 0 {
+            for _ in 0..2 {
+                let v0y = _mm_add_epi64(y, load(v));
+                let v1x = _mm_sub_epi64(x, load(&v[16..]));
+                x = _mm_aesdec_si128(x, v0y);
+                y = _mm_aesdec_si128(y, v1x);
+                v = &v[32..];
+            }
+        }
+
+        if len & 32 != 0 {
+            let v0y = _mm_add_epi64(y, load(v));
+            let v1x = _mm_sub_epi64(x, load(&v[16..]));
+            x = _mm_aesdec_si128(x, v0y);
+            y = _mm_aesdec_si128(y, v1x);
+            v = &v[32..];
+        }
+
+        if len & 16 != 0 {
+            y = _mm_add_epi64(x, y);
+            x = _mm_aesdec_si128(x, load(v));
+            v = &v[16..];
+        }
+
+        x = _mm_add_epi64(_mm_aesdec_si128(x, _mm_aesenc_si128(y, x)), y);
+        let (lo, hi) = lanes(x);
+        a = lo;
+        b = hi;
+    }
+
+    tail_ab(a, b, v)
+}
+
+#[inline(always)]
+unsafe fn t1ha0_ia32aes_b(data: &[u8], seed: u64) -> u64 {
+    let len = data.len();
+    let mut a = seed;
+    let mut b = len as u64;
+    let mut v = data;
+
+    if len > 32 {
+        let mut x = _mm_set_epi64x(a as i64, b as i64);
+        let mut y = _mm_aesenc_si128(x, _mm_set_epi64x(PRIME_0 as i64, PRIME_1 as i64));
+        let (blocks, tail) = data.split_at(len & !15);
+        v = blocks;
+
+        if len & 16 != 0 {
+            x = _mm_add_epi64(x, load(v));
+            y = _mm_aesenc_si128(x, y);
+            v = &v[16..];
+        }
+
+        let mut salt = y;
+
+        while v.len() > 112 {
+            let mut t = _mm_aesenc_si128(load(v), salt);
+            for i in 1..8 {
+                t = _mm_aesdec_si128(t, load(&v[i * 16..]));
+            }
+
+            salt = _mm_add_epi64(salt, _mm_set_epi64x(PRIME_5 as i64, PRIME_6 as i64));
+            t = _mm_aesenc_si128(x, t);
+            x = _mm_add_epi64(y, x);
+            y = t;
+            v = &v[128..];
+        }
+
+        while !v.is_empty() {
+            let v0y = _mm_add_epi64(y, load(v));
+            let v1x = _mm_sub_epi64(x, load(&v[16..]));
+            x = _mm_aesdec_si128(x, v0y);
+            y = _mm_aesdec_si128(y, v1x);
+            v = &v[32..];
+        }
+
+        x = _mm_add_epi64(_mm_aesdec_si128(x, _mm_aesenc_si128(y, x)), y);
+        let (lo, hi) = lanes(x);
+        a = lo;
+        b = hi;
+        v = tail;
+    }
+
+    tail_ab(a, b, v)
+}
+
+#[target_feature(enable = "aes")]
+pub unsafe fn t1ha0_ia32aes_noavx(data: &[u8], seed: u64) -> u64 {
+    t1ha0_ia32aes_a(data, seed)
+}
+
+#[target_feature(enable = "aes,avx")]
+pub unsafe fn t1ha0_ia32aes_avx(data: &[u8], seed: u64) -> u64 {
+    t1ha0_ia32aes_a(data, seed)
+}
+
+#[target_feature(enable = "aes,avx,avx2")]
+pub unsafe fn t1ha0_ia32aes_avx2(data: &[u8], seed: u64) -> u64 {
+    t1ha0_ia32aes_b(data, seed)
+}
@@ -0,0 +1,125 @@
+//! The 32-bit `t1ha0`, selected on the targets without 64-bit pointers.
+use core::convert::TryInto;
+
+use super::{Be, Le, PRIME_0, PRIME_4, PRIME_6};
+
+/// The byte order of the 32-bit words.
+trait Endian32 {
+    fn fetch32(v: &[u8]) -> u32;
+
+    /// Reads the last 1 to 4 bytes as a 32-bit word.
+    fn tail32(v: &[u8]) -> u32;
+}
+
+impl Endian32 for Le {
+    #[inline(always)]
+    fn fetch32(v: &[u8]) -> u32 {
+        u32::from_le_bytes(v[..4].try_into().unwrap())
+    }
+
+    #[inline(always)]
+    fn tail32(v: &[u8]) -> u32 {
+        v.iter().rev().fold(0, |r, &b| r << 8 | u32::from(b))
+    }
+}
+
+impl Endian32 for Be {
+    #[inline(always)]
+    fn fetch32(v: &[u8]) -> u32 {
+        u32::from_be_bytes(v[..4].try_into().unwrap())
+    }
+
+    #[inline(always)]
+    fn tail32(v: &[u8]) -> u32 {
+        v.iter().fold(0, |r, &b| r << 8 | u32::from(b))
+    }
+}
+
+/* 32-bit 'magic' primes */
+const PRIME32_0: u32 = 0x92D7_8269;
+const PRIME32_1: u32 = 0xCA9B_4735;
+const PRIME32_2: u32 = 0xA4AB_A1C3;
+const PRIME32_3: u32 = 0xF649_9843;
+const PRIME32_4: u32 = 0x86F0_FD61;
+const PRIME32_5: u32 = 0xCA2D_A6FB;
+const PRIME32_6: u32 = 0xC4BB_3575;
+
+#[inline(always)]
+fn mixup32(a: &mut u32, b: &mut u32, v: u32, prime: u32) {
+    let l = u64::from(b.wrapping_add(v)) * u64::from(prime);
+
+    *a ^= l as u32;
+    *b = b.wrapping_add((l >> 32) as u32);
+}
+
+#[inline(always)]
+fn final32(a: u32, b: u32) -> u64 {
+    let mut l = u64::from(b ^ a.rotate_right(13)) | u64::from(a) << 32;
+    l = l.wrapping_mul(PRIME_0);
+    l ^= l >> 41;
+    l = l.wrapping_mul(PRIME_4);
+    l ^= l >> 47;
+    l.wrapping_mul(PRIME_6)
+}
+
+#[inline(always)]
+fn t1ha0_32<E: Endian32>(data: &[u8], seed: u64) -> u64 {
+    let len = data.len() as u32;
+    let mut a = len.rotate_right(17).wrapping_add(seed as u32);
+    let mut b = len ^ (seed >> 32) as u32;
+    let mut v = data;
+
+    if data.len() > 16 {
+        let mut c = !a;
+        let mut d = b.rotate_right(5);
+        let mut blocks = data.chunks_exact(16);
+
+        for block in &mut blocks {
+            let w0 = E::fetch32(block);
+            let w1 = E::fetch32(&block[4..]);
+            let w2 = E::fetch32(&block[8..]);
+            let w3 = E::fetch32(&block[12..]);
+
+            let d13 = w1.wrapping_add(w3.wrapping_add(d).rotate_right(17));
+            let c02 = w0 ^ w2.wrapping_add(c).rotate_right(11);
+            d ^= a.wrapping_add(w0).rotate_right(3);
+            c ^= b.wrapping_add(w1).rotate_right(7);
+            b = PRIME32_1.wrapping_mul(c02.wrapping_add(w3));
+            a = PRIME32_0.wrapping_mul(d13 ^ w2);
+        }
+
+        c = c.wrapping_add(a);
+        d = d.wrapping_add(b);
+        a ^= PRIME32_6.wrapping_mul(c.rotate_right(16).wrapping_add(d));
+        b ^= PRIME32_5.wrapping_mul(c.wrapping_add(d.rotate_right(16)));
+        v = blocks.remainder();
+    }
+
+    let len = v.len();
+
+    if len > 12 {
+        mixup32(&mut a, &mut b, E::fetch32(v), PRIME32_4);
+        v = &v[4..];
+    }
+    if len > 8 {
+        mixup32(&mut b, &mut a, E::fetch32(v), PRIME32_3);
+        v = &v[4..];
+    }
+    if len > 4 {
+        mixup32(&mut a, &mut b, E::fetch32(v), PRIME32_2);
+        v = &v[4..];
+    }
+    if len > 0 {
+        mixup32(&mut b, &mut a, E::tail32(v), PRIME32_1);
+    }
+
+    final32(a, b)
+}
+
+pub fn t1ha0_32le(data: &[u8], seed: u64) -> u64 {
+    t1ha0_32::<Le>(data, seed)
+}
+
+pub fn t1ha0_32be(data: &[u8], seed: u64) -> u64 {
+    t1ha0_32::<Be>(data, seed)
+}
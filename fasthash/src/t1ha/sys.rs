@@ -0,0 +1,149 @@
+//! `t1ha` backed by the vendored C implementation.
+use alloc::boxed::Box;
+use core::mem;
+use core::ptr;
+
+use crate::ffi;
+
+use super::Progress;
+
+#[inline(always)]
+pub fn t1ha2_atonce(bytes: &[u8], seed: u64) -> u64 {
+    unsafe { ffi::t1ha2_atonce(bytes.as_ptr() as *const _, bytes.len(), seed) }
+}
+
+#[inline(always)]
+pub fn t1ha2_atonce128(bytes: &[u8], seed: u64) -> u128 {
+    let mut hi = 0;
+    let lo =
+        unsafe { ffi::t1ha2_atonce128(&mut hi, bytes.as_ptr() as *const _, bytes.len(), seed) };
+
+    u128::from(hi).wrapping_shl(64) + u128::from(lo)
+}
+
+#[inline(always)]
+pub fn t1ha1_le(bytes: &[u8], seed: u64) -> u64 {
+    unsafe { ffi::t1ha1_le(bytes.as_ptr() as *const _, bytes.len(), seed) }
+}
+
+#[inline(always)]
+pub fn t1ha1_be(bytes: &[u8], seed: u64) -> u64 {
+    unsafe { ffi::t1ha1_be(bytes.as_ptr() as *const _, bytes.len(), seed) }
+}
+
+cfg_if! {
+    if #[cfg(all(feature = "runtime", target_arch = "x86_64"))] {
+        lazy_static! {
+            static ref T1HA0: ffi::t1ha0_function_t = Some(resolve());
+        }
+
+        /// Select the fastest AES variant supported by the current CPU.
+        fn resolve() -> unsafe extern "C" fn(*const core::ffi::c_void, usize, u64) -> u64 {
+            if is_x86_feature_detected!("aes") {
+                if is_x86_feature_detected!("avx2") {
+                    ffi::t1ha0_ia32aes_avx2
+                } else if is_x86_feature_detected!("avx") {
+                    ffi::t1ha0_ia32aes_avx
+                } else {
+                    ffi::t1ha0_ia32aes_noavx
+                }
+            } else {
+                ffi::t1ha1_le
+            }
+        }
+    } else {
+        lazy_static! {
+            static ref T1HA0: ffi::t1ha0_function_t = unsafe { ffi::t1ha0_resolve() };
+        }
+    }
+}
+
+#[inline(always)]
+pub fn t1ha0(bytes: &[u8], seed: u64) -> u64 {
+    unsafe { T1HA0.unwrap_or(ffi::t1ha0_64)(bytes.as_ptr() as *const _, bytes.len(), seed) }
+}
+
+#[cfg(all(
+    test,
+    feature = "pure-t1ha",
+    feature = "aes",
+    any(target_arch = "x86", target_arch = "x86_64")
+))]
+pub fn t1ha0_ia32aes_noavx(bytes: &[u8], seed: u64) -> u64 {
+    unsafe { ffi::t1ha0_ia32aes_noavx(bytes.as_ptr() as *const _, bytes.len(), seed) }
+}
+
+#[cfg(all(
+    test,
+    feature = "pure-t1ha",
+    feature = "avx",
+    any(target_arch = "x86", target_arch = "x86_64")
+))]
+pub fn t1ha0_ia32aes_avx(bytes: &[u8], seed: u64) -> u64 {
+    unsafe { ffi::t1ha0_ia32aes_avx(bytes.as_ptr() as *const _, bytes.len(), seed) }
+}
+
+#[cfg(all(
+    test,
+    feature = "pure-t1ha",
+    feature = "avx2",
+    any(target_arch = "x86", target_arch = "x86_64")
+))]
+pub fn t1ha0_ia32aes_avx2(bytes: &[u8], seed: u64) -> u64 {
+    unsafe { ffi::t1ha0_ia32aes_avx2(bytes.as_ptr() as *const _, bytes.len(), seed) }
+}
+
+/// The streaming state of `t1ha2`.
+#[derive(Clone)]
+pub struct State(Box<ffi::t1ha_context_t>);
+
+impl State {
+    #[inline(always)]
+    pub fn new(seed_x: u64, seed_y: u64) -> Self {
+        let mut ctx: Box<ffi::t1ha_context_t> = Box::new(unsafe { mem::zeroed() });
+
+        unsafe { ffi::t1ha2_init(&mut *ctx, seed_x, seed_y) }
+
+        State(ctx)
+    }
+
+    #[inline(always)]
+    pub fn update(&mut self, bytes: &[u8]) {
+        unsafe { ffi::t1ha2_update(&mut *self.0, bytes.as_ptr() as *const _, bytes.len()) }
+    }
+
+    // `t1ha2_final` pads the context with the length, so it finalizes a copy
+    #[inline(always)]
+    pub fn finish64(&self) -> u64 {
+        let mut ctx = *self.0;
+
+        unsafe { ffi::t1ha2_final(&mut ctx, ptr::null_mut()) }
+    }
+
+    #[inline(always)]
+    pub fn finish128(&self) -> u128 {
+        let mut ctx = *self.0;
+        let mut hi = 0;
+        let lo = unsafe { ffi::t1ha2_final(&mut ctx, &mut hi) };
+
+        (u128::from(hi) << 64) + u128::from(lo)
+    }
+
+    pub fn progress(&self) -> Progress {
+        unsafe {
+            Progress {
+                state: self.0.state.u64,
+                buffer: self.0.buffer.bytes,
+                partial: self.0.partial,
+                total: self.0.total,
+            }
+        }
+    }
+
+    pub fn set_progress(&mut self, progress: &Progress) {
+        self.0.state.u64 = progress.state;
+        self.0.buffer.bytes = progress.buffer;
+        self.0.partial = progress.partial;
+        self.0.total = progress.total;
+    }
+}
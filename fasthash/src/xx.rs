@@ -28,13 +28,18 @@
 //! assert_eq!(h, hash(&"hello world"));
 //! ```
 //!
-use std::hash::Hasher;
-use std::os::raw::c_void;
-use std::ptr::NonNull;
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use core::ffi::c_void;
+use core::hash::Hasher;
+use core::ptr::NonNull;
 
 use crate::ffi;
 
-use crate::hasher::{FastHash, FastHasher, StreamHasher};
+#[cfg(feature = "std")]
+use crate::hasher::StreamHasher;
+use crate::hasher::{FastHash, FastHasher};
+use crate::state::{Error, HasherState, Reader, Writer};
 
 /// xxHash 32-bit hash functions
 ///
@@ -47,9 +52,45 @@ use crate::hasher::{FastHash, FastHasher, StreamHasher};
 /// assert_eq!(Hash32::hash_with_seed(b"hello", 123), 2147069998);
 /// assert_eq!(Hash32::hash(b"helloworld"), 593682946);
 /// ```
+///
+/// The integer keys are hashed in Rust without calling into C,
+/// with the same result as hashing their little endian bytes.
+///
+/// ```
+/// use fasthash::{xx::Hash32, FastHash};
+///
+/// assert_eq!(Hash32::hash_u32(123), Hash32::hash(123_u32.to_le_bytes()));
+/// assert_eq!(
+///     Hash32::hash_u64_with_seed(123, 456),
+///     Hash32::hash_with_seed(123_u64.to_le_bytes(), 456)
+/// );
+/// ```
 #[derive(Clone)]
 pub struct Hash32;
 
+const PRIME32_2: u32 = 0x85EB_CA77;
+const PRIME32_3: u32 = 0xC2B2_AE3D;
+const PRIME32_4: u32 = 0x27D4_EB2F;
+const PRIME32_5: u32 = 0x1656_67B1;
+
+/// Mix a 4-byte lane into the `XXH32` state of a short input.
+#[inline(always)]
+fn xxh32_lane(h32: u32, lane: u32) -> u32 {
+    h32.wrapping_add(lane.wrapping_mul(PRIME32_3))
+        .rotate_left(17)
+        .wrapping_mul(PRIME32_4)
+}
+
+/// The final avalanche of `XXH32`.
+#[inline(always)]
+fn xxh32_avalanche(mut h32: u32) -> u32 {
+    h32 ^= h32 >> 15;
+    h32 = h32.wrapping_mul(PRIME32_2);
+    h32 ^= h32 >> 13;
+    h32 = h32.wrapping_mul(PRIME32_3);
+    h32 ^ (h32 >> 16)
+}
+
 impl FastHash for Hash32 {
     type Hash = u32;
     type Seed = u32;
@@ -64,6 +105,30 @@ impl FastHash for Hash32 {
             )
         }
     }
+
+    #[inline(always)]
+    fn hash_u32(n: u32) -> u32 {
+        Self::hash_u32_with_seed(n, 0)
+    }
+
+    #[inline(always)]
+    fn hash_u32_with_seed(n: u32, seed: u32) -> u32 {
+        let h32 = seed.wrapping_add(PRIME32_5).wrapping_add(4);
+
+        xxh32_avalanche(xxh32_lane(h32, n))
+    }
+
+    #[inline(always)]
+    fn hash_u64(n: u64) -> u32 {
+        Self::hash_u64_with_seed(n, 0)
+    }
+
+    #[inline(always)]
+    fn hash_u64_with_seed(n: u64, seed: u32) -> u32 {
+        let h32 = seed.wrapping_add(PRIME32_5).wrapping_add(8);
+
+        xxh32_avalanche(xxh32_lane(xxh32_lane(h32, n as u32), (n >> 32) as u32))
+    }
 }
 
 /// xxHash 64-bit hash functions
@@ -77,9 +142,38 @@ impl FastHash for Hash32 {
 /// assert_eq!(Hash64::hash_with_seed(b"hello", 123), 2900467397628653179);
 /// assert_eq!(Hash64::hash(b"helloworld"), 9228181307863624271);
 /// ```
+///
+/// The integer keys are hashed in Rust without calling into C,
+/// with the same result as hashing their little endian bytes.
+///
+/// ```
+/// use fasthash::{xx::Hash64, FastHash};
+///
+/// assert_eq!(Hash64::hash_u64(123), Hash64::hash(123_u64.to_le_bytes()));
+/// assert_eq!(
+///     Hash64::hash_u32_with_seed(123, 456),
+///     Hash64::hash_with_seed(123_u32.to_le_bytes(), 456)
+/// );
+/// ```
 #[derive(Clone)]
 pub struct Hash64;
 
+const PRIME64_1: u64 = 0x9E37_79B1_85EB_CA87;
+const PRIME64_2: u64 = 0xC2B2_AE3D_27D4_EB4F;
+const PRIME64_3: u64 = 0x1656_67B1_9E37_79F9;
+const PRIME64_4: u64 = 0x85EB_CA77_C2B2_AE63;
+const PRIME64_5: u64 = 0x27D4_EB2F_1656_67C5;
+
+/// The final avalanche of `XXH64`.
+#[inline(always)]
+fn xxh64_avalanche(mut h64: u64) -> u64 {
+    h64 ^= h64 >> 33;
+    h64 = h64.wrapping_mul(PRIME64_2);
+    h64 ^= h64 >> 29;
+    h64 = h64.wrapping_mul(PRIME64_3);
+    h64 ^ (h64 >> 32)
+}
+
 impl FastHash for Hash64 {
     type Hash = u64;
     type Seed = u64;
@@ -94,6 +188,43 @@ impl FastHash for Hash64 {
             )
         }
     }
+
+    #[inline(always)]
+    fn hash_u32(n: u32) -> u64 {
+        Self::hash_u32_with_seed(n, 0)
+    }
+
+    #[inline(always)]
+    fn hash_u32_with_seed(n: u32, seed: u64) -> u64 {
+        let h64 =
+            seed.wrapping_add(PRIME64_5).wrapping_add(4) ^ u64::from(n).wrapping_mul(PRIME64_1);
+
+        xxh64_avalanche(
+            h64.rotate_left(23)
+                .wrapping_mul(PRIME64_2)
+                .wrapping_add(PRIME64_3),
+        )
+    }
+
+    #[inline(always)]
+    fn hash_u64(n: u64) -> u64 {
+        Self::hash_u64_with_seed(n, 0)
+    }
+
+    #[inline(always)]
+    fn hash_u64_with_seed(n: u64, seed: u64) -> u64 {
+        let k1 = n
+            .wrapping_mul(PRIME64_2)
+            .rotate_left(31)
+            .wrapping_mul(PRIME64_1);
+        let h64 = seed.wrapping_add(PRIME64_5).wrapping_add(8) ^ k1;
+
+        xxh64_avalanche(
+            h64.rotate_left(27)
+                .wrapping_mul(PRIME64_1)
+                .wrapping_add(PRIME64_4),
+        )
+    }
 }
 
 /// xxHash 32-bit hash functions for a byte array.
@@ -170,6 +301,12 @@ impl Clone for Hasher32 {
             Hasher32(NonNull::new_unchecked(state))
         }
     }
+
+    fn clone_from(&mut self, source: &Self) {
+        unsafe {
+            ffi::XXH32_copyState(self.0.as_ptr(), source.0.as_ptr());
+        }
+    }
 }
 
 impl Hasher for Hasher32 {
@@ -206,9 +343,59 @@ impl FastHasher for Hasher32 {
     }
 }
 
+#[cfg(feature = "std")]
 impl StreamHasher for Hasher32 {}
 
+impl HasherState for Hasher32 {
+    fn to_bytes(&self) -> Vec<u8> {
+        let state = unsafe { self.0.as_ref() };
+        let mut w = Writer::new("fasthash::xx::Hasher32");
+
+        w.u32(state.total_len_32)
+            .u32(state.large_len)
+            .u32(state.v1)
+            .u32(state.v2)
+            .u32(state.v3)
+            .u32(state.v4)
+            .u32(state.memsize);
+
+        // the buffered input, in the order of the bytes
+        for n in &state.mem32 {
+            w.bytes(&n.to_ne_bytes());
+        }
+
+        w.into_bytes()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let mut r = Reader::new(bytes, "fasthash::xx::Hasher32")?;
+        let h = Self::new();
+        let state = unsafe { &mut *h.0.as_ptr() };
+
+        state.total_len_32 = r.u32()?;
+        state.large_len = r.u32()?;
+        state.v1 = r.u32()?;
+        state.v2 = r.u32()?;
+        state.v3 = r.u32()?;
+        state.v4 = r.u32()?;
+        state.memsize = r.u32()?;
+
+        if state.memsize >= 16 {
+            return Err(Error::InvalidFormat);
+        }
+
+        for n in &mut state.mem32 {
+            *n = u32::from_ne_bytes(r.bytes(4)?.try_into().unwrap());
+        }
+
+        r.finish()?;
+
+        Ok(h)
+    }
+}
+
 impl_build_hasher!(Hasher32, Hash32);
+impl_serde!(Hasher32);
 
 /// An implementation of `std::hash::Hasher`.
 ///
@@ -257,6 +444,12 @@ impl Clone for Hasher64 {
             Hasher64(NonNull::new_unchecked(state))
         }
     }
+
+    fn clone_from(&mut self, source: &Self) {
+        unsafe {
+            ffi::XXH64_copyState(self.0.as_ptr(), source.0.as_ptr());
+        }
+    }
 }
 
 impl Hasher for Hasher64 {
@@ -293,6 +486,54 @@ impl FastHasher for Hasher64 {
     }
 }
 
+#[cfg(feature = "std")]
 impl StreamHasher for Hasher64 {}
 
+impl HasherState for Hasher64 {
+    fn to_bytes(&self) -> Vec<u8> {
+        let state = unsafe { self.0.as_ref() };
+        let mut w = Writer::new("fasthash::xx::Hasher64");
+
+        w.u64(state.total_len)
+            .u64(state.v1)
+            .u64(state.v2)
+            .u64(state.v3)
+            .u64(state.v4)
+            .u32(state.memsize);
+
+        // the buffered input, in the order of the bytes
+        for n in &state.mem64 {
+            w.bytes(&n.to_ne_bytes());
+        }
+
+        w.into_bytes()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let mut r = Reader::new(bytes, "fasthash::xx::Hasher64")?;
+        let h = Self::new();
+        let state = unsafe { &mut *h.0.as_ptr() };
+
+        state.total_len = r.u64()?;
+        state.v1 = r.u64()?;
+        state.v2 = r.u64()?;
+        state.v3 = r.u64()?;
+        state.v4 = r.u64()?;
+        state.memsize = r.u32()?;
+
+        if state.memsize >= 32 {
+            return Err(Error::InvalidFormat);
+        }
+
+        for n in &mut state.mem64 {
+            *n = u64::from_ne_bytes(r.bytes(8)?.try_into().unwrap());
+        }
+
+        r.finish()?;
+
+        Ok(h)
+    }
+}
+
 impl_build_hasher!(Hasher64, Hash64);
+impl_serde!(Hasher64);
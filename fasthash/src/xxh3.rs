@@ -1,9 +1,29 @@
 //! XXH3 is a new hash algorithm, featuring vastly improved speed performance for both small and large inputs.
-use std::hash::Hasher;
-use std::mem;
-use std::ptr::NonNull;
-
-use crate::{FastHash, FastHasher, HasherExt, StreamHasher};
+//!
+//! By default it is backed by the vendored C implementation,
+//! the `pure-xxh3` feature switches to a pure Rust port which gives bit for bit the same results.
+use alloc::borrow::Cow;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::hash::{BuildHasher, Hasher};
+
+use crate::state::{Error, HasherState, Reader, Writer};
+#[cfg(feature = "std")]
+use crate::StreamHasher;
+use crate::{FastHash, FastHasher, HasherExt};
+
+#[cfg(feature = "pure-xxh3")]
+mod pure;
+#[cfg(all(feature = "sys", any(test, not(feature = "pure-xxh3"))))]
+mod sys;
+
+cfg_if! {
+    if #[cfg(feature = "pure-xxh3")] {
+        use self::pure as imp;
+    } else {
+        use self::sys as imp;
+    }
+}
 
 /// 64-bit hash functions for a byte array.
 ///
@@ -70,6 +90,151 @@ pub fn hash128_with_seed<T: AsRef<[u8]>>(v: T, seed: u64) -> u128 {
     Hash128::hash_with_seed(v, seed)
 }
 
+/// 64-bit hash function for a byte array, keyed with a custom secret.
+///
+/// # Example
+///
+/// ```
+/// use fasthash::xxh3::{self, Secret};
+///
+/// let key = (0..192).map(|i| i as u8).collect::<Vec<_>>();
+/// let secret = Secret::new(&key[..]).unwrap();
+///
+/// assert_eq!(xxh3::hash64_with_secret("hello world", &secret), 2362342105173581446);
+/// ```
+#[inline(always)]
+pub fn hash64_with_secret<T: AsRef<[u8]>>(v: T, secret: &Secret) -> u64 {
+    Hash64::hash_with_secret(v, secret)
+}
+
+/// 128-bit hash function for a byte array, keyed with a custom secret.
+///
+/// # Example
+///
+/// ```
+/// use fasthash::xxh3::{self, Secret};
+///
+/// let key = (0..192).map(|i| i as u8).collect::<Vec<_>>();
+/// let secret = Secret::new(&key[..]).unwrap();
+///
+/// assert_eq!(
+///     xxh3::hash128_with_secret("hello world", &secret),
+///     187580323909199528309755103376138470178,
+/// );
+/// ```
+#[inline(always)]
+pub fn hash128_with_secret<T: AsRef<[u8]>>(v: T, secret: &Secret) -> u128 {
+    Hash128::hash_with_secret(v, secret)
+}
+
+/// The minimum size of a custom secret, in bytes.
+pub const SECRET_SIZE_MIN: usize = 136;
+
+/// A custom secret to key the XXH3 hash functions.
+///
+/// The secret replaces the default secret of XXH3 as a whole, which makes it
+/// a much larger key than a 64-bit seed. It should look like random bytes,
+/// and must be at least `SECRET_SIZE_MIN` bytes long.
+///
+/// # Example
+///
+/// ```
+/// use fasthash::xxh3::{Secret, SECRET_SIZE_MIN};
+///
+/// assert!(Secret::new(vec![0; SECRET_SIZE_MIN - 1]).is_none());
+///
+/// let secret = Secret::new(vec![0; SECRET_SIZE_MIN]).unwrap();
+///
+/// assert_eq!(secret.len(), SECRET_SIZE_MIN);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Secret<'a>(Cow<'a, [u8]>);
+
+impl<'a> Secret<'a> {
+    /// Constructs a secret from owned or borrowed bytes.
+    ///
+    /// Returns `None` if the secret is shorter than `SECRET_SIZE_MIN`.
+    #[inline(always)]
+    pub fn new<T: Into<Cow<'a, [u8]>>>(bytes: T) -> Option<Self> {
+        let bytes = bytes.into();
+
+        if bytes.len() < SECRET_SIZE_MIN {
+            None
+        } else {
+            Some(Secret(bytes))
+        }
+    }
+
+    /// Returns the number of bytes in the secret.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the secret has a length of 0, which is never the case.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Extracts a slice containing the entire secret.
+    #[inline(always)]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Converts the secret into an owned one, cloning the bytes if borrowed.
+    #[inline(always)]
+    pub fn into_owned(self) -> Secret<'static> {
+        Secret(Cow::Owned(self.0.into_owned()))
+    }
+}
+
+impl<'a> AsRef<[u8]> for Secret<'a> {
+    #[inline(always)]
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// A `BuildHasher` which creates `Hasher64` keyed with a custom secret.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// use fasthash::xxh3::{Secret, SecretState};
+///
+/// let key = (0..192).map(|i| i as u8).collect::<Vec<_>>();
+/// let secret = Secret::new(key).unwrap();
+///
+/// let mut map = HashMap::with_hasher(SecretState::new(secret));
+///
+/// assert_eq!(map.insert(37, "a"), None);
+/// assert_eq!(map.insert(37, "b"), Some("a"));
+/// assert_eq!(map[&37], "b");
+/// ```
+#[derive(Clone, Debug)]
+pub struct SecretState(Arc<Secret<'static>>);
+
+impl SecretState {
+    /// Constructs a new `SecretState` with the custom secret.
+    #[inline(always)]
+    pub fn new<S: Into<Arc<Secret<'static>>>>(secret: S) -> Self {
+        SecretState(secret.into())
+    }
+}
+
+impl BuildHasher for SecretState {
+    type Hasher = Hasher64;
+
+    #[inline(always)]
+    fn build_hasher(&self) -> Hasher64 {
+        Hasher64::with_secret(self.0.clone())
+    }
+}
+
 /// An implementation of `std::hash::Hasher`.
 ///
 /// # Example
@@ -97,16 +262,20 @@ impl FastHash for Hash64 {
 
     #[inline(always)]
     fn hash<T: AsRef<[u8]>>(bytes: T) -> Self::Hash {
-        let bytes = bytes.as_ref();
-
-        unsafe { ffi::XXH3_64bits(bytes.as_ptr() as *const _, bytes.len()) }
+        imp::hash64(bytes.as_ref())
     }
 
     #[inline(always)]
     fn hash_with_seed<T: AsRef<[u8]>>(bytes: T, seed: Self::Seed) -> Self::Hash {
-        let bytes = bytes.as_ref();
+        imp::hash64_with_seed(bytes.as_ref(), seed)
+    }
+}
 
-        unsafe { ffi::XXH3_64bits_withSeed(bytes.as_ptr() as *const _, bytes.len(), seed) }
+impl Hash64 {
+    /// Hash functions for a byte array, keyed with a custom secret.
+    #[inline(always)]
+    pub fn hash_with_secret<T: AsRef<[u8]>>(bytes: T, secret: &Secret) -> u64 {
+        imp::hash64_with_secret(bytes.as_ref(), secret.as_bytes())
     }
 }
 
@@ -127,45 +296,24 @@ impl FastHash for Hash64 {
 /// h.write(b"world");
 /// assert_eq!(h.finish(), 5799861518677282342);
 /// ```
-pub struct Hasher64(NonNull<ffi::XXH3_state_t>);
+#[derive(Clone)]
+pub struct Hasher64(imp::State);
 
 impl Default for Hasher64 {
     fn default() -> Self {
-        Hasher64(unsafe { NonNull::new_unchecked(ffi::XXH3_createState()) })
-    }
-}
-
-impl Clone for Hasher64 {
-    fn clone(&self) -> Self {
-        unsafe {
-            let state = ffi::XXH3_createState();
-
-            ffi::XXH3_copyState(state, self.0.as_ptr());
-
-            Hasher64(NonNull::new_unchecked(state))
-        }
-    }
-}
-
-impl Drop for Hasher64 {
-    fn drop(&mut self) {
-        unsafe {
-            ffi::XXH3_freeState(self.0.as_ptr());
-        }
+        Hasher64::new()
     }
 }
 
 impl Hasher for Hasher64 {
     #[inline(always)]
     fn finish(&self) -> u64 {
-        unsafe { ffi::XXH3_64bits_digest(self.0.as_ptr()) }
+        self.0.digest64()
     }
 
     #[inline(always)]
     fn write(&mut self, bytes: &[u8]) {
-        unsafe {
-            ffi::XXH3_64bits_update(self.0.as_ptr(), bytes.as_ptr() as *const _, bytes.len());
-        }
+        self.0.update64(bytes)
     }
 }
 
@@ -175,19 +323,59 @@ impl FastHasher for Hasher64 {
 
     #[inline(always)]
     fn with_seed(seed: u64) -> Self {
-        unsafe {
-            let state = ffi::XXH3_createState();
-
-            ffi::XXH3_64bits_reset_withSeed(state, seed);
+        Hasher64(imp::State::reset64_with_seed(seed))
+    }
+}
 
-            Hasher64(NonNull::new_unchecked(state))
-        }
+impl Hasher64 {
+    /// Constructs a new hasher keyed with a custom secret.
+    ///
+    /// The hasher keeps the secret alive, and shares it with its clones.
+    ///
+    /// Its exported state embeds the secret as is, keep it as confidential as the secret.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::hash::Hasher;
+    ///
+    /// use fasthash::xxh3::{self, Hasher64, Secret};
+    ///
+    /// let key = (0..192).map(|i| i as u8).collect::<Vec<_>>();
+    /// let secret = Secret::new(key).unwrap();
+    ///
+    /// let mut h = Hasher64::with_secret(secret.clone());
+    ///
+    /// h.write(b"hello");
+    /// h.write(b" world");
+    /// assert_eq!(h.finish(), xxh3::hash64_with_secret("hello world", &secret));
+    /// ```
+    pub fn with_secret<S: Into<Arc<Secret<'static>>>>(secret: S) -> Self {
+        Hasher64(imp::State::reset64_with_secret(secret.into()))
     }
 }
 
+#[cfg(feature = "std")]
 impl StreamHasher for Hasher64 {}
 
+impl HasherState for Hasher64 {
+    fn to_bytes(&self) -> Vec<u8> {
+        state_to_bytes("fasthash::xxh3::Hasher64", &self.0)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        state_from_bytes(
+            bytes,
+            "fasthash::xxh3::Hasher64",
+            imp::State::reset64_with_seed,
+            imp::State::reset64_with_secret,
+        )
+        .map(Hasher64)
+    }
+}
+
 impl_build_hasher!(Hasher64, Hash64);
+impl_serde!(Hasher64);
 
 /// An implementation of `std::hash::Hasher`.
 ///
@@ -216,22 +404,20 @@ impl FastHash for Hash128 {
 
     #[inline(always)]
     fn hash<T: AsRef<[u8]>>(bytes: T) -> Self::Hash {
-        let bytes = bytes.as_ref();
-
-        unsafe { mem::transmute(ffi::XXH3_128bits(bytes.as_ptr() as *const _, bytes.len())) }
+        imp::hash128(bytes.as_ref())
     }
 
     #[inline(always)]
     fn hash_with_seed<T: AsRef<[u8]>>(bytes: T, seed: Self::Seed) -> Self::Hash {
-        let bytes = bytes.as_ref();
-
-        unsafe {
-            mem::transmute(ffi::XXH3_128bits_withSeed(
-                bytes.as_ptr() as *const _,
-                bytes.len(),
-                seed,
-            ))
-        }
+        imp::hash128_with_seed(bytes.as_ref(), seed)
+    }
+}
+
+impl Hash128 {
+    /// Hash functions for a byte array, keyed with a custom secret.
+    #[inline(always)]
+    pub fn hash_with_secret<T: AsRef<[u8]>>(bytes: T, secret: &Secret) -> u128 {
+        imp::hash128_with_secret(bytes.as_ref(), secret.as_bytes())
     }
 }
 
@@ -252,54 +438,31 @@ impl FastHash for Hash128 {
 /// h.write(b"world");
 /// assert_eq!(h.finish_ext(), 235571704612606125258077068431826739245);
 /// ```
-pub struct Hasher128(NonNull<ffi::XXH3_state_t>);
+#[derive(Clone)]
+pub struct Hasher128(imp::State);
 
 impl Default for Hasher128 {
     fn default() -> Self {
-        Hasher128(unsafe { NonNull::new_unchecked(ffi::XXH3_createState()) })
-    }
-}
-
-impl Clone for Hasher128 {
-    fn clone(&self) -> Self {
-        unsafe {
-            let state = ffi::XXH3_createState();
-
-            ffi::XXH3_copyState(state, self.0.as_ptr());
-
-            Hasher128(NonNull::new_unchecked(state))
-        }
-    }
-}
-
-impl Drop for Hasher128 {
-    fn drop(&mut self) {
-        unsafe {
-            ffi::XXH3_freeState(self.0.as_ptr());
-        }
+        Hasher128::new()
     }
 }
 
 impl Hasher for Hasher128 {
     #[inline(always)]
     fn finish(&self) -> u64 {
-        unsafe { ffi::XXH3_128bits_digest(self.0.as_ptr()).low64 }
+        self.0.digest128() as u64
     }
 
     #[inline(always)]
     fn write(&mut self, bytes: &[u8]) {
-        unsafe {
-            ffi::XXH3_128bits_update(self.0.as_ptr(), bytes.as_ptr() as *const _, bytes.len());
-        }
+        self.0.update128(bytes)
     }
 }
 
 impl HasherExt for Hasher128 {
     #[inline(always)]
     fn finish_ext(&self) -> u128 {
-        let h = unsafe { ffi::XXH3_128bits_digest(self.0.as_ptr()) };
-
-        u128::from(h.low64) + (u128::from(h.high64) << 64)
+        self.0.digest128()
     }
 }
 
@@ -309,16 +472,350 @@ impl FastHasher for Hasher128 {
 
     #[inline(always)]
     fn with_seed(seed: u64) -> Self {
-        unsafe {
-            let state = ffi::XXH3_createState();
-
-            ffi::XXH3_128bits_reset_withSeed(state, seed);
+        Hasher128(imp::State::reset128_with_seed(seed))
+    }
+}
 
-            Hasher128(NonNull::new_unchecked(state))
-        }
+impl Hasher128 {
+    /// Constructs a new hasher keyed with a custom secret.
+    ///
+    /// The hasher keeps the secret alive, and shares it with its clones.
+    ///
+    /// Its exported state embeds the secret as is, keep it as confidential as the secret.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::hash::Hasher;
+    ///
+    /// use fasthash::xxh3::{self, Hasher128, Secret};
+    /// use fasthash::HasherExt;
+    ///
+    /// let key = (0..192).map(|i| i as u8).collect::<Vec<_>>();
+    /// let secret = Secret::new(key).unwrap();
+    ///
+    /// let mut h = Hasher128::with_secret(secret.clone());
+    ///
+    /// h.write(b"hello");
+    /// h.write(b" world");
+    /// assert_eq!(h.finish_ext(), xxh3::hash128_with_secret("hello world", &secret));
+    /// ```
+    pub fn with_secret<S: Into<Arc<Secret<'static>>>>(secret: S) -> Self {
+        Hasher128(imp::State::reset128_with_secret(secret.into()))
     }
 }
 
+#[cfg(feature = "std")]
 impl StreamHasher for Hasher128 {}
 
+impl HasherState for Hasher128 {
+    fn to_bytes(&self) -> Vec<u8> {
+        state_to_bytes("fasthash::xxh3::Hasher128", &self.0)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        state_from_bytes(
+            bytes,
+            "fasthash::xxh3::Hasher128",
+            imp::State::reset128_with_seed,
+            imp::State::reset128_with_secret,
+        )
+        .map(Hasher128)
+    }
+}
+
 impl_build_hasher!(Hasher128, Hash128);
+impl_serde!(Hasher128);
+
+/// The size of the internal buffer of a streaming state.
+const INTERNAL_BUFFER_SIZE: usize = 256;
+
+/// The size of the secret derived from a seed.
+const SECRET_DEFAULT_SIZE: usize = 192;
+
+/// The progress of a streaming state, which is the same for both implementations.
+struct Progress {
+    acc: [u64; 8],
+    buffer: [u8; INTERNAL_BUFFER_SIZE],
+    buffered_size: usize,
+    nb_stripes_so_far: usize,
+    total_len: u64,
+}
+
+fn state_to_bytes(name: &str, state: &imp::State) -> Vec<u8> {
+    let mut w = Writer::new(name);
+
+    match state.secret() {
+        Some(secret) => w.u8(1).u32(secret.len() as u32).bytes(secret.as_bytes()),
+        None => w.u8(0).u64(state.seed()),
+    };
+
+    let progress = state.progress();
+
+    w.words(&progress.acc)
+        .bytes(&progress.buffer)
+        .u32(progress.buffered_size as u32)
+        .u32(progress.nb_stripes_so_far as u32)
+        .u64(progress.total_len);
+    w.into_bytes()
+}
+
+fn state_from_bytes(
+    bytes: &[u8],
+    name: &str,
+    with_seed: fn(u64) -> imp::State,
+    with_secret: fn(Arc<Secret<'static>>) -> imp::State,
+) -> Result<imp::State, Error> {
+    let mut r = Reader::new(bytes, name)?;
+    let mut state = match r.u8()? {
+        0 => with_seed(r.u64()?),
+        1 => {
+            let len = r.u32()? as usize;
+            let secret = Secret::new(r.bytes(len)?.to_vec()).ok_or(Error::InvalidFormat)?;
+
+            with_secret(Arc::new(secret))
+        }
+        _ => return Err(Error::InvalidFormat),
+    };
+
+    let mut progress = Progress {
+        acc: [0; 8],
+        buffer: [0; INTERNAL_BUFFER_SIZE],
+        buffered_size: 0,
+        nb_stripes_so_far: 0,
+        total_len: 0,
+    };
+
+    r.words(&mut progress.acc)?;
+    progress
+        .buffer
+        .copy_from_slice(r.bytes(INTERNAL_BUFFER_SIZE)?);
+    progress.buffered_size = r.u32()? as usize;
+    progress.nb_stripes_so_far = r.u32()? as usize;
+    progress.total_len = r.u64()?;
+
+    r.finish()?;
+
+    // a block consumes 8 bytes of the secret per stripe, except the last stripe of the secret
+    let secret_len = state
+        .secret()
+        .map_or(SECRET_DEFAULT_SIZE, |secret| secret.len());
+    let nb_stripes_per_block = (secret_len - 64) / 8;
+
+    if progress.buffered_size > INTERNAL_BUFFER_SIZE
+        || progress.nb_stripes_so_far >= nb_stripes_per_block
+    {
+        return Err(Error::InvalidFormat);
+    }
+
+    state.set_progress(&progress);
+
+    Ok(state)
+}
+
+#[cfg(all(test, feature = "sys", feature = "pure-xxh3"))]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{pure, sys, Secret};
+
+    const SEEDS: [u64; 3] = [0, 123_456_789, 0x9E37_79B1_85EB_CA87];
+
+    fn sample(len: usize) -> Vec<u8> {
+        let mut x = 0x9E37_79B1_u64;
+
+        (0..len)
+            .map(|_| {
+                x = x.wrapping_mul(0x9E37_79B1_85EB_CA87);
+                (x >> 56) as u8
+            })
+            .collect()
+    }
+
+    fn secrets() -> Vec<Arc<Secret<'static>>> {
+        [super::SECRET_SIZE_MIN, 192, 257]
+            .iter()
+            .map(|&len| Arc::new(Secret::new(sample(len + 1)[1..].to_vec()).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_one_shot() {
+        let data = sample(4096);
+        let secrets = secrets();
+
+        for len in (0..=1024).chain(vec![2047, 2048, 2240, 2243, 4096]) {
+            let v = &data[..len];
+
+            assert_eq!(pure::hash64(v), sys::hash64(v), "hash64, len={}", len);
+            assert_eq!(pure::hash128(v), sys::hash128(v), "hash128, len={}", len);
+
+            for &seed in &SEEDS {
+                assert_eq!(
+                    pure::hash64_with_seed(v, seed),
+                    sys::hash64_with_seed(v, seed),
+                    "hash64_with_seed, len={}, seed={}",
+                    len,
+                    seed
+                );
+                assert_eq!(
+                    pure::hash128_with_seed(v, seed),
+                    sys::hash128_with_seed(v, seed),
+                    "hash128_with_seed, len={}, seed={}",
+                    len,
+                    seed
+                );
+            }
+
+            for secret in &secrets {
+                assert_eq!(
+                    pure::hash64_with_secret(v, secret.as_bytes()),
+                    sys::hash64_with_secret(v, secret.as_bytes()),
+                    "hash64_with_secret, len={}, secret={}",
+                    len,
+                    secret.len()
+                );
+                assert_eq!(
+                    pure::hash128_with_secret(v, secret.as_bytes()),
+                    sys::hash128_with_secret(v, secret.as_bytes()),
+                    "hash128_with_secret, len={}, secret={}",
+                    len,
+                    secret.len()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_streaming() {
+        let data = sample(4096);
+        let secrets = secrets();
+
+        for &len in &[0, 3, 16, 100, 240, 241, 256, 300, 513, 1024, 2243, 4096] {
+            for &chunk in &[1, 7, 64, 100, 256, 300, 4096] {
+                let mut states = vec![];
+
+                for &seed in &SEEDS {
+                    states.push((
+                        pure::State::reset64_with_seed(seed),
+                        sys::State::reset64_with_seed(seed),
+                        pure::State::reset128_with_seed(seed),
+                        sys::State::reset128_with_seed(seed),
+                    ));
+                }
+                for secret in &secrets {
+                    states.push((
+                        pure::State::reset64_with_secret(secret.clone()),
+                        sys::State::reset64_with_secret(secret.clone()),
+                        pure::State::reset128_with_secret(secret.clone()),
+                        sys::State::reset128_with_secret(secret.clone()),
+                    ));
+                }
+
+                for (i, (pure64, sys64, pure128, sys128)) in states.iter_mut().enumerate() {
+                    for (n, v) in data[..len].chunks(chunk).enumerate() {
+                        pure64.update64(v);
+                        sys64.update64(v);
+                        pure128.update128(v);
+                        sys128.update128(v);
+
+                        assert_eq!(
+                            pure64.digest64(),
+                            sys64.digest64(),
+                            "digest64, state={}, len={}, chunk={}, n={}",
+                            i,
+                            len,
+                            chunk,
+                            n
+                        );
+                        assert_eq!(
+                            pure128.digest128(),
+                            sys128.digest128(),
+                            "digest128, state={}, len={}, chunk={}, n={}",
+                            i,
+                            len,
+                            chunk,
+                            n
+                        );
+                    }
+
+                    assert_eq!(pure64.digest64(), sys64.digest64());
+                    assert_eq!(pure128.digest128(), sys128.digest128());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_streaming_one_shot() {
+        let data = sample(4096);
+
+        // a short last stripe after the long inputs consumed in place
+        for &len in &[257, 300, 513, 1025, 2243, 4096 - 63] {
+            for &seed in &SEEDS {
+                let mut pure64 = pure::State::reset64_with_seed(seed);
+                let mut sys64 = sys::State::reset64_with_seed(seed);
+                let mut pure128 = pure::State::reset128_with_seed(seed);
+                let mut sys128 = sys::State::reset128_with_seed(seed);
+
+                pure64.update64(&data[..len]);
+                sys64.update64(&data[..len]);
+                pure128.update128(&data[..len]);
+                sys128.update128(&data[..len]);
+
+                let h64 = pure::hash64_with_seed(&data[..len], seed);
+                let h128 = pure::hash128_with_seed(&data[..len], seed);
+
+                assert_eq!(pure64.digest64(), h64, "pure64, len={}, seed={}", len, seed);
+                assert_eq!(sys64.digest64(), h64, "sys64, len={}, seed={}", len, seed);
+                assert_eq!(
+                    pure128.digest128(),
+                    h128,
+                    "pure128, len={}, seed={}",
+                    len,
+                    seed
+                );
+                assert_eq!(
+                    sys128.digest128(),
+                    h128,
+                    "sys128, len={}, seed={}",
+                    len,
+                    seed
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_progress() {
+        let data = sample(4096);
+
+        for &len in &[0, 100, 256, 300, 1024, 2243] {
+            for &seed in &SEEDS {
+                let mut pure64 = pure::State::reset64_with_seed(seed);
+                let mut sys64 = sys::State::reset64_with_seed(seed);
+
+                pure64.update64(&data[..len]);
+                sys64.update64(&data[..len]);
+
+                assert_eq!(pure64.seed(), sys64.seed());
+                assert!(pure64.secret().is_none() && sys64.secret().is_none());
+
+                // each implementation resumes the state of the other
+                let mut from_sys = pure::State::reset64_with_seed(seed);
+                let mut from_pure = sys::State::reset64_with_seed(seed);
+
+                from_sys.set_progress(&sys64.progress());
+                from_pure.set_progress(&pure64.progress());
+
+                from_sys.update64(&data[len..]);
+                from_pure.update64(&data[len..]);
+
+                let h64 = pure::hash64_with_seed(&data, seed);
+
+                assert_eq!(from_sys.digest64(), h64, "from sys, len={}", len);
+                assert_eq!(from_pure.digest64(), h64, "from pure, len={}", len);
+            }
+        }
+    }
+}
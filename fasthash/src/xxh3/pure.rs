@@ -0,0 +1,828 @@
+//! XXH3 implemented in Rust, bit for bit compatible with the vendored C implementation.
+use alloc::sync::Arc;
+use core::convert::TryInto;
+
+use super::{Progress, Secret};
+
+const PRIME32_1: u64 = 0x9E37_79B1;
+const PRIME32_2: u64 = 0x85EB_CA77;
+const PRIME32_3: u64 = 0xC2B2_AE3D;
+
+const PRIME64_1: u64 = 0x9E37_79B1_85EB_CA87;
+const PRIME64_2: u64 = 0xC2B2_AE3D_27D4_EB4F;
+const PRIME64_3: u64 = 0x1656_67B1_9E37_79F9;
+const PRIME64_4: u64 = 0x85EB_CA77_C2B2_AE63;
+const PRIME64_5: u64 = 0x27D4_EB2F_1656_67C5;
+
+const SECRET_DEFAULT_SIZE: usize = 192;
+
+const DEFAULT_SECRET: [u8; SECRET_DEFAULT_SIZE] = [
+    0xb8, 0xfe, 0x6c, 0x39, 0x23, 0xa4, 0x4b, 0xbe, 0x7c, 0x01, 0x81, 0x2c, 0xf7, 0x21, 0xad, 0x1c,
+    0xde, 0xd4, 0x6d, 0xe9, 0x83, 0x90, 0x97, 0xdb, 0x72, 0x40, 0xa4, 0xa4, 0xb7, 0xb3, 0x67, 0x1f,
+    0xcb, 0x79, 0xe6, 0x4e, 0xcc, 0xc0, 0xe5, 0x78, 0x82, 0x5a, 0xd0, 0x7d, 0xcc, 0xff, 0x72, 0x21,
+    0xb8, 0x08, 0x46, 0x74, 0xf7, 0x43, 0x24, 0x8e, 0xe0, 0x35, 0x90, 0xe6, 0x81, 0x3a, 0x26, 0x4c,
+    0x3c, 0x28, 0x52, 0xbb, 0x91, 0xc3, 0x00, 0xcb, 0x88, 0xd0, 0x65, 0x8b, 0x1b, 0x53, 0x2e, 0xa3,
+    0x71, 0x64, 0x48, 0x97, 0xa2, 0x0d, 0xf9, 0x4e, 0x38, 0x19, 0xef, 0x46, 0xa9, 0xde, 0xac, 0xd8,
+    0xa8, 0xfa, 0x76, 0x3f, 0xe3, 0x9c, 0x34, 0x3f, 0xf9, 0xdc, 0xbb, 0xc7, 0xc7, 0x0b, 0x4f, 0x1d,
+    0x8a, 0x51, 0xe0, 0x4b, 0xcd, 0xb4, 0x59, 0x31, 0xc8, 0x9f, 0x7e, 0xc9, 0xd9, 0x78, 0x73, 0x64,
+    0xea, 0xc5, 0xac, 0x83, 0x34, 0xd3, 0xeb, 0xc3, 0xc5, 0x81, 0xa0, 0xff, 0xfa, 0x13, 0x63, 0xeb,
+    0x17, 0x0d, 0xdd, 0x51, 0xb7, 0xf0, 0xda, 0x49, 0xd3, 0x16, 0x55, 0x26, 0x29, 0xd4, 0x68, 0x9e,
+    0x2b, 0x16, 0xbe, 0x58, 0x7d, 0x47, 0xa1, 0xfc, 0x8f, 0xf8, 0xb8, 0xd1, 0x7a, 0xd0, 0x31, 0xce,
+    0x45, 0xcb, 0x3a, 0x8f, 0x95, 0x16, 0x04, 0x28, 0xaf, 0xd7, 0xfb, 0xca, 0xbb, 0x4b, 0x40, 0x7e,
+];
+
+const STRIPE_LEN: usize = 64;
+const ACC_NB: usize = STRIPE_LEN / 8;
+const ACC_SIZE: usize = ACC_NB * 8;
+/// nb of secret bytes consumed at each accumulation
+const SECRET_CONSUME_RATE: usize = 8;
+/// do not align on 8, so that secret is different from accumulator
+const SECRET_MERGEACCS_START: usize = 11;
+/// do not align on 8, so that secret is different from scrambler
+const SECRET_LASTACC_START: usize = 7;
+
+const MIDSIZE_MAX: usize = 240;
+const MIDSIZE_STARTOFFSET: usize = 3;
+const MIDSIZE_LASTOFFSET: usize = 17;
+
+const INTERNAL_BUFFER_SIZE: usize = 256;
+const INTERNAL_BUFFER_STRIPES: usize = INTERNAL_BUFFER_SIZE / STRIPE_LEN;
+
+const INIT_ACC: [u64; ACC_NB] = [
+    PRIME32_3, PRIME64_1, PRIME64_2, PRIME64_3, PRIME64_4, PRIME32_2, PRIME64_5, PRIME32_1,
+];
+
+/// The 64-bit and 128-bit variants mix the input into the accumulators differently.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum AccWidth {
+    Acc64Bits,
+    Acc128Bits,
+}
+
+#[inline(always)]
+fn read32(b: &[u8]) -> u32 {
+    u32::from_le_bytes(b[..4].try_into().unwrap())
+}
+
+#[inline(always)]
+fn read64(b: &[u8]) -> u64 {
+    u64::from_le_bytes(b[..8].try_into().unwrap())
+}
+
+#[inline(always)]
+fn mul128_fold64(lhs: u64, rhs: u64) -> u64 {
+    let product = u128::from(lhs).wrapping_mul(u128::from(rhs));
+
+    (product as u64) ^ ((product >> 64) as u64)
+}
+
+#[inline(always)]
+fn avalanche(mut h64: u64) -> u64 {
+    h64 ^= h64 >> 37;
+    h64 = h64.wrapping_mul(PRIME64_3);
+    h64 ^ (h64 >> 32)
+}
+
+#[inline(always)]
+fn mix_16bytes(data: &[u8], secret: &[u8], seed: u64) -> u64 {
+    mul128_fold64(
+        read64(data) ^ read64(secret).wrapping_add(seed),
+        read64(&data[8..]) ^ read64(&secret[8..]).wrapping_sub(seed),
+    )
+}
+
+/// Derives a custom secret from the default one and a seed.
+#[inline(always)]
+fn custom_secret(seed: u64) -> [u8; SECRET_DEFAULT_SIZE] {
+    let mut secret = [0; SECRET_DEFAULT_SIZE];
+
+    for off in (0..SECRET_DEFAULT_SIZE).step_by(16) {
+        let lo = read64(&DEFAULT_SECRET[off..]).wrapping_add(seed);
+        let hi = read64(&DEFAULT_SECRET[off + 8..]).wrapping_sub(seed);
+
+        secret[off..off + 8].copy_from_slice(&lo.to_le_bytes());
+        secret[off + 8..off + 16].copy_from_slice(&hi.to_le_bytes());
+    }
+
+    secret
+}
+
+/* ===   Short keys   === */
+
+#[inline(always)]
+fn len_1to3_64b(data: &[u8], secret: &[u8], seed: u64) -> u64 {
+    let len = data.len();
+    let c1 = u32::from(data[0]);
+    let c2 = u32::from(data[len >> 1]);
+    let c3 = u32::from(data[len - 1]);
+    let combined = c1 + (c2 << 8) + (c3 << 16) + ((len as u32) << 24);
+    let keyed = u64::from(combined) ^ u64::from(read32(secret)).wrapping_add(seed);
+
+    avalanche(keyed.wrapping_mul(PRIME64_1))
+}
+
+#[inline(always)]
+fn len_4to8_64b(data: &[u8], secret: &[u8], seed: u64) -> u64 {
+    let len = data.len();
+    let in1 = u64::from(read32(data));
+    let in2 = u64::from(read32(&data[len - 4..]));
+    let in64 = in1.wrapping_add(in2 << 32);
+    let keyed = in64 ^ read64(secret).wrapping_add(seed);
+    let mix64 = (len as u64).wrapping_add((keyed ^ (keyed >> 51)).wrapping_mul(PRIME32_1));
+
+    avalanche((mix64 ^ (mix64 >> 47)).wrapping_mul(PRIME64_2))
+}
+
+#[inline(always)]
+fn len_9to16_64b(data: &[u8], secret: &[u8], seed: u64) -> u64 {
+    let len = data.len();
+    let ll1 = read64(data) ^ read64(secret).wrapping_add(seed);
+    let ll2 = read64(&data[len - 8..]) ^ read64(&secret[8..]).wrapping_sub(seed);
+    let acc = (len as u64)
+        .wrapping_add(ll1)
+        .wrapping_add(ll2)
+        .wrapping_add(mul128_fold64(ll1, ll2));
+
+    avalanche(acc)
+}
+
+#[inline(always)]
+fn len_17to128_64b(data: &[u8], secret: &[u8], seed: u64) -> u64 {
+    let len = data.len();
+    let mut acc = (len as u64).wrapping_mul(PRIME64_1);
+
+    if len > 32 {
+        if len > 64 {
+            if len > 96 {
+                acc = acc
+                    .wrapping_add(mix_16bytes(&data[48..], &secret[96..], seed))
+                    .wrapping_add(mix_16bytes(&data[len - 64..], &secret[112..], seed));
+            }
+            acc = acc
+                .wrapping_add(mix_16bytes(&data[32..], &secret[64..], seed))
+                .wrapping_add(mix_16bytes(&data[len - 48..], &secret[80..], seed));
+        }
+        acc = acc
+            .wrapping_add(mix_16bytes(&data[16..], &secret[32..], seed))
+            .wrapping_add(mix_16bytes(&data[len - 32..], &secret[48..], seed));
+    }
+    acc = acc
+        .wrapping_add(mix_16bytes(data, secret, seed))
+        .wrapping_add(mix_16bytes(&data[len - 16..], &secret[16..], seed));
+
+    avalanche(acc)
+}
+
+#[inline(always)]
+fn len_129to240_64b(data: &[u8], secret: &[u8], seed: u64) -> u64 {
+    let len = data.len();
+    let nb_rounds = len / 16;
+
+    let mut acc = (len as u64).wrapping_mul(PRIME64_1);
+    for i in 0..8 {
+        acc = acc.wrapping_add(mix_16bytes(&data[16 * i..], &secret[16 * i..], seed));
+    }
+    acc = avalanche(acc);
+
+    for i in 8..nb_rounds {
+        acc = acc.wrapping_add(mix_16bytes(
+            &data[16 * i..],
+            &secret[16 * (i - 8) + MIDSIZE_STARTOFFSET..],
+            seed,
+        ));
+    }
+    acc = acc.wrapping_add(mix_16bytes(
+        &data[len - 16..],
+        &secret[super::SECRET_SIZE_MIN - MIDSIZE_LASTOFFSET..],
+        seed,
+    ));
+
+    avalanche(acc)
+}
+
+#[inline(always)]
+fn hash64_short(data: &[u8], secret: &[u8], seed: u64) -> u64 {
+    match data.len() {
+        0 => 0,
+        1..=3 => len_1to3_64b(data, secret, seed),
+        4..=8 => len_4to8_64b(data, secret, seed),
+        9..=16 => len_9to16_64b(data, secret, seed),
+        17..=128 => len_17to128_64b(data, secret, seed),
+        _ => len_129to240_64b(data, secret, seed),
+    }
+}
+
+#[inline(always)]
+fn len_1to3_128b(data: &[u8], secret: &[u8], seed: u64) -> u128 {
+    let len = data.len();
+    let c1 = u32::from(data[0]);
+    let c2 = u32::from(data[len >> 1]);
+    let c3 = u32::from(data[len - 1]);
+    let combinedl = c1 + (c2 << 8) + (c3 << 16) + ((len as u32) << 24);
+    let combinedh = combinedl.swap_bytes();
+    let keyedl = u64::from(combinedl) ^ u64::from(read32(secret)).wrapping_add(seed);
+    let keyedh = u64::from(combinedh) ^ u64::from(read32(&secret[4..])).wrapping_sub(seed);
+    let low64 = avalanche(keyedl.wrapping_mul(PRIME64_1));
+    let high64 = avalanche(keyedh.wrapping_mul(PRIME64_2));
+
+    u128::from(low64) + (u128::from(high64) << 64)
+}
+
+#[inline(always)]
+fn len_4to8_128b(data: &[u8], secret: &[u8], seed: u64) -> u128 {
+    let len = data.len();
+    let in1 = u64::from(read32(data));
+    let in2 = u64::from(read32(&data[len - 4..]));
+    let in64l = in1.wrapping_add(in2 << 32);
+    let in64h = in64l.swap_bytes();
+    let keyedl = in64l ^ read64(secret).wrapping_add(seed);
+    let keyedh = in64h ^ read64(&secret[8..]).wrapping_sub(seed);
+    let mix64l1 = (len as u64).wrapping_add((keyedl ^ (keyedl >> 51)).wrapping_mul(PRIME32_1));
+    let mix64l2 = (mix64l1 ^ (mix64l1 >> 47)).wrapping_mul(PRIME64_2);
+    let mix64h1 = (keyedh ^ (keyedh >> 47))
+        .wrapping_mul(PRIME64_1)
+        .wrapping_sub(len as u64);
+    let mix64h2 = (mix64h1 ^ (mix64h1 >> 43)).wrapping_mul(PRIME64_4);
+
+    u128::from(avalanche(mix64l2)) + (u128::from(avalanche(mix64h2)) << 64)
+}
+
+#[inline(always)]
+fn len_9to16_128b(data: &[u8], secret: &[u8], seed: u64) -> u128 {
+    let len = data.len();
+    let ll1 = read64(data) ^ read64(secret).wrapping_add(seed);
+    let ll2 = read64(&data[len - 8..]) ^ read64(&secret[8..]).wrapping_sub(seed);
+
+    let m128 = u128::from(ll1 ^ ll2).wrapping_mul(u128::from(PRIME64_1));
+    let high64 = ((m128 >> 64) as u64).wrapping_add(ll2.wrapping_mul(PRIME64_1));
+    let low64 = (m128 as u64) ^ (high64 >> 32);
+
+    let h128 = u128::from(low64).wrapping_mul(u128::from(PRIME64_2));
+    let high64 = ((h128 >> 64) as u64).wrapping_add(high64.wrapping_mul(PRIME64_2));
+    let low64 = h128 as u64;
+
+    u128::from(avalanche(low64)) + (u128::from(avalanche(high64)) << 64)
+}
+
+#[inline(always)]
+fn mix_acc128(acc1: u64, acc2: u64, len: usize, seed: u64) -> u128 {
+    let low64 = acc1.wrapping_add(acc2);
+    let high64 = acc1
+        .wrapping_mul(PRIME64_1)
+        .wrapping_add(acc2.wrapping_mul(PRIME64_4))
+        .wrapping_add((len as u64).wrapping_sub(seed).wrapping_mul(PRIME64_2));
+
+    u128::from(avalanche(low64)) + (u128::from(0u64.wrapping_sub(avalanche(high64))) << 64)
+}
+
+#[inline(always)]
+fn len_17to128_128b(data: &[u8], secret: &[u8], seed: u64) -> u128 {
+    let len = data.len();
+    let mut acc1 = (len as u64).wrapping_mul(PRIME64_1);
+    let mut acc2 = 0u64;
+
+    if len > 32 {
+        if len > 64 {
+            if len > 96 {
+                acc1 = acc1.wrapping_add(mix_16bytes(&data[48..], &secret[96..], seed));
+                acc2 = acc2.wrapping_add(mix_16bytes(&data[len - 64..], &secret[112..], seed));
+            }
+            acc1 = acc1.wrapping_add(mix_16bytes(&data[32..], &secret[64..], seed));
+            acc2 = acc2.wrapping_add(mix_16bytes(&data[len - 48..], &secret[80..], seed));
+        }
+        acc1 = acc1.wrapping_add(mix_16bytes(&data[16..], &secret[32..], seed));
+        acc2 = acc2.wrapping_add(mix_16bytes(&data[len - 32..], &secret[48..], seed));
+    }
+    acc1 = acc1.wrapping_add(mix_16bytes(data, secret, seed));
+    acc2 = acc2.wrapping_add(mix_16bytes(&data[len - 16..], &secret[16..], seed));
+
+    mix_acc128(acc1, acc2, len, seed)
+}
+
+#[inline(always)]
+fn len_129to240_128b(data: &[u8], secret: &[u8], seed: u64) -> u128 {
+    let len = data.len();
+    let nb_rounds = len / 32;
+    let neg_seed = 0u64.wrapping_sub(seed);
+
+    let mut acc1 = (len as u64).wrapping_mul(PRIME64_1);
+    let mut acc2 = 0u64;
+    for i in 0..4 {
+        acc1 = acc1.wrapping_add(mix_16bytes(&data[32 * i..], &secret[32 * i..], seed));
+        acc2 = acc2.wrapping_add(mix_16bytes(
+            &data[32 * i + 16..],
+            &secret[32 * i + 16..],
+            neg_seed,
+        ));
+    }
+    acc1 = avalanche(acc1);
+    acc2 = avalanche(acc2);
+
+    for i in 4..nb_rounds {
+        acc1 = acc1.wrapping_add(mix_16bytes(
+            &data[32 * i..],
+            &secret[32 * (i - 4) + MIDSIZE_STARTOFFSET..],
+            seed,
+        ));
+        acc2 = acc2.wrapping_add(mix_16bytes(
+            &data[32 * i + 16..],
+            &secret[32 * (i - 4) + 16 + MIDSIZE_STARTOFFSET..],
+            neg_seed,
+        ));
+    }
+    acc1 = acc1.wrapping_add(mix_16bytes(
+        &data[len - 16..],
+        &secret[super::SECRET_SIZE_MIN - MIDSIZE_LASTOFFSET..],
+        seed,
+    ));
+    acc2 = acc2.wrapping_add(mix_16bytes(
+        &data[len - 32..],
+        &secret[super::SECRET_SIZE_MIN - MIDSIZE_LASTOFFSET - 16..],
+        neg_seed,
+    ));
+
+    mix_acc128(acc1, acc2, len, seed)
+}
+
+#[inline(always)]
+fn hash128_short(data: &[u8], secret: &[u8], seed: u64) -> u128 {
+    match data.len() {
+        0 => 0,
+        1..=3 => len_1to3_128b(data, secret, seed),
+        4..=8 => len_4to8_128b(data, secret, seed),
+        9..=16 => len_9to16_128b(data, secret, seed),
+        17..=128 => len_17to128_128b(data, secret, seed),
+        _ => len_129to240_128b(data, secret, seed),
+    }
+}
+
+/* ===   Long keys   === */
+
+#[inline(always)]
+fn accumulate_512(acc: &mut [u64; ACC_NB], data: &[u8], secret: &[u8], width: AccWidth) {
+    for i in (0..ACC_NB).step_by(2) {
+        let in1 = read64(&data[8 * i..]);
+        let in2 = read64(&data[8 * (i + 1)..]);
+        let data_key1 = in1 ^ read64(&secret[8 * i..]);
+        let data_key2 = in2 ^ read64(&secret[8 * (i + 1)..]);
+
+        acc[i] = acc[i].wrapping_add((data_key1 & 0xFFFF_FFFF).wrapping_mul(data_key1 >> 32));
+        acc[i + 1] =
+            acc[i + 1].wrapping_add((data_key2 & 0xFFFF_FFFF).wrapping_mul(data_key2 >> 32));
+
+        if width == AccWidth::Acc128Bits {
+            acc[i] = acc[i].wrapping_add(in2);
+            acc[i + 1] = acc[i + 1].wrapping_add(in1);
+        } else {
+            acc[i] = acc[i].wrapping_add(in1);
+            acc[i + 1] = acc[i + 1].wrapping_add(in2);
+        }
+    }
+}
+
+#[inline(always)]
+fn scramble_acc(acc: &mut [u64; ACC_NB], secret: &[u8]) {
+    for (i, acc) in acc.iter_mut().enumerate() {
+        let mut acc64 = *acc;
+
+        acc64 ^= acc64 >> 47;
+        acc64 ^= read64(&secret[8 * i..]);
+        *acc = acc64.wrapping_mul(PRIME32_1);
+    }
+}
+
+#[inline(always)]
+fn accumulate(
+    acc: &mut [u64; ACC_NB],
+    data: &[u8],
+    secret: &[u8],
+    nb_stripes: usize,
+    width: AccWidth,
+) {
+    for n in 0..nb_stripes {
+        accumulate_512(
+            acc,
+            &data[n * STRIPE_LEN..],
+            &secret[n * SECRET_CONSUME_RATE..],
+            width,
+        );
+    }
+}
+
+#[inline(always)]
+fn hash_long(data: &[u8], secret: &[u8], width: AccWidth) -> [u64; ACC_NB] {
+    let len = data.len();
+    let nb_rounds = (secret.len() - STRIPE_LEN) / SECRET_CONSUME_RATE;
+    let block_len = STRIPE_LEN * nb_rounds;
+    let nb_blocks = len / block_len;
+
+    let mut acc = INIT_ACC;
+
+    for n in 0..nb_blocks {
+        accumulate(&mut acc, &data[n * block_len..], secret, nb_rounds, width);
+        scramble_acc(&mut acc, &secret[secret.len() - STRIPE_LEN..]);
+    }
+
+    // last partial block
+    let nb_stripes = (len - block_len * nb_blocks) / STRIPE_LEN;
+
+    accumulate(
+        &mut acc,
+        &data[nb_blocks * block_len..],
+        secret,
+        nb_stripes,
+        width,
+    );
+
+    // last stripe
+    if len & (STRIPE_LEN - 1) != 0 {
+        accumulate_512(
+            &mut acc,
+            &data[len - STRIPE_LEN..],
+            &secret[secret.len() - STRIPE_LEN - SECRET_LASTACC_START..],
+            width,
+        );
+    }
+
+    acc
+}
+
+#[inline(always)]
+fn merge_accs(acc: &[u64; ACC_NB], secret: &[u8], start: u64) -> u64 {
+    let mut result = start;
+
+    for i in 0..ACC_NB / 2 {
+        result = result.wrapping_add(mul128_fold64(
+            acc[2 * i] ^ read64(&secret[16 * i..]),
+            acc[2 * i + 1] ^ read64(&secret[16 * i + 8..]),
+        ));
+    }
+
+    avalanche(result)
+}
+
+#[inline(always)]
+fn merge_accs64(acc: &[u64; ACC_NB], secret: &[u8], len: u64) -> u64 {
+    merge_accs(
+        acc,
+        &secret[SECRET_MERGEACCS_START..],
+        len.wrapping_mul(PRIME64_1),
+    )
+}
+
+#[inline(always)]
+fn merge_accs128(acc: &[u64; ACC_NB], secret: &[u8], len: u64) -> u128 {
+    let low64 = merge_accs64(acc, secret, len);
+    let high64 = merge_accs(
+        acc,
+        &secret[secret.len() - ACC_SIZE - SECRET_MERGEACCS_START..],
+        !len.wrapping_mul(PRIME64_2),
+    );
+
+    u128::from(low64) + (u128::from(high64) << 64)
+}
+
+#[inline(always)]
+fn hash64_long(data: &[u8], secret: &[u8]) -> u64 {
+    let acc = hash_long(data, secret, AccWidth::Acc64Bits);
+
+    merge_accs64(&acc, secret, data.len() as u64)
+}
+
+#[inline(always)]
+fn hash128_long(data: &[u8], secret: &[u8]) -> u128 {
+    let acc = hash_long(data, secret, AccWidth::Acc128Bits);
+
+    merge_accs128(&acc, secret, data.len() as u64)
+}
+
+/* ===   One-shot   === */
+
+#[inline(always)]
+pub fn hash64(bytes: &[u8]) -> u64 {
+    hash64_with_seed(bytes, 0)
+}
+
+#[inline(always)]
+pub fn hash64_with_seed(bytes: &[u8], seed: u64) -> u64 {
+    if bytes.len() <= MIDSIZE_MAX {
+        hash64_short(bytes, &DEFAULT_SECRET, seed)
+    } else if seed == 0 {
+        hash64_long(bytes, &DEFAULT_SECRET)
+    } else {
+        hash64_long(bytes, &custom_secret(seed))
+    }
+}
+
+#[inline(always)]
+pub fn hash64_with_secret(bytes: &[u8], secret: &[u8]) -> u64 {
+    if bytes.len() <= MIDSIZE_MAX {
+        hash64_short(bytes, secret, 0)
+    } else {
+        hash64_long(bytes, secret)
+    }
+}
+
+#[inline(always)]
+pub fn hash128(bytes: &[u8]) -> u128 {
+    hash128_with_seed(bytes, 0)
+}
+
+#[inline(always)]
+pub fn hash128_with_seed(bytes: &[u8], seed: u64) -> u128 {
+    if bytes.len() <= MIDSIZE_MAX {
+        hash128_short(bytes, &DEFAULT_SECRET, seed)
+    } else if seed == 0 {
+        hash128_long(bytes, &DEFAULT_SECRET)
+    } else {
+        hash128_long(bytes, &custom_secret(seed))
+    }
+}
+
+#[inline(always)]
+pub fn hash128_with_secret(bytes: &[u8], secret: &[u8]) -> u128 {
+    if bytes.len() <= MIDSIZE_MAX {
+        hash128_short(bytes, secret, 0)
+    } else {
+        hash128_long(bytes, secret)
+    }
+}
+
+/* ===   Streaming   === */
+
+/// The secret used by a streaming state.
+#[derive(Clone)]
+enum Key {
+    Seeded([u8; SECRET_DEFAULT_SIZE]),
+    Custom(Arc<Secret<'static>>),
+}
+
+impl Key {
+    #[inline(always)]
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            Key::Seeded(secret) => &secret[..],
+            Key::Custom(secret) => secret.as_bytes(),
+        }
+    }
+}
+
+/// The streaming state, laid out like `XXH3_state_t`.
+#[derive(Clone)]
+pub struct State {
+    acc: [u64; ACC_NB],
+    buffer: [u8; INTERNAL_BUFFER_SIZE],
+    buffered_size: usize,
+    nb_stripes_so_far: usize,
+    total_len: u64,
+    seed: u64,
+    key: Key,
+    width: AccWidth,
+}
+
+/// Accumulates whole stripes, scrambling the accumulators at the end of each block.
+#[inline(always)]
+fn consume_stripes(
+    acc: &mut [u64; ACC_NB],
+    nb_stripes_so_far: &mut usize,
+    data: &[u8],
+    total_stripes: usize,
+    secret: &[u8],
+    width: AccWidth,
+) {
+    let secret_limit = secret.len() - STRIPE_LEN;
+    let nb_stripes_per_block = secret_limit / SECRET_CONSUME_RATE;
+
+    if nb_stripes_per_block - *nb_stripes_so_far <= total_stripes {
+        // need a scrambling operation
+        let nb_stripes = nb_stripes_per_block - *nb_stripes_so_far;
+
+        accumulate(
+            acc,
+            data,
+            &secret[*nb_stripes_so_far * SECRET_CONSUME_RATE..],
+            nb_stripes,
+            width,
+        );
+        scramble_acc(acc, &secret[secret_limit..]);
+        accumulate(
+            acc,
+            &data[nb_stripes * STRIPE_LEN..],
+            secret,
+            total_stripes - nb_stripes,
+            width,
+        );
+
+        *nb_stripes_so_far = total_stripes - nb_stripes;
+    } else {
+        accumulate(
+            acc,
+            data,
+            &secret[*nb_stripes_so_far * SECRET_CONSUME_RATE..],
+            total_stripes,
+            width,
+        );
+
+        *nb_stripes_so_far += total_stripes;
+    }
+}
+
+impl State {
+    #[inline(always)]
+    fn new(seed: u64, key: Key, width: AccWidth) -> Self {
+        State {
+            acc: INIT_ACC,
+            buffer: [0; INTERNAL_BUFFER_SIZE],
+            buffered_size: 0,
+            nb_stripes_so_far: 0,
+            total_len: 0,
+            seed,
+            key,
+            width,
+        }
+    }
+
+    #[inline(always)]
+    pub fn reset64_with_seed(seed: u64) -> Self {
+        State::new(seed, Key::Seeded(custom_secret(seed)), AccWidth::Acc64Bits)
+    }
+
+    #[inline(always)]
+    pub fn reset64_with_secret(secret: Arc<Secret<'static>>) -> Self {
+        State::new(0, Key::Custom(secret), AccWidth::Acc64Bits)
+    }
+
+    #[inline(always)]
+    pub fn reset128_with_seed(seed: u64) -> Self {
+        State::new(seed, Key::Seeded(custom_secret(seed)), AccWidth::Acc128Bits)
+    }
+
+    #[inline(always)]
+    pub fn reset128_with_secret(secret: Arc<Secret<'static>>) -> Self {
+        State::new(0, Key::Custom(secret), AccWidth::Acc128Bits)
+    }
+
+    #[inline(always)]
+    pub fn update64(&mut self, bytes: &[u8]) {
+        self.update(bytes)
+    }
+
+    #[inline(always)]
+    pub fn update128(&mut self, bytes: &[u8]) {
+        self.update(bytes)
+    }
+
+    fn update(&mut self, mut input: &[u8]) {
+        if input.is_empty() {
+            return;
+        }
+
+        self.total_len = self.total_len.wrapping_add(input.len() as u64);
+
+        if self.buffered_size + input.len() <= INTERNAL_BUFFER_SIZE {
+            self.buffer[self.buffered_size..self.buffered_size + input.len()]
+                .copy_from_slice(input);
+            self.buffered_size += input.len();
+            return;
+        }
+
+        if self.buffered_size > 0 {
+            // some data within internal buffer: fill then consume it
+            let (load, rest) = input.split_at(INTERNAL_BUFFER_SIZE - self.buffered_size);
+
+            self.buffer[self.buffered_size..].copy_from_slice(load);
+            input = rest;
+
+            consume_stripes(
+                &mut self.acc,
+                &mut self.nb_stripes_so_far,
+                &self.buffer,
+                INTERNAL_BUFFER_STRIPES,
+                self.key.as_bytes(),
+                self.width,
+            );
+            self.buffered_size = 0;
+        }
+
+        // consume input by full buffer quantities
+        let (blocks, rest) = input.split_at(input.len() - input.len() % INTERNAL_BUFFER_SIZE);
+
+        if !blocks.is_empty() {
+            for block in blocks.chunks(INTERNAL_BUFFER_SIZE) {
+                consume_stripes(
+                    &mut self.acc,
+                    &mut self.nb_stripes_so_far,
+                    block,
+                    INTERNAL_BUFFER_STRIPES,
+                    self.key.as_bytes(),
+                    self.width,
+                );
+            }
+
+            // keep the last consumed stripe at the end of the buffer, `digest_long` completes
+            // a short last stripe with it, like the one-shot hash does with the input
+            self.buffer[INTERNAL_BUFFER_SIZE - STRIPE_LEN..]
+                .copy_from_slice(&blocks[blocks.len() - STRIPE_LEN..]);
+            input = rest;
+        }
+
+        // some remaining input data: buffer it
+        self.buffer[..input.len()].copy_from_slice(input);
+        self.buffered_size = input.len();
+    }
+
+    /// Digests the pending stripes into a copy of the accumulators,
+    /// the state itself can continue ingesting more data afterwards.
+    fn digest_long(&self) -> [u64; ACC_NB] {
+        let secret = self.key.as_bytes();
+        let last_secret = &secret[secret.len() - STRIPE_LEN - SECRET_LASTACC_START..];
+        let mut acc = self.acc;
+
+        if self.buffered_size >= STRIPE_LEN {
+            let mut nb_stripes_so_far = self.nb_stripes_so_far;
+
+            consume_stripes(
+                &mut acc,
+                &mut nb_stripes_so_far,
+                &self.buffer,
+                self.buffered_size / STRIPE_LEN,
+                secret,
+                self.width,
+            );
+
+            if self.buffered_size & (STRIPE_LEN - 1) != 0 {
+                accumulate_512(
+                    &mut acc,
+                    &self.buffer[self.buffered_size - STRIPE_LEN..],
+                    last_secret,
+                    self.width,
+                );
+            }
+        } else if self.buffered_size > 0 {
+            // one last stripe, completed with the tail of the internal buffer
+            let mut last_stripe = [0; STRIPE_LEN];
+            let catchup_size = STRIPE_LEN - self.buffered_size;
+
+            last_stripe[..catchup_size]
+                .copy_from_slice(&self.buffer[INTERNAL_BUFFER_SIZE - catchup_size..]);
+            last_stripe[catchup_size..].copy_from_slice(&self.buffer[..self.buffered_size]);
+
+            accumulate_512(&mut acc, &last_stripe, last_secret, self.width);
+        }
+
+        acc
+    }
+
+    #[inline(always)]
+    pub fn digest64(&self) -> u64 {
+        if self.total_len > MIDSIZE_MAX as u64 {
+            merge_accs64(&self.digest_long(), self.key.as_bytes(), self.total_len)
+        } else if self.seed != 0 {
+            hash64_with_seed(&self.buffer[..self.buffered_size], self.seed)
+        } else {
+            hash64_with_secret(&self.buffer[..self.buffered_size], self.key.as_bytes())
+        }
+    }
+
+    #[inline(always)]
+    pub fn digest128(&self) -> u128 {
+        if self.total_len > MIDSIZE_MAX as u64 {
+            merge_accs128(&self.digest_long(), self.key.as_bytes(), self.total_len)
+        } else if self.seed != 0 {
+            hash128_with_seed(&self.buffer[..self.buffered_size], self.seed)
+        } else {
+            hash128_with_secret(&self.buffer[..self.buffered_size], self.key.as_bytes())
+        }
+    }
+
+    #[inline(always)]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    #[inline(always)]
+    pub fn secret(&self) -> Option<&Arc<Secret<'static>>> {
+        match self.key {
+            Key::Seeded(_) => None,
+            Key::Custom(ref secret) => Some(secret),
+        }
+    }
+
+    pub fn progress(&self) -> Progress {
+        Progress {
+            acc: self.acc,
+            buffer: self.buffer,
+            buffered_size: self.buffered_size,
+            nb_stripes_so_far: self.nb_stripes_so_far,
+            total_len: self.total_len,
+        }
+    }
+
+    pub fn set_progress(&mut self, progress: &Progress) {
+        self.acc = progress.acc;
+        self.buffer = progress.buffer;
+        self.buffered_size = progress.buffered_size;
+        self.nb_stripes_so_far = progress.nb_stripes_so_far;
+        self.total_len = progress.total_len;
+    }
+}
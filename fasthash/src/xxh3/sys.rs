@@ -0,0 +1,251 @@
+//! XXH3 backed by the vendored C implementation.
+use alloc::sync::Arc;
+use core::mem;
+use core::ptr::NonNull;
+
+use super::{Progress, Secret, INTERNAL_BUFFER_SIZE};
+
+#[inline(always)]
+pub fn hash64(bytes: &[u8]) -> u64 {
+    unsafe { ffi::XXH3_64bits(bytes.as_ptr() as *const _, bytes.len()) }
+}
+
+#[inline(always)]
+pub fn hash64_with_seed(bytes: &[u8], seed: u64) -> u64 {
+    unsafe { ffi::XXH3_64bits_withSeed(bytes.as_ptr() as *const _, bytes.len(), seed) }
+}
+
+#[inline(always)]
+pub fn hash64_with_secret(bytes: &[u8], secret: &[u8]) -> u64 {
+    unsafe {
+        ffi::XXH3_64bits_withSecret(
+            bytes.as_ptr() as *const _,
+            bytes.len(),
+            secret.as_ptr() as *const _,
+            secret.len(),
+        )
+    }
+}
+
+#[inline(always)]
+pub fn hash128(bytes: &[u8]) -> u128 {
+    unsafe { mem::transmute(ffi::XXH3_128bits(bytes.as_ptr() as *const _, bytes.len())) }
+}
+
+#[inline(always)]
+pub fn hash128_with_seed(bytes: &[u8], seed: u64) -> u128 {
+    unsafe {
+        mem::transmute(ffi::XXH3_128bits_withSeed(
+            bytes.as_ptr() as *const _,
+            bytes.len(),
+            seed,
+        ))
+    }
+}
+
+#[inline(always)]
+pub fn hash128_with_secret(bytes: &[u8], secret: &[u8]) -> u128 {
+    unsafe {
+        mem::transmute(ffi::XXH3_128bits_withSecret(
+            bytes.as_ptr() as *const _,
+            bytes.len(),
+            secret.as_ptr() as *const _,
+            secret.len(),
+        ))
+    }
+}
+
+/// The streaming state, allocated by `XXH3_createState`.
+///
+/// A custom secret is referenced by the C state, so it is kept alive here.
+pub struct State {
+    state: NonNull<ffi::XXH3_state_t>,
+    secret: Option<Arc<Secret<'static>>>,
+}
+
+impl State {
+    #[inline(always)]
+    fn new(secret: Option<Arc<Secret<'static>>>) -> Self {
+        State {
+            state: unsafe { NonNull::new_unchecked(ffi::XXH3_createState()) },
+            secret,
+        }
+    }
+
+    #[inline(always)]
+    pub fn reset64_with_seed(seed: u64) -> Self {
+        let state = State::new(None);
+
+        unsafe {
+            ffi::XXH3_64bits_reset_withSeed(state.state.as_ptr(), seed);
+        }
+
+        state
+    }
+
+    #[inline(always)]
+    pub fn reset64_with_secret(secret: Arc<Secret<'static>>) -> Self {
+        let state = State::new(Some(secret.clone()));
+
+        unsafe {
+            ffi::XXH3_64bits_reset_withSecret(
+                state.state.as_ptr(),
+                secret.as_bytes().as_ptr() as *const _,
+                secret.len(),
+            );
+        }
+
+        state
+    }
+
+    #[inline(always)]
+    pub fn reset128_with_seed(seed: u64) -> Self {
+        let state = State::new(None);
+
+        unsafe {
+            ffi::XXH3_128bits_reset_withSeed(state.state.as_ptr(), seed);
+        }
+
+        state
+    }
+
+    #[inline(always)]
+    pub fn reset128_with_secret(secret: Arc<Secret<'static>>) -> Self {
+        let state = State::new(Some(secret.clone()));
+
+        unsafe {
+            ffi::XXH3_128bits_reset_withSecret(
+                state.state.as_ptr(),
+                secret.as_bytes().as_ptr() as *const _,
+                secret.len(),
+            );
+        }
+
+        state
+    }
+
+    /// Feeds the state less than a buffer at a time.
+    ///
+    /// `XXH3_64bits_update` consumes a large input in place, without copying it into the buffer,
+    /// but the digest completes a short last stripe from the end of the buffer, so a long message
+    /// wouldn't hash like the one-shot `XXH3_64bits`.
+    #[inline(always)]
+    pub fn update64(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(INTERNAL_BUFFER_SIZE - 1) {
+            unsafe {
+                ffi::XXH3_64bits_update(
+                    self.state.as_ptr(),
+                    chunk.as_ptr() as *const _,
+                    chunk.len(),
+                );
+            }
+        }
+    }
+
+    /// Feeds the state less than a buffer at a time, see `update64`.
+    #[inline(always)]
+    pub fn update128(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(INTERNAL_BUFFER_SIZE - 1) {
+            unsafe {
+                ffi::XXH3_128bits_update(
+                    self.state.as_ptr(),
+                    chunk.as_ptr() as *const _,
+                    chunk.len(),
+                );
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub fn digest64(&self) -> u64 {
+        unsafe { ffi::XXH3_64bits_digest(self.state.as_ptr()) }
+    }
+
+    #[inline(always)]
+    pub fn digest128(&self) -> u128 {
+        let h = unsafe { ffi::XXH3_128bits_digest(self.state.as_ptr()) };
+
+        u128::from(h.low64) + (u128::from(h.high64) << 64)
+    }
+
+    #[inline(always)]
+    pub fn seed(&self) -> u64 {
+        unsafe { self.state.as_ref().seed }
+    }
+
+    #[inline(always)]
+    pub fn secret(&self) -> Option<&Arc<Secret<'static>>> {
+        self.secret.as_ref()
+    }
+
+    pub fn progress(&self) -> Progress {
+        let state = unsafe { self.state.as_ref() };
+        let mut buffer = [0; INTERNAL_BUFFER_SIZE];
+
+        for (b, &c) in buffer.iter_mut().zip(state.buffer.iter()) {
+            *b = c as u8;
+        }
+
+        Progress {
+            acc: state.acc,
+            buffer,
+            buffered_size: state.bufferedSize as usize,
+            nb_stripes_so_far: state.nbStripesSoFar as usize,
+            total_len: state.totalLen,
+        }
+    }
+
+    pub fn set_progress(&mut self, progress: &Progress) {
+        let state = unsafe { self.state.as_mut() };
+
+        for (c, &b) in state.buffer.iter_mut().zip(progress.buffer.iter()) {
+            *c = b as _;
+        }
+
+        state.acc = progress.acc;
+        state.bufferedSize = progress.buffered_size as u32;
+        state.nbStripesSoFar = progress.nb_stripes_so_far as u32;
+        state.totalLen = progress.total_len;
+    }
+}
+
+impl State {
+    /// Copies the C state of `source`.
+    ///
+    /// A seeded state points to the secret derived into its own buffer,
+    /// which `XXH3_copyState` copies as is, so it would still point into `source`.
+    fn copy_state(&mut self, source: &State) {
+        unsafe {
+            let dst = self.state.as_ptr();
+            let src = source.state.as_ptr();
+
+            ffi::XXH3_copyState(dst, src);
+
+            if (*src).secret == (*src).customSecret.as_ptr() as *const _ {
+                (*dst).secret = (*dst).customSecret.as_ptr() as *const _;
+            }
+        }
+    }
+}
+
+impl Clone for State {
+    fn clone(&self) -> Self {
+        let mut state = State::new(self.secret.clone());
+
+        state.copy_state(self);
+        state
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.copy_state(source);
+        self.secret = source.secret.clone();
+    }
+}
+
+impl Drop for State {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::XXH3_freeState(self.state.as_ptr());
+        }
+    }
+}
@@ -0,0 +1,318 @@
+//! Property tests of the streaming hashers.
+//!
+//! Random keys are split at random points and written chunk by chunk, the hash must be the same
+//! as the one-shot `hash` and `hash_with_seed` of the whole key, for a random seed.
+//!
+//! A snapshot of the hasher taken midway must continue like the hasher itself,
+//! and so must a hasher imported from its exported state.
+//!
+//! The equality doesn't hold for every hasher, they are checked separately:
+//!
+//! - `sea::Hasher64`, the `SeaHasher` of seahash 3 hashes every write on its own and mixes it
+//!   into the state, so its hash depends on the chunks.
+//! - `t1ha2::Hasher128`, the streaming `t1ha2` pads the message with its length, and seeds
+//!   the state with two words instead of the seed and the length, so it's a different function
+//!   than `t1ha2::Hash64AtOnce` and `t1ha2::Hash128AtOnce`. It only has to ignore the chunks.
+#![cfg_attr(not(feature = "sys"), allow(dead_code, unused_macros))]
+
+use std::fmt;
+use std::hash::Hasher;
+
+use proptest::prelude::*;
+use proptest::sample::Index;
+
+use fasthash::*;
+
+/// The random keys, longer than the blocks of the hashers.
+fn keys() -> impl Strategy<Value = Vec<u8>> {
+    prop::collection::vec(any::<u8>(), 0..1500)
+}
+
+/// The random split points of a key.
+fn points() -> impl Strategy<Value = Vec<Index>> {
+    prop::collection::vec(any::<Index>(), 0..8)
+}
+
+/// Splits the key at the points, some chunks may be empty.
+fn split<'a>(key: &'a [u8], points: &[Index]) -> Vec<&'a [u8]> {
+    let mut points = points
+        .iter()
+        .map(|point| point.index(key.len() + 1))
+        .collect::<Vec<_>>();
+
+    points.sort();
+
+    let mut chunks = Vec::with_capacity(points.len() + 1);
+    let mut start = 0;
+
+    for point in points {
+        chunks.push(&key[start..point]);
+        start = point;
+    }
+
+    chunks.push(&key[start..]);
+    chunks
+}
+
+/// Writes the chunks to the hasher.
+fn stream<T: Hasher>(mut h: T, chunks: &[&[u8]]) -> T {
+    for chunk in chunks {
+        h.write(chunk);
+    }
+
+    h
+}
+
+/// Checks the snapshots and the exported states of a hasher taken after the first split point,
+/// the hashes of the key must be `hash`.
+fn check_snapshot<T: Snapshot + HasherState, O: PartialEq + fmt::Debug>(
+    h: T,
+    key: &[u8],
+    points: &[Index],
+    hash: O,
+    finish: fn(&T) -> O,
+) -> Result<(), TestCaseError> {
+    let mid = points.first().map_or(0, |point| point.index(key.len() + 1));
+    let (prefix, suffix) = key.split_at(mid);
+    let mut h = stream(h, &[prefix]);
+
+    // a hash midway doesn't change the state
+    finish(&h);
+
+    let snapshot = h.snapshot();
+    let exported = h.to_bytes();
+
+    // the snapshot doesn't share the state of the hasher
+    h.write(b"garbage");
+    drop(h);
+
+    let mut fork = stream(snapshot.snapshot(), &[suffix]);
+
+    prop_assert_eq!(&finish(&fork), &hash, "snapshot");
+
+    fork.restore(&snapshot);
+    drop(snapshot);
+    fork.write(suffix);
+
+    prop_assert_eq!(&finish(&fork), &hash, "restored");
+
+    let imported = stream(T::from_bytes(&exported).unwrap(), &[suffix]);
+
+    prop_assert_eq!(finish(&imported), hash, "imported");
+
+    Ok(())
+}
+
+/// Checks the `FastHasher` of a variant, `seed` converts its seed into the seed of the `FastHasher`,
+/// and `finish` returns the hash of the `FastHasher`.
+fn check<H, F>(
+    key: &[u8],
+    points: &[Index],
+    seed: H::Seed,
+    to_seed: F,
+    finish: fn(&H::FastHasher) -> u128,
+) -> Result<(), TestCaseError>
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+    H::Seed: Copy,
+    H::FastHasher: Snapshot + HasherState,
+    F: Fn(H::Seed) -> <H::FastHasher as FastHasher>::Seed,
+{
+    let chunks = split(key, points);
+
+    prop_assert_eq!(
+        finish(&stream(<H::FastHasher as FastHasher>::new(), &chunks)),
+        H::hash(key).into(),
+        "unseeded"
+    );
+    prop_assert_eq!(
+        finish(&stream(
+            <H::FastHasher as FastHasher>::with_seed(to_seed(seed)),
+            &chunks
+        )),
+        H::hash_with_seed(key, seed).into(),
+        "seeded"
+    );
+
+    check_snapshot(
+        <H::FastHasher as FastHasher>::with_seed(to_seed(seed)),
+        key,
+        points,
+        H::hash_with_seed(key, seed).into(),
+        finish,
+    )
+}
+
+/// Checks that the hash of a `FastHasher` doesn't depend on the chunks.
+fn check_chunks<T>(
+    key: &[u8],
+    points: &[Index],
+    seed: T::Seed,
+    finish: fn(&T) -> u128,
+) -> Result<(), TestCaseError>
+where
+    T: Snapshot + HasherState,
+{
+    let chunks = split(key, points);
+
+    prop_assert_eq!(
+        finish(&stream(T::new(), &chunks)),
+        finish(&stream(T::new(), &[key])),
+        "unseeded"
+    );
+    prop_assert_eq!(
+        finish(&stream(T::with_seed(seed), &chunks)),
+        finish(&stream(T::with_seed(seed), &[key])),
+        "seeded"
+    );
+
+    check_snapshot(
+        T::with_seed(seed),
+        key,
+        points,
+        finish(&stream(T::with_seed(seed), &[key])),
+        finish,
+    )
+}
+
+fn finish<H: Hasher>(h: &H) -> u128 {
+    u128::from(h.finish())
+}
+
+fn finish_ext<H: HasherExt>(h: &H) -> u128 {
+    h.finish_ext()
+}
+
+macro_rules! streaming {
+    ($($name:ident: $hash:ty => $finish:ident $(, $seed:expr)?;)*) => {
+        proptest! {
+            $(
+                #[test]
+                fn $name(key in keys(), points in points(), seed in any::<<$hash as FastHash>::Seed>()) {
+                    check::<$hash, _>(&key, &points, seed, streaming!(@seed $($seed)?), $finish)?;
+                }
+            )*
+        }
+    };
+    (@seed) => { |seed| seed };
+    (@seed $seed:expr) => { $seed };
+}
+
+#[cfg(any(feature = "sys", feature = "pure-city"))]
+streaming! {
+    city_hash32: city::Hash32 => finish;
+    city_hash64: city::Hash64 => finish;
+    city_hash128: city::Hash128 => finish_ext;
+}
+
+// the pure Rust port only covers the fingerprints
+#[cfg(feature = "sys")]
+streaming! {
+    farm_hash32: farm::Hash32 => finish;
+    farm_hash64: farm::Hash64 => finish;
+    farm_hash128: farm::Hash128 => finish_ext;
+}
+
+#[cfg(any(feature = "sys", feature = "pure-highway"))]
+streaming! {
+    highway_hash64: highway::Hash64 => finish;
+    highway_hash128: highway::Hash128 => finish_ext;
+}
+
+#[cfg(any(feature = "sys", feature = "pure-t1ha"))]
+streaming! {
+    t1ha0_hash64: t1ha0::Hash64 => finish;
+    t1ha1_hash64_le: t1ha1::Hash64Le => finish;
+    t1ha1_hash64_be: t1ha1::Hash64Be => finish;
+}
+
+#[cfg(any(feature = "sys", feature = "pure-xxh3"))]
+streaming! {
+    xxh3_hash64: xxh3::Hash64 => finish;
+    xxh3_hash128: xxh3::Hash128 => finish_ext;
+}
+
+#[cfg(feature = "sys")]
+streaming! {
+    lookup3_hash32: lookup3::Hash32 => finish;
+    metro_hash64_1: metro::Hash64_1 => finish;
+    metro_hash64_2: metro::Hash64_2 => finish;
+    metro_hash128_1: metro::Hash128_1 => finish_ext;
+    metro_hash128_2: metro::Hash128_2 => finish_ext;
+    mum_hash64: mum::Hash64 => finish;
+    murmur_hash32: murmur::Hash32 => finish;
+    murmur_hash32_aligned: murmur::Hash32Aligned => finish;
+    murmur2_hash32: murmur2::Hash32 => finish;
+    murmur2_hash32a: murmur2::Hash32A => finish;
+    murmur2_hash32_neutral: murmur2::Hash32Neutral => finish;
+    murmur2_hash32_aligned: murmur2::Hash32Aligned => finish;
+    murmur2_hash64_x64: murmur2::Hash64_x64 => finish;
+    murmur2_hash64_x86: murmur2::Hash64_x86 => finish;
+    murmur3_hash32: murmur3::Hash32 => finish;
+    murmur3_hash128_x86: murmur3::Hash128_x86 => finish_ext;
+    murmur3_hash128_x64: murmur3::Hash128_x64 => finish_ext;
+    spooky_hash32: spooky::Hash32 => finish;
+    spooky_hash64: spooky::Hash64 => finish;
+    spooky_hash128: spooky::Hash128 => finish_ext, |seed: u128| ((seed >> 64) as u64, seed as u64);
+    xx_hash32: xx::Hash32 => finish;
+    xx_hash64: xx::Hash64 => finish;
+}
+
+#[cfg(all(feature = "sys", any(feature = "sse42", target_feature = "sse4.2")))]
+streaming! {
+    metro_crc_hash64_1: metro::crc::Hash64_1 => finish;
+    metro_crc_hash64_2: metro::crc::Hash64_2 => finish;
+    metro_crc_hash128_1: metro::crc::Hash128_1 => finish_ext;
+    metro_crc_hash128_2: metro::crc::Hash128_2 => finish_ext;
+}
+
+#[cfg(any(
+    feature = "pure-city",
+    all(feature = "sys", any(feature = "sse42", target_feature = "sse4.2"))
+))]
+streaming! {
+    city_crc_hash128: city::crc::Hash128 => finish_ext;
+}
+
+// `Hash256` doesn't implement `FastHash`, its 256-bit hash doesn't fit in an integer
+#[cfg(any(feature = "sys", feature = "pure-highway"))]
+proptest! {
+    #[test]
+    fn highway_hash256(key in keys(), points in points(), seed in any::<highway::Seed>()) {
+        let chunks = split(&key, &points);
+        let finish = highway::Hasher256::finish256;
+
+        prop_assert_eq!(
+            finish(&stream(highway::Hasher256::new(), &chunks)),
+            highway::Hash256::hash(&key),
+            "unseeded"
+        );
+        prop_assert_eq!(
+            finish(&stream(highway::Hasher256::with_seed(seed), &chunks)),
+            highway::Hash256::hash_with_seed(&key, seed),
+            "seeded"
+        );
+
+        check_snapshot(
+            highway::Hasher256::with_seed(seed),
+            &key,
+            &points,
+            highway::Hash256::hash_with_seed(&key, seed),
+            finish,
+        )?;
+    }
+}
+
+#[cfg(any(feature = "sys", feature = "pure-t1ha"))]
+proptest! {
+    #[test]
+    fn t1ha2_hasher128_finish(key in keys(), points in points(), seed in any::<(u64, u64)>()) {
+        check_chunks::<t1ha2::Hasher128>(&key, &points, seed, finish)?;
+    }
+
+    #[test]
+    fn t1ha2_hasher128_finish_ext(key in keys(), points in points(), seed in any::<(u64, u64)>()) {
+        check_chunks::<t1ha2::Hasher128>(&key, &points, seed, finish_ext)?;
+    }
+}
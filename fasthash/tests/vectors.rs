@@ -0,0 +1,1628 @@
+//! Known-answer tests of every hash function.
+//!
+//! Each file in `tests/vectors` lists the hashes of a variant, one line per case.
+//!
+//! ```text
+//! # len seed hash
+//! 14 - 8282dcc4994e35c8
+//! 14 9e3779b1 c3bd6bf63deb6df0
+//! ```
+//!
+//! The key is the first `len` bytes of the `xxhsum` sanity buffer, for all the lengths up to 256 bytes
+//! and a few larger ones. The seed is `-` for the unseeded `hash`, or the comma separated 64-bit words
+//! of the seed, low word first for a `u128`. The hash is in hexadecimal, the 256-bit hashes are
+//! their four 64-bit words in order.
+//!
+//! Every case is checked with the one-shot functions, `hash_u32` or `hash_u64` for the 4 or 8-byte keys,
+//! and the `FastHasher` of the variant, written at once and in small chunks.
+//!
+//! The files are snapshots generated from the bundled C sources, they are not published values.
+//! They catch any drift of the C sources or of the pure Rust ports, but only the published
+//! reference values tell whether they are right:
+//!
+//! - `upstream` checks the `xxhsum` sanity values of XXH32 and XXH64 and the SMHasher verification
+//!   codes of the other C functions, with the `sys` feature.
+//! - `city_reference` and `t1ha_reference` check the SMHasher verification codes of `city` and `t1ha`,
+//!   and the self-check of `t1ha`, including the AES-NI `t1ha0`, with the C sources or the pure Rust ports.
+//! - `highway_reference` checks the `HighwayHash64` verifier of every target.
+//!
+//! The XXH3, 128 and 256-bit `HighwayHash` and `FarmHash` fingerprint files have no other reference
+//! here, the pure Rust ports are only checked against these snapshots.
+//! Run the tests with `FASTHASH_UPDATE_VECTORS=1` to regenerate them after an intended change,
+//! e.g. updating a submodule, and review the diff.
+//!
+//! Each variant is checked when its module is built, by the C implementation or a pure Rust port.
+// the helpers are not all used by the pure Rust ports
+#![cfg_attr(not(feature = "sys"), allow(dead_code, unused_macros))]
+
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::hash::Hasher;
+use std::mem;
+use std::path::Path;
+
+use fasthash::*;
+
+const PRIME32: u64 = 0x9e37_79b1;
+const PRIME64: u64 = 0x9e37_79b1_85eb_ca8d;
+
+/// The larger lengths of the keys, after all the lengths up to 256 bytes.
+const LENGTHS: &[usize] = &[512, 1000, 1024, 4096, 65536];
+
+/// The chunk sizes of the streaming writes.
+const CHUNKS: &[usize] = &[1, 2, 3, 5, 8, 13, 21, 34, 55, 89];
+
+/// Returns the `xxhsum` sanity buffer.
+fn buffer(len: usize) -> Vec<u8> {
+    let mut gen = PRIME32;
+
+    (0..len)
+        .map(|_| {
+            let b = (gen >> 56) as u8;
+            gen = gen.wrapping_mul(PRIME64);
+            b
+        })
+        .collect()
+}
+
+/// The seeds that can be written as 64-bit words.
+trait Words: Copy {
+    /// The number of words.
+    const WORDS: usize;
+    /// The mask of each word.
+    const MASK: u64 = !0;
+
+    fn from_words(words: &[u64]) -> Self;
+
+    fn to_words(self) -> Vec<u64>;
+
+    /// The seeds of the vectors.
+    fn seeds() -> Vec<Self> {
+        vec![
+            (0..Self::WORDS as u64).map(|i| PRIME32 + i).collect(),
+            (0..Self::WORDS as u32)
+                .map(|i| 0x0123_4567_89ab_cdef_u64.rotate_left(8 * i) & Self::MASK)
+                .collect::<Vec<_>>(),
+        ]
+        .into_iter()
+        .map(|words| Self::from_words(&words))
+        .collect()
+    }
+}
+
+impl Words for u32 {
+    const WORDS: usize = 1;
+    const MASK: u64 = 0xffff_ffff;
+
+    fn from_words(words: &[u64]) -> Self {
+        words[0] as u32
+    }
+
+    fn to_words(self) -> Vec<u64> {
+        vec![u64::from(self)]
+    }
+}
+
+impl Words for u64 {
+    const WORDS: usize = 1;
+
+    fn from_words(words: &[u64]) -> Self {
+        words[0]
+    }
+
+    fn to_words(self) -> Vec<u64> {
+        vec![self]
+    }
+}
+
+impl Words for u128 {
+    const WORDS: usize = 2;
+
+    fn from_words(words: &[u64]) -> Self {
+        u128::from(words[1]) << 64 | u128::from(words[0])
+    }
+
+    fn to_words(self) -> Vec<u64> {
+        vec![self as u64, (self >> 64) as u64]
+    }
+}
+
+impl Words for (u64, u64, u64, u64) {
+    const WORDS: usize = 4;
+
+    fn from_words(words: &[u64]) -> Self {
+        (words[0], words[1], words[2], words[3])
+    }
+
+    fn to_words(self) -> Vec<u64> {
+        vec![self.0, self.1, self.2, self.3]
+    }
+}
+
+impl Words for [u64; 4] {
+    const WORDS: usize = 4;
+
+    fn from_words(words: &[u64]) -> Self {
+        [words[0], words[1], words[2], words[3]]
+    }
+
+    fn to_words(self) -> Vec<u64> {
+        self.to_vec()
+    }
+}
+
+impl Words for (u64, u64) {
+    const WORDS: usize = 2;
+
+    fn from_words(words: &[u64]) -> Self {
+        (words[0], words[1])
+    }
+
+    fn to_words(self) -> Vec<u64> {
+        vec![self.0, self.1]
+    }
+}
+
+/// The variants without a seed.
+impl Words for () {
+    const WORDS: usize = 0;
+
+    fn from_words(_: &[u64]) -> Self {}
+
+    fn to_words(self) -> Vec<u64> {
+        vec![]
+    }
+
+    fn seeds() -> Vec<Self> {
+        vec![]
+    }
+}
+
+/// The hashes written in hexadecimal.
+trait Hex: PartialEq + std::fmt::Debug {
+    fn to_hex(&self, bits: usize) -> String;
+}
+
+impl Hex for u128 {
+    fn to_hex(&self, bits: usize) -> String {
+        format!("{:01$x}", self, bits / 4)
+    }
+}
+
+impl Hex for [u64; 4] {
+    fn to_hex(&self, _bits: usize) -> String {
+        self.iter().map(|w| format!("{:016x}", w)).collect()
+    }
+}
+
+/// Renders the vectors of `hash`, with `bits` hashes, and compares them with the file.
+fn check_vectors<S, D, F>(file: &str, title: &str, bits: usize, hash: F)
+where
+    S: Words,
+    D: Hex,
+    F: Fn(&[u8], Option<S>) -> D,
+{
+    let data = buffer(*LENGTHS.last().unwrap());
+    let mut vectors = format!("# {}, see tests/vectors.rs\n# len seed hash\n", title);
+
+    for len in (0..=256).chain(LENGTHS.iter().cloned()) {
+        for seed in Some(None)
+            .into_iter()
+            .chain(S::seeds().into_iter().map(Some))
+        {
+            let words = seed.map_or_else(
+                || "-".to_owned(),
+                |seed| {
+                    seed.to_words()
+                        .iter()
+                        .map(|w| format!("{:x}", w))
+                        .collect::<Vec<_>>()
+                        .join(",")
+                },
+            );
+
+            writeln!(
+                vectors,
+                "{} {} {}",
+                len,
+                words,
+                hash(&data[..len], seed).to_hex(bits)
+            )
+            .unwrap();
+        }
+    }
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/vectors")
+        .join(file)
+        .with_extension("txt");
+
+    if env::var_os("FASTHASH_UPDATE_VECTORS").is_some() {
+        fs::write(&path, vectors).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("failed to read {}: {}", path.display(), err));
+
+    for (line, (actual, expected)) in vectors.lines().zip(expected.lines()).enumerate() {
+        assert_eq!(
+            actual,
+            expected,
+            "{}:{} doesn't match",
+            path.display(),
+            line + 1
+        );
+    }
+
+    assert_eq!(
+        vectors.lines().count(),
+        expected.lines().count(),
+        "{} has a different number of vectors",
+        path.display()
+    );
+}
+
+/// Hashes a key with the one-shot functions.
+fn at_once<H>(key: &[u8], seed: Option<H::Seed>) -> u128
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+    H::Seed: Words,
+{
+    let mut n = [0; 8];
+
+    n[..key.len().min(8)].copy_from_slice(&key[..key.len().min(8)]);
+
+    let n32 = u32::from_le_bytes([n[0], n[1], n[2], n[3]]);
+    let n64 = u64::from_le_bytes(n);
+    let (hash, short) = match seed {
+        Some(seed) => (
+            H::hash_with_seed(key, seed),
+            match key.len() {
+                4 => Some(H::hash_u32_with_seed(n32, seed)),
+                8 => Some(H::hash_u64_with_seed(n64, seed)),
+                _ => None,
+            },
+        ),
+        None => (
+            H::hash(key),
+            match key.len() {
+                4 => Some(H::hash_u32(n32)),
+                8 => Some(H::hash_u64(n64)),
+                _ => None,
+            },
+        ),
+    };
+    let hash = hash.into();
+
+    if let Some(short) = short {
+        assert_eq!(
+            short.into(),
+            hash,
+            "{} bytes, seed {:?}, integer",
+            key.len(),
+            seed.map(Words::to_words)
+        );
+    }
+
+    hash
+}
+
+/// Hashes a key with a hasher, written at once and in small chunks.
+fn streaming<T, D, F>(key: &[u8], new: F, finish: fn(&T) -> D) -> D
+where
+    T: Hasher,
+    D: Hex,
+    F: Fn() -> T,
+{
+    let mut h = new();
+
+    h.write(key);
+
+    let hash = finish(&h);
+    let mut h = new();
+    let mut rest = key;
+
+    for &chunk in CHUNKS.iter().cycle() {
+        if rest.is_empty() {
+            break;
+        }
+
+        let (head, tail) = rest.split_at(chunk.min(rest.len()));
+
+        h.write(head);
+        rest = tail;
+    }
+
+    assert_eq!(finish(&h), hash, "{} bytes, in chunks", key.len());
+
+    hash
+}
+
+/// Checks a variant whose `FastHasher` gives the same hashes, `seed` converts its seed
+/// into the seed of the `FastHasher`, and `finish` returns the hash of the `FastHasher`.
+fn check<H, F>(file: &str, seed: F, finish: fn(&H::FastHasher) -> u128)
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+    H::Seed: Words,
+    F: Fn(H::Seed) -> <H::FastHasher as FastHasher>::Seed,
+{
+    let title = std::any::type_name::<H>();
+
+    check_vectors(file, title, mem::size_of::<H::Hash>() * 8, |key, s| {
+        let hash = at_once::<H>(key, s);
+        let new = || {
+            s.map_or_else(<H::FastHasher as FastHasher>::new, |s| {
+                <H::FastHasher as FastHasher>::with_seed(seed(s))
+            })
+        };
+
+        assert_eq!(
+            streaming(key, new, finish),
+            hash,
+            "{} bytes, seed {:?}, streaming",
+            key.len(),
+            s.map(Words::to_words)
+        );
+
+        hash
+    });
+}
+
+/// Checks a variant with the one-shot functions only.
+fn check_at_once<H>(file: &str)
+where
+    H: FastHash,
+    H::Hash: Into<u128>,
+    H::Seed: Words,
+{
+    let title = std::any::type_name::<H>();
+
+    check_vectors(file, title, mem::size_of::<H::Hash>() * 8, at_once::<H>);
+}
+
+/// Checks a hasher whose hashes differ from the one-shot functions.
+fn check_stream<T>(file: &str, method: &str, bits: usize, finish: fn(&T) -> u128)
+where
+    T: FastHasher,
+    T::Seed: Words,
+{
+    let title = format!("{}::{}", std::any::type_name::<T>(), method);
+
+    check_vectors(file, &title, bits, |key, seed: Option<T::Seed>| {
+        streaming(key, || seed.map_or_else(T::new, T::with_seed), finish)
+    });
+}
+
+fn finish<H: Hasher>(h: &H) -> u128 {
+    u128::from(h.finish())
+}
+
+fn finish_ext<H: HasherExt>(h: &H) -> u128 {
+    h.finish_ext()
+}
+
+macro_rules! vectors {
+    ($($name:ident: $hash:ty => $finish:ident $(, $seed:expr)?;)*) => {
+        $(
+            #[test]
+            fn $name() {
+                check::<$hash, _>(stringify!($name), vectors!(@seed $($seed)?), $finish);
+            }
+        )*
+    };
+    (@seed) => { |seed| seed };
+    (@seed $seed:expr) => { $seed };
+}
+
+macro_rules! at_once {
+    ($($name:ident: $hash:ty;)*) => {
+        $(
+            #[test]
+            fn $name() {
+                check_at_once::<$hash>(stringify!($name));
+            }
+        )*
+    };
+}
+
+macro_rules! streams {
+    ($($name:ident: $hasher:ty => $finish:ident, $bits:expr;)*) => {
+        $(
+            #[test]
+            fn $name() {
+                check_stream::<$hasher>(stringify!($name), stringify!($finish), $bits, $finish);
+            }
+        )*
+    };
+}
+
+#[cfg(any(feature = "sys", feature = "pure-city"))]
+vectors! {
+    city_hash32: city::Hash32 => finish;
+    city_hash64: city::Hash64 => finish;
+    city_hash128: city::Hash128 => finish_ext;
+}
+
+// the pure Rust port only covers the fingerprints
+#[cfg(feature = "sys")]
+vectors! {
+    farm_hash32: farm::Hash32 => finish;
+    farm_hash64: farm::Hash64 => finish;
+    farm_hash128: farm::Hash128 => finish_ext;
+}
+
+#[cfg(any(feature = "sys", feature = "pure-highway"))]
+vectors! {
+    highway_hash64: highway::Hash64 => finish;
+    highway_hash128: highway::Hash128 => finish_ext;
+}
+
+#[cfg(any(feature = "sys", feature = "pure-t1ha"))]
+vectors! {
+    t1ha1_hash64_le: t1ha1::Hash64Le => finish;
+    t1ha1_hash64_be: t1ha1::Hash64Be => finish;
+}
+
+#[cfg(any(feature = "sys", feature = "pure-xxh3"))]
+vectors! {
+    xxh3_hash64: xxh3::Hash64 => finish;
+    xxh3_hash128: xxh3::Hash128 => finish_ext;
+}
+
+#[cfg(feature = "sys")]
+vectors! {
+    lookup3_hash32: lookup3::Hash32 => finish;
+    metro_hash64_1: metro::Hash64_1 => finish;
+    metro_hash64_2: metro::Hash64_2 => finish;
+    metro_hash128_1: metro::Hash128_1 => finish_ext;
+    metro_hash128_2: metro::Hash128_2 => finish_ext;
+    mum_hash64: mum::Hash64 => finish;
+    murmur_hash32: murmur::Hash32 => finish;
+    murmur_hash32_aligned: murmur::Hash32Aligned => finish;
+    murmur2_hash32: murmur2::Hash32 => finish;
+    murmur2_hash32a: murmur2::Hash32A => finish;
+    murmur2_hash32_neutral: murmur2::Hash32Neutral => finish;
+    murmur2_hash32_aligned: murmur2::Hash32Aligned => finish;
+    murmur2_hash64_x64: murmur2::Hash64_x64 => finish;
+    murmur2_hash64_x86: murmur2::Hash64_x86 => finish;
+    murmur3_hash32: murmur3::Hash32 => finish;
+    murmur3_hash128_x86: murmur3::Hash128_x86 => finish_ext;
+    murmur3_hash128_x64: murmur3::Hash128_x64 => finish_ext;
+    spooky_hash32: spooky::Hash32 => finish;
+    spooky_hash64: spooky::Hash64 => finish;
+    spooky_hash128: spooky::Hash128 => finish_ext, |seed: u128| ((seed >> 64) as u64, seed as u64);
+    xx_hash32: xx::Hash32 => finish;
+    xx_hash64: xx::Hash64 => finish;
+}
+
+#[cfg(all(feature = "sys", any(feature = "sse42", target_feature = "sse4.2")))]
+vectors! {
+    city_crc_hash128: city::crc::Hash128 => finish_ext;
+    metro_crc_hash64_1: metro::crc::Hash64_1 => finish;
+    metro_crc_hash64_2: metro::crc::Hash64_2 => finish;
+    metro_crc_hash128_1: metro::crc::Hash128_1 => finish_ext;
+    metro_crc_hash128_2: metro::crc::Hash128_2 => finish_ext;
+}
+
+// `SeaHasher` of seahash 3 hashes every write on its own, and the streaming `t1ha2` is a different
+// function than the one-shot ones, so they don't match their `FastHash`.
+at_once! {
+    sea_hash64: sea::Hash64;
+}
+
+#[cfg(any(feature = "sys", feature = "pure-t1ha"))]
+at_once! {
+    t1ha2_hash64: t1ha2::Hash64AtOnce;
+    t1ha2_hash128: t1ha2::Hash128AtOnce;
+}
+
+#[cfg(any(feature = "sys", feature = "pure-t1ha"))]
+streams! {
+    t1ha2_hasher64: t1ha2::Hasher128 => finish, 64;
+    t1ha2_hasher128: t1ha2::Hasher128 => finish_ext, 128;
+}
+
+/// Returns whether `t1ha0` selects an AES-NI implementation, and whether it uses AVX2.
+#[cfg(any(feature = "sys", feature = "pure-t1ha"))]
+fn t1ha0_aes() -> Option<bool> {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("aes") {
+            return Some(is_x86_feature_detected!("avx2"));
+        }
+    }
+
+    None
+}
+
+// `t1ha0` selects the fastest implementation of the CPU, the AES-NI ones give different hashes,
+// it is checked with the vectors of the selected one.
+#[cfg(all(
+    any(feature = "sys", feature = "pure-t1ha"),
+    target_pointer_width = "64",
+    target_endian = "little"
+))]
+#[test]
+fn t1ha0_hash64() {
+    let title = std::any::type_name::<t1ha0::Hash64>();
+    let (file, title) = match t1ha0_aes() {
+        Some(true) => (
+            "t1ha0_hash64_aes_avx2",
+            format!("{} with AES-NI and AVX2", title),
+        ),
+        Some(false) => ("t1ha0_hash64_aes", format!("{} with AES-NI", title)),
+        // the same as `t1ha1` on the 64-bit targets
+        None => (
+            "t1ha1_hash64_le",
+            std::any::type_name::<t1ha1::Hash64Le>().to_owned(),
+        ),
+    };
+
+    check_vectors(file, &title, 64, at_once::<t1ha0::Hash64>);
+}
+
+#[cfg(any(feature = "sys", feature = "pure-farm"))]
+#[test]
+fn farm_fingerprints() {
+    check_vectors(
+        "farm_fingerprint32",
+        "fasthash::farm::fingerprint32",
+        32,
+        |key, _: Option<()>| u128::from(farm::fingerprint32(key)),
+    );
+    check_vectors(
+        "farm_fingerprint64",
+        "fasthash::farm::fingerprint64",
+        64,
+        |key, _: Option<()>| u128::from(farm::fingerprint64(key)),
+    );
+    check_vectors(
+        "farm_fingerprint128",
+        "fasthash::farm::fingerprint128",
+        128,
+        |key, _: Option<()>| farm::fingerprint128(key),
+    );
+}
+
+#[cfg(any(feature = "sys", feature = "pure-highway"))]
+#[test]
+fn highway_hash256() {
+    let title = std::any::type_name::<highway::Hash256>();
+
+    check_vectors(
+        "highway_hash256",
+        title,
+        256,
+        |key, seed: Option<highway::Seed>| {
+            let hash = seed.map_or_else(
+                || highway::Hash256::hash(key),
+                |seed| highway::Hash256::hash_with_seed(key, seed),
+            );
+            let new = || seed.map_or_else(highway::Hasher256::new, highway::Hasher256::with_seed);
+
+            assert_eq!(
+                streaming(key, new, highway::Hasher256::finish256),
+                hash,
+                "{} bytes, seed {:?}, streaming",
+                key.len(),
+                seed
+            );
+
+            hash
+        },
+    );
+}
+
+/// The secret of the vectors, like `xxhsum`, the sanity buffer from the 7th byte.
+#[cfg(any(feature = "sys", feature = "pure-xxh3"))]
+fn xxh3_secret() -> xxh3::Secret<'static> {
+    xxh3::Secret::new(buffer(7 + xxh3::SECRET_SIZE_MIN + 11)[7..].to_vec()).unwrap()
+}
+
+#[cfg(any(feature = "sys", feature = "pure-xxh3"))]
+#[test]
+fn xxh3_hash64_secret() {
+    let secret = xxh3_secret();
+
+    check_vectors(
+        "xxh3_hash64_secret",
+        "fasthash::xxh3::hash64_with_secret",
+        64,
+        |key, _: Option<()>| {
+            let hash = u128::from(xxh3::hash64_with_secret(key, &secret));
+            let new = || xxh3::Hasher64::with_secret(secret.clone());
+
+            assert_eq!(
+                streaming(key, new, finish),
+                hash,
+                "{} bytes, streaming",
+                key.len()
+            );
+
+            hash
+        },
+    );
+}
+
+#[cfg(any(feature = "sys", feature = "pure-xxh3"))]
+#[test]
+fn xxh3_hash128_secret() {
+    let secret = xxh3_secret();
+
+    check_vectors(
+        "xxh3_hash128_secret",
+        "fasthash::xxh3::hash128_with_secret",
+        128,
+        |key, _: Option<()>| {
+            let hash = xxh3::hash128_with_secret(key, &secret);
+            let new = || xxh3::Hasher128::with_secret(secret.clone());
+
+            assert_eq!(
+                streaming(key, new, finish_ext),
+                hash,
+                "{} bytes, streaming",
+                key.len()
+            );
+
+            hash
+        },
+    );
+}
+
+/// The SMHasher verification code, the hash of the hashes of the keys `0..i` with the seeds `256 - i`.
+fn verification<F: Fn(&[u8], u32) -> Vec<u8>>(hash: F) -> u32 {
+    let key = (0..=255).collect::<Vec<u8>>();
+    let hashes = (0..256)
+        .flat_map(|i| hash(&key[..i], 256 - i as u32))
+        .collect::<Vec<_>>();
+    let h = hash(&hashes, 0);
+
+    u32::from_le_bytes([h[0], h[1], h[2], h[3]])
+}
+
+/// Checks the SMHasher verification codes of the variants, `$to_seed` converts the 32-bit seed.
+macro_rules! verify {
+    ($($hash:ty: $code:expr, |$seed:ident| $to_seed:expr;)*) => {
+        $(
+            assert_eq!(
+                verification(|key, $seed| <$hash>::hash_with_seed(key, $to_seed).to_le_bytes().to_vec()),
+                $code,
+                "{}",
+                stringify!($hash)
+            );
+        )*
+    };
+}
+
+#[cfg(feature = "sys")]
+#[test]
+fn upstream() {
+    let data = buffer(14);
+
+    for &(len, seed, h32, h64) in &[
+        (0, 0, 0x02cc_5d05, 0xef46_db37_51d8_e999),
+        (0, PRIME32, 0x36b7_8ae7, 0xac75_fda2_929b_17ef),
+        (1, 0, 0xcf65_b03e, 0xe934_a84a_db05_2768),
+        (1, PRIME32, 0xb454_5aa4, 0x5014_6076_43a9_b4c3),
+        (14, 0, 0x1208_e7e2, 0x8282_dcc4_994e_35c8),
+        (14, PRIME32, 0x6af1_d1fe, 0xc3bd_6bf6_3deb_6df0),
+    ] {
+        assert_eq!(xx::hash32_with_seed(&data[..len], seed as u32), h32);
+        assert_eq!(xx::hash64_with_seed(&data[..len], seed), h64);
+    }
+
+    verify! {
+        farm::Hash64: 0x35f8_4a93, |seed| u64::from(seed);
+        lookup3::Hash32: 0x3d83_917a, |seed| seed;
+        metro::Hash64_1: 0xee88_f7d2, |seed| seed;
+        metro::Hash64_2: 0xe1fc_7c6e, |seed| seed;
+        metro::Hash128_1: 0x20e8_a1d7, |seed| seed;
+        metro::Hash128_2: 0x5437_c684, |seed| seed;
+        murmur::Hash32: 0x9ea7_d056, |seed| seed;
+        murmur2::Hash32: 0x2786_4c1e, |seed| seed;
+        murmur2::Hash32A: 0x7fbd_4396, |seed| seed;
+        murmur2::Hash64_x64: 0x1f0d_3804, |seed| u64::from(seed);
+        murmur2::Hash64_x86: 0xdd53_7c05, |seed| u64::from(seed);
+        murmur3::Hash32: 0xb0f5_7ee3, |seed| seed;
+        murmur3::Hash128_x86: 0xb3ec_e62a, |seed| seed;
+        murmur3::Hash128_x64: 0x6384_ba69, |seed| seed;
+        spooky::Hash32: 0x3f79_8bbb, |seed| seed;
+        spooky::Hash64: 0xa7f9_55f1, |seed| u64::from(seed);
+        xx::Hash32: 0xba88_b743, |seed| seed;
+        xx::Hash64: 0x024b_7cf4, |seed| u64::from(seed);
+    }
+
+    // SMHasher stores the first seed word first
+    assert_eq!(
+        verification(|key, seed| {
+            let h = spooky::Hash128::hash_with_seed(key, u128::from(seed) << 64 | u128::from(seed));
+
+            [(h >> 64) as u64, h as u64]
+                .iter()
+                .flat_map(|w| w.to_le_bytes().to_vec())
+                .collect()
+        }),
+        0x8d26_3080
+    );
+}
+
+/// The published reference values of `t1ha`, from `t1ha/tests/test.c`.
+#[cfg(any(feature = "sys", feature = "pure-t1ha"))]
+mod t1ha_refval {
+    pub const T1HA2_ATONCE: [u64; 81] = [
+        0,
+        0x772c_7311_be32_ff42,
+        0x4447_53d2_3f20_7e03,
+        0x71f6_df5d_a3b4_f532,
+        0x5558_5963_5365_f660,
+        0xe988_08f1_cd39_c626,
+        0x2eb1_8faf_2163_bb09,
+        0x7b9d_d892_c801_9c87,
+        0xe2b1_431c_4da4_d15a,
+        0x1984_e718_a547_7f70,
+        0x08dd_17b2_6648_4f79,
+        0x4c83_a05d_766a_d550,
+        0x92dc_ebb1_31d1_907d,
+        0xd67b_c6fc_881b_8549,
+        0xf6a9_8865_55fb_f66b,
+        0x6e31_616d_7f33_e25e,
+        0x36e3_1b74_26e3_049d,
+        0x4f8e_4faf_46a1_3f5f,
+        0x03eb_0cb3_253f_819f,
+        0x636a_7769_9057_70d2,
+        0x3adf_3781_d16d_1148,
+        0x92d1_9cb1_818b_c9c2,
+        0x283e_68f4_d459_c533,
+        0xfa83_a8a8_8dec_aa04,
+        0x8c6f_0036_8eac_538c,
+        0x7b66_b0cf_3797_b322,
+        0x5131_e122_fdab_a3ff,
+        0x6e59_ff51_5c08_c7a9,
+        0xba2c_5269_b2c3_77b0,
+        0xa9d2_4fd3_68fe_8a2b,
+        0x22db_13d3_2e33_e891,
+        0x7b97_dfc8_04b8_76e5,
+        0xc598_bdfc_d0e8_34f9,
+        0xb256_163d_3687_f5a7,
+        0x66d7_a73c_6aef_50b3,
+        0x25a7_201c_85d9_e2a3,
+        0x9115_73ed_a152_99aa,
+        0x5c00_62b6_69e1_8e4c,
+        0x1773_4ade_08d5_4e28,
+        0xfff0_36e3_3883_f43b,
+        0xfe07_56e7_777d_f11e,
+        0x3797_2472_d023_f129,
+        0x6cfc_e201_b55c_7f57,
+        0xe019_d1d8_9f02_b3e1,
+        0xae5c_c580_fa1b_b7e6,
+        0x2956_95fb_7e59_fc3a,
+        0x76b6_c820_a40d_d35e,
+        0xb168_0a17_6846_2b17,
+        0x2fb6_af27_9137_dada,
+        0x28fb_6b43_66c7_8535,
+        0xec27_8e53_9245_41b1,
+        0x164f_8aab_8a2a_28b5,
+        0xb6c3_30ae_ac45_78ad,
+        0x7f6f_3710_7008_5084,
+        0x94de_ad60_c0f4_48d3,
+        0x9973_7ac2_32c5_59ef,
+        0x6f54_a6f9_ca8e_dd57,
+        0x979b_01e9_26bf_ce0c,
+        0xf7d2_0bc8_5439_c5b4,
+        0x64ed_b27c_d808_7c12,
+        0x1148_8de5_f79c_0be2,
+        0x2554_1ddd_1680_b5a4,
+        0x8b63_3d33_be9d_1973,
+        0x404a_3113_acf7_f6c6,
+        0xc59d_bdef_8550_cd56,
+        0x039d_23c6_8f4f_992c,
+        0x5bbb_48e4_bdd6_fd86,
+        0x41e3_1224_8780_df5a,
+        0xd347_91ce_75d4_e94f,
+        0xed52_3e5d_04dc_dcff,
+        0x7a6b_ce0b_6182_d879,
+        0x21fb_3748_3cac_28d8,
+        0x19a1_b66e_8da8_78ad,
+        0x6f80_4c52_95b0_9abe,
+        0x2a4b_e501_4115_ba81,
+        0xa678_ecc5_fc92_4be0,
+        0x50f7_a54a_99a3_6f59,
+        0x0fd7_e63a_39a6_6452,
+        0x5ab1_b213_dd29_c4e4,
+        0xf3ed_80d9_df65_34c5,
+        0xc736_b12e_f906_15fd,
+    ];
+
+    pub const T1HA2_ATONCE128: [u64; 81] = [
+        0x4ec7_f6a4_8e33_b00a,
+        0xb7b7_faa5_bd7d_8c1e,
+        0x3269_533f_6653_4a76,
+        0x6c3e_c6b6_8792_3bfc,
+        0xc096_f5e7_efa4_71a9,
+        0x79d8_afb5_50ce_a471,
+        0xcee0_507a_20fd_5119,
+        0xfb04_cffc_14a9_f4bf,
+        0xbd44_06e9_2380_7af2,
+        0x375c_02ff_1101_0491,
+        0xa6ea_4c2a_59e1_73ff,
+        0xe0a6_06f0_002c_addf,
+        0xe13b_eae6_ebc0_7897,
+        0xf069_c246_3e48_ea10,
+        0x75be_e1a9_7089_b5fa,
+        0x378f_22f8_de0b_8085,
+        0x9c72_6fc4_d53d_0d8b,
+        0x71f6_130a_2d08_f788,
+        0x7a9b_2043_3ff6_cf69,
+        0xff49_b7cd_59bf_6d61,
+        0xccaa_ee0d_1ca9_c6b3,
+        0xc778_89d8_6039_d2ad,
+        0x7b37_8b5b_ea9b_0475,
+        0x6520_bfa7_9d59_ad66,
+        0x2441_490c_b8a3_7267,
+        0xa715_a66b_7d5c_f473,
+        0x9ae8_92c8_8334_fd67,
+        0xd2ff_e9ae_c1d2_169a,
+        0x790b_993f_18b1_8cbb,
+        0xa0d0_2fbc_f6a7_b1ad,
+        0xa908_33e6_f151_d0c1,
+        0x1ac7_afa3_7bd7_9be0,
+        0xd538_3628_b288_1a24,
+        0xe552_6f9d_63f9_f8f1,
+        0xc1f1_65a0_1a6d_1f4d,
+        0x6cce_f8ff_3fcf_a3f2,
+        0x2030_f183_25e6_df48,
+        0x2892_0723_0e3f_b17a,
+        0x077b_66f7_13a3_c4b9,
+        0x9f39_843c_af87_1754,
+        0x512f_da0f_808a_ccf3,
+        0xf4d9_801c_d0cd_1f14,
+        0x28a0_c749_ed32_3638,
+        0x9484_4caf_a671_f01c,
+        0xd0e2_6187_6b8a_ca51,
+        0x8fc2_a648_a479_2ea2,
+        0x8ef8_7282_136a_f5fe,
+        0x5fe6_a54a_9fba_6b40,
+        0xa3cc_5b8f_e622_3d54,
+        0xa8c3_c0dd_651b_b01c,
+        0x625e_9fdd_5347_16f3,
+        0x1ab2_6040_83c3_3ac5,
+        0xde09_8853_f869_2f12,
+        0x4b08_1389_1bd8_7624,
+        0x4ab8_9c45_53d1_82ad,
+        0x92c1_5aa2_a3c2_7ada,
+        0xff29_18d6_8191_f5d9,
+        0x0636_3174_f641_c325,
+        0x6671_12ad_a74a_2059,
+        0x4bd6_05d6_b5e5_3d7d,
+        0xf251_2c53_663a_14c8,
+        0x2185_7bcb_1852_667c,
+        0xafbe_bd03_69ae_e228,
+        0x7049_340e_48fb_fd6b,
+        0x5071_0e19_24f4_6954,
+        0x869a_75e0_4a97_6a3f,
+        0x5a41_abbd_d637_3889,
+        0xa781_7783_89b4_b188,
+        0x21a3_afce_d6c9_25b6,
+        0x1072_2619_2ec1_0b42,
+        0x62a8_62e8_4ec2_f9b1,
+        0x2b15_e916_5960_6dd7,
+        0x6139_34d1_f9ec_5a42,
+        0x4dc3_a96d_c536_1baf,
+        0xc80b_ba4c_b5f1_2903,
+        0x3e3e_dae9_9a7d_6987,
+        0x8f97_b2d5_5941_dcb0,
+        0x4c97_8736_4c3e_4ec1,
+        0xef0a_2d07_bea9_0ca7,
+        0x5fab_f32c_70ae_eafb,
+        0x3356_a5cf_a8f2_3bf4,
+    ];
+
+    pub const T1HA2_STREAM: [u64; 81] = [
+        0x3c84_26e3_3cb4_1606,
+        0xfd74_be70_ee73_e617,
+        0xf43d_e3cd_d8a2_0486,
+        0x882f_bcb3_7e8e_a3bb,
+        0x1aa2_cdd3_4caa_3d4b,
+        0xee75_5b2b_fae0_7ed5,
+        0xd4e2_2525_0d92_e213,
+        0xa09b_4908_3205_965b,
+        0xd47b_2172_4ef9_ec9e,
+        0xac88_8fc3_858c_ee11,
+        0x94f8_20d8_5736_f244,
+        0x1707_951c_ca92_0932,
+        0x8e0e_4560_3f78_77f0,
+        0x9fd2_592c_0e3a_7212,
+        0x9a66_370f_3ae3_d427,
+        0xd333_82d2_161d_e2b7,
+        0x9a35_be07_9da7_115f,
+        0x7345_7c7f_f58b_4ec3,
+        0xbe86_10bd_53d7_ce98,
+        0x6550_6dfe_5ccd_5371,
+        0x286a_321a_f9d5_d9fa,
+        0xb81e_f9a7_ef3c_536d,
+        0x2cfd_b5e6_825c_6e86,
+        0xb2a5_8cbf_dfdd_303a,
+        0xd260_94a4_2b95_0635,
+        0xa34d_666a_5f02_ad9a,
+        0x0151_e013_ebcc_72e5,
+        0x9254_a6ea_7fcb_6bb5,
+        0x10c9_361b_3869_dc2b,
+        0xd7ec_55a0_6060_6276,
+        0xa2ff_7f8b_f897_6ffd,
+        0xb518_1bb6_852d_cc88,
+        0x0ee3_94bb_6178_baff,
+        0x3a8b_4b40_0d21_b89c,
+        0xec27_0461_9709_60fd,
+        0x6159_67fa_b053_877e,
+        0xfa51_bf1c_feb4_714c,
+        0x29fd_a838_3070_f375,
+        0xc3b6_6306_1bc5_2eda,
+        0x192b_baf1_f1a5_7923,
+        0x6d19_3b52_f93c_53af,
+        0x7f6f_5639_fe87_ca1e,
+        0x69f7_f914_0b32_edc8,
+        0xd0f2_416f_b243_25b6,
+        0x62c0_e37f_edd4_9ff3,
+        0x5786_6a4b_809d_373d,
+        0x9848_d24b_d935_e137,
+        0xdfc9_05b6_6734_d50a,
+        0x9a93_8dd1_94a6_8529,
+        0x8276_c44d_f062_5228,
+        0xa4b3_5d00_ad67_c0ab,
+        0x3d9c_b359_842d_b452,
+        0x4241_bfa8_c23b_267f,
+        0x650f_a517_bef1_5952,
+        0x782d_e2ab_d8c7_b1e1,
+        0x4eae_4561_66ca_3e15,
+        0x40cd_f3a0_2614_e337,
+        0xad84_092c_4610_2172,
+        0x0c68_479b_03f9_a167,
+        0x7e1b_a046_749e_181c,
+        0x3f3a_b41a_6973_82c1,
+        0xc5e5_dd65_86eb_fdc4,
+        0xff92_6cd4_eb02_555c,
+        0x035c_fe67_f89e_709b,
+        0x89f0_6ab6_464a_1b9d,
+        0x8eff_58f3_f7de_a758,
+        0x8b54_ac65_7902_089f,
+        0xc6c4_f1f9_f8da_4d64,
+        0xbdb7_2904_8aaa_c93a,
+        0xea76_ba62_8f5e_5cd6,
+        0x7421_59b7_28b8_a979,
+        0x6d15_1cd3_c720_e53d,
+        0xe97f_ff93_68fc_dc42,
+        0xca5b_3831_4914_fbda,
+        0xdd92_c91d_8b85_8eae,
+        0x66e5_f07c_f647_cbf2,
+        0xd4cf_9b42_f498_5afb,
+        0x72ae_17ac_7d92_f6b7,
+        0xb820_6b22_ab04_72e1,
+        0x3858_76b5_cfd4_2479,
+        0x0329_4a24_9ebe_6b26,
+    ];
+
+    pub const T1HA2_STREAM128: [u64; 81] = [
+        0xcd28_01d3_b922_37d6,
+        0x10e4_d47b_d821_546d,
+        0x9100_704b_9d65_cd06,
+        0xd695_1cb4_0163_13ef,
+        0x24db_636f_96f4_74da,
+        0x3f4a_f7df_3c49_e422,
+        0xbff2_5b8a_f143_459b,
+        0xa157_ec13_538b_e549,
+        0xd3f5_f52c_47db_d419,
+        0x0ef3_d7d7_35af_1575,
+        0x46b7_b892_823f_7b1b,
+        0xee22_ea46_5521_3289,
+        0x56ad_76f0_2fe9_29bc,
+        0x9cf6_cd1a_c886_546e,
+        0xaf45_ce47_aea0_b933,
+        0x535f_9dc0_9f39_96b7,
+        0x1f0c_3c01_694a_e128,
+        0x1849_5069_be07_66f7,
+        0x37e5_ffb3_d72a_4cb1,
+        0x6d6c_2e92_99f3_0709,
+        0x4f39_e693_f50b_41e3,
+        0xb11f_c4ef_0658_e116,
+        0x48bf_aacb_78e5_079b,
+        0xe1b4_c89c_781b_3ad0,
+        0x81d2_f348_88d3_33a1,
+        0xf6d0_2270_d2ea_449c,
+        0xc884_c3c2_c3ce_1503,
+        0x711a_e16b_a157_a9b9,
+        0x1e61_40c6_4255_8c9d,
+        0x35ab_3d23_8f5d_c55b,
+        0x33f0_7b6a_ef05_1177,
+        0xe573_3677_6eef_a71c,
+        0x6d44_5f83_18ba_3752,
+        0xd4f5_f663_1934_c988,
+        0xd5e2_6008_5727_c4a2,
+        0x5b54_b41e_c180_b4fa,
+        0x7f5d_7576_9c15_a898,
+        0xae5a_6db8_50ca_33c6,
+        0x038c_cb80_4466_3403,
+        0xda16_3101_33dc_92b8,
+        0x6a2f_fb7a_b2b7_ce2b,
+        0xdc18_32d9_229b_ae20,
+        0x8c62_c479_f5ab_c9e4,
+        0x5eb7_b617_857c_9ccb,
+        0xb79c_f7d7_49a1_e80d,
+        0xde7f_ac37_9832_4fd3,
+        0x8178_9118_1368_5d06,
+        0x6a72_6cbd_394d_4410,
+        0x6cbe_6b32_80da_1113,
+        0x6829_ba44_10cf_1148,
+        0xfa7e_417e_b26c_5bc6,
+        0x22ed_8788_4d6e_3a49,
+        0x15f1_472d_5115_669d,
+        0x2ea0_b4c8_bf69_d318,
+        0xdfe8_7070_aa54_5503,
+        0x6b4c_14b5_f714_4ab9,
+        0xc1ed_49c0_6126_551a,
+        0x3519_19fc_425c_3899,
+        0x7b56_9c0f_a6f1_bd3e,
+        0x713a_c235_0844_cffd,
+        0xe936_7f9a_638c_2ff3,
+        0x97f1_7d32_5aea_0786,
+        0xbcb9_07cc_6cf7_5f91,
+        0x0cb7_517d_af24_7719,
+        0xbe16_093c_c45b_e8a9,
+        0x786e_ee97_359a_d6ab,
+        0xb7af_a4f3_26b9_7e78,
+        0x2694_b67f_e23e_502e,
+        0x4cb4_9282_6e98_e0b4,
+        0x838d_119f_74a4_16c7,
+        0x70d6_a91e_4e56_77fd,
+        0xf3e4_027a_d300_00e6,
+        0x9bdf_6927_9580_7f77,
+        0x6a37_1f96_6e03_4a54,
+        0x8789_cf41_ae4d_67ef,
+        0x0268_8755_484d_60ae,
+        0xd583_4b3a_4bf5_ce42,
+        0x9405_fc61_440d_e25d,
+        0x35eb_280a_1579_79b6,
+        0x48d4_0d6a_5252_97ac,
+        0x6a87_dc18_5054_bada,
+    ];
+
+    pub const T1HA1_LE: [u64; 81] = [
+        0,
+        0x6a58_0668_d604_8674,
+        0xa2fe_904a_ff0d_0879,
+        0xe3ab_9c06_faf4_d023,
+        0x6af1_c608_74c9_5442,
+        0xb355_7e56_1a6c_5d82,
+        0x0ae7_3c69_6f3d_37c0,
+        0x5ef2_5f70_6232_4941,
+        0x9b78_4f3b_4ce6_af33,
+        0x6993_bb20_6a74_f070,
+        0xf1e9_5df1_0907_6c4c,
+        0x4e1e_b70c_58e4_8540,
+        0x5fdd_7649_d8ec_44e4,
+        0x5591_22c7_0634_3421,
+        0x3801_33d5_8665_e93d,
+        0x9ce7_4296_c8c5_5ae4,
+        0x3556_f9a5_757a_b6d0,
+        0xf627_51f7_f25c_469e,
+        0x851e_ec67_f651_6d94,
+        0xed46_3ee3_848a_8695,
+        0xdc87_91fe_ff8e_d3ac,
+        0x2569_c744_e1a2_82cf,
+        0xf90e_b7c1_d70a_80b9,
+        0x68df_a6a1_b805_0a4c,
+        0x94cc_a5e8_210d_2134,
+        0xf5cc_0bea_bc25_9f52,
+        0x40db_c1f5_1618_fda7,
+        0x0807_945b_f0fb_52c6,
+        0xe5ef_7e09_de70_848d,
+        0x63e1_df35_febe_994a,
+        0x2025_e737_6972_0d5a,
+        0xad61_20b2_b8a1_52e1,
+        0x2a71_d9f1_3959_f2b7,
+        0x8a20_849a_27c3_2548,
+        0x0bcb_c9fe_3b57_884e,
+        0x0e02_8d25_5667_aead,
+        0xbe66_dad3_043a_b694,
+        0xb00e_4c12_38f9_e2d4,
+        0x5c54_bde5_ae28_0e82,
+        0x0e22_b867_54bc_3bc4,
+        0x0167_07eb_f858_b84d,
+        0x9900_15fb_c9e0_95ee,
+        0x8b9a_f0a3_e71f_042f,
+        0x6aa5_6e88_bd38_0564,
+        0xaace_5711_3e68_1a0f,
+        0x19f8_1514_afa9_a22d,
+        0x80da_ba3d_62be_ac79,
+        0x7152_1041_2cab_bf46,
+        0xd8fa_0b9e_9d6a_a93f,
+        0x6c2f_c5a4_109f_d3a2,
+        0x5b3e_60ee_b51d_dcd8,
+        0x0a7c_7170_1775_6fe7,
+        0xa737_7380_5ca3_1934,
+        0x4dbd_6bb7_a31e_85fd,
+        0x24f6_19d3_d5bc_2db4,
+        0x3e4a_f35a_1678_d636,
+        0x84a1_a8df_8d60_9239,
+        0x359c_862c_d3be_4fcd,
+        0xcf3a_39f5_c27d_c125,
+        0xc0ff_62f8_fd5f_4c77,
+        0x5e9f_2493_ddaa_166c,
+        0x1742_4152_be1c_a266,
+        0xa78a_fa5a_b4bb_e0cd,
+        0x7bfb_2e2c_ef11_8346,
+        0x647c_3e0f_f3e3_d241,
+        0x0352_e405_5c13_242e,
+        0x6f42_fc70_eb66_0e38,
+        0x0beb_ad4f_abf5_23ba,
+        0x9269_f421_4414_d61d,
+        0x1ca8_7602_77e6_006c,
+        0x7bad_25a8_59d8_7b5d,
+        0xad64_5adc_f741_4f1d,
+        0xb07f_517e_88d7_afb3,
+        0xb321_c06f_b5ff_ab5c,
+        0xd50f_162a_1efd_d844,
+        0x1dfd_3d19_24fb_e319,
+        0xdfae_ab2f_09ef_7e78,
+        0xa760_3b5a_f07a_0b1e,
+        0x41cd_044c_0e5a_4ee3,
+        0xf64d_2f86_e813_bf33,
+        0xff9f_db99_305e_b06a,
+    ];
+
+    pub const T1HA1_BE: [u64; 81] = [
+        0,
+        0x6a58_0668_d604_8674,
+        0xdecc_975a_0e3b_8177,
+        0xe3ab_9c06_faf4_d023,
+        0xe401_fa8f_1b6a_f969,
+        0x67db_1dae_56fb_94e3,
+        0x1106_266a_09b7_a073,
+        0x5503_39b1_ef2c_7bbb,
+        0x290a_2baf_5900_45bb,
+        0xa182_c125_8c09_f54a,
+        0x137d_53c3_4be7_143a,
+        0xf6d2_b69c_6f42_bedc,
+        0x3964_3eaf_2ca2_e4b4,
+        0x22a8_1f13_9a2c_9559,
+        0x5b3d_6aef_0af3_3807,
+        0x56e3_f80a_6864_3c08,
+        0x9e42_3be5_0237_8780,
+        0xcdb0_986f_9a5b_2fd5,
+        0xd5b3_c84e_7933_293f,
+        0xe5fb_8c90_399e_9742,
+        0x5d39_3c1f_77b2_cf3d,
+        0xc8c8_2f5b_2ff0_9266,
+        0xaca0_230c_a6f7_b593,
+        0xcb58_05e2_960d_1655,
+        0x7e2a_d5b7_04d7_7c95,
+        0xc5e9_03cd_b8b9_eb5d,
+        0x4cc7_d0d2_1cc0_3511,
+        0x8385_df38_2cfb_3e93,
+        0xf176_99d0_564d_348a,
+        0xf77e_e7f8_274a_4c8d,
+        0xb9d8_cee4_8903_babe,
+        0xfe0e_bd2a_82b9_cfe9,
+        0xb49f_b639_7270_f565,
+        0x1737_35c8_c342_108e,
+        0xa37c_7fbb_eec0_a2ea,
+        0xc13f_66f4_62bb_0b6e,
+        0x0c04_f3c2_b551_467e,
+        0x76a9_cb15_6810_c96e,
+        0x2038_8509_19b0_b151,
+        0xcea1_9f2b_6eed_647b,
+        0x6746_656d_2fa1_09a4,
+        0xf051_37f2_2100_7f37,
+        0x892f_a9e1_3a3b_4948,
+        0x4d57_b70d_3754_8a32,
+        0x1a7c_fb3d_5665_80e6,
+        0x7cb3_0272_a45e_3fac,
+        0x137c_cffd_9d51_423f,
+        0xb87d_96f3_b82d_f266,
+        0x3334_9aee_7472_ed37,
+        0x5cc0_d3c9_9555_bc07,
+        0x4a8f_4fa1_96d9_64ef,
+        0xe82a_0d64_f281_fbfa,
+        0x38a1_bac2_c368_23e1,
+        0x77d1_97c2_39fd_737e,
+        0xfb07_746b_4e07_df26,
+        0xc8a2_198e_9676_72bd,
+        0x5f1a_146d_143f_a05a,
+        0x26b8_77a1_201a_b7ac,
+        0x74e5_b145_2147_23f8,
+        0xe9ce_10e3_c702_54bc,
+        0x2993_93a0_c05b_79e8,
+        0xfd2d_2b98_22a5_e7e2,
+        0x8542_4fea_50c8_e50a,
+        0xe683_9e71_4b1f_ffe5,
+        0x2797_1ccb_46f9_112a,
+        0xc986_95a2_e071_5aa9,
+        0x338e_1cbb_4f85_8226,
+        0xfc6b_5c5c_f7a8_d806,
+        0x8973_caad_de8d_a50c,
+        0x9c6d_47ae_32eb_ae72,
+        0x1ebf_1f9f_21d2_6d78,
+        0x80a9_704b_8e15_3859,
+        0x6afd_20a9_39f1_41fb,
+        0xc35f_6c2b_3b55_3eef,
+        0x5952_9e8b_0dc9_4c1a,
+        0x1569_df03_6ebc_4fa1,
+        0xda32_b885_93c1_18f9,
+        0xf01e_4155_ff5a_5660,
+        0x765a_2522_dce2_b185,
+        0xcee9_5554_1280_73ef,
+        0x60f0_72a5_ca51_de2f,
+    ];
+
+    pub const T1HA0_IA32AES_A: [u64; 81] = [
+        0,
+        0x772c_7311_be32_ff42,
+        0xb231_ac66_0e5b_23b5,
+        0x71f6_df5d_a3b4_f532,
+        0x5558_5963_5365_f660,
+        0xe988_08f1_cd39_c626,
+        0x2eb1_8faf_2163_bb09,
+        0x7b9d_d892_c801_9c87,
+        0xe2b1_431c_4da4_d15a,
+        0x1984_e718_a547_7f70,
+        0x08dd_17b2_6648_4f79,
+        0x4c83_a05d_766a_d550,
+        0x92dc_ebb1_31d1_907d,
+        0xd67b_c6fc_881b_8549,
+        0xf6a9_8865_55fb_f66b,
+        0x6e31_616d_7f33_e25e,
+        0x36e3_1b74_26e3_049d,
+        0x4f8e_4faf_46a1_3f5f,
+        0x03eb_0cb3_253f_819f,
+        0x636a_7769_9057_70d2,
+        0x3adf_3781_d16d_1148,
+        0x92d1_9cb1_818b_c9c2,
+        0x283e_68f4_d459_c533,
+        0xfa83_a8a8_8dec_aa04,
+        0x8c6f_0036_8eac_538c,
+        0x7b66_b0cf_3797_b322,
+        0x5131_e122_fdab_a3ff,
+        0x6e59_ff51_5c08_c7a9,
+        0xba2c_5269_b2c3_77b0,
+        0xa9d2_4fd3_68fe_8a2b,
+        0x22db_13d3_2e33_e891,
+        0x7b97_dfc8_04b8_76e5,
+        0xc598_bdfc_d0e8_34f9,
+        0xb256_163d_3687_f5a7,
+        0x66d7_a73c_6aef_50b3,
+        0xbb34_c6a4_3966_95d2,
+        0x7f46_e198_1c32_56ad,
+        0x4b25_a9b2_17a6_c5b4,
+        0x7a0a_6bcd_d232_1da9,
+        0x0a1f_55e6_90a7_b44e,
+        0x8f45_1a91_d7f0_5244,
+        0x624d_5d3c_9b98_00a7,
+        0x09dd_c2b6_409d_dc25,
+        0x3e15_5765_8656_22b6,
+        0x9651_9fac_9511_b381,
+        0x512e_5848_2fe4_fbf0,
+        0x1ab2_60ea_7d54_ae1c,
+        0x6797_6f12_cc28_bbbd,
+        0x0607_b5b2_e625_0156,
+        0x7e70_0bea_717a_d36e,
+        0x06a0_58d9_d61c_abb3,
+        0x57da_5324_a824_972f,
+        0x1193_ba74_dbeb_f7e7,
+        0xc18d_c314_0e70_02d4,
+        0x9f7c_cc11_dfa0_ef17,
+        0xc487_d6c2_0666_a13a,
+        0xb671_90e4_b50e_f0c8,
+        0xa53d_aa60_8df0_b9a5,
+        0x7e13_101d_e87f_9ed3,
+        0x7f89_55ae_2f05_088b,
+        0x2df7_e5a0_97ad_383f,
+        0xf027_683a_21ea_14b5,
+        0x9bb8_aec3_e336_0942,
+        0x92be_39b5_4967_e7fe,
+        0x978c_6d33_2e7a_fd27,
+        0xed51_2fe9_6a4f_ae81,
+        0x9e10_99b8_140d_7ba3,
+        0xdfd5_a5be_1e6f_e9a6,
+        0x1d82_600e_23b6_6dd4,
+        0x3fa3_c3b7_ee7b_52ce,
+        0xee84_f7d2_a655_ef4c,
+        0x2a43_61ec_769e_3beb,
+        0x22e4_b389_1663_6702,
+        0x0063_096f_5d39_a115,
+        0x6c51_b24d_aafa_5434,
+        0xbafb_1db1_b411_e344,
+        0xff52_9f16_1ae0_c4b0,
+        0x1290_eae3_ac0a_686f,
+        0xa7b0_d458_5447_d1be,
+        0xaed3_d18c_b6cc_ad53,
+        0xfc73_d46f_8b41_bec6,
+    ];
+
+    pub const T1HA0_IA32AES_B: [u64; 81] = [
+        0,
+        0x772c_7311_be32_ff42,
+        0x4398_f62a_8cb6_f72a,
+        0x71f6_df5d_a3b4_f532,
+        0x5558_5963_5365_f660,
+        0xe988_08f1_cd39_c626,
+        0x2eb1_8faf_2163_bb09,
+        0x7b9d_d892_c801_9c87,
+        0xe2b1_431c_4da4_d15a,
+        0x1984_e718_a547_7f70,
+        0x08dd_17b2_6648_4f79,
+        0x4c83_a05d_766a_d550,
+        0x92dc_ebb1_31d1_907d,
+        0xd67b_c6fc_881b_8549,
+        0xf6a9_8865_55fb_f66b,
+        0x6e31_616d_7f33_e25e,
+        0x36e3_1b74_26e3_049d,
+        0x4f8e_4faf_46a1_3f5f,
+        0x03eb_0cb3_253f_819f,
+        0x636a_7769_9057_70d2,
+        0x3adf_3781_d16d_1148,
+        0x92d1_9cb1_818b_c9c2,
+        0x283e_68f4_d459_c533,
+        0xfa83_a8a8_8dec_aa04,
+        0x8c6f_0036_8eac_538c,
+        0x7b66_b0cf_3797_b322,
+        0x5131_e122_fdab_a3ff,
+        0x6e59_ff51_5c08_c7a9,
+        0xba2c_5269_b2c3_77b0,
+        0xa9d2_4fd3_68fe_8a2b,
+        0x22db_13d3_2e33_e891,
+        0x7b97_dfc8_04b8_76e5,
+        0xc598_bdfc_d0e8_34f9,
+        0xb256_163d_3687_f5a7,
+        0x66d7_a73c_6aef_50b3,
+        0xe810_f88e_85ce_a11a,
+        0x4814_f8f3_b83e_4394,
+        0x9cab_a22d_10a2_f690,
+        0x0d10_0325_11f5_8111,
+        0xe9a3_6ef5_eea3_cd58,
+        0xc792_42de_194d_9d7c,
+        0xc387_1aa0_435e_e5c8,
+        0x5289_0bed_43cc_f4cd,
+        0x07a1_d086_1acc_d373,
+        0x227b_816f_f0fe_e9ed,
+        0x59ff_bf73_aacf_c0c4,
+        0x09ab_564f_2bed_ad0c,
+        0xc05f_744f_2ee3_8318,
+        0x7b50_b621_d547_c661,
+        0x0c1f_71cb_4e68_e5d1,
+        0x0e33_a478_81d4_dbaa,
+        0xf5c3_bf19_8e9a_7c2e,
+        0x1632_8fd8_c0f6_8a91,
+        0xa3e3_99c9_ab3e_9a59,
+        0x163a_e71c_bcbb_18b8,
+        0x18f1_7e4a_8c79_f7ab,
+        0x9250_e2ea_3701_4b45,
+        0x7bbb_b111_d60b_03e4,
+        0x3daa_4a30_71a0_bd88,
+        0xa288_28d7_90a2_d6dc,
+        0xbc70_fc88_f64b_e3f1,
+        0xa3e4_8008_ba43_33c7,
+        0x739e_435a_cafc_79f7,
+        0x42bb_b360_be00_7cc6,
+        0x4ffb_6fd2_af74_ec92,
+        0x2a79_9a29_9467_3146,
+        0xbe0a_045b_69d4_8e9f,
+        0x5494_32f5_4fc6_a278,
+        0x371d_3c60_369f_c702,
+        0xdb45_57d4_15b0_8ca7,
+        0xe869_2f0a_8385_0b37,
+        0x022e_46ae_b36e_9aab,
+        0x117a_c9b8_14e4_652d,
+        0xa361_0412_67ae_9048,
+        0x277c_b51c_961c_3dda,
+        0xaffc_96f3_77cb_8a8d,
+        0x83cc_79fa_01dd_1ba7,
+        0xa494_842a_cf4b_802c,
+        0xfc6d_9cdd_e2c3_4a3f,
+        0x4ed6_863c_e455_f7a7,
+        0x6309_14d0_db7a_ae98,
+    ];
+}
+
+/// The keys and seeds of the `t1ha` self-check, hashed in the order of its reference values.
+fn t1ha_selfcheck<F: Fn(&[u8], u64) -> u64>(hash: F) -> Vec<u64> {
+    const PATTERN: [u8; 64] = [
+        0, 1, 2, 3, 4, 5, 6, 7, 0xff, 0x7f, 0x3f, 0x1f, 0xf, 8, 16, 32, 64, 0x80, 0xfe, 0xfc, 0xf8,
+        0xf0, 0xe0, 0xc0, 0xfd, 0xfb, 0xf7, 0xef, 0xdf, 0xbf, 0x55, 0xaa, 11, 17, 19, 23, 29, 37,
+        42, 43, b'a', b'b', b'c', b'd', b'e', b'f', b'g', b'h', b'i', b'j', b'k', b'l', b'm', b'n',
+        b'o', b'p', b'q', b'r', b's', b't', b'u', b'v', b'w', b'x',
+    ];
+
+    let long = (0..512).map(|i| i as u8).collect::<Vec<_>>();
+    let mut hashes = vec![hash(&[], 0), hash(&[], !0), hash(&PATTERN, 0)];
+
+    hashes.extend((1..64).map(|i| hash(&PATTERN[..i], 1 << (i - 1))));
+
+    let mut seed = !0;
+
+    for i in 1..=7 {
+        seed <<= 1;
+        hashes.push(hash(&PATTERN[i..], seed));
+    }
+
+    hashes.extend((0..=7).map(|i| hash(&long[i..i + 128 + i * 17], seed)));
+    hashes
+}
+
+#[cfg(any(feature = "sys", feature = "pure-city"))]
+#[test]
+fn city_reference() {
+    verify! {
+        city::Hash32: 0x5c28_ad62, |seed| seed;
+        city::Hash64: 0x25a2_0825, |seed| u64::from(seed);
+        city::Hash128: 0x6531_f54e, |seed| u128::from(seed);
+    }
+}
+
+#[cfg(any(feature = "sys", feature = "pure-t1ha"))]
+#[test]
+fn t1ha_reference() {
+    use t1ha_refval::*;
+
+    verify! {
+        t1ha1::Hash64Le: 0xd683_6381, |seed| u64::from(seed);
+        t1ha1::Hash64Be: 0x93f8_64de, |seed| u64::from(seed);
+        t1ha2::Hash64AtOnce: 0x8f16_c948, |seed| u64::from(seed);
+        t1ha2::Hash128AtOnce: 0xb44c_43a1, |seed| u64::from(seed);
+    }
+
+    assert_eq!(
+        t1ha_selfcheck(|key, seed| t1ha2::Hash64AtOnce::hash_with_seed(key, seed)),
+        &T1HA2_ATONCE[..]
+    );
+    // the 128-bit hashes are checked by their low word
+    assert_eq!(
+        t1ha_selfcheck(|key, seed| t1ha2::Hash128AtOnce::hash_with_seed(key, seed) as u64),
+        &T1HA2_ATONCE128[..]
+    );
+    assert_eq!(
+        t1ha_selfcheck(|key, seed| {
+            let mut h = t1ha2::Hasher128::with_seed((seed, seed));
+
+            h.write(key);
+            h.finish()
+        }),
+        &T1HA2_STREAM[..]
+    );
+    assert_eq!(
+        t1ha_selfcheck(|key, seed| {
+            let mut h = t1ha2::Hasher128::with_seed((seed, seed));
+
+            h.write(key);
+            h.finish_ext() as u64
+        }),
+        &T1HA2_STREAM128[..]
+    );
+    assert_eq!(
+        t1ha_selfcheck(|key, seed| t1ha1::Hash64Le::hash_with_seed(key, seed)),
+        &T1HA1_LE[..]
+    );
+    assert_eq!(
+        t1ha_selfcheck(|key, seed| t1ha1::Hash64Be::hash_with_seed(key, seed)),
+        &T1HA1_BE[..]
+    );
+
+    #[cfg(all(target_pointer_width = "64", target_endian = "little"))]
+    {
+        let refval = match t1ha0_aes() {
+            Some(true) => &T1HA0_IA32AES_B,
+            Some(false) => &T1HA0_IA32AES_A,
+            None => &T1HA1_LE,
+        };
+
+        assert_eq!(
+            t1ha_selfcheck(|key, seed| t1ha0::Hash64::hash_with_seed(key, seed)),
+            &refval[..]
+        );
+    }
+}
+
+/// The published reference values of `HighwayHash64`, from the `HighwayHash` verifier bundled with `t1ha`,
+/// the hashes of the keys `0..i` with the key `0..32`.
+#[cfg(any(feature = "sys", feature = "pure-highway"))]
+const HIGHWAY_HASH64: [u64; 65] = [
+    0x907a_56de_22c2_6e53,
+    0x7eab_43aa_c7cd_dd78,
+    0xb8d0_569a_b0b5_3d62,
+    0x5c6b_efab_8a46_3d80,
+    0xf205_a468_9300_7eda,
+    0x2b8a_1668_e4a9_4541,
+    0xbd4c_cc32_5bef_ca6f,
+    0x4d02_ae17_38f5_9482,
+    0xe120_5108_e55f_3171,
+    0x32d2_644e_c77a_1584,
+    0xf6e1_0acd_b103_a90b,
+    0xc3bb_f461_5b41_5c15,
+    0x243c_c204_0063_fa9c,
+    0xa89a_58ce_65e6_41ff,
+    0x24b0_31a3_4845_5a23,
+    0x4079_3f86_a449_f33b,
+    0xcfab_3489_f97e_b832,
+    0x19fe_67d2_c8c5_c0e2,
+    0x04dd_90a6_9c56_5cc2,
+    0x75d9_518e_2371_c504,
+    0x38ad_9b11_41d3_dd16,
+    0x0264_432c_cd8a_70e0,
+    0xa9db_5a62_8868_3390,
+    0xd7b0_5492_003f_028c,
+    0x205f_615a_ea59_e51e,
+    0xeee0_c896_2105_2884,
+    0x1bfc_1a93_a728_4f4f,
+    0x5121_75b5_b70d_a91d,
+    0xf71f_8976_a0a2_c639,
+    0xae09_3fef_1f84_e3e7,
+    0x22ca_92b0_1161_860f,
+    0x9fc7_007c_cf03_5a68,
+    0xa0c9_64d9_ecd5_80fc,
+    0x2c90_f73c_a031_81fc,
+    0x185c_f84e_5691_eb9e,
+    0x4fc1_f5ef_2752_aa9b,
+    0xf5b7_391a_5e0a_33eb,
+    0xb9b8_4b83_b4e9_6c9c,
+    0x5e42_fe71_2a5c_d9b4,
+    0xa150_f2f9_0c3f_97dc,
+    0x7fa5_22d7_5e2d_637d,
+    0x181a_d0cc_0dff_d32b,
+    0x3889_ed98_1e85_4028,
+    0xfb42_97e8_c586_ee2d,
+    0x6d06_4a45_bb28_059c,
+    0x9056_3609_b3ec_860c,
+    0x7aa4_fce9_4097_c666,
+    0x1326_bac0_6b91_1e08,
+    0xb926_168d_2b15_4f34,
+    0x9919_8489_45b1_948d,
+    0xa2a9_8fc5_3482_5ebe,
+    0xe980_9095_213e_f0b6,
+    0x582e_5483_707b_c0e9,
+    0x086e_9414_a88a_6af5,
+    0xee86_b98d_20f6_743d,
+    0xf89b_7ff6_09b1_c0a7,
+    0x4c7d_9cc1_9e22_c3e8,
+    0x9a97_0050_2456_2a6f,
+    0x5dd4_1cf4_23e6_ebef,
+    0xdf13_609c_0468_e227,
+    0x6e0d_a4f6_4188_155a,
+    0xb755_ba4b_50d7_d4a1,
+    0x887a_3484_6474_79bd,
+    0xab8e_ebe9_bf21_39a0,
+    0x7554_2c5d_4cd2_a6ff,
+];
+
+#[cfg(any(feature = "sys", feature = "pure-highway"))]
+#[test]
+fn highway_reference() {
+    let seed = [
+        0x0706_0504_0302_0100,
+        0x0f0e_0d0c_0b0a_0908,
+        0x1716_1514_1312_1110,
+        0x1f1e_1d1c_1b1a_1918,
+    ];
+    let data = (0..=64).collect::<Vec<u8>>();
+    let key = (128..128 + 33).collect::<Vec<u8>>();
+
+    for target in highway::Target::available() {
+        for (len, &hash) in HIGHWAY_HASH64.iter().enumerate() {
+            assert_eq!(
+                highway::Hash64::hash_with_target(&data[..len], seed, target),
+                Some(hash),
+                "{} with {} bytes",
+                target,
+                len
+            );
+        }
+
+        assert_eq!(
+            highway::Hash64::hash_with_target(&key, [1, 2, 3, 4], target),
+            Some(0x53c5_16cc_e478_cad7),
+            "{}",
+            target
+        );
+    }
+}